@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use aeron_rs::concurrent::strategies::{BusySpinIdleStrategy, Strategy};
+
+use log::warn;
+
+use sbe::{ExecutionReport, Message, OrderCancelReject, Uuid, decode_message};
+
+use tokio::sync::mpsc;
+
+use transport::MessageSource;
+
+use crate::order::OpenOrders;
+
+/// An order as the engine knows it: by account and `ClOrdID`, since clients choose the id.
+pub type OrderKey = (Uuid, Uuid);
+
+/// What a request waiting on an order hears from the engine.
+#[derive(Debug)]
+pub enum AckEvent {
+    Report(ExecutionReport),
+    CancelReject(OrderCancelReject),
+    /// The engine has moved on to other orders, so the order's immediate fills are all in.
+    Settled,
+}
+
+struct Waiter {
+    token: u64,
+    events: mpsc::UnboundedSender<AckEvent>,
+    /// The engine has answered, so later reports for other orders may settle it.
+    acked: bool,
+    /// Trades of the order, whose other sides are not the engine moving on.
+    match_ids: Vec<u64>,
+}
+
+#[derive(Default)]
+struct Waiters {
+    by_order: HashMap<OrderKey, Vec<Waiter>>,
+    /// The order and waiter of each cancel in flight, by the cancel's own `ClOrdID`, which is
+    /// all a cancel reject carries.
+    by_cancel: HashMap<Uuid, (OrderKey, u64)>,
+}
+
+/// Routes the engine's answers to the requests waiting for them, by account and `ClOrdId`.
+#[derive(Default)]
+pub struct Acks {
+    waiters: Mutex<Waiters>,
+    next_token: AtomicU64,
+}
+
+/// A request's registration; dropping it stops routing answers to the request.
+pub struct AckReceiver {
+    acks: Arc<Acks>,
+    key: OrderKey,
+    cancel_id: Option<Uuid>,
+    token: u64,
+    events: mpsc::UnboundedReceiver<AckEvent>,
+}
+
+impl AckReceiver {
+    pub async fn recv(&mut self) -> Option<AckEvent> {
+        self.events.recv().await
+    }
+}
+
+impl Drop for AckReceiver {
+    fn drop(&mut self) {
+        let mut waiters = self.acks.lock();
+        if let Some(cancel_id) = self.cancel_id {
+            waiters.by_cancel.remove(&cancel_id);
+        }
+        if let Some(entry) = waiters.by_order.get_mut(&self.key) {
+            entry.retain(|waiter| waiter.token != self.token);
+            if entry.is_empty() {
+                waiters.by_order.remove(&self.key);
+            }
+        }
+    }
+}
+
+impl Acks {
+    fn lock(&self) -> MutexGuard<'_, Waiters> {
+        self.waiters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Starts collecting the answers for the account's order `cl_ord_id`; call it before the
+    /// request is sent, so the answer cannot arrive first.
+    pub fn register(self: &Arc<Self>, account: Uuid, cl_ord_id: Uuid) -> AckReceiver {
+        self.register_with(account, cl_ord_id, None)
+    }
+
+    /// Like `register`, and also collects the reject of the cancel `cancel_id` of the order.
+    pub fn register_cancel(
+        self: &Arc<Self>,
+        account: Uuid,
+        cl_ord_id: Uuid,
+        cancel_id: Uuid,
+    ) -> AckReceiver {
+        self.register_with(account, cl_ord_id, Some(cancel_id))
+    }
+
+    fn register_with(
+        self: &Arc<Self>,
+        account: Uuid,
+        cl_ord_id: Uuid,
+        cancel_id: Option<Uuid>,
+    ) -> AckReceiver {
+        let (sender, events) = mpsc::unbounded_channel();
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let key = (account, cl_ord_id);
+        let mut waiters = self.lock();
+        waiters.by_order.entry(key).or_default().push(Waiter {
+            token,
+            events: sender,
+            acked: false,
+            match_ids: Vec::new(),
+        });
+        if let Some(cancel_id) = cancel_id {
+            waiters.by_cancel.insert(cancel_id, (key, token));
+        }
+        AckReceiver {
+            acks: Arc::clone(self),
+            key,
+            cancel_id,
+            token,
+            events,
+        }
+    }
+
+    pub fn on_report(&self, report: ExecutionReport) {
+        let mut waiters = self.lock();
+
+        if let Some(entry) = waiters
+            .by_order
+            .get_mut(&(report.account, report.cl_ord_id))
+        {
+            for waiter in entry {
+                waiter.acked = true;
+                waiter.match_ids.extend(report.trd_match_id);
+                // The request may have given up; its registration goes when it is dropped.
+                let _ = waiter.events.send(AckEvent::Report(report));
+            }
+            return;
+        }
+
+        // The engine publishes everything an order causes before it takes the next message, so
+        // a report that is neither the order's nor the other side of one of its trades means the
+        // order's immediate fills are complete.
+        for waiter in waiters.by_order.values_mut().flatten() {
+            let counterparty = report
+                .trd_match_id
+                .is_some_and(|match_id| waiter.match_ids.contains(&match_id));
+            if waiter.acked && !counterparty {
+                waiter.acked = false;
+                let _ = waiter.events.send(AckEvent::Settled);
+            }
+        }
+    }
+
+    pub fn on_cancel_reject(&self, reject: OrderCancelReject) {
+        let waiters = self.lock();
+        let Some((key, token)) = waiters.by_cancel.get(&reject.cl_ord_id) else {
+            return;
+        };
+        let waiter = waiters
+            .by_order
+            .get(key)
+            .and_then(|entry| entry.iter().find(|waiter| waiter.token == *token));
+        if let Some(waiter) = waiter {
+            let _ = waiter.events.send(AckEvent::CancelReject(reject));
+        }
+    }
+}
+
+/// Hands a frame of the execution report stream to the open orders and the waiting requests.
+pub fn on_frame(frame: &[u8], acks: &Acks, open_orders: &OpenOrders) {
+    match decode_message(frame) {
+        Ok(Message::ExecutionReport(report)) => {
+            // Tracked first, so a request that hears of its order finds it open.
+            open_orders.on_report(&report);
+            acks.on_report(report);
+        }
+        Ok(Message::OrderCancelReject(reject)) => acks.on_cancel_reject(reject),
+        Ok(_) => {}
+        Err(err) => warn!(
+            "Rejecting malformed report of {} bytes: {:?}",
+            frame.len(),
+            err
+        ),
+    }
+}
+
+/// Hands a frame of the engine's response stream to the requests waiting for a status report.
+pub fn on_response(frame: &[u8], statuses: &Acks) {
+    match decode_message(frame) {
+        Ok(Message::ExecutionReport(report)) => statuses.on_report(report),
+        Ok(_) => {}
+        Err(err) => warn!(
+            "Rejecting malformed response of {} bytes: {:?}",
+            frame.len(),
+            err
+        ),
+    }
+}
+
+/// Reads the execution report stream for as long as the gateway runs.
+pub fn listen(reports: impl MessageSource, acks: &Acks, open_orders: &OpenOrders) {
+    poll(reports, |frame| on_frame(frame, acks, open_orders));
+}
+
+/// Reads the response stream for as long as the gateway runs.
+pub fn listen_responses(responses: impl MessageSource, statuses: &Acks) {
+    poll(responses, |frame| on_response(frame, statuses));
+}
+
+fn poll(mut source: impl MessageSource, mut handler: impl FnMut(&[u8])) {
+    let poll_idle_strategy = BusySpinIdleStrategy::default();
+    loop {
+        let fragments_read = source.poll(&mut handler, 10);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        poll_idle_strategy.idle_opt(fragments_read as i32);
+    }
+}
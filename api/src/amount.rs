@@ -0,0 +1,85 @@
+//! Prices and sizes as clients send them, read exactly into a `Decimal64`. A JSON number would
+//! be rounded to the nearest `f64` by serde before any check could see it, so requests keep
+//! amounts as raw JSON and they are parsed here from their text, whether a string (`"0.1"`) or
+//! a number, in plain (`0.1`) or exponent (`1e-8`) notation.
+
+use serde_json::value::RawValue;
+
+use sbe::Decimal64;
+use sbe::decimal64::ParseDecimalError;
+
+/// Exponents past these are refused rather than expanded into a long string of zeros; no
+/// amount a client means needs them.
+const MAX_EXPONENT: i32 = 19;
+const MIN_EXPONENT: i32 = -40;
+
+/// Parses an amount from its raw JSON, a string or a number, without rounding.
+pub fn parse_json(raw: &RawValue) -> Result<Decimal64, ParseDecimalError> {
+    let text = raw.get().trim();
+    if text.starts_with('"') {
+        let text: String = serde_json::from_str(text).map_err(|_| ParseDecimalError::Invalid)?;
+        parse(&text)
+    } else {
+        parse(text)
+    }
+}
+
+/// Parses plain or exponent notation without rounding.
+pub fn parse(text: &str) -> Result<Decimal64, ParseDecimalError> {
+    let Some((number, exponent)) = text.split_once(['e', 'E']) else {
+        return text.parse();
+    };
+    let exponent: i32 = exponent.parse().map_err(|_| ParseDecimalError::Invalid)?;
+
+    let (sign, unsigned) = match number.as_bytes().first() {
+        Some(b'-') => ("-", &number[1..]),
+        Some(b'+') => ("", &number[1..]),
+        _ => ("", number),
+    };
+    let (integral, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let digits = format!("{integral}{fraction}");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseDecimalError::Invalid);
+    }
+    if digits.bytes().all(|b| b == b'0') {
+        return Ok(Decimal64::ZERO);
+    }
+    if exponent > MAX_EXPONENT {
+        return Err(ParseDecimalError::Overflow);
+    }
+    if exponent < MIN_EXPONENT {
+        return Err(ParseDecimalError::TooPrecise);
+    }
+
+    // Where the decimal point falls in `digits` once the exponent is applied.
+    let point = integral.len() as i64 + i64::from(exponent);
+    let plain = if point <= 0 {
+        format!(
+            "{sign}0.{}{digits}",
+            "0".repeat(point.unsigned_abs() as usize)
+        )
+    } else if point as usize >= digits.len() {
+        format!(
+            "{sign}{digits}{}",
+            "0".repeat(point as usize - digits.len())
+        )
+    } else {
+        let (integral, fraction) = digits.split_at(point as usize);
+        format!("{sign}{integral}.{fraction}")
+    };
+    plain.parse()
+}
+
+/// An amount the trade reporter recorded as a double, as it did before it wrote mantissas, back
+/// at the decimal's scale.
+pub fn from_f64(value: f64) -> Decimal64 {
+    Decimal64::try_from(value).unwrap_or_else(|_| {
+        // Within the decimal's range, so only the digits past its scale are lost.
+        let mantissa = (value * Decimal64::SCALE as f64).round();
+        if mantissa.abs() < i64::MAX as f64 {
+            Decimal64::from_mantissa(mantissa as i64)
+        } else {
+            Decimal64::ZERO
+        }
+    })
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use api::RateLimitConfig;
+
+use settings::Settings;
+
+use transport::{ChannelConfig, TransportConfig};
+
+/// Orders are published to the engine's inbound stream by default.
+const DEFAULT_PUB_CHANNEL: &str = "aeron:udp?endpoint=localhost:40123";
+const DEFAULT_PUB_STREAM_ID: i32 = 1001;
+
+/// Execution reports are read from the engine's multicast report stream by default.
+const DEFAULT_SUB_CHANNEL: &str = "aeron:udp?endpoint=224.1.1.1:40456|interface=localhost";
+const DEFAULT_SUB_STREAM_ID: i32 = 1002;
+
+/// Status reports are read from the engine's multicast response stream by default.
+const DEFAULT_RESP_CHANNEL: &str = "aeron:udp?endpoint=224.1.1.1:40457|interface=localhost";
+const DEFAULT_RESP_STREAM_ID: i32 = 1003;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub aeron: TransportConfig,
+    /// Where orders are published; `PUB_CHANNEL`/`PUB_STREAM_ID`.
+    #[serde(rename = "pub")]
+    pub orders: ChannelConfig,
+    /// Where execution reports are read from; `SUB_CHANNEL`/`SUB_STREAM_ID`.
+    #[serde(rename = "sub")]
+    pub reports: ChannelConfig,
+    /// Where the engine answers order status requests; `RESP_CHANNEL`/`RESP_STREAM_ID`.
+    #[serde(rename = "resp")]
+    pub responses: ChannelConfig,
+    /// Address the HTTP server listens on.
+    pub bind_addr: String,
+    /// How long an order request, or a query of an order's state, waits for the engine to answer.
+    pub ack_timeout_ms: u64,
+    /// How long an accepted order waits for more of its immediate fills.
+    pub fill_window_ms: u64,
+    /// How long the gateway remembers the answer to an order with a `client_oid`, for retries.
+    pub client_oid_retention_secs: u64,
+    /// PostgreSQL wire connection string of the QuestDB the order history is read from.
+    pub questdb_conf: String,
+    /// Copy of the auth service's `/api/auth/jwks`, the keys session tokens are checked with.
+    pub jwks_path: String,
+    /// `iss` session tokens must carry; empty to accept any.
+    pub jwt_issuer: String,
+    /// `aud` session tokens must carry; empty to accept any.
+    pub jwt_audience: String,
+    /// File the API keys are kept in.
+    pub api_keys_path: String,
+    /// How far a signed request's timestamp may be from the gateway's clock.
+    pub signature_window_secs: u64,
+    /// The shared product definitions, served as reference data.
+    pub products_path: String,
+    /// Request budgets of client IPs and of accounts by tier.
+    pub rate_limits: RateLimitConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aeron: TransportConfig::default(),
+            orders: ChannelConfig::new(DEFAULT_PUB_CHANNEL, DEFAULT_PUB_STREAM_ID),
+            reports: ChannelConfig::new(DEFAULT_SUB_CHANNEL, DEFAULT_SUB_STREAM_ID),
+            responses: ChannelConfig::new(DEFAULT_RESP_CHANNEL, DEFAULT_RESP_STREAM_ID),
+            bind_addr: "0.0.0.0:8000".to_owned(),
+            ack_timeout_ms: 2000,
+            fill_window_ms: 5,
+            client_oid_retention_secs: 600,
+            questdb_conf: "host=localhost port=8812 user=admin password=quest dbname=qdb"
+                .to_owned(),
+            jwks_path: "jwks.json".to_owned(),
+            jwt_issuer: "http://localhost:8000".to_owned(),
+            jwt_audience: "http://localhost:8000".to_owned(),
+            api_keys_path: "api-keys.json".to_owned(),
+            signature_window_secs: 30,
+            products_path: "products.json".to_owned(),
+            rate_limits: RateLimitConfig::default(),
+        }
+    }
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        self.aeron.validate()?;
+        self.orders.validate("pub")?;
+        self.reports.validate("sub")?;
+        self.responses.validate("resp")?;
+        if self.ack_timeout_ms == 0 {
+            return Err("ack_timeout_ms must be greater than zero".to_owned());
+        }
+        settings::required("questdb_conf", &self.questdb_conf)?;
+        settings::required("jwks_path", &self.jwks_path)?;
+        settings::required("api_keys_path", &self.api_keys_path)?;
+        if self.signature_window_secs == 0 {
+            return Err("signature_window_secs must be greater than zero".to_owned());
+        }
+        settings::required("products_path", &self.products_path)?;
+        self.rate_limits.validate("rate_limits")?;
+        settings::socket_addr("bind_addr", &self.bind_addr)
+    }
+}
+
+impl Config {
+    pub const fn ack_timeout(&self) -> Duration {
+        Duration::from_millis(self.ack_timeout_ms)
+    }
+
+    pub const fn fill_window(&self) -> Duration {
+        Duration::from_millis(self.fill_window_ms)
+    }
+
+    pub const fn client_oid_retention(&self) -> Duration {
+        Duration::from_secs(self.client_oid_retention_secs)
+    }
+
+    pub const fn signature_window(&self) -> Duration {
+        Duration::from_secs(self.signature_window_secs)
+    }
+}
@@ -1,8 +1,11 @@
 // src/errors.rs (or a similar module)
+use std::time::Duration;
+
+use api::{AuthError, KeyError, StoreError};
 use axum::{
     Json,
     extract::rejection::JsonRejection,
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde::Serialize;
@@ -10,23 +13,94 @@ use serde_json::json;
 
 #[derive(Debug)]
 pub enum AppError {
+    /// The request's credentials are missing or not accepted.
+    Unauthorized(AuthError),
+    /// API keys are managed with a session, not with another key.
+    SessionRequired,
+    /// The account has no API key with this id.
+    KeyNotFound(String),
+    /// The account already holds as many API keys as it may.
+    TooManyKeys,
+    /// The client spent its request budget; it may try again after this long.
+    RateLimited(Duration),
     InvalidSide,
     InvalidOrderType,
     ValidationError(String),
     JsonDeserializationError(String), // For custom messages from JsonRejection
+    /// The order could not be sent to the engine.
+    EngineUnavailable(String),
+    /// The order was sent but the engine did not answer in time, so its state is unknown.
+    AckTimeout(String),
+    /// The order is unknown to the gateway, or the engine no longer has it working.
+    OrderNotFound(String),
+    /// The engine already has an order with the `client_oid`, whose state the gateway does not
+    /// know yet.
+    OrderExists(String),
+    /// The engine refused to cancel the order for a reason other than not having it.
+    CancelRejected {
+        order_id: String,
+        reason: String,
+    },
+    /// The cancel was sent but the engine did not answer in time.
+    CancelTimeout(String),
+    /// No product has this id.
+    ProductNotFound(String),
+    /// The order history could not be read.
+    StoreUnavailable(String),
     InternalServerError(String),
 }
 
-#[derive(Serialize)]
-struct ErrorResponse {
-    message: String,
+#[derive(Serialize, Debug)]
+pub struct ErrorResponse {
+    pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    details: Option<String>,
+    pub details: Option<String>,
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
+/// Whole seconds, rounded up so a client that waits that long gets through.
+fn retry_after_secs(retry_after: Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
+
+impl AppError {
+    /// The status and body the error is answered with, which batches give each entry.
+    pub fn into_parts(self) -> (StatusCode, ErrorResponse) {
         let (status, error_message, error_details) = match self {
+            AppError::Unauthorized(AuthError::Unverified) => (
+                StatusCode::FORBIDDEN,
+                "A verified Minecraft account is required to trade.".to_string(),
+                Some("Verify your IGN with the auth service first.".to_string()),
+            ),
+            AppError::Unauthorized(err @ AuthError::NotPermitted(_)) => (
+                StatusCode::FORBIDDEN,
+                "The API key is not permitted to do this.".to_string(),
+                Some(err.to_string()),
+            ),
+            AppError::SessionRequired => (
+                StatusCode::FORBIDDEN,
+                "API keys can only be managed with a session.".to_string(),
+                None,
+            ),
+            AppError::KeyNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "API key not found.".to_string(),
+                Some(format!("The account has no API key {id}.")),
+            ),
+            AppError::TooManyKeys => (
+                StatusCode::CONFLICT,
+                "Too many API keys.".to_string(),
+                Some(KeyError::TooManyKeys.to_string()),
+            ),
+            AppError::Unauthorized(err) => (
+                StatusCode::UNAUTHORIZED,
+                "Authentication required.".to_string(),
+                Some(err.to_string()),
+            ),
+            AppError::RateLimited(retry_after) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests.".to_string(),
+                Some(format!("Try again in {} s.", retry_after_secs(retry_after))),
+            ),
             AppError::InvalidSide => (
                 StatusCode::BAD_REQUEST,
                 "Invalid side provided.".to_string(),
@@ -41,6 +115,52 @@ impl IntoResponse for AppError {
             AppError::JsonDeserializationError(msg) => {
                 (StatusCode::UNPROCESSABLE_ENTITY, msg, None)
             }
+            AppError::EngineUnavailable(reason) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "The order could not be sent to the matching engine.".to_string(),
+                Some(reason),
+            ),
+            AppError::AckTimeout(order_id) => (
+                StatusCode::GATEWAY_TIMEOUT,
+                "The matching engine did not acknowledge the order in time.".to_string(),
+                Some(format!(
+                    "Order {order_id} may still be accepted; check its status before retrying."
+                )),
+            ),
+            AppError::OrderNotFound(order_id) => (
+                StatusCode::NOT_FOUND,
+                "Order not found.".to_string(),
+                Some(format!("Order {order_id} is unknown or no longer open.")),
+            ),
+            AppError::OrderExists(order_id) => (
+                StatusCode::CONFLICT,
+                "The order was already placed.".to_string(),
+                Some(format!(
+                    "Order {order_id} is working; query it for its current state."
+                )),
+            ),
+            AppError::CancelRejected { order_id, reason } => (
+                StatusCode::CONFLICT,
+                "The matching engine rejected the cancel.".to_string(),
+                Some(format!("Order {order_id} could not be canceled: {reason}.")),
+            ),
+            AppError::CancelTimeout(order_id) => (
+                StatusCode::GATEWAY_TIMEOUT,
+                "The matching engine did not answer the cancel in time.".to_string(),
+                Some(format!(
+                    "Order {order_id} may still be canceled; check its status before retrying."
+                )),
+            ),
+            AppError::ProductNotFound(id) => (
+                StatusCode::NOT_FOUND,
+                "Product not found.".to_string(),
+                Some(format!("No product is traded as {id}.")),
+            ),
+            AppError::StoreUnavailable(reason) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "The order history is unavailable.".to_string(),
+                Some(reason),
+            ),
             AppError::InternalServerError(msg) => {
                 // It's good practice to log internal errors on the server
                 eprintln!("Internal Server Error: {}", msg);
@@ -52,12 +172,48 @@ impl IntoResponse for AppError {
             }
         };
 
-        let body = Json(json!(ErrorResponse {
-            message: error_message,
-            details: error_details,
-        }));
+        (
+            status,
+            ErrorResponse {
+                message: error_message,
+                details: error_details,
+            },
+        )
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let header = match &self {
+            AppError::Unauthorized(AuthError::Unverified | AuthError::NotPermitted(_)) => None,
+            AppError::Unauthorized(_) => Some((header::WWW_AUTHENTICATE, "Bearer".to_string())),
+            AppError::RateLimited(retry_after) => Some((
+                header::RETRY_AFTER,
+                retry_after_secs(*retry_after).to_string(),
+            )),
+            _ => None,
+        };
+        let (status, body) = self.into_parts();
+        let body = Json(json!(body));
+        match header {
+            Some(header) => (status, [header], body).into_response(),
+            None => (status, body).into_response(),
+        }
+    }
+}
+
+impl From<KeyError> for AppError {
+    fn from(err: KeyError) -> Self {
+        match err {
+            KeyError::TooManyKeys => AppError::TooManyKeys,
+            KeyError::Storage(_) => AppError::InternalServerError(err.to_string()),
+        }
+    }
+}
 
-        (status, body).into_response()
+impl From<StoreError> for AppError {
+    fn from(err: StoreError) -> Self {
+        AppError::StoreUnavailable(err.0)
     }
 }
 
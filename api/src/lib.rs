@@ -0,0 +1,22 @@
+//! Order history for the REST gateway, read behind `store::OrderStore` so that the query
+//! endpoints do not depend on QuestDB being there. `amount` reads the prices and sizes of
+//! requests exactly, and `ratelimit` budgets how many requests each client may make. Whose
+//! account a request acts for is checked by the `credentials` crate, and what is traded comes
+//! from the `products` crate, both shared with the other services.
+
+pub mod amount;
+pub mod questdb;
+pub mod ratelimit;
+pub mod store;
+
+pub use credentials::{
+    Access, Account, ApiKey, ApiKeys, AuthError, KeyError, Permission, SignedRequest, Verifier,
+    auth, keys,
+};
+pub use products::{Product, Products, TradingStatus};
+pub use questdb::QuestDbStore;
+pub use ratelimit::{RateLimitConfig, RateLimits};
+pub use store::{
+    Cursor, FillQuery, FillRecord, MemoryStore, OrderQuery, OrderRecord, OrderStore, Page,
+    StatusFilter, StoreError,
+};
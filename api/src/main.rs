@@ -1,77 +1,228 @@
-mod aeron_handler;
-use aeron_handler::{build_context, create_publication, get_aeron_dir, unwrap_publication};
-
-use aeron_rs::publication::Publication;
-
+mod acks;
+mod config;
 mod order;
+mod submissions;
 
 mod routes;
-use routes::{get_order, post_order};
+use routes::{
+    create_key, delete_order, delete_orders, get_order, get_product, list_fills, list_keys,
+    list_orders, list_products, post_order, post_orders, revoke_key,
+};
 
 mod errors;
+mod middleware;
 
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use aeron_rs::aeron::Aeron;
-use aeron_rs::concurrent::atomic_buffer::{AlignedBuffer, AtomicBuffer};
-use aeron_rs::concurrent::status::status_indicator_reader::channel_status_to_str;
-
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{delete, get, post},
+};
 
 use log::{error, info};
 
+use transport::{AeronTransport, MessageSink};
+
+use api::{ApiKeys, OrderStore, Products, QuestDbStore, RateLimits, Verifier};
+
+use acks::Acks;
+use config::Config;
+use order::OpenOrders;
+use submissions::Submissions;
+
 pub struct AppState {
-    publication: Publication,
-    buffer: AtomicBuffer,
+    /// Shared by every request, so offers are serialized.
+    publication: Mutex<Box<dyn MessageSink + Send>>,
+    acks: Arc<Acks>,
+    /// Requests waiting for the engine's answer to an order status request.
+    statuses: Arc<Acks>,
+    store: Box<dyn OrderStore>,
+    verifier: Verifier,
+    api_keys: ApiKeys,
+    products: Products,
+    rate_limits: RateLimits,
+    /// Orders resting on the book, kept up to date by the execution reports.
+    open_orders: Arc<OpenOrders>,
+    submissions: Arc<Submissions>,
+    ack_timeout: Duration,
+    fill_window: Duration,
 }
 
 #[tokio::main]
 async fn main() {
+    let config: Config = settings::load("api");
     env_logger::init();
 
     let start_time = Instant::now();
 
-    let aeron_dir = get_aeron_dir();
-    info!("Aeron: Using directory: {:?}", aeron_dir);
+    info!("Aeron: Using directory: {:?}", config.aeron.dir);
 
-    let context = build_context(&aeron_dir);
-    let mut aeron = match Aeron::new(context) {
-        Ok(a) => {
+    // Kept alive for as long as the server runs, as the publication depends on it.
+    let mut transport = match AeronTransport::connect(&config.aeron) {
+        Ok(transport) => {
             info!("Aeron: Instance created");
-            a
+            transport
         }
         Err(e) => {
-            error!("Aeron: Failed to create instance: {:?}", e);
+            error!("Aeron: Failed to create instance: {}", e);
             return;
         }
     };
 
-    let wrapped_publication = create_publication(&mut aeron, "aeron:udp?endpoint=localhost:40123", 1001);
-    let publication = unwrap_publication(wrapped_publication);
+    let publication = match transport.add_publication(&config.orders) {
+        Ok(publication) => {
+            info!("Aeron: Publication on {}", config.orders);
+            publication
+        }
+        Err(e) => {
+            error!("Aeron: Failed to add publication: {}", e);
+            return;
+        }
+    };
 
-    let pub_status = publication.channel_status();
-    info!("Aeron: Publication {}", channel_status_to_str(pub_status));
+    let reports = match transport.add_subscription(&config.reports) {
+        Ok(subscription) => {
+            info!("Aeron: Subscription on {}", config.reports);
+            subscription
+        }
+        Err(e) => {
+            error!("Aeron: Failed to add subscription: {}", e);
+            return;
+        }
+    };
 
-    let aligned_buffer = AlignedBuffer::with_capacity(72);
+    let responses = match transport.add_subscription(&config.responses) {
+        Ok(subscription) => {
+            info!("Aeron: Response subscription on {}", config.responses);
+            subscription
+        }
+        Err(e) => {
+            error!("Aeron: Failed to add response subscription: {}", e);
+            return;
+        }
+    };
+
+    let verifier = match Verifier::from_file(
+        config.jwks_path.as_ref(),
+        &config.jwt_issuer,
+        &config.jwt_audience,
+    ) {
+        Ok(verifier) => {
+            info!("Auth: Loaded session token keys from {}", config.jwks_path);
+            verifier
+        }
+        Err(e) => {
+            error!("Auth: Failed to load session token keys: {}", e);
+            return;
+        }
+    };
+
+    let api_keys = match ApiKeys::open(config.api_keys_path.as_ref(), config.signature_window()) {
+        Ok(api_keys) => {
+            info!("Auth: Loaded API keys from {}", config.api_keys_path);
+            api_keys
+        }
+        Err(e) => {
+            error!("Auth: Failed to load API keys: {}", e);
+            return;
+        }
+    };
+
+    let products = match Products::load(config.products_path.as_ref()) {
+        Ok(products) => {
+            info!(
+                "Products: Loaded {} products from {}",
+                products.len(),
+                config.products_path
+            );
+            products
+        }
+        Err(e) => {
+            error!("Products: Failed to load: {}", e);
+            return;
+        }
+    };
+
+    // Validated with the configuration, so only a bug can fail here.
+    let rate_limits = RateLimits::new(&config.rate_limits).expect("rate limits are validated");
+
+    let store = match QuestDbStore::connect(&config.questdb_conf).await {
+        Ok(store) => {
+            info!("QuestDB: Connected for order history");
+            store
+        }
+        Err(e) => {
+            error!("QuestDB: Failed to connect: {}", e);
+            return;
+        }
+    };
+
+    let acks = Arc::new(Acks::default());
+    let open_orders = Arc::new(OpenOrders::default());
+    let listener_acks = Arc::clone(&acks);
+    let listener_open_orders = Arc::clone(&open_orders);
+    thread::spawn(move || acks::listen(reports, &listener_acks, &listener_open_orders));
+    let statuses = Arc::new(Acks::default());
+    let listener_statuses = Arc::clone(&statuses);
+    thread::spawn(move || acks::listen_responses(responses, &listener_statuses));
 
     let shared_state = Arc::new(AppState {
-        publication,
-        buffer: AtomicBuffer::from_aligned(&aligned_buffer),
+        publication: Mutex::new(Box::new(publication)),
+        acks,
+        statuses,
+        store: Box::new(store),
+        verifier,
+        api_keys,
+        products,
+        rate_limits,
+        open_orders,
+        submissions: Arc::new(Submissions::new(config.client_oid_retention())),
+        ack_timeout: config.ack_timeout(),
+        fill_window: config.fill_window(),
     });
 
-    let app = Router::new().route(
-        "/api/v1/orders",
-        get(get_order).post(post_order).with_state(shared_state),
-    );
-
-    let listener = match tokio::net::TcpListener::bind("0.0.0.0:8000").await {
+    let app = Router::new()
+        .route("/api/v1/orders", get(list_orders).post(post_order))
+        .route(
+            "/api/v1/orders/batch",
+            post(post_orders).delete(delete_orders),
+        )
+        .route(
+            "/api/v1/orders/{order_id}",
+            get(get_order).delete(delete_order),
+        )
+        .route("/api/v1/fills", get(list_fills))
+        .route("/api/v1/keys", get(list_keys).post(create_key))
+        .route("/api/v1/keys/{key_id}", delete(revoke_key))
+        // Layers run last to first: the IP's budget, authentication, then the account's budget.
+        .route_layer(from_fn_with_state(
+            Arc::clone(&shared_state),
+            middleware::limit_account,
+        ))
+        .route_layer(from_fn_with_state(
+            Arc::clone(&shared_state),
+            middleware::authenticate,
+        ))
+        // Reference data is public, so only the IP's budget applies.
+        .route("/api/v1/products", get(list_products))
+        .route("/api/v1/products/{product_id}", get(get_product))
+        .route_layer(from_fn_with_state(
+            Arc::clone(&shared_state),
+            middleware::limit_ip,
+        ))
+        .with_state(shared_state);
+
+    let listener = match tokio::net::TcpListener::bind(&config.bind_addr).await {
         Ok(listener) => {
-            info!("Server: Bound to 0.0.0.0:8000");
+            info!("Server: Bound to {}", config.bind_addr);
             listener
         }
         Err(e) => {
-            error!("Server: Failed to bind to port 8000: {}", e);
+            error!("Server: Failed to bind to {}: {}", config.bind_addr, e);
             return;
         }
     };
@@ -82,6 +233,8 @@ async fn main() {
         startup_duration
     );
 
+    // Connection info gives the rate limits the client's IP.
+    let app = app.into_make_service_with_connect_info::<SocketAddr>();
     if let Err(e) = axum::serve(listener, app).await {
         error!("Server failed: {}", e);
     }
@@ -0,0 +1,127 @@
+use super::AppState;
+use super::errors::AppError;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+
+use axum::{
+    Extension,
+    body::{Body, to_bytes},
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, Method, header},
+    middleware::Next,
+    response::Response,
+};
+
+use api::keys::{ACCESS_KEY_HEADER, ACCESS_SIGN_HEADER, ACCESS_TIMESTAMP_HEADER};
+use api::ratelimit::Class;
+use api::{Access, Account, AuthError, Permission, SignedRequest};
+
+/// Largest body a signed request may have, as it is read whole to check the signature.
+const MAX_SIGNED_BODY: usize = 1024 * 1024;
+
+/// Reads are queries, and anything else order entry.
+fn class_of(method: &Method) -> Class {
+    match *method {
+        Method::GET | Method::HEAD => Class::Queries,
+        _ => Class::Orders,
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Resolves the account of every request, from its session token or its API key signature, for
+/// handlers to take as an `Extension<Account>` along with its `Extension<Access>`. Reading needs
+/// the `view` permission and anything else `trade`.
+pub async fn authenticate(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let (account, access, mut request) = match header(request.headers(), ACCESS_KEY_HEADER) {
+        Some(_) => {
+            let (parts, body) = request.into_parts();
+            let body = to_bytes(body, MAX_SIGNED_BODY).await.map_err(|err| {
+                AppError::Unauthorized(AuthError::Invalid(format!("unreadable body: {err}")))
+            })?;
+            let missing = |name: &str| {
+                AppError::Unauthorized(AuthError::Invalid(format!("missing {name} header")))
+            };
+            let signed = SignedRequest {
+                key: header(&parts.headers, ACCESS_KEY_HEADER).unwrap_or_default(),
+                timestamp: header(&parts.headers, ACCESS_TIMESTAMP_HEADER)
+                    .ok_or_else(|| missing(ACCESS_TIMESTAMP_HEADER))?,
+                signature: header(&parts.headers, ACCESS_SIGN_HEADER)
+                    .ok_or_else(|| missing(ACCESS_SIGN_HEADER))?,
+                method: parts.method.as_str(),
+                path: parts.uri.path_and_query().map_or("/", |path| path.as_str()),
+                body: &body,
+            };
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let (account, access) = state
+                .api_keys
+                .verify(&signed, now)
+                .map_err(AppError::Unauthorized)?;
+            (
+                account,
+                access,
+                Request::from_parts(parts, Body::from(body)),
+            )
+        }
+        None => {
+            let authorization = header(request.headers(), header::AUTHORIZATION.as_str());
+            let account = state
+                .verifier
+                .verify_bearer(authorization)
+                .map_err(AppError::Unauthorized)?;
+            (account, Access::Session, request)
+        }
+    };
+
+    let needed = match class_of(request.method()) {
+        Class::Queries => Permission::View,
+        Class::Orders => Permission::Trade,
+    };
+    if !access.allows(needed) {
+        return Err(AppError::Unauthorized(AuthError::NotPermitted(needed)));
+    }
+
+    request.extensions_mut().insert(account);
+    request.extensions_mut().insert(access);
+    Ok(next.run(request).await)
+}
+
+/// Spends the client IP's budget, before the request is authenticated so that failing to
+/// authenticate is limited too.
+pub async fn limit_ip(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    state
+        .rate_limits
+        .check_ip(addr.ip(), class_of(request.method()), Instant::now())
+        .map_err(AppError::RateLimited)?;
+    Ok(next.run(request).await)
+}
+
+/// Spends the account's budget, by its tier; runs after `authenticate`.
+pub async fn limit_account(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    state
+        .rate_limits
+        .check_account(account.id, class_of(request.method()), Instant::now())
+        .map_err(AppError::RateLimited)?;
+    Ok(next.run(request).await)
+}
@@ -1,29 +1,92 @@
-use sbe::WriteBuf;
-use sbe::message_header_codec::ENCODED_LENGTH;
-use sbe::new_order_single_codec::{NewOrderSingleEncoder, SBE_BLOCK_LENGTH};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use sbe::decimal64::RoundingMode;
+use sbe::exec_type_enum::ExecTypeEnum;
+use sbe::ord_rej_reason_enum::OrdRejReasonEnum;
+use sbe::ord_status_enum::OrdStatusEnum;
 use sbe::ord_type_enum::OrdTypeEnum;
 use sbe::side_enum::SideEnum;
-use uuid::Uuid;
+use sbe::{
+    Decimal64, ExecutionReport, NewOrderSingle, OrderCancelRequest, OrderStatusRequest, Uuid,
+};
+
+use api::{FillRecord, OrderRecord};
 
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 
 use serde::Serialize;
 
-pub const MESSAGE_SIZE: usize = SBE_BLOCK_LENGTH as usize + ENCODED_LENGTH;
+pub const MESSAGE_SIZE: usize = NewOrderSingle::ENCODED_LENGTH;
+pub const CANCEL_MESSAGE_SIZE: usize = OrderCancelRequest::ENCODED_LENGTH;
+pub const STATUS_MESSAGE_SIZE: usize = OrderStatusRequest::ENCODED_LENGTH;
 
-#[derive(Serialize, Debug)]
+#[derive(Clone, Serialize, Debug)]
 pub struct Order {
     pub id: String,
+    /// The id the client chose for the order, which is then also its `id`.
+    pub client_oid: Option<String>,
     pub product_id: String,
     pub side: String,
     pub r#type: String,
     pub created_at: String,
-    pub executed_value: f64,
+    pub executed_value: Decimal64,
+    /// `pending` until the engine answers, then `open`, `done` or `rejected`.
     pub status: String,
+    /// `filled` or `canceled` once the order is `done`.
+    pub done_reason: Option<String>,
+    pub reject_reason: Option<String>,
     pub settled: bool,
-    pub price: Option<f64>,
+    pub price: Option<Decimal64>,
     pub cancel_after: Option<String>,
-    pub size: f64,
+    pub size: Decimal64,
+    pub filled_size: Decimal64,
+    pub fills: Vec<Fill>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct Fill {
+    pub trade_id: u64,
+    pub price: Decimal64,
+    pub size: Decimal64,
+    pub created_at: String,
+}
+
+impl From<&FillRecord> for Fill {
+    fn from(fill: &FillRecord) -> Self {
+        Fill {
+            trade_id: fill.trade_id,
+            price: fill.price,
+            size: fill.size,
+            created_at: format_timestamp_ns(fill.created_at),
+        }
+    }
+}
+
+/// A fill in the account's fill history, which says which order it belongs to.
+#[derive(Serialize, Debug)]
+pub struct AccountFill {
+    pub trade_id: u64,
+    pub order_id: String,
+    pub product_id: String,
+    pub side: String,
+    pub price: Decimal64,
+    pub size: Decimal64,
+    pub created_at: String,
+}
+
+impl From<&FillRecord> for AccountFill {
+    fn from(fill: &FillRecord) -> Self {
+        AccountFill {
+            trade_id: fill.trade_id,
+            order_id: fill.order_id.to_string(),
+            product_id: fill.product_id.clone(),
+            side: side_name(fill.side),
+            price: fill.price,
+            size: fill.size,
+            created_at: format_timestamp_ns(fill.created_at),
+        }
+    }
 }
 
 pub fn format_timestamp_ns(timestamp_ns: u64) -> String {
@@ -37,13 +100,6 @@ pub fn format_timestamp_ns(timestamp_ns: u64) -> String {
     datetime.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
 }
 
-fn u64s_to_uuid(uuid: [u64; 2]) -> Uuid {
-    let mut bytes = [0u8; 16];
-    bytes[0..8].copy_from_slice(&uuid[0].to_be_bytes());
-    bytes[8..16].copy_from_slice(&uuid[1].to_be_bytes());
-    Uuid::from_bytes(bytes)
-}
-
 impl Order {
     pub fn from_buffer(
         cl_ord_id: UuidType,
@@ -51,42 +107,224 @@ impl Order {
         side: SideEnum,
         ord_type: OrdTypeEnum,
         timestamp_ns: u64,
-        qty_mantissa: i64,
-        price_mantissa: i64,
+        qty: Decimal64,
+        price: Option<Decimal64>,
     ) -> Self {
         Order {
-            id: u64s_to_uuid(cl_ord_id).to_string(),
+            id: Uuid::from_words(cl_ord_id).to_string(),
+            client_oid: None,
             product_id: String::from_utf8_lossy(&symbol)
                 .trim_end_matches('\0')
                 .to_string(),
-            side: match side {
-                SideEnum::Buy => "buy".to_string(),
-                SideEnum::Sell => "sell".to_string(),
-                _ => "unknown".to_string(),
-            },
+            side: side_name(side),
             r#type: match ord_type {
                 OrdTypeEnum::Limit => "limit".to_string(),
                 OrdTypeEnum::Market => "market".to_string(),
                 _ => "unknown".to_string(),
             },
             created_at: format_timestamp_ns(timestamp_ns),
-            executed_value: 0.0,
-            status: "open".to_string(),
+            executed_value: Decimal64::ZERO,
+            status: "pending".to_string(),
+            done_reason: None,
+            reject_reason: None,
             settled: false,
-            price: if price_mantissa != i64::MIN {
-                Some(price_mantissa as f64 / 100_000_000.0)
+            price,
+            cancel_after: None,
+            size: qty,
+            filled_size: Decimal64::ZERO,
+            fills: Vec::new(),
+        }
+    }
+
+    /// An order as the order history has it, with its fills.
+    pub fn from_record(record: &OrderRecord, fills: Vec<Fill>) -> Self {
+        let (status, done_reason) = status_of(record.ord_status);
+        let filled_size = record.filled_size;
+        let avg_px = record.avg_px.unwrap_or_default();
+        Order {
+            id: record.id.to_string(),
+            client_oid: None,
+            product_id: record.product_id.clone(),
+            side: side_name(record.side),
+            r#type: if record.price.is_some() {
+                "limit".to_string()
             } else {
-                None
+                "market".to_string()
             },
+            created_at: format_timestamp_ns(record.created_at),
+            executed_value: avg_px
+                .checked_mul(filled_size, RoundingMode::HalfEven)
+                .unwrap_or(Decimal64::MAX),
+            status: status.to_string(),
+            done_reason: done_reason.map(str::to_string),
+            reject_reason: None,
+            settled: status != "open",
+            price: record.price,
             cancel_after: None,
-            size: qty_mantissa as f64 / 100_000_000.0,
+            size: record.size,
+            filled_size,
+            fills,
         }
     }
+
+    /// An order the history does not have yet, as the engine reports its status. The report
+    /// does not say when the order was placed or how it was filled, so `created_at` is when the
+    /// engine answered and `fills` is empty.
+    pub fn from_status(report: &ExecutionReport) -> Self {
+        let ord_type = if report.price.is_some() {
+            OrdTypeEnum::Limit
+        } else {
+            OrdTypeEnum::Market
+        };
+        let mut order = Order::from_buffer(
+            report.cl_ord_id.words(),
+            report.symbol,
+            report.side,
+            ord_type,
+            report.transact_time,
+            report.order_qty,
+            report.price,
+        );
+        order.apply(report);
+        order
+    }
+
+    /// Brings the order up to date with a report from the engine.
+    pub fn apply(&mut self, report: &ExecutionReport) {
+        if report.exec_type == ExecTypeEnum::Trade {
+            let price = report.last_px.unwrap_or_default();
+            let size = report.last_qty.unwrap_or_default();
+            self.executed_value = price
+                .checked_mul(size, RoundingMode::HalfEven)
+                .and_then(|value| self.executed_value.checked_add(value))
+                .unwrap_or(Decimal64::MAX);
+            self.fills.push(Fill {
+                trade_id: report.trd_match_id.unwrap_or_default(),
+                price,
+                size,
+                created_at: format_timestamp_ns(report.transact_time),
+            });
+        } else if report.exec_type == ExecTypeEnum::OrderStatus {
+            // A status report only has the totals of the order's fills.
+            self.executed_value = report
+                .avg_px
+                .unwrap_or_default()
+                .checked_mul(report.cum_qty, RoundingMode::HalfEven)
+                .unwrap_or(Decimal64::MAX);
+        }
+        self.filled_size = report.cum_qty;
+
+        let (status, done_reason) = status_of(report.ord_status);
+        self.status = status.to_string();
+        self.done_reason = done_reason.map(str::to_string);
+        self.settled = status != "open";
+        if report.exec_type == ExecTypeEnum::Rejected {
+            self.reject_reason = Some(reject_reason(report.ord_rej_reason));
+        }
+    }
+
+    /// True once nothing more can happen to the order.
+    pub fn is_final(&self) -> bool {
+        self.status == "done" || self.status == "rejected"
+    }
+}
+
+fn side_name(side: SideEnum) -> String {
+    match side {
+        SideEnum::Buy => "buy",
+        SideEnum::Sell => "sell",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+/// The order's `status` and `done_reason` for an `OrdStatus`.
+fn status_of(ord_status: OrdStatusEnum) -> (&'static str, Option<&'static str>) {
+    match ord_status {
+        OrdStatusEnum::Filled => ("done", Some("filled")),
+        OrdStatusEnum::Canceled => ("done", Some("canceled")),
+        OrdStatusEnum::Rejected => ("rejected", None),
+        _ => ("open", None),
+    }
+}
+
+fn reject_reason(reason: Option<OrdRejReasonEnum>) -> String {
+    match reason {
+        Some(OrdRejReasonEnum::DuplicateOrder) => "duplicate_order",
+        Some(OrdRejReasonEnum::UnknownOrder) => "unknown_order",
+        Some(OrdRejReasonEnum::StaleOrder) => "stale_order",
+        _ => "other",
+    }
+    .to_string()
 }
 
 pub type UuidType = [u64; 2];
 pub type SymbolType = [u8; 6];
 
+/// The `Symbol` field holding `product_id`, padded with zeros.
+pub fn symbol_of(product_id: &str) -> SymbolType {
+    let mut symbol = [0u8; 6];
+    let bytes = product_id.as_bytes();
+    let len = bytes.len().min(symbol.len());
+    symbol[..len].copy_from_slice(&bytes[..len]);
+    symbol
+}
+
+/// What the gateway keeps of an order that may still be resting, to cancel it.
+#[derive(Clone, Copy, Debug)]
+pub struct OpenOrder {
+    pub account: UuidType,
+    pub symbol: SymbolType,
+    pub side: SideEnum,
+}
+
+/// The orders resting on the book, by account and `ClOrdID`, as the execution reports tell:
+/// added when the engine accepts one and dropped once it is done, so the map holds no more than
+/// the book does.
+#[derive(Default)]
+pub struct OpenOrders {
+    orders: Mutex<HashMap<(Uuid, Uuid), OpenOrder>>,
+}
+
+impl OpenOrders {
+    fn lock(&self) -> MutexGuard<'_, HashMap<(Uuid, Uuid), OpenOrder>> {
+        self.orders
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn get(&self, account: Uuid, cl_ord_id: Uuid) -> Option<OpenOrder> {
+        self.lock().get(&(account, cl_ord_id)).copied()
+    }
+
+    pub fn remove(&self, account: Uuid, cl_ord_id: Uuid) {
+        self.lock().remove(&(account, cl_ord_id));
+    }
+
+    pub fn on_report(&self, report: &ExecutionReport) {
+        let key = (report.account, report.cl_ord_id);
+        match report.ord_status {
+            OrdStatusEnum::New | OrdStatusEnum::PartiallyFilled => {
+                self.lock().insert(
+                    key,
+                    OpenOrder {
+                        account: report.account.words(),
+                        symbol: report.symbol,
+                        side: report.side,
+                    },
+                );
+            }
+            // Rejects a second order with the id, while the first still rests.
+            OrdStatusEnum::Rejected
+                if report.ord_rej_reason == Some(OrdRejReasonEnum::DuplicateOrder) => {}
+            OrdStatusEnum::Filled | OrdStatusEnum::Canceled | OrdStatusEnum::Rejected => {
+                self.lock().remove(&key);
+            }
+            OrdStatusEnum::NullVal => {}
+        }
+    }
+}
+
 pub fn create_order_buffer(
     cl_ord_id: &UuidType,
     account: &UuidType,
@@ -94,43 +332,64 @@ pub fn create_order_buffer(
     side: SideEnum,
     ord_type: OrdTypeEnum,
     timestamp_ns: u64,
-    qty_mantissa: i64,
-    price_mantissa: i64,
+    qty: Decimal64,
+    price: Option<Decimal64>,
 ) -> [u8; MESSAGE_SIZE] {
     let mut buffer = [0u8; MESSAGE_SIZE];
-    let write_buf = WriteBuf::new(&mut buffer[..]);
-
-    let mut order_encoder = NewOrderSingleEncoder::default().wrap(write_buf, ENCODED_LENGTH);
-
-    let mut header_composite_encoder = order_encoder.header(0);
-    order_encoder = header_composite_encoder
-        .parent()
-        .expect("Failed to retrieve parent encoder after SBE header encoding");
-
-    order_encoder.cl_ord_id(cl_ord_id);
-    order_encoder.account(account);
-    order_encoder.symbol(symbol);
-    order_encoder.side(side);
-
-    let mut transact_time_encoder = order_encoder.transact_time_encoder();
-    transact_time_encoder.time(timestamp_ns);
-    order_encoder = transact_time_encoder
-        .parent()
-        .expect("Failed to retrieve parent encoder after transact_time encoding");
-
-    order_encoder.ord_type(ord_type);
-
-    let mut order_qty_encoder = order_encoder.order_qty_encoder();
-    order_qty_encoder.mantissa(qty_mantissa);
-    order_encoder = order_qty_encoder
-        .parent()
-        .expect("Failed to retrieve parent encoder after order_qty encoding");
-
-    let mut price_encoder_composite = order_encoder.price_encoder();
-    price_encoder_composite.mantissa(price_mantissa);
-    price_encoder_composite
-        .parent()
-        .expect("Failed to retrieve parent encoder after price encoding");
+    NewOrderSingle {
+        cl_ord_id: Uuid::from_words(*cl_ord_id),
+        account: Uuid::from_words(*account),
+        transact_time: timestamp_ns,
+        order_qty: qty,
+        price,
+        symbol: *symbol,
+        side,
+        ord_type,
+    }
+    .encode(&mut buffer)
+    .expect("buffer is sized for a NewOrderSingle");
+
+    buffer
+}
+
+/// The engine finds the order by account and `OrigClOrdID`; the cancel's own `ClOrdID` is what
+/// a reject of it is answered to.
+pub fn create_cancel_buffer(
+    cancel_id: &UuidType,
+    cl_ord_id: &UuidType,
+    order: &OpenOrder,
+    timestamp_ns: u64,
+) -> [u8; CANCEL_MESSAGE_SIZE] {
+    let mut buffer = [0u8; CANCEL_MESSAGE_SIZE];
+    OrderCancelRequest {
+        orig_cl_ord_id: Uuid::from_words(*cl_ord_id),
+        cl_ord_id: Uuid::from_words(*cancel_id),
+        account: Uuid::from_words(order.account),
+        transact_time: timestamp_ns,
+        symbol: order.symbol,
+        side: order.side,
+    }
+    .encode(&mut buffer)
+    .expect("buffer is sized for an OrderCancelRequest");
+
+    buffer
+}
+
+/// Asks the engine for the state of the account's order `cl_ord_id`, answered on the response
+/// stream.
+pub fn create_status_buffer(
+    account: Uuid,
+    cl_ord_id: Uuid,
+    timestamp_ns: u64,
+) -> [u8; STATUS_MESSAGE_SIZE] {
+    let mut buffer = [0u8; STATUS_MESSAGE_SIZE];
+    OrderStatusRequest {
+        cl_ord_id,
+        account,
+        transact_time: timestamp_ns,
+    }
+    .encode(&mut buffer)
+    .expect("buffer is sized for an OrderStatusRequest");
 
     buffer
 }
@@ -0,0 +1,301 @@
+//! Reads the trade reporter's tables over QuestDB's PostgreSQL wire protocol. The `orders` table
+//! has a row per report, so an order is the aggregate of its rows; the `fills` table has signed
+//! rows, so a fill is the sum of its rows and is gone once busted. Amounts are read from their
+//! `_mantissa` columns, which hold them exactly.
+
+use crate::amount;
+use crate::store::{
+    Cursor, FillQuery, FillRecord, OrderQuery, OrderRecord, OrderStore, Page, StatusFilter,
+    StoreError,
+};
+
+use async_trait::async_trait;
+
+use log::error;
+
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::side_enum::SideEnum;
+use sbe::{Decimal64, Uuid};
+
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, NoTls, Row};
+
+const ORDERS: &str = "SELECT cl_ord_id, account, symbol, side, last(ord_status) ord_status, \
+     last(price) price, last(order_qty) order_qty, last(cum_qty) cum_qty, last(avg_px) avg_px, \
+     last(price_mantissa) price_mantissa, last(order_qty_mantissa) order_qty_mantissa, \
+     last(cum_qty_mantissa) cum_qty_mantissa, last(avg_px_mantissa) avg_px_mantissa, \
+     cast(first(timestamp) AS long) created_at, cast(last(timestamp) AS long) updated_at \
+     FROM orders";
+
+const FILLS: &str = "SELECT trd_match_id, cl_ord_id, account, symbol, side, sum(qty) qty, last(px) px, \
+     sum(qty_mantissa) qty_mantissa, last(px_mantissa) px_mantissa, \
+     cast(first(timestamp) AS long) created_at \
+     FROM fills";
+
+impl From<tokio_postgres::Error> for StoreError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        Self(err.to_string())
+    }
+}
+
+pub struct QuestDbStore {
+    client: Client,
+}
+
+impl QuestDbStore {
+    /// Connects with a PostgreSQL connection string, such as
+    /// `host=localhost port=8812 user=admin password=quest dbname=qdb`.
+    pub async fn connect(conf: &str) -> Result<Self, StoreError> {
+        let (client, connection) = tokio_postgres::connect(conf, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                error!("QuestDB: Connection failed: {}", err);
+            }
+        });
+        Ok(Self { client })
+    }
+
+    async fn query(&self, sql: &str, filter: &Filter) -> Result<Vec<Row>, StoreError> {
+        let params: Vec<&(dyn ToSql + Sync)> = filter
+            .params
+            .iter()
+            .map(|param| param.as_ref() as &(dyn ToSql + Sync))
+            .collect();
+        Ok(self.client.query(sql, &params).await?)
+    }
+}
+
+/// Conditions of a query and the values bound to them.
+#[derive(Default)]
+struct Filter {
+    clauses: Vec<String>,
+    params: Vec<Box<dyn ToSql + Sync + Send>>,
+}
+
+impl Filter {
+    /// Binds `value` and returns its placeholder.
+    fn bind(&mut self, value: impl ToSql + Sync + Send + 'static) -> String {
+        self.params.push(Box::new(value));
+        format!("${}", self.params.len())
+    }
+
+    fn clause(&mut self, clause: String) {
+        self.clauses.push(clause);
+    }
+
+    fn where_clause(&mut self) -> String {
+        if self.clauses.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", std::mem::take(&mut self.clauses).join(" AND "))
+        }
+    }
+
+    fn account(&mut self, account: Uuid) {
+        let account = self.bind(account.to_string());
+        self.clause(format!("account = {account}"));
+    }
+
+    /// Limits the aggregated rows to a creation time range.
+    fn created_between(&mut self, start: Option<u64>, end: Option<u64>) {
+        if let Some(start) = start {
+            self.clause(format!("created_at >= {}", start / 1000));
+        }
+        if let Some(end) = end {
+            self.clause(format!("created_at < {}", end.div_ceil(1000)));
+        }
+    }
+}
+
+const fn side_column(side: SideEnum) -> bool {
+    matches!(side, SideEnum::Buy)
+}
+
+const fn side_from_column(buy: bool) -> SideEnum {
+    if buy { SideEnum::Buy } else { SideEnum::Sell }
+}
+
+fn ord_status(status: &str) -> Result<OrdStatusEnum, StoreError> {
+    match status {
+        "new" => Ok(OrdStatusEnum::New),
+        "partially_filled" => Ok(OrdStatusEnum::PartiallyFilled),
+        "filled" => Ok(OrdStatusEnum::Filled),
+        "canceled" => Ok(OrdStatusEnum::Canceled),
+        other => Err(StoreError(format!("unknown order status {other:?}"))),
+    }
+}
+
+fn uuid(value: &str) -> Result<Uuid, StoreError> {
+    value
+        .parse()
+        .map_err(|_| StoreError(format!("invalid order id {value:?}")))
+}
+
+/// QuestDB timestamps are in microseconds.
+fn nanos(micros: i64) -> u64 {
+    u64::try_from(micros).unwrap_or_default() * 1000
+}
+
+/// The amount in `column`, from its exact `_mantissa` column, or from the double for rows
+/// written before there was one.
+fn decimal(row: &Row, column: &str) -> Result<Option<Decimal64>, StoreError> {
+    let mantissa: Option<i64> = row.try_get(format!("{column}_mantissa").as_str())?;
+    Ok(match mantissa {
+        Some(mantissa) => Some(Decimal64::from_mantissa(mantissa)),
+        None => row.try_get::<_, Option<f64>>(column)?.map(amount::from_f64),
+    })
+}
+
+fn required_decimal(row: &Row, column: &str) -> Result<Decimal64, StoreError> {
+    decimal(row, column)?.ok_or_else(|| StoreError(format!("{column} is missing")))
+}
+
+fn order_record(row: &Row) -> Result<OrderRecord, StoreError> {
+    let cum_qty = required_decimal(row, "cum_qty")?;
+    Ok(OrderRecord {
+        id: uuid(row.try_get("cl_ord_id")?)?,
+        account: uuid(row.try_get("account")?)?,
+        product_id: row.try_get("symbol")?,
+        side: side_from_column(row.try_get("side")?),
+        price: decimal(row, "price")?,
+        // Written since the size was added to the table.
+        size: decimal(row, "order_qty")?.unwrap_or(cum_qty),
+        filled_size: cum_qty,
+        avg_px: decimal(row, "avg_px")?,
+        ord_status: ord_status(row.try_get("ord_status")?)?,
+        created_at: nanos(row.try_get("created_at")?),
+        updated_at: nanos(row.try_get("updated_at")?),
+    })
+}
+
+fn fill_record(row: &Row) -> Result<FillRecord, StoreError> {
+    Ok(FillRecord {
+        trade_id: u64::try_from(row.try_get::<_, i64>("trd_match_id")?).unwrap_or_default(),
+        order_id: uuid(row.try_get("cl_ord_id")?)?,
+        account: uuid(row.try_get("account")?)?,
+        product_id: row.try_get("symbol")?,
+        side: side_from_column(row.try_get("side")?),
+        price: required_decimal(row, "px")?,
+        size: required_decimal(row, "qty")?,
+        created_at: nanos(row.try_get("created_at")?),
+    })
+}
+
+/// Continues a listing after `cursor`; fill listings also order by trade.
+fn after_cursor(filter: &mut Filter, cursor: Cursor, with_trade: bool) {
+    let created_at = cursor.created_at / 1000;
+    let id = filter.bind(cursor.id.to_string());
+    let tie = if with_trade {
+        format!(
+            "(trd_match_id < {trade} OR trd_match_id = {trade} AND cl_ord_id < {id})",
+            trade = cursor.trade_id
+        )
+    } else {
+        format!("cl_ord_id < {id}")
+    };
+    filter.clause(format!(
+        "(created_at < {created_at} OR created_at = {created_at} AND {tie})"
+    ));
+}
+
+#[async_trait]
+impl OrderStore for QuestDbStore {
+    async fn order(&self, account: Uuid, id: Uuid) -> Result<Option<OrderRecord>, StoreError> {
+        let mut filter = Filter::default();
+        filter.account(account);
+        let id = filter.bind(id.to_string());
+        filter.clause(format!("cl_ord_id = {id}"));
+        let sql = format!(
+            "{ORDERS}{} GROUP BY cl_ord_id, account, symbol, side",
+            filter.where_clause()
+        );
+        let rows = self.query(&sql, &filter).await?;
+        rows.first().map(order_record).transpose()
+    }
+
+    async fn orders(&self, query: &OrderQuery) -> Result<Page<OrderRecord>, StoreError> {
+        let mut filter = Filter::default();
+        filter.account(query.account);
+        if let Some(product_id) = &query.product_id {
+            let product_id = filter.bind(product_id.clone());
+            filter.clause(format!("symbol = {product_id}"));
+        }
+        if let Some(side) = query.side {
+            let side = filter.bind(side_column(side));
+            filter.clause(format!("side = {side}"));
+        }
+        let inner = format!(
+            "{ORDERS}{} GROUP BY cl_ord_id, account, symbol, side",
+            filter.where_clause()
+        );
+
+        match query.status {
+            Some(StatusFilter::Open) => {
+                filter.clause("ord_status IN ('new', 'partially_filled')".to_owned());
+            }
+            Some(StatusFilter::Done) => {
+                filter.clause("ord_status IN ('filled', 'canceled')".to_owned());
+            }
+            None => {}
+        }
+        filter.created_between(query.start, query.end);
+        if let Some(cursor) = query.after {
+            after_cursor(&mut filter, cursor, false);
+        }
+        let sql = format!(
+            "SELECT * FROM ({inner}){} ORDER BY created_at DESC, cl_ord_id DESC LIMIT {}",
+            filter.where_clause(),
+            query.limit + 1
+        );
+
+        let orders = self
+            .query(&sql, &filter)
+            .await?
+            .iter()
+            .map(order_record)
+            .collect::<Result<_, _>>()?;
+        Ok(Page::from_overfetch(
+            orders,
+            query.limit,
+            OrderRecord::cursor,
+        ))
+    }
+
+    async fn fills(&self, query: &FillQuery) -> Result<Page<FillRecord>, StoreError> {
+        let mut filter = Filter::default();
+        filter.account(query.account);
+        if let Some(order_id) = query.order_id {
+            let order_id = filter.bind(order_id.to_string());
+            filter.clause(format!("cl_ord_id = {order_id}"));
+        }
+        if let Some(product_id) = &query.product_id {
+            let product_id = filter.bind(product_id.clone());
+            filter.clause(format!("symbol = {product_id}"));
+        }
+        let inner = format!(
+            "{FILLS}{} GROUP BY trd_match_id, cl_ord_id, account, symbol, side",
+            filter.where_clause()
+        );
+
+        // Busted trades net out to nothing.
+        filter.clause("qty != 0".to_owned());
+        filter.created_between(query.start, query.end);
+        if let Some(cursor) = query.after {
+            after_cursor(&mut filter, cursor, true);
+        }
+        let sql = format!(
+            "SELECT * FROM ({inner}){} \
+             ORDER BY created_at DESC, trd_match_id DESC, cl_ord_id DESC LIMIT {}",
+            filter.where_clause(),
+            query.limit + 1
+        );
+
+        let fills = self
+            .query(&sql, &filter)
+            .await?
+            .iter()
+            .map(fill_record)
+            .collect::<Result<_, _>>()?;
+        Ok(Page::from_overfetch(fills, query.limit, FillRecord::cursor))
+    }
+}
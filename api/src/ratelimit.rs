@@ -0,0 +1,275 @@
+//! Token buckets that keep one client from flooding the engine or the order history. Every
+//! client IP has a budget, checked before the request is authenticated, and every account one
+//! by its tier, checked after. Order entry, anything but a read, and queries are budgeted apart,
+//! so a client polling its orders cannot stop itself from canceling them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use sbe::Uuid;
+
+use serde::{Deserialize, Serialize};
+
+/// Checks between sweeps of the buckets that have filled up again, which are as good as gone.
+const SWEEP_INTERVAL: u64 = 4096;
+
+/// What a request spends its budget on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Class {
+    /// Placing and canceling orders, and any other change.
+    Orders,
+    Queries,
+}
+
+/// A bucket of `burst` tokens, refilled at `per_second`; each request takes one.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Budget {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+impl Budget {
+    pub const fn new(burst: u32, per_second: f64) -> Self {
+        Self { burst, per_second }
+    }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        if self.burst == 0 {
+            return Err(format!("{name}.burst must be greater than zero"));
+        }
+        if !(self.per_second.is_finite() && self.per_second > 0.0) {
+            return Err(format!("{name}.per_second must be greater than zero"));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new(10, 5.0)
+    }
+}
+
+/// The budgets of one client.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tier {
+    pub orders: Budget,
+    pub queries: Budget,
+}
+
+impl Tier {
+    pub const fn budget(&self, class: Class) -> &Budget {
+        match class {
+            Class::Orders => &self.orders,
+            Class::Queries => &self.queries,
+        }
+    }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        self.orders.validate(&format!("{name}.orders"))?;
+        self.queries.validate(&format!("{name}.queries"))
+    }
+}
+
+/// The `rate_limits` section of the gateway's configuration.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Budgets of accounts by tier; accounts not in `accounts` are `default`.
+    pub tiers: BTreeMap<String, Tier>,
+    /// Tier of each account, by its Minecraft UUID.
+    pub accounts: BTreeMap<String, String>,
+    /// Budgets of each client IP, whichever accounts it acts for.
+    pub ip: Tier,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let tiers = BTreeMap::from([
+            (
+                "default".to_owned(),
+                Tier {
+                    orders: Budget::new(20, 10.0),
+                    queries: Budget::new(40, 20.0),
+                },
+            ),
+            (
+                "market_maker".to_owned(),
+                Tier {
+                    orders: Budget::new(200, 100.0),
+                    queries: Budget::new(100, 50.0),
+                },
+            ),
+        ]);
+        Self {
+            tiers,
+            accounts: BTreeMap::new(),
+            ip: Tier {
+                orders: Budget::new(50, 25.0),
+                queries: Budget::new(100, 50.0),
+            },
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn validate(&self, name: &str) -> Result<(), String> {
+        if !self.tiers.contains_key("default") {
+            return Err(format!("{name}.tiers.default is required"));
+        }
+        for (tier_name, tier) in &self.tiers {
+            tier.validate(&format!("{name}.tiers.{tier_name}"))?;
+        }
+        for (account, tier) in &self.accounts {
+            if account.parse::<Uuid>().is_err() {
+                return Err(format!("{name}.accounts.{account} is not an account UUID"));
+            }
+            if !self.tiers.contains_key(tier) {
+                return Err(format!("{name}.accounts.{account} names no tier {tier}"));
+            }
+        }
+        self.ip.validate(&format!("{name}.ip"))
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    budget: Budget,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.budget.per_second).min(f64::from(self.budget.burst));
+        self.updated = now;
+    }
+}
+
+struct State<K> {
+    buckets: HashMap<(K, Class), Bucket>,
+    checks: u64,
+}
+
+/// Buckets by client and class.
+struct Buckets<K> {
+    state: Mutex<State<K>>,
+}
+
+impl<K: Eq + Hash> Buckets<K> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(State {
+                buckets: HashMap::new(),
+                checks: 0,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<K>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Takes `tokens` from `key`'s bucket, or says how long until there are as many. More than
+    /// the bucket holds waits for it to be full and leaves it owing the rest, which is refilled
+    /// before anything else passes.
+    fn take(
+        &self,
+        key: K,
+        class: Class,
+        tier: &Tier,
+        tokens: u32,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut state = self.lock();
+        state.checks += 1;
+        if state.checks.is_multiple_of(SWEEP_INTERVAL) {
+            state.buckets.retain(|_, bucket| {
+                bucket.refill(now);
+                bucket.tokens < f64::from(bucket.budget.burst)
+            });
+        }
+
+        let budget = *tier.budget(class);
+        let bucket = state.buckets.entry((key, class)).or_insert(Bucket {
+            tokens: f64::from(budget.burst),
+            updated: now,
+            budget,
+        });
+        bucket.refill(now);
+
+        let needed = f64::from(tokens.min(budget.burst));
+        if bucket.tokens >= needed {
+            bucket.tokens -= f64::from(tokens);
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (needed - bucket.tokens) / budget.per_second,
+            ))
+        }
+    }
+}
+
+/// The gateway's rate limits.
+pub struct RateLimits {
+    default: Tier,
+    /// Accounts not in the default tier.
+    accounts: HashMap<Uuid, Tier>,
+    ip: Tier,
+    by_account: Buckets<Uuid>,
+    by_ip: Buckets<IpAddr>,
+}
+
+impl RateLimits {
+    pub fn new(config: &RateLimitConfig) -> Result<Self, String> {
+        config.validate("rate_limits")?;
+        let mut accounts = HashMap::new();
+        for (account, tier) in &config.accounts {
+            let account: Uuid = account
+                .parse()
+                .map_err(|_| format!("{account} is not an account UUID"))?;
+            accounts.insert(account, config.tiers[tier]);
+        }
+        Ok(Self {
+            default: config.tiers["default"],
+            accounts,
+            ip: config.ip,
+            by_account: Buckets::new(),
+            by_ip: Buckets::new(),
+        })
+    }
+
+    /// Spends one of the IP's tokens; `Err` holds how long until it may try again.
+    pub fn check_ip(&self, ip: IpAddr, class: Class, now: Instant) -> Result<(), Duration> {
+        self.by_ip.take(ip, class, &self.ip, 1, now)
+    }
+
+    /// Spends one of the account's tokens; `Err` holds how long until it may try again.
+    pub fn check_account(&self, account: Uuid, class: Class, now: Instant) -> Result<(), Duration> {
+        self.spend_account(account, class, 1, now)
+    }
+
+    /// Spends `requests` of the account's tokens at once, as a batch does for its entries.
+    pub fn spend_account(
+        &self,
+        account: Uuid,
+        class: Class,
+        requests: usize,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        if requests == 0 {
+            return Ok(());
+        }
+        let tier = self.accounts.get(&account).unwrap_or(&self.default);
+        let tokens = u32::try_from(requests).unwrap_or(u32::MAX);
+        self.by_account.take(account, class, tier, tokens, now)
+    }
+}
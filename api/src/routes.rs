@@ -1,17 +1,39 @@
 use super::AppState;
-use super::errors::AppError;
-use super::order::{Order, SymbolType, UuidType, create_order_buffer};
+use super::acks::{AckEvent, AckReceiver};
+use super::errors::{AppError, ErrorResponse};
+use super::order::{
+    AccountFill, CANCEL_MESSAGE_SIZE, Fill, MESSAGE_SIZE, OpenOrder, Order, SymbolType, UuidType,
+    create_cancel_buffer, create_order_buffer, create_status_buffer, symbol_of,
+};
+use super::submissions::{Claim, Outcome, Submission};
 
+use api::ratelimit::Class;
+use api::{
+    Access, Account, ApiKey, Cursor, FillQuery, OrderQuery, Permission, Product, Products,
+    StatusFilter, amount,
+};
+
+use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
 
-use axum::{Json, extract::State, http::StatusCode};
+use sbe::cxl_rej_reason_enum::CxlRejReasonEnum;
+use sbe::exec_type_enum::ExecTypeEnum;
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::{Decimal64, ExecutionReport, ord_type_enum::OrdTypeEnum, side_enum::SideEnum};
 
-use sbe::{ord_type_enum::OrdTypeEnum, side_enum::SideEnum};
+use log::{error, warn};
 
-use serde::Deserialize;
+use tokio::time::timeout;
 
-use super::order::MESSAGE_SIZE;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 
 use uuid::Uuid;
 
@@ -20,8 +42,18 @@ pub struct CreateOrder {
     pub product_id: String,
     pub side: String,
     pub r#type: String,
-    pub size: f64,
-    pub price: Option<f64>,
+    /// A decimal string or number, read exactly.
+    pub size: Box<RawValue>,
+    pub price: Option<Box<RawValue>>,
+    /// A UUID the client picks to identify the order, so that retrying the request cannot
+    /// place it twice.
+    pub client_oid: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateKey {
+    pub name: String,
+    pub permissions: Vec<Permission>,
 }
 
 #[derive(Debug)]
@@ -31,8 +63,8 @@ struct ParsedOrderInput {
     symbol: SymbolType,
     side: SideEnum,
     ord_type: OrdTypeEnum,
-    qty_mantissa: i64,
-    price_mantissa: i64,
+    qty: Decimal64,
+    price: Option<Decimal64>,
 }
 
 fn uuid_to_u64s(uuid: Uuid) -> [u64; 2] {
@@ -42,21 +74,56 @@ fn uuid_to_u64s(uuid: Uuid) -> [u64; 2] {
     [high, low]
 }
 
-fn parse_and_validate_order_payload(payload: &CreateOrder) -> Result<ParsedOrderInput, AppError> {
-    let cl_ord_id = uuid_to_u64s(Uuid::new_v4()); // Uuid::into_bytes() returns [u8; 16] directly
-    let account = uuid_to_u64s(Uuid::new_v4()); // Hardcoded for now, ensure it's [u8; 16]
+/// Reads an amount exactly, rejecting values with more than 8 fractional digits or out of
+/// range instead of rounding them.
+fn parse_decimal(field: &str, value: &RawValue) -> Result<Decimal64, AppError> {
+    amount::parse_json(value).map_err(|err| {
+        AppError::ValidationError(format!(
+            "{field} {} is not a valid amount: {err}.",
+            value.get()
+        ))
+    })
+}
+
+fn now_ns() -> Result<u64, AppError> {
+    Ok(SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to get system time: {}", e)))?
+        .as_nanos() as u64)
+}
+
+fn cancel_reject_error(order_id: String, reason: CxlRejReasonEnum) -> AppError {
+    match reason {
+        CxlRejReasonEnum::UnknownOrder => AppError::OrderNotFound(order_id),
+        CxlRejReasonEnum::NullVal => AppError::CancelRejected {
+            order_id,
+            reason: "no reason given".to_string(),
+        },
+    }
+}
+
+fn parse_and_validate_order_payload(
+    account: &Account,
+    products: &Products,
+    payload: &CreateOrder,
+) -> Result<ParsedOrderInput, AppError> {
+    let cl_ord_id = match &payload.client_oid {
+        Some(client_oid) => client_oid.parse::<Uuid>().map_err(|_| {
+            AppError::ValidationError(format!("client_oid '{client_oid}' is not a UUID."))
+        })?,
+        None => Uuid::new_v4(),
+    };
+    let cl_ord_id = uuid_to_u64s(cl_ord_id); // Uuid::into_bytes() returns [u8; 16] directly
+    let account = account.id.words();
 
     // --- Symbol Validation ---
-    // TODO: VALIDATE SYMBOL (enhance this as needed)
-    let mut symbol = [0u8; 6];
-    let symbol_str = payload.product_id.to_uppercase();
-    if symbol_str.is_empty() || symbol_str.len() > 6 {
-        return Err(AppError::ValidationError(format!(
-            "Product ID '{}' is invalid. It must be between 1 and 6 characters.",
+    let product = products.get(&payload.product_id).ok_or_else(|| {
+        AppError::ValidationError(format!(
+            "Product ID '{}' is not traded.",
             payload.product_id
-        )));
-    }
-    symbol[..symbol_str.len()].copy_from_slice(symbol_str.as_bytes());
+        ))
+    })?;
+    let symbol = product.symbol();
 
     // --- Side Validation ---
     let side = match payload.side.to_lowercase().as_str() {
@@ -72,22 +139,28 @@ fn parse_and_validate_order_payload(payload: &CreateOrder) -> Result<ParsedOrder
         _ => return Err(AppError::InvalidOrderType),
     };
 
+    let price = payload
+        .price
+        .as_deref()
+        .map(|p| parse_decimal("Price", p))
+        .transpose()?;
+
     // --- Price Validation based on Order Type ---
     match ord_type {
         OrdTypeEnum::Limit => {
-            if payload.price.is_none() {
+            let Some(price) = price else {
                 return Err(AppError::ValidationError(
                     "Price is required for limit orders.".to_string(),
                 ));
-            }
-            if payload.price.unwrap_or(-1.0) <= 0.0 {
+            };
+            if !price.is_positive() {
                 return Err(AppError::ValidationError(
                     "Price must be positive for limit orders.".to_string(),
                 ));
             }
         }
         OrdTypeEnum::Market => {
-            if payload.price.is_some() {
+            if price.is_some() {
                 return Err(AppError::ValidationError(
                     "Price should not be provided for market orders.".to_string(),
                 ));
@@ -97,23 +170,15 @@ fn parse_and_validate_order_payload(payload: &CreateOrder) -> Result<ParsedOrder
     }
 
     // --- Size (Quantity) Validation ---
-    if payload.size <= 0.0 {
+    let qty = parse_decimal("Size", &payload.size)?;
+    if !qty.is_positive() {
         return Err(AppError::ValidationError(
             "Size must be greater than 0.".to_string(),
         ));
     }
-    let qty_mantissa = (payload.size * 100_000_000.0).round() as i64;
-    if qty_mantissa <= 0 {
-        // Double check after conversion
-        return Err(AppError::ValidationError(
-            "Calculated quantity (size) must be positive.".to_string(),
-        ));
-    }
-
-    let price_mantissa = match payload.price {
-        Some(p) => (p * 100_000_000.0).round() as i64,
-        None => i64::MIN, // Sentinel for market orders or when price is not applicable
-    };
+    product
+        .check_order(qty, price)
+        .map_err(AppError::ValidationError)?;
 
     Ok(ParsedOrderInput {
         cl_ord_id,
@@ -121,68 +186,859 @@ fn parse_and_validate_order_payload(payload: &CreateOrder) -> Result<ParsedOrder
         symbol,
         side,
         ord_type,
-        qty_mantissa,
-        price_mantissa,
+        qty,
+        price,
     })
 }
 
-pub async fn get_order() {}
+/// Page size of listings when the request does not give one, and the largest it may ask for.
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
 
-pub async fn post_order(
+#[derive(Deserialize, Debug)]
+pub struct ListOrders {
+    pub product_id: Option<String>,
+    /// `open` or `done`.
+    pub status: Option<String>,
+    pub side: Option<String>,
+    /// RFC 3339 times bounding when the orders were created.
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    /// The `cursor` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ListFills {
+    pub order_id: Option<String>,
+    pub product_id: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OrderPage {
+    pub orders: Vec<Order>,
+    pub has_next: bool,
+    /// Passed back as `cursor` for the next page.
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FillPage {
+    pub fills: Vec<AccountFill>,
+    pub has_next: bool,
+    pub cursor: Option<String>,
+}
+
+fn parse_order_id(order_id: &str) -> Result<sbe::Uuid, AppError> {
+    order_id
+        .parse()
+        .map_err(|_| AppError::ValidationError(format!("Order ID '{order_id}' is not a UUID.")))
+}
+
+fn parse_product_id(product_id: Option<&String>) -> Option<String> {
+    product_id.map(|product_id| product_id.to_uppercase())
+}
+
+fn parse_date(field: &str, value: Option<&String>) -> Result<Option<u64>, AppError> {
+    value
+        .map(|value| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .and_then(|date| date.timestamp_nanos_opt())
+                .and_then(|nanos| u64::try_from(nanos).ok())
+                .ok_or_else(|| {
+                    AppError::ValidationError(format!("{field} '{value}' is not an RFC 3339 time."))
+                })
+        })
+        .transpose()
+}
+
+fn parse_cursor(cursor: Option<&String>) -> Result<Option<Cursor>, AppError> {
+    cursor
+        .map(|cursor| {
+            cursor
+                .parse()
+                .map_err(|_| AppError::ValidationError(format!("Cursor '{cursor}' is invalid.")))
+        })
+        .transpose()
+}
+
+fn parse_limit(limit: Option<usize>) -> Result<usize, AppError> {
+    match limit.unwrap_or(DEFAULT_LIMIT) {
+        limit @ 1..=MAX_LIMIT => Ok(limit),
+        _ => Err(AppError::ValidationError(format!(
+            "Limit must be between 1 and {MAX_LIMIT}."
+        ))),
+    }
+}
+
+/// The account's order `order_id` with its fills as recorded and its state as the engine has
+/// it, which the history may not have caught up with. If the engine does not know the order or
+/// does not answer, the history's state is all there is.
+pub async fn get_order(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<CreateOrder>,
-) -> Result<(StatusCode, Json<Order>), AppError> {
-    // TODO: ADD AUTH MIDDLEWARE
-    // TODO: VALIDATE BODY SHAPE (i believe axum already does this for us with 422 error... want to control error messages ourselves tho)
+    Extension(account): Extension<Account>,
+    Path(order_id): Path<String>,
+) -> Result<Json<Order>, AppError> {
+    let id = parse_order_id(&order_id)?;
+    let status = engine_status(&state, &account, id).await;
+    let order = match (stored_order(&state, &account, id).await?, status) {
+        (Some(mut order), Some(report)) => {
+            order.apply(&report);
+            order
+        }
+        (Some(order), None) => order,
+        (None, Some(report)) => Order::from_status(&report),
+        (None, None) => return Err(AppError::OrderNotFound(id.to_string())),
+    };
+    Ok(Json(order))
+}
+
+/// Asks the engine for the state of the account's order `cl_ord_id`. `None` if the engine no
+/// longer knows the order, or cannot be asked.
+async fn engine_status(
+    state: &AppState,
+    account: &Account,
+    cl_ord_id: sbe::Uuid,
+) -> Option<ExecutionReport> {
+    let buffer = create_status_buffer(account.id, cl_ord_id, now_ns().ok()?);
+
+    // Registered before sending, so the answer cannot arrive first.
+    let mut statuses = state.statuses.register(account.id, cl_ord_id);
+    // A failed offer is logged by `publish`.
+    publish(state, &[&buffer]).ok()?;
+
+    match timeout(state.ack_timeout, statuses.recv()).await {
+        Ok(Some(AckEvent::Report(report))) if report.exec_type == ExecTypeEnum::OrderStatus => {
+            // The engine answers with a rejected report for orders it does not know.
+            (report.ord_status != OrdStatusEnum::Rejected).then_some(report)
+        }
+        Ok(_) => None,
+        Err(_) => {
+            warn!("Order {cl_ord_id}: the engine did not answer the status request in time");
+            None
+        }
+    }
+}
+
+/// The account's order `id` with its fills, as recorded.
+async fn stored_order(
+    state: &AppState,
+    account: &Account,
+    id: sbe::Uuid,
+) -> Result<Option<Order>, AppError> {
+    let Some(record) = state.store.order(account.id, id).await? else {
+        return Ok(None);
+    };
+    let fills = state
+        .store
+        .fills(&FillQuery {
+            account: account.id,
+            order_id: Some(id),
+            limit: MAX_LIMIT,
+            ..FillQuery::default()
+        })
+        .await?;
+    // Fills are listed newest first, orders show them in the order they happened.
+    let fills = fills.items.iter().rev().map(Fill::from).collect();
+    Ok(Some(Order::from_record(&record, fills)))
+}
+
+fn order_status(order: &Order) -> StatusCode {
+    if order.status == "rejected" {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else {
+        StatusCode::CREATED
+    }
+}
+
+/// A retry must ask for the order its `client_oid` already names.
+fn check_same_order(requested: &Order, order: &Order) -> Result<(), AppError> {
+    let same = order.product_id == requested.product_id
+        && order.side == requested.side
+        && order.r#type == requested.r#type
+        && order.size == requested.size
+        && order.price == requested.price;
+    if !same {
+        return Err(AppError::ValidationError(format!(
+            "client_oid '{}' already names a different order.",
+            order.id
+        )));
+    }
+    Ok(())
+}
+
+/// Answers a retry with the answer the order got.
+fn replay(requested: &Order, (status, mut order): Outcome) -> Result<Outcome, AppError> {
+    check_same_order(requested, &order)?;
+    order.client_oid = Some(order.id.clone());
+    Ok((status, order))
+}
+
+pub async fn list_orders(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Query(params): Query<ListOrders>,
+) -> Result<Json<OrderPage>, AppError> {
+    let status = match params.status.as_deref().map(str::to_lowercase).as_deref() {
+        None => None,
+        Some("open") => Some(StatusFilter::Open),
+        Some("done") => Some(StatusFilter::Done),
+        Some(_) => {
+            return Err(AppError::ValidationError(
+                "Status must be 'open' or 'done'.".to_string(),
+            ));
+        }
+    };
+    let side = match params.side.as_deref().map(str::to_lowercase).as_deref() {
+        None => None,
+        Some("buy") => Some(SideEnum::Buy),
+        Some("sell") => Some(SideEnum::Sell),
+        Some(_) => return Err(AppError::InvalidSide),
+    };
+    let query = OrderQuery {
+        account: account.id,
+        product_id: parse_product_id(params.product_id.as_ref()),
+        status,
+        side,
+        start: parse_date("Start date", params.start_date.as_ref())?,
+        end: parse_date("End date", params.end_date.as_ref())?,
+        after: parse_cursor(params.cursor.as_ref())?,
+        limit: parse_limit(params.limit)?,
+    };
+
+    let page = state.store.orders(&query).await?;
+    Ok(Json(OrderPage {
+        orders: page
+            .items
+            .iter()
+            .map(|record| Order::from_record(record, Vec::new()))
+            .collect(),
+        has_next: page.next.is_some(),
+        cursor: page.next.map(|cursor| cursor.to_string()),
+    }))
+}
+
+pub async fn list_fills(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Query(params): Query<ListFills>,
+) -> Result<Json<FillPage>, AppError> {
+    let query = FillQuery {
+        account: account.id,
+        order_id: params.order_id.as_deref().map(parse_order_id).transpose()?,
+        product_id: parse_product_id(params.product_id.as_ref()),
+        start: parse_date("Start date", params.start_date.as_ref())?,
+        end: parse_date("End date", params.end_date.as_ref())?,
+        after: parse_cursor(params.cursor.as_ref())?,
+        limit: parse_limit(params.limit)?,
+    };
+
+    let page = state.store.fills(&query).await?;
+    Ok(Json(FillPage {
+        fills: page.items.iter().map(AccountFill::from).collect(),
+        has_next: page.next.is_some(),
+        cursor: page.next.map(|cursor| cursor.to_string()),
+    }))
+}
+
+/// Most orders, or cancels, one batch may hold.
+const MAX_BATCH_SIZE: usize = 20;
+
+#[derive(Deserialize, Debug)]
+pub struct CreateOrders {
+    pub orders: Vec<CreateOrder>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CancelOrders {
+    pub order_ids: Vec<String>,
+}
+
+/// An entry of a batch, answered as it would have been on its own.
+#[derive(Serialize, Debug)]
+pub struct OrderResult {
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<Order>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorResponse>,
+}
+
+impl From<Result<Outcome, AppError>> for OrderResult {
+    fn from(result: Result<Outcome, AppError>) -> Self {
+        match result {
+            Ok((status, order)) => OrderResult {
+                status: status.as_u16(),
+                order: Some(order),
+                error: None,
+            },
+            Err(err) => {
+                let (status, error) = err.into_parts();
+                OrderResult {
+                    status: status.as_u16(),
+                    order: None,
+                    error: Some(error),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct CancelResult {
+    pub status: u16,
+    pub order_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorResponse>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BatchResults<T> {
+    /// In the order of the request's entries.
+    pub results: Vec<T>,
+}
+
+/// An order checked and ready to be sent to the engine.
+struct NewOrder {
+    cl_ord_id: sbe::Uuid,
+    order: Order,
+    buffer: [u8; MESSAGE_SIZE],
+    submission: Option<Submission>,
+}
+
+/// What checking an order request came to.
+enum Prepared {
+    Send(NewOrder),
+    /// A retry, with the answer the order already got.
+    Answered(Outcome),
+}
+
+async fn prepare_order(
+    state: &AppState,
+    account: &Account,
+    payload: &CreateOrder,
+) -> Result<Prepared, AppError> {
     // TODO: VALIDATE FIX-PROTOCOL
-    // TODO: VALIDATE PRICE AND QUANTITY ARE IN-BOUNDS
     // TODO: VALIDATE USER FUNDS
 
-    // let account = b"AAAAAAAAAAAAAAAA"; // FOR NOW SINCE WE DON'T HAVE AUTH IMPLEMENTED WE WILL JUST USE A HARDCODED ID
+    let input = parse_and_validate_order_payload(account, &state.products, payload)?;
 
-    let parsed_input = parse_and_validate_order_payload(&payload)?;
+    let timestamp_ns = now_ns()?;
+    let cl_ord_id = sbe::Uuid::from_words(input.cl_ord_id);
+    let mut order = Order::from_buffer(
+        input.cl_ord_id,
+        input.symbol,
+        input.side,
+        input.ord_type,
+        timestamp_ns,
+        input.qty,
+        input.price,
+    );
 
-    let mut timestamp_ns = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map_err(|e| AppError::InternalServerError(format!("Failed to get system time: {}", e)))?
-        .as_nanos() as u64;
-
-    let order_buffer = create_order_buffer(
-        &parsed_input.cl_ord_id,
-        &parsed_input.account,
-        &parsed_input.symbol,
-        parsed_input.side,
-        parsed_input.ord_type,
+    // Orders with a client-supplied id are placed once however often the request is retried.
+    let mut submission = None;
+    if payload.client_oid.is_some() {
+        order.client_oid = Some(order.id.clone());
+        let claimed = loop {
+            match state.submissions.claim(account.id, cl_ord_id) {
+                Claim::New(claimed) => break claimed,
+                Claim::Taken(mut outcome) => {
+                    // A request that gave up without an answer frees the id to be claimed again.
+                    if let Ok(outcome) = outcome.wait_for(Option::is_some).await {
+                        let outcome = outcome.clone().expect("waited for an outcome");
+                        return replay(&order, outcome).map(Prepared::Answered);
+                    }
+                }
+            }
+        };
+        // Placed before this gateway last started, so only recorded.
+        match stored_order(state, account, cl_ord_id).await {
+            Ok(Some(stored)) => {
+                let outcome = (order_status(&stored), stored);
+                let answer = replay(&order, outcome.clone())?;
+                claimed.complete(&outcome);
+                return Ok(Prepared::Answered(answer));
+            }
+            Ok(None) => {}
+            Err(err) => warn!(
+                "Cannot check order {} for a resubmission: {:?}",
+                cl_ord_id, err
+            ),
+        }
+        submission = Some(claimed);
+    }
+
+    let buffer = create_order_buffer(
+        &input.cl_ord_id,
+        &input.account,
+        &input.symbol,
+        input.side,
+        input.ord_type,
         timestamp_ns,
-        parsed_input.qty_mantissa,
-        parsed_input.price_mantissa,
+        input.qty,
+        input.price,
     );
 
-    state.buffer.put_bytes(0, &order_buffer);
+    Ok(Prepared::Send(NewOrder {
+        cl_ord_id,
+        order,
+        buffer,
+        submission,
+    }))
+}
 
-    let result = state
-        .publication
-        .offer_part(state.buffer, 0, MESSAGE_SIZE as i32);
+/// Offers the frames back to back under one hold of the publication, so no other request's
+/// messages come between them. Stops at the first that cannot be sent, with how many were.
+fn publish(state: &AppState, frames: &[&[u8]]) -> Result<(), (usize, AppError)> {
+    let mut publication = state.publication.lock().map_err(|_| {
+        (
+            0,
+            AppError::InternalServerError("Publication lock poisoned".to_string()),
+        )
+    })?;
+    for (sent, frame) in frames.iter().enumerate() {
+        publication.offer(frame).map_err(|err| {
+            error!("Offer with error: {}", err);
+            (sent, AppError::EngineUnavailable(err.to_string()))
+        })?;
+    }
+    Ok(())
+}
 
-    match result {
-        Ok(_code) => {
-            timestamp_ns = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .map_err(|e| AppError::InternalServerError(format!("Failed to get system time: {}", e)))?
-                .as_nanos() as u64;
+/// Waits for the engine to accept or reject a sent order, then for the fills it gets right away.
+async fn await_order(
+    state: &AppState,
+    account: &Account,
+    new: NewOrder,
+    mut acks: AckReceiver,
+) -> Result<Outcome, AppError> {
+    let NewOrder {
+        cl_ord_id,
+        mut order,
+        submission,
+        ..
+    } = new;
+
+    let mut wait = state.ack_timeout;
+    loop {
+        match timeout(wait, acks.recv()).await {
+            Ok(Some(AckEvent::Report(report))) => {
+                order.apply(&report);
+                if order.is_final() {
+                    break;
+                }
+                wait = state.fill_window;
+            }
+            Ok(Some(AckEvent::Settled)) => break,
+            // Only the request that sent a cancel hears its reject.
+            Ok(Some(AckEvent::CancelReject(_))) => {}
+            Ok(None) => {
+                return Err(AppError::InternalServerError(
+                    "Execution report listener stopped".to_string(),
+                ));
+            }
+            Err(_) if order.status == "pending" => {
+                return Err(AppError::AckTimeout(order.id));
+            }
+            Err(_) => break,
         }
-        Err(err) => println!("Offer with error: {}", err),
     }
 
-    let order = Order::from_buffer(
-        parsed_input.cl_ord_id,
-        parsed_input.symbol,
-        parsed_input.side,
-        parsed_input.ord_type,
-        timestamp_ns,
-        parsed_input.qty_mantissa,
-        parsed_input.price_mantissa,
-    );
+    // The engine still has the order from a submission this gateway no longer remembers. Until
+    // the history has it, its state is not known here.
+    if order.reject_reason.as_deref() == Some("duplicate_order") && submission.is_some() {
+        let client_oid = order.client_oid.take();
+        let stored = stored_order(state, account, cl_ord_id)
+            .await?
+            .ok_or_else(|| AppError::OrderExists(order.id.clone()))?;
+        check_same_order(&order, &stored)?;
+        order = stored;
+        order.client_oid = client_oid;
+    }
+
+    let outcome = (order_status(&order), order);
+    if let Some(submission) = submission {
+        submission.complete(&outcome);
+    }
+    Ok(outcome)
+}
+
+pub async fn post_order(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Json(payload): Json<CreateOrder>,
+) -> Result<(StatusCode, Json<Order>), AppError> {
+    // TODO: VALIDATE BODY SHAPE (i believe axum already does this for us with 422 error... want to control error messages ourselves tho)
+    let new = match prepare_order(&state, &account, &payload).await? {
+        Prepared::Send(new) => new,
+        Prepared::Answered((status, order)) => return Ok((status, Json(order))),
+    };
+
+    // Registered before sending, so the answer cannot arrive first.
+    let acks = state.acks.register(account.id, new.cl_ord_id);
+    publish(&state, &[&new.buffer]).map_err(|(_, err)| err)?;
+
+    let (status, order) = await_order(&state, &account, new, acks).await?;
+    Ok((status, Json(order)))
+}
 
-    Ok((StatusCode::CREATED, Json(order)))
+/// Spends the budget of a batch's entries past the first, which the request itself paid for.
+fn charge_batch(state: &AppState, account: &Account, entries: usize) -> Result<(), AppError> {
+    if entries == 0 || entries > MAX_BATCH_SIZE {
+        return Err(AppError::ValidationError(format!(
+            "A batch holds 1 to {MAX_BATCH_SIZE} entries, not {entries}."
+        )));
+    }
+    state
+        .rate_limits
+        .spend_account(account.id, Class::Orders, entries - 1, Instant::now())
+        .map_err(AppError::RateLimited)
 }
+
+/// Places several orders at once. Each is checked on its own, the valid ones reach the engine
+/// back to back, and each gets the answer it would have gotten alone.
+pub async fn post_orders(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Json(payload): Json<CreateOrders>,
+) -> Result<Json<BatchResults<OrderResult>>, AppError> {
+    charge_batch(&state, &account, payload.orders.len())?;
+
+    let mut results: Vec<Option<Result<Outcome, AppError>>> = Vec::new();
+    let mut sending = Vec::new();
+    let mut client_oids = HashSet::new();
+    for (index, entry) in payload.orders.iter().enumerate() {
+        // A retry of an entry would wait on the entry itself, which is not sent yet.
+        if let Some(client_oid) = &entry.client_oid
+            && !client_oids.insert(client_oid.to_lowercase())
+        {
+            results.push(Some(Err(AppError::ValidationError(format!(
+                "client_oid '{client_oid}' appears more than once in the batch."
+            )))));
+            continue;
+        }
+        match prepare_order(&state, &account, entry).await {
+            Ok(Prepared::Send(new)) => {
+                let acks = state.acks.register(account.id, new.cl_ord_id);
+                sending.push((index, new, acks));
+                results.push(None);
+            }
+            Ok(Prepared::Answered(outcome)) => results.push(Some(Ok(outcome))),
+            Err(err) => results.push(Some(Err(err))),
+        }
+    }
+
+    let frames: Vec<&[u8]> = sending.iter().map(|(_, new, _)| &new.buffer[..]).collect();
+    let sent = match publish(&state, &frames) {
+        Ok(()) => sending.len(),
+        Err((sent, err)) => {
+            let (_, error) = err.into_parts();
+            let reason = error.details.unwrap_or(error.message);
+            for (index, _, _) in &sending[sent..] {
+                results[*index] = Some(Err(AppError::EngineUnavailable(reason.clone())));
+            }
+            sent
+        }
+    };
+
+    let waits: Vec<_> = sending
+        .into_iter()
+        .take(sent)
+        .map(|(index, new, acks)| {
+            let state = Arc::clone(&state);
+            let account = account.clone();
+            let wait = tokio::spawn(async move { await_order(&state, &account, new, acks).await });
+            (index, wait)
+        })
+        .collect();
+    for (index, wait) in waits {
+        results[index] = Some(wait.await.unwrap_or_else(|err| {
+            Err(AppError::InternalServerError(format!(
+                "Order wait failed: {err}"
+            )))
+        }));
+    }
+
+    Ok(Json(BatchResults {
+        results: results
+            .into_iter()
+            .map(|result| OrderResult::from(result.expect("every entry is answered")))
+            .collect(),
+    }))
+}
+
+/// A cancel checked and ready to be sent to the engine.
+struct NewCancel {
+    cl_ord_id: sbe::Uuid,
+    /// The cancel's own `ClOrdID`, which a reject answers to.
+    cancel_id: sbe::Uuid,
+    buffer: [u8; CANCEL_MESSAGE_SIZE],
+}
+
+/// The account's order `cl_ord_id` if it may still be resting: as the reports have told this
+/// gateway, or as the history has it for orders that rested before the gateway started.
+async fn open_order(
+    state: &AppState,
+    account: &Account,
+    cl_ord_id: sbe::Uuid,
+) -> Result<Option<OpenOrder>, AppError> {
+    if let Some(open_order) = state.open_orders.get(account.id, cl_ord_id) {
+        return Ok(Some(open_order));
+    }
+    let record = state.store.order(account.id, cl_ord_id).await?;
+    Ok(record
+        .filter(|record| record.is_open())
+        .map(|record| OpenOrder {
+            account: record.account.words(),
+            symbol: symbol_of(&record.product_id),
+            side: record.side,
+        }))
+}
+
+async fn prepare_cancel(
+    state: &AppState,
+    account: &Account,
+    order_id: &str,
+) -> Result<NewCancel, AppError> {
+    let cl_ord_id = parse_order_id(order_id)?;
+
+    let open_order = open_order(state, account, cl_ord_id)
+        .await?
+        .ok_or_else(|| AppError::OrderNotFound(cl_ord_id.to_string()))?;
+
+    let cancel_id = uuid_to_u64s(Uuid::new_v4());
+    Ok(NewCancel {
+        cl_ord_id,
+        cancel_id: sbe::Uuid::from_words(cancel_id),
+        buffer: create_cancel_buffer(&cancel_id, &cl_ord_id.words(), &open_order, now_ns()?),
+    })
+}
+
+/// Waits for the engine to cancel the order or reject the cancel.
+async fn await_cancel(
+    state: &AppState,
+    account: &Account,
+    cl_ord_id: sbe::Uuid,
+    mut acks: AckReceiver,
+) -> Result<String, AppError> {
+    let order_id = cl_ord_id.to_string();
+
+    // Fills may still arrive ahead of the engine's answer to the cancel; only the answer counts.
+    let deadline = tokio::time::Instant::now() + state.ack_timeout;
+    loop {
+        match tokio::time::timeout_at(deadline, acks.recv()).await {
+            Ok(Some(AckEvent::Report(report))) if report.exec_type == ExecTypeEnum::Canceled => {
+                return Ok(order_id);
+            }
+            Ok(Some(AckEvent::CancelReject(reject))) => {
+                // The order went without a report this gateway heard, or the history is behind.
+                if reject.cxl_rej_reason == CxlRejReasonEnum::UnknownOrder {
+                    state.open_orders.remove(account.id, cl_ord_id);
+                }
+                return Err(cancel_reject_error(order_id, reject.cxl_rej_reason));
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(AppError::InternalServerError(
+                    "Execution report listener stopped".to_string(),
+                ));
+            }
+            Err(_) => return Err(AppError::CancelTimeout(order_id)),
+        }
+    }
+}
+
+pub async fn delete_order(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Path(order_id): Path<String>,
+) -> Result<Json<String>, AppError> {
+    let cancel = prepare_cancel(&state, &account, &order_id).await?;
+
+    // Registered before sending, so the answer cannot arrive first.
+    let acks = state
+        .acks
+        .register_cancel(account.id, cancel.cl_ord_id, cancel.cancel_id);
+    publish(&state, &[&cancel.buffer]).map_err(|(_, err)| err)?;
+
+    await_cancel(&state, &account, cancel.cl_ord_id, acks)
+        .await
+        .map(Json)
+}
+
+/// Cancels several orders at once, sent to the engine back to back, each answered as it would
+/// have been alone.
+pub async fn delete_orders(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Json(payload): Json<CancelOrders>,
+) -> Result<Json<BatchResults<CancelResult>>, AppError> {
+    charge_batch(&state, &account, payload.order_ids.len())?;
+
+    let mut results: Vec<Option<Result<String, AppError>>> = Vec::new();
+    let mut sending = Vec::new();
+    let mut cl_ord_ids = HashSet::new();
+    for (index, order_id) in payload.order_ids.iter().enumerate() {
+        match prepare_cancel(&state, &account, order_id).await {
+            // Both would be answered by the one cancel the engine acts on.
+            Ok(cancel) if !cl_ord_ids.insert(cancel.cl_ord_id) => {
+                results.push(Some(Err(AppError::ValidationError(format!(
+                    "Order {} appears more than once in the batch.",
+                    cancel.cl_ord_id
+                )))));
+            }
+            Ok(cancel) => {
+                let (cl_ord_id, cancel_id) = (cancel.cl_ord_id, cancel.cancel_id);
+                let acks = state.acks.register_cancel(account.id, cl_ord_id, cancel_id);
+                sending.push((index, cancel, acks));
+                results.push(None);
+            }
+            Err(err) => results.push(Some(Err(err))),
+        }
+    }
+
+    let frames: Vec<&[u8]> = sending
+        .iter()
+        .map(|(_, cancel, _)| &cancel.buffer[..])
+        .collect();
+    let sent = match publish(&state, &frames) {
+        Ok(()) => sending.len(),
+        Err((sent, err)) => {
+            let (_, error) = err.into_parts();
+            let reason = error.details.unwrap_or(error.message);
+            for (index, _, _) in &sending[sent..] {
+                results[*index] = Some(Err(AppError::EngineUnavailable(reason.clone())));
+            }
+            sent
+        }
+    };
+
+    let waits: Vec<_> = sending
+        .into_iter()
+        .take(sent)
+        .map(|(index, cancel, acks)| {
+            let state = Arc::clone(&state);
+            let account = account.clone();
+            let wait = tokio::spawn(async move {
+                await_cancel(&state, &account, cancel.cl_ord_id, acks).await
+            });
+            (index, wait)
+        })
+        .collect();
+    for (index, wait) in waits {
+        results[index] = Some(wait.await.unwrap_or_else(|err| {
+            Err(AppError::InternalServerError(format!(
+                "Cancel wait failed: {err}"
+            )))
+        }));
+    }
+
+    Ok(Json(BatchResults {
+        results: results
+            .into_iter()
+            .zip(&payload.order_ids)
+            .map(
+                |(result, order_id)| match result.expect("every entry is answered") {
+                    Ok(order_id) => CancelResult {
+                        status: StatusCode::OK.as_u16(),
+                        order_id,
+                        error: None,
+                    },
+                    Err(err) => {
+                        let (status, error) = err.into_parts();
+                        CancelResult {
+                            status: status.as_u16(),
+                            order_id: order_id.clone(),
+                            error: Some(error),
+                        }
+                    }
+                },
+            )
+            .collect(),
+    }))
+}
+
+/// Keys are managed with the user's session only, so a leaked key cannot mint others.
+fn require_session(access: &Access) -> Result<(), AppError> {
+    match access {
+        Access::Session => Ok(()),
+        Access::Key { .. } => Err(AppError::SessionRequired),
+    }
+}
+
+pub async fn create_key(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Extension(access): Extension<Access>,
+    Json(payload): Json<CreateKey>,
+) -> Result<(StatusCode, Json<ApiKey>), AppError> {
+    require_session(&access)?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError(
+            "name must not be empty".to_string(),
+        ));
+    }
+    if payload.permissions.is_empty() {
+        return Err(AppError::ValidationError(
+            "permissions must name view, trade or both".to_string(),
+        ));
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let key = state
+        .api_keys
+        .create(&account, name, &payload.permissions, now)?;
+    Ok((StatusCode::CREATED, Json(key)))
+}
+
+pub async fn list_keys(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Extension(access): Extension<Access>,
+) -> Result<Json<Vec<ApiKey>>, AppError> {
+    require_session(&access)?;
+    Ok(Json(state.api_keys.list(&account)))
+}
+
+pub async fn list_products(State(state): State<Arc<AppState>>) -> Json<Vec<Product>> {
+    Json(state.products.all().cloned().collect())
+}
+
+pub async fn get_product(
+    State(state): State<Arc<AppState>>,
+    Path(product_id): Path<String>,
+) -> Result<Json<Product>, AppError> {
+    state
+        .products
+        .get(&product_id)
+        .cloned()
+        .map(Json)
+        .ok_or(AppError::ProductNotFound(product_id))
+}
+
+pub async fn revoke_key(
+    State(state): State<Arc<AppState>>,
+    Extension(account): Extension<Account>,
+    Extension(access): Extension<Access>,
+    Path(key_id): Path<String>,
+) -> Result<Json<String>, AppError> {
+    require_session(&access)?;
+    if state.api_keys.revoke(&account, &key_id)? {
+        Ok(Json(key_id))
+    } else {
+        Err(AppError::KeyNotFound(key_id))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,411 @@
+//! The order routes against a stand-in for the engine, which answers each request as the engine
+//! would, on the report path the gateway listens to.
+
+use super::*;
+use crate::acks::{self, Acks};
+use crate::order::OpenOrders;
+use crate::submissions::Submissions;
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use api::{ApiKeys, MemoryStore, OrderRecord, Products, RateLimitConfig, RateLimits, Verifier};
+
+use jsonwebtoken::jwk::JwkSet;
+
+use sbe::cxl_rej_response_to_enum::CxlRejResponseToEnum;
+use sbe::ord_rej_reason_enum::OrdRejReasonEnum;
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::{ExecutionReport, Message, OrderCancelReject, decode_message};
+
+use serde_json::json;
+
+use transport::{MessageSink, SendError};
+
+const ALICE: sbe::Uuid = sbe::Uuid::from_u128(0xA);
+const BOB: sbe::Uuid = sbe::Uuid::from_u128(0xB);
+const CLIENT_OID: &str = "7f1c2a9e-5b1d-4c3e-9a8f-0d6e4b2c1a00";
+const DEFINITIONS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../products.json");
+
+/// Rests every order it is sent, by account and `ClOrdID`, cancels them on request and tells
+/// whether one rests.
+struct Engine {
+    acks: Arc<Acks>,
+    open_orders: Arc<OpenOrders>,
+    statuses: Arc<Acks>,
+    resting: Arc<Mutex<HashSet<(sbe::Uuid, sbe::Uuid)>>>,
+}
+
+impl Engine {
+    fn report(&self, message: Message) {
+        deliver(&self.acks, &self.open_orders, &message);
+    }
+}
+
+/// Hands a message to the gateway as its report listener would.
+fn deliver(acks: &Acks, open_orders: &OpenOrders, message: &Message) {
+    let mut frame = vec![0; message.encoded_length()];
+    message.encode(&mut frame).unwrap();
+    acks::on_frame(&frame, acks, open_orders);
+}
+
+fn report(
+    account: sbe::Uuid,
+    cl_ord_id: sbe::Uuid,
+    exec_type: ExecTypeEnum,
+    ord_status: OrdStatusEnum,
+) -> ExecutionReport {
+    ExecutionReport {
+        account,
+        cl_ord_id,
+        trd_match_id: None,
+        order_id: 1,
+        exec_id: 1,
+        transact_time: 0,
+        price: None,
+        order_qty: Decimal64::ZERO,
+        last_qty: None,
+        last_px: None,
+        leaves_qty: Decimal64::ZERO,
+        cum_qty: Decimal64::ZERO,
+        avg_px: None,
+        symbol: *b"JSP\0\0\0",
+        exec_type,
+        ord_status,
+        ord_rej_reason: None,
+        side: SideEnum::Buy,
+        mass_status_req_id: None,
+        tot_num_reports: None,
+    }
+}
+
+impl MessageSink for Engine {
+    fn offer(&mut self, frame: &[u8]) -> Result<(), SendError> {
+        match decode_message(frame).unwrap() {
+            Message::NewOrderSingle(order) => {
+                let key = (order.account, order.cl_ord_id);
+                let mut report = report(
+                    order.account,
+                    order.cl_ord_id,
+                    ExecTypeEnum::New,
+                    OrdStatusEnum::New,
+                );
+                report.side = order.side;
+                report.order_qty = order.order_qty;
+                report.leaves_qty = order.order_qty;
+                report.price = order.price;
+                if !self.resting.lock().unwrap().insert(key) {
+                    report.exec_type = ExecTypeEnum::Rejected;
+                    report.ord_status = OrdStatusEnum::Rejected;
+                    report.ord_rej_reason = Some(OrdRejReasonEnum::DuplicateOrder);
+                }
+                self.report(Message::ExecutionReport(report));
+            }
+            Message::OrderCancelRequest(cancel) => {
+                let key = (cancel.account, cancel.orig_cl_ord_id);
+                if self.resting.lock().unwrap().remove(&key) {
+                    self.report(Message::ExecutionReport(report(
+                        cancel.account,
+                        cancel.orig_cl_ord_id,
+                        ExecTypeEnum::Canceled,
+                        OrdStatusEnum::Canceled,
+                    )));
+                } else {
+                    self.report(Message::OrderCancelReject(OrderCancelReject {
+                        cl_ord_id: cancel.cl_ord_id,
+                        orig_cl_ord_id: cancel.orig_cl_ord_id,
+                        order_id: None,
+                        ord_status: None,
+                        cxl_rej_response_to: CxlRejResponseToEnum::OrderCancelRequest,
+                        cxl_rej_reason: CxlRejReasonEnum::UnknownOrder,
+                        exec_id: Some(1),
+                    }));
+                }
+            }
+            Message::OrderStatusRequest(request) => {
+                let key = (request.account, request.cl_ord_id);
+                let mut status = report(
+                    request.account,
+                    request.cl_ord_id,
+                    ExecTypeEnum::OrderStatus,
+                    OrdStatusEnum::New,
+                );
+                status.exec_id = 0;
+                if !self.resting.lock().unwrap().contains(&key) {
+                    status.ord_status = OrdStatusEnum::Rejected;
+                    status.ord_rej_reason = Some(OrdRejReasonEnum::UnknownOrder);
+                }
+                let message = Message::ExecutionReport(status);
+                let mut frame = vec![0; message.encoded_length()];
+                message.encode(&mut frame).unwrap();
+                acks::on_response(&frame, &self.statuses);
+            }
+            message => panic!("the gateway sent {message:?}"),
+        }
+        Ok(())
+    }
+}
+
+/// A key set the routes never use; sessions are checked before a request reaches them.
+fn jwks() -> JwkSet {
+    serde_json::from_value(json!({
+        "keys": [{
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": "_J45tYe2eOPsT_1MRhJhtqud1Wzg-T7T6XRpITU6Zw4",
+        }]
+    }))
+    .unwrap()
+}
+
+fn state(
+    resting: &Arc<Mutex<HashSet<(sbe::Uuid, sbe::Uuid)>>>,
+    store: MemoryStore,
+) -> Arc<AppState> {
+    let acks = Arc::new(Acks::default());
+    let open_orders = Arc::new(OpenOrders::default());
+    let statuses = Arc::new(Acks::default());
+    let engine = Engine {
+        acks: Arc::clone(&acks),
+        open_orders: Arc::clone(&open_orders),
+        statuses: Arc::clone(&statuses),
+        resting: Arc::clone(resting),
+    };
+    Arc::new(AppState {
+        publication: Mutex::new(Box::new(engine)),
+        acks,
+        statuses,
+        store: Box::new(store),
+        verifier: Verifier::new(&jwks(), "", "").unwrap(),
+        api_keys: ApiKeys::in_memory(Duration::from_secs(30)),
+        products: Products::load(Path::new(DEFINITIONS)).unwrap(),
+        rate_limits: RateLimits::new(&RateLimitConfig::default()).unwrap(),
+        open_orders,
+        submissions: Arc::new(Submissions::new(Duration::from_secs(600))),
+        ack_timeout: Duration::from_millis(500),
+        fill_window: Duration::from_millis(5),
+    })
+}
+
+fn account(id: sbe::Uuid) -> Account {
+    Account {
+        user_id: id.to_string(),
+        id,
+    }
+}
+
+fn order(side: &str) -> CreateOrder {
+    limit("JSP", side, "2", "10.5")
+}
+
+fn limit(product_id: &str, side: &str, size: &str, price: &str) -> CreateOrder {
+    serde_json::from_value(json!({
+        "product_id": product_id,
+        "side": side,
+        "type": "limit",
+        "size": size,
+        "price": price,
+        "client_oid": CLIENT_OID,
+    }))
+    .unwrap()
+}
+
+async fn place(state: &Arc<AppState>, id: sbe::Uuid, side: &str) -> Result<Order, AppError> {
+    let (status, Json(order)) = post_order(
+        State(Arc::clone(state)),
+        Extension(account(id)),
+        Json(order(side)),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::CREATED);
+    Ok(order)
+}
+
+async fn cancel(state: &Arc<AppState>, id: sbe::Uuid) -> Result<String, AppError> {
+    delete_order(
+        State(Arc::clone(state)),
+        Extension(account(id)),
+        Path(CLIENT_OID.to_string()),
+    )
+    .await
+    .map(|Json(order_id)| order_id)
+}
+
+#[tokio::test]
+async fn keeps_the_orders_of_accounts_sharing_a_client_oid_apart() {
+    let state = state(&Arc::default(), MemoryStore::default());
+    let (alice, bob) = tokio::join!(place(&state, ALICE, "buy"), place(&state, BOB, "sell"));
+    let (alice, bob) = (alice.unwrap(), bob.unwrap());
+    assert_eq!(
+        (alice.status.as_str(), alice.side.as_str()),
+        ("open", "buy")
+    );
+    assert_eq!((bob.status.as_str(), bob.side.as_str()), ("open", "sell"));
+
+    let id: sbe::Uuid = CLIENT_OID.parse().unwrap();
+    assert_eq!(
+        state.open_orders.get(ALICE, id).unwrap().side,
+        SideEnum::Buy
+    );
+    assert_eq!(state.open_orders.get(BOB, id).unwrap().side, SideEnum::Sell);
+
+    assert_eq!(cancel(&state, ALICE).await.unwrap(), CLIENT_OID);
+    assert!(state.open_orders.get(ALICE, id).is_none());
+    assert!(state.open_orders.get(BOB, id).is_some());
+    assert!(matches!(
+        cancel(&state, ALICE).await,
+        Err(AppError::OrderNotFound(_))
+    ));
+
+    assert_eq!(cancel(&state, BOB).await.unwrap(), CLIENT_OID);
+    assert!(state.open_orders.get(BOB, id).is_none());
+}
+
+#[tokio::test]
+async fn does_not_guess_the_state_of_an_order_it_did_not_place() {
+    let id: sbe::Uuid = CLIENT_OID.parse().unwrap();
+    let resting = Arc::new(Mutex::new(HashSet::from([(ALICE, id)])));
+    let state = state(&resting, MemoryStore::default());
+    assert!(matches!(
+        place(&state, ALICE, "buy").await,
+        Err(AppError::OrderExists(_))
+    ));
+}
+
+#[tokio::test]
+async fn forgets_orders_once_they_are_done() {
+    let state = state(&Arc::default(), MemoryStore::default());
+    place(&state, ALICE, "buy").await.unwrap();
+    let id: sbe::Uuid = CLIENT_OID.parse().unwrap();
+    assert!(state.open_orders.get(ALICE, id).is_some());
+
+    let filled = report(ALICE, id, ExecTypeEnum::Trade, OrdStatusEnum::Filled);
+    deliver(
+        &state.acks,
+        &state.open_orders,
+        &Message::ExecutionReport(filled),
+    );
+    assert!(state.open_orders.get(ALICE, id).is_none());
+    assert!(matches!(
+        cancel(&state, ALICE).await,
+        Err(AppError::OrderNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn cancels_orders_placed_before_the_gateway_started() {
+    let id: sbe::Uuid = CLIENT_OID.parse().unwrap();
+    let resting = Arc::new(Mutex::new(HashSet::from([(ALICE, id)])));
+    let store = MemoryStore::default();
+    store.put_order(OrderRecord {
+        id,
+        account: ALICE,
+        product_id: "JSP".to_string(),
+        side: SideEnum::Buy,
+        price: Some("10.5".parse().unwrap()),
+        size: "2".parse().unwrap(),
+        filled_size: Decimal64::ZERO,
+        avg_px: None,
+        ord_status: OrdStatusEnum::New,
+        created_at: 1,
+        updated_at: 1,
+    });
+    let state = state(&resting, store);
+
+    assert!(state.open_orders.get(ALICE, id).is_none());
+    assert_eq!(cancel(&state, ALICE).await.unwrap(), CLIENT_OID);
+    assert!(resting.lock().unwrap().is_empty());
+    // Bob's history has no such order, whatever Alice's has.
+    assert!(matches!(
+        cancel(&state, BOB).await,
+        Err(AppError::OrderNotFound(_))
+    ));
+}
+
+async fn query(state: &Arc<AppState>, id: sbe::Uuid) -> Result<Order, AppError> {
+    get_order(
+        State(Arc::clone(state)),
+        Extension(account(id)),
+        Path(CLIENT_OID.to_string()),
+    )
+    .await
+    .map(|Json(order)| order)
+}
+
+#[tokio::test]
+async fn answers_order_queries_with_what_the_engine_and_the_history_know() {
+    let id: sbe::Uuid = CLIENT_OID.parse().unwrap();
+    let store = MemoryStore::default();
+    store.put_order(OrderRecord {
+        id,
+        account: BOB,
+        product_id: "JSP".to_string(),
+        side: SideEnum::Sell,
+        price: Some("10.5".parse().unwrap()),
+        size: "2".parse().unwrap(),
+        filled_size: "2".parse().unwrap(),
+        avg_px: Some("10.5".parse().unwrap()),
+        ord_status: OrdStatusEnum::Filled,
+        created_at: 1,
+        updated_at: 1,
+    });
+    let state = state(&Arc::default(), store);
+
+    // Resting, but not in the history yet.
+    place(&state, ALICE, "buy").await.unwrap();
+    let order = query(&state, ALICE).await.unwrap();
+    assert_eq!(
+        (
+            order.id.as_str(),
+            order.status.as_str(),
+            order.side.as_str()
+        ),
+        (CLIENT_OID, "open", "buy")
+    );
+    assert_eq!(order.product_id, "JSP");
+
+    // Done, so only the history has it.
+    let order = query(&state, BOB).await.unwrap();
+    assert_eq!(
+        (order.status.as_str(), order.done_reason.as_deref()),
+        ("done", Some("filled"))
+    );
+
+    assert_eq!(cancel(&state, ALICE).await.unwrap(), CLIENT_OID);
+    assert!(matches!(
+        query(&state, ALICE).await,
+        Err(AppError::OrderNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn rejects_orders_the_product_does_not_allow() {
+    let state = state(&Arc::default(), MemoryStore::default());
+    for (product_id, size, price) in [
+        ("XYZ", "2", "10.5"),
+        ("JSP", "2", "10.55"),
+        ("JSP", "2.5", "10.5"),
+        ("JSP", "10001", "10.5"),
+        ("DRG", "101", "10"),
+    ] {
+        let result = post_order(
+            State(Arc::clone(&state)),
+            Extension(account(ALICE)),
+            Json(limit(product_id, "buy", size, price)),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(AppError::ValidationError(_))),
+            "{size} {product_id} at {price}"
+        );
+    }
+    // Nothing reached the engine.
+    assert!(
+        state
+            .open_orders
+            .get(ALICE, CLIENT_OID.parse().unwrap())
+            .is_none()
+    );
+}
@@ -0,0 +1,278 @@
+//! Order and fill history, as the trade reporter writes it to the `orders` and `fills` tables.
+//! The query endpoints read it through an `OrderStore`: `QuestDbStore` in production, and a
+//! `MemoryStore` where there is no database, as in tests.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::side_enum::SideEnum;
+use sbe::{Decimal64, Uuid};
+
+/// An order as of its latest report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderRecord {
+    pub id: Uuid,
+    pub account: Uuid,
+    pub product_id: String,
+    pub side: SideEnum,
+    /// `None` for market orders.
+    pub price: Option<Decimal64>,
+    pub size: Decimal64,
+    pub filled_size: Decimal64,
+    pub avg_px: Option<Decimal64>,
+    pub ord_status: OrdStatusEnum,
+    /// Nanoseconds since the epoch.
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl OrderRecord {
+    pub const fn is_open(&self) -> bool {
+        matches!(
+            self.ord_status,
+            OrdStatusEnum::New | OrdStatusEnum::PartiallyFilled
+        )
+    }
+
+    pub const fn cursor(&self) -> Cursor {
+        Cursor {
+            created_at: self.created_at,
+            trade_id: 0,
+            id: self.id,
+        }
+    }
+}
+
+/// One side of a trade, net of any bust or correction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FillRecord {
+    pub trade_id: u64,
+    pub order_id: Uuid,
+    pub account: Uuid,
+    pub product_id: String,
+    pub side: SideEnum,
+    pub price: Decimal64,
+    pub size: Decimal64,
+    pub created_at: u64,
+}
+
+impl FillRecord {
+    pub const fn cursor(&self) -> Cursor {
+        Cursor {
+            created_at: self.created_at,
+            trade_id: self.trade_id,
+            id: self.order_id,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusFilter {
+    Open,
+    Done,
+}
+
+/// Lists the orders of one account.
+#[derive(Clone, Debug, Default)]
+pub struct OrderQuery {
+    pub account: Uuid,
+    pub product_id: Option<String>,
+    pub status: Option<StatusFilter>,
+    pub side: Option<SideEnum>,
+    /// Orders created at or after this time, in nanoseconds since the epoch.
+    pub start: Option<u64>,
+    /// Orders created before this time.
+    pub end: Option<u64>,
+    /// Continues a listing after the order the cursor points at.
+    pub after: Option<Cursor>,
+    pub limit: usize,
+}
+
+/// Lists the fills of one account.
+#[derive(Clone, Debug, Default)]
+pub struct FillQuery {
+    pub account: Uuid,
+    pub order_id: Option<Uuid>,
+    pub product_id: Option<String>,
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+    pub after: Option<Cursor>,
+    pub limit: usize,
+}
+
+/// A place in a listing. Listings run newest first, by creation time, then trade and order id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cursor {
+    pub created_at: u64,
+    /// Zero for orders.
+    pub trade_id: u64,
+    pub id: Uuid,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.created_at, self.trade_id, self.id)
+    }
+}
+
+/// The string is not a cursor returned by a listing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseCursorError;
+
+impl fmt::Display for ParseCursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid cursor")
+    }
+}
+
+impl std::error::Error for ParseCursorError {}
+
+impl FromStr for Cursor {
+    type Err = ParseCursorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '.');
+        let mut next = || parts.next().ok_or(ParseCursorError);
+        Ok(Self {
+            created_at: next()?.parse().map_err(|_| ParseCursorError)?,
+            trade_id: next()?.parse().map_err(|_| ParseCursorError)?,
+            id: next()?.parse().map_err(|_| ParseCursorError)?,
+        })
+    }
+}
+
+/// One page of a listing, and where the next one starts if there is more.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from up to `limit + 1` items in listing order; the extra one only tells that
+    /// there is another page.
+    pub fn from_overfetch(mut items: Vec<T>, limit: usize, cursor: impl Fn(&T) -> Cursor) -> Self {
+        let next = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(cursor)
+        } else {
+            None
+        };
+        Self { items, next }
+    }
+}
+
+#[derive(Debug)]
+pub struct StoreError(pub String);
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "order history unavailable: {}", self.0)
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+#[async_trait]
+pub trait OrderStore: Send + Sync {
+    /// The order `id` if it belongs to `account`.
+    async fn order(&self, account: Uuid, id: Uuid) -> Result<Option<OrderRecord>, StoreError>;
+
+    async fn orders(&self, query: &OrderQuery) -> Result<Page<OrderRecord>, StoreError>;
+
+    async fn fills(&self, query: &FillQuery) -> Result<Page<FillRecord>, StoreError>;
+}
+
+/// Order history held in memory.
+#[derive(Default)]
+pub struct MemoryStore {
+    /// By account and order id, as order ids are only unique within an account.
+    orders: Mutex<HashMap<(Uuid, Uuid), OrderRecord>>,
+    fills: Mutex<Vec<FillRecord>>,
+}
+
+impl MemoryStore {
+    /// Adds an order, or replaces it with a later state.
+    pub fn put_order(&self, order: OrderRecord) {
+        self.orders
+            .lock()
+            .unwrap()
+            .insert((order.account, order.id), order);
+    }
+
+    pub fn add_fill(&self, fill: FillRecord) {
+        self.fills.lock().unwrap().push(fill);
+    }
+}
+
+fn in_range(created_at: u64, start: Option<u64>, end: Option<u64>) -> bool {
+    start.is_none_or(|start| created_at >= start) && end.is_none_or(|end| created_at < end)
+}
+
+fn listing<T: Clone>(
+    items: impl Iterator<Item = T>,
+    after: Option<Cursor>,
+    limit: usize,
+    cursor: impl Fn(&T) -> Cursor,
+) -> Page<T> {
+    let mut items: Vec<T> = items
+        .filter(|item| after.is_none_or(|after| cursor(item) < after))
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(cursor(item)));
+    items.truncate(limit + 1);
+    Page::from_overfetch(items, limit, cursor)
+}
+
+#[async_trait]
+impl OrderStore for MemoryStore {
+    async fn order(&self, account: Uuid, id: Uuid) -> Result<Option<OrderRecord>, StoreError> {
+        Ok(self.orders.lock().unwrap().get(&(account, id)).cloned())
+    }
+
+    async fn orders(&self, query: &OrderQuery) -> Result<Page<OrderRecord>, StoreError> {
+        let orders = self.orders.lock().unwrap();
+        let matching = orders.values().filter(|order| {
+            order.account == query.account
+                && query
+                    .product_id
+                    .as_ref()
+                    .is_none_or(|product_id| &order.product_id == product_id)
+                && query.side.is_none_or(|side| order.side == side)
+                && query.status.is_none_or(|status| match status {
+                    StatusFilter::Open => order.is_open(),
+                    StatusFilter::Done => !order.is_open(),
+                })
+                && in_range(order.created_at, query.start, query.end)
+        });
+        Ok(listing(
+            matching.cloned(),
+            query.after,
+            query.limit,
+            OrderRecord::cursor,
+        ))
+    }
+
+    async fn fills(&self, query: &FillQuery) -> Result<Page<FillRecord>, StoreError> {
+        let fills = self.fills.lock().unwrap();
+        let matching = fills.iter().filter(|fill| {
+            fill.account == query.account
+                && query.order_id.is_none_or(|id| fill.order_id == id)
+                && query
+                    .product_id
+                    .as_ref()
+                    .is_none_or(|product_id| &fill.product_id == product_id)
+                && in_range(fill.created_at, query.start, query.end)
+        });
+        Ok(listing(
+            matching.cloned(),
+            query.after,
+            query.limit,
+            FillRecord::cursor,
+        ))
+    }
+}
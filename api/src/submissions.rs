@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use axum::http::StatusCode;
+
+use sbe::Uuid;
+
+use tokio::sync::watch;
+
+use crate::order::Order;
+
+/// The answer a submission got, which its retries get as well.
+pub type Outcome = (StatusCode, Order);
+
+struct Entry {
+    outcome: watch::Sender<Option<Outcome>>,
+    completed_at: Option<Instant>,
+}
+
+/// Orders submitted with a client-supplied id, by account and id, so a retry of a request gets
+/// the original answer instead of placing the order again. The engine only knows the ids of
+/// orders still resting, so it cannot tell a retry of a filled order from a new one.
+pub struct Submissions {
+    entries: Mutex<HashMap<(Uuid, Uuid), Entry>>,
+    /// How long answers are kept for retries.
+    retention: Duration,
+}
+
+/// What registering a client-supplied id found.
+pub enum Claim {
+    /// The id is new, and the request submits the order.
+    New(Submission),
+    /// Another request submitted the order; its answer, once it has one.
+    Taken(watch::Receiver<Option<Outcome>>),
+}
+
+/// A request's claim on an id; dropping it before `complete` frees the id for a retry.
+pub struct Submission {
+    submissions: Arc<Submissions>,
+    key: (Uuid, Uuid),
+    completed: bool,
+}
+
+impl Submission {
+    /// Records the answer and hands it to the retries waiting for it.
+    pub fn complete(mut self, outcome: &Outcome) {
+        let mut entries = self.submissions.lock();
+        if let Some(entry) = entries.get_mut(&self.key) {
+            entry.completed_at = Some(Instant::now());
+            entry.outcome.send_replace(Some(outcome.clone()));
+        }
+        self.completed = true;
+    }
+}
+
+impl Drop for Submission {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.submissions.lock().remove(&self.key);
+        }
+    }
+}
+
+impl Submissions {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            retention,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<(Uuid, Uuid), Entry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Claims `cl_ord_id` for `account`, unless a request already has.
+    pub fn claim(self: &Arc<Self>, account: Uuid, cl_ord_id: Uuid) -> Claim {
+        let mut entries = self.lock();
+        let retention = self.retention;
+        entries.retain(|_, entry| {
+            entry
+                .completed_at
+                .is_none_or(|completed_at| completed_at.elapsed() < retention)
+        });
+
+        let key = (account, cl_ord_id);
+        if let Some(entry) = entries.get(&key) {
+            return Claim::Taken(entry.outcome.subscribe());
+        }
+        entries.insert(
+            key,
+            Entry {
+                outcome: watch::Sender::new(None),
+                completed_at: None,
+            },
+        );
+        Claim::New(Submission {
+            submissions: Arc::clone(self),
+            key,
+            completed: false,
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use api::amount::{from_f64, parse, parse_json};
+
+use sbe::Decimal64;
+use sbe::decimal64::ParseDecimalError;
+
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+#[derive(Deserialize)]
+struct Amounts {
+    size: Box<RawValue>,
+    price: Option<Box<RawValue>>,
+}
+
+fn amounts(json: &str) -> Amounts {
+    serde_json::from_str(json).unwrap()
+}
+
+fn decimal(mantissa: i64) -> Decimal64 {
+    Decimal64::from_mantissa(mantissa)
+}
+
+#[test]
+fn reads_strings_and_numbers_exactly() {
+    let order = amounts(r#"{"size": "0.1", "price": 92233720368.54775807}"#);
+    assert_eq!(parse_json(&order.size), Ok(decimal(10_000_000)));
+    assert_eq!(
+        parse_json(order.price.as_deref().unwrap()),
+        Ok(Decimal64::MAX)
+    );
+
+    let order = amounts(r#"{"size": 0.30000000, "price": "12345678.12345678"}"#);
+    assert_eq!(parse_json(&order.size), Ok(decimal(30_000_000)));
+    assert_eq!(
+        parse_json(order.price.as_deref().unwrap()),
+        Ok(decimal(1_234_567_812_345_678))
+    );
+}
+
+#[test]
+fn reads_a_missing_or_null_price_as_none() {
+    assert!(amounts(r#"{"size": 1}"#).price.is_none());
+    assert!(amounts(r#"{"size": 1, "price": null}"#).price.is_none());
+}
+
+#[test]
+fn reads_exponent_notation() {
+    assert_eq!(parse("1e-8"), Ok(decimal(1)));
+    assert_eq!(parse("1.5E3"), Ok(decimal(150_000_000_000)));
+    assert_eq!(parse("-2.5e-1"), Ok(decimal(-25_000_000)));
+    assert_eq!(parse("12.5e+0"), Ok(decimal(1_250_000_000)));
+    assert_eq!(parse("0e-999"), Ok(Decimal64::ZERO));
+}
+
+#[test]
+fn rejects_excess_precision_overflow_and_garbage() {
+    assert_eq!(parse("0.123456789"), Err(ParseDecimalError::TooPrecise));
+    assert_eq!(parse("1e-9"), Err(ParseDecimalError::TooPrecise));
+    assert_eq!(
+        parse("92233720368.54775808"),
+        Err(ParseDecimalError::Overflow)
+    );
+    assert_eq!(parse("1e11"), Err(ParseDecimalError::Overflow));
+    assert_eq!(parse("1e999"), Err(ParseDecimalError::Overflow));
+    assert_eq!(parse("1.5e"), Err(ParseDecimalError::Invalid));
+    assert_eq!(parse("abc"), Err(ParseDecimalError::Invalid));
+
+    let order = amounts(r#"{"size": true, "price": "1.2.3"}"#);
+    assert_eq!(parse_json(&order.size), Err(ParseDecimalError::Invalid));
+    assert_eq!(
+        parse_json(order.price.as_deref().unwrap()),
+        Err(ParseDecimalError::Invalid)
+    );
+}
+
+#[test]
+fn brings_recorded_doubles_back_to_the_decimal_scale() {
+    assert_eq!(from_f64(0.1), decimal(10_000_000));
+    assert_eq!(from_f64(0.1 + 0.2), decimal(30_000_000));
+    assert_eq!(from_f64(2.0 / 3.0), decimal(66_666_667));
+}
@@ -0,0 +1,166 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use api::ratelimit::{Budget, Class, Tier};
+use api::{RateLimitConfig, RateLimits};
+
+use sbe::Uuid;
+
+const ACCOUNT: Uuid = Uuid::from_u128(0xA);
+const MAKER: Uuid = Uuid::from_u128(0xB);
+const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+fn tier(orders: u32, queries: u32) -> Tier {
+    Tier {
+        orders: Budget::new(orders, 1.0),
+        queries: Budget::new(queries, 1.0),
+    }
+}
+
+fn config() -> RateLimitConfig {
+    RateLimitConfig {
+        tiers: [("default", tier(2, 3)), ("maker", tier(5, 3))]
+            .into_iter()
+            .map(|(name, tier)| (name.to_owned(), tier))
+            .collect(),
+        accounts: [(MAKER.to_string(), "maker".to_owned())].into(),
+        ip: tier(4, 4),
+    }
+}
+
+fn passes(limits: &RateLimits, account: Uuid, class: Class, now: Instant) -> usize {
+    (0..100)
+        .take_while(|_| limits.check_account(account, class, now).is_ok())
+        .count()
+}
+
+#[test]
+fn allows_a_burst_then_refills() {
+    let limits = RateLimits::new(&config()).unwrap();
+    let start = Instant::now();
+    assert_eq!(passes(&limits, ACCOUNT, Class::Orders, start), 2);
+
+    let retry_after = limits
+        .check_account(ACCOUNT, Class::Orders, start)
+        .unwrap_err();
+    assert_eq!(retry_after, Duration::from_secs(1));
+
+    let later = start + Duration::from_millis(500);
+    let retry_after = limits
+        .check_account(ACCOUNT, Class::Orders, later)
+        .unwrap_err();
+    assert_eq!(retry_after, Duration::from_millis(500));
+
+    assert_eq!(
+        passes(
+            &limits,
+            ACCOUNT,
+            Class::Orders,
+            start + Duration::from_secs(1)
+        ),
+        1
+    );
+    assert_eq!(
+        passes(
+            &limits,
+            ACCOUNT,
+            Class::Orders,
+            start + Duration::from_secs(60)
+        ),
+        2
+    );
+}
+
+#[test]
+fn budgets_orders_and_queries_apart() {
+    let limits = RateLimits::new(&config()).unwrap();
+    let now = Instant::now();
+    assert_eq!(passes(&limits, ACCOUNT, Class::Orders, now), 2);
+    assert_eq!(passes(&limits, ACCOUNT, Class::Queries, now), 3);
+}
+
+#[test]
+fn gives_accounts_the_budgets_of_their_tier() {
+    let limits = RateLimits::new(&config()).unwrap();
+    let now = Instant::now();
+    assert_eq!(passes(&limits, MAKER, Class::Orders, now), 5);
+    assert_eq!(passes(&limits, ACCOUNT, Class::Orders, now), 2);
+}
+
+#[test]
+fn limits_each_ip_apart_from_its_accounts() {
+    let limits = RateLimits::new(&config()).unwrap();
+    let now = Instant::now();
+    for _ in 0..4 {
+        assert!(limits.check_ip(IP, Class::Orders, now).is_ok());
+    }
+    assert!(limits.check_ip(IP, Class::Orders, now).is_err());
+    assert!(limits.check_ip(IP, Class::Queries, now).is_ok());
+
+    let other = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+    assert!(limits.check_ip(other, Class::Orders, now).is_ok());
+    assert!(limits.check_account(ACCOUNT, Class::Orders, now).is_ok());
+}
+
+#[test]
+fn rejects_unusable_configurations() {
+    let mut no_default = config();
+    no_default.tiers.remove("default");
+    assert!(RateLimits::new(&no_default).is_err());
+
+    let mut unknown_tier = config();
+    unknown_tier
+        .accounts
+        .insert(ACCOUNT.to_string(), "gold".to_owned());
+    assert!(RateLimits::new(&unknown_tier).is_err());
+
+    let mut not_an_account = config();
+    not_an_account
+        .accounts
+        .insert("steve".to_owned(), "maker".to_owned());
+    assert!(RateLimits::new(&not_an_account).is_err());
+
+    let mut no_refill = config();
+    no_refill.ip.queries.per_second = 0.0;
+    assert!(RateLimits::new(&no_refill).is_err());
+
+    assert!(RateLimits::new(&RateLimitConfig::default()).is_ok());
+}
+
+#[test]
+fn charges_batches_per_entry() {
+    let limits = RateLimits::new(&config()).unwrap();
+    let now = Instant::now();
+    assert!(limits.spend_account(MAKER, Class::Orders, 4, now).is_ok());
+    assert_eq!(passes(&limits, MAKER, Class::Orders, now), 1);
+    assert_eq!(
+        limits.spend_account(MAKER, Class::Orders, 3, now),
+        Err(Duration::from_secs(3))
+    );
+
+    // A batch larger than the bucket needs it full, rather than never passing, and is charged
+    // every entry: the 45 the bucket lacked are refilled before anything else passes.
+    let later = now + Duration::from_secs(60);
+    assert!(
+        limits
+            .spend_account(MAKER, Class::Orders, 50, later)
+            .is_ok()
+    );
+    assert_eq!(
+        limits.check_account(MAKER, Class::Orders, later),
+        Err(Duration::from_secs(46))
+    );
+    assert_eq!(
+        limits.spend_account(MAKER, Class::Orders, 50, later + Duration::from_secs(45)),
+        Err(Duration::from_secs(5))
+    );
+    assert_eq!(
+        passes(
+            &limits,
+            MAKER,
+            Class::Orders,
+            later + Duration::from_secs(46)
+        ),
+        1
+    );
+}
@@ -0,0 +1,254 @@
+use api::{
+    Cursor, FillQuery, FillRecord, MemoryStore, OrderQuery, OrderRecord, OrderStore, StatusFilter,
+};
+
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::side_enum::SideEnum;
+use sbe::{Decimal64, Uuid};
+
+const ACCOUNT: Uuid = Uuid::from_u128(0xA);
+const OTHER_ACCOUNT: Uuid = Uuid::from_u128(0xB);
+
+fn amount(value: i64) -> Decimal64 {
+    Decimal64::from_mantissa(value * Decimal64::SCALE)
+}
+
+fn order(
+    id: u128,
+    product_id: &str,
+    side: SideEnum,
+    status: OrdStatusEnum,
+    at: u64,
+) -> OrderRecord {
+    OrderRecord {
+        id: Uuid::from_u128(id),
+        account: ACCOUNT,
+        product_id: product_id.to_owned(),
+        side,
+        price: Some(amount(10)),
+        size: amount(2),
+        filled_size: Decimal64::ZERO,
+        avg_px: None,
+        ord_status: status,
+        created_at: at,
+        updated_at: at,
+    }
+}
+
+fn fill(trade_id: u64, order_id: u128, product_id: &str, at: u64) -> FillRecord {
+    FillRecord {
+        trade_id,
+        order_id: Uuid::from_u128(order_id),
+        account: ACCOUNT,
+        product_id: product_id.to_owned(),
+        side: SideEnum::Buy,
+        price: amount(10),
+        size: Decimal64::ONE,
+        created_at: at,
+    }
+}
+
+fn ids(orders: &[OrderRecord]) -> Vec<u128> {
+    orders.iter().map(|order| order.id.as_u128()).collect()
+}
+
+fn store() -> MemoryStore {
+    let store = MemoryStore::default();
+    for (id, product_id, side, status) in [
+        (1, "HYPER", SideEnum::Buy, OrdStatusEnum::New),
+        (2, "HYPER", SideEnum::Sell, OrdStatusEnum::Filled),
+        (3, "ENCH", SideEnum::Buy, OrdStatusEnum::PartiallyFilled),
+        (4, "HYPER", SideEnum::Buy, OrdStatusEnum::Canceled),
+    ] {
+        let at = u64::try_from(id).unwrap() * 100;
+        store.put_order(order(id, product_id, side, status, at));
+    }
+    store
+}
+
+#[tokio::test]
+async fn looks_up_orders_by_id_with_their_latest_state() {
+    let store = store();
+    let mut filled = order(1, "HYPER", SideEnum::Buy, OrdStatusEnum::Filled, 100);
+    filled.filled_size = amount(2);
+    filled.updated_at = 150;
+    store.put_order(filled.clone());
+
+    let id = Uuid::from_u128(1);
+    assert_eq!(store.order(ACCOUNT, id).await.unwrap(), Some(filled));
+    assert_eq!(
+        store.order(ACCOUNT, Uuid::from_u128(9)).await.unwrap(),
+        None
+    );
+    assert_eq!(store.order(OTHER_ACCOUNT, id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn lists_orders_newest_first_with_filters() {
+    let store = store();
+    let all = OrderQuery {
+        account: ACCOUNT,
+        limit: 10,
+        ..OrderQuery::default()
+    };
+    let page = store.orders(&all).await.unwrap();
+    assert_eq!(ids(&page.items), vec![4, 3, 2, 1]);
+    assert_eq!(page.next, None);
+
+    let open = OrderQuery {
+        status: Some(StatusFilter::Open),
+        ..all.clone()
+    };
+    assert_eq!(ids(&store.orders(&open).await.unwrap().items), vec![3, 1]);
+
+    let done_hyper = OrderQuery {
+        product_id: Some("HYPER".to_owned()),
+        status: Some(StatusFilter::Done),
+        ..all.clone()
+    };
+    assert_eq!(
+        ids(&store.orders(&done_hyper).await.unwrap().items),
+        vec![4, 2]
+    );
+
+    let buys_in_range = OrderQuery {
+        side: Some(SideEnum::Buy),
+        start: Some(100),
+        end: Some(400),
+        ..all
+    };
+    assert_eq!(
+        ids(&store.orders(&buys_in_range).await.unwrap().items),
+        vec![3, 1]
+    );
+}
+
+#[tokio::test]
+async fn keeps_accounts_apart() {
+    let store = store();
+    let mut theirs = order(1, "HYPER", SideEnum::Buy, OrdStatusEnum::New, 500);
+    theirs.account = OTHER_ACCOUNT;
+    store.put_order(theirs);
+    let mut their_fill = fill(9, 1, "HYPER", 500);
+    their_fill.account = OTHER_ACCOUNT;
+    store.add_fill(their_fill);
+
+    let ours = OrderQuery {
+        account: ACCOUNT,
+        limit: 10,
+        ..OrderQuery::default()
+    };
+    assert_eq!(
+        ids(&store.orders(&ours).await.unwrap().items),
+        vec![4, 3, 2, 1]
+    );
+    let theirs = OrderQuery {
+        account: OTHER_ACCOUNT,
+        ..ours
+    };
+    assert_eq!(ids(&store.orders(&theirs).await.unwrap().items), vec![1]);
+
+    let our_fills = FillQuery {
+        account: ACCOUNT,
+        limit: 10,
+        ..FillQuery::default()
+    };
+    assert!(store.fills(&our_fills).await.unwrap().items.is_empty());
+}
+
+#[tokio::test]
+async fn pages_through_orders_with_cursors() {
+    let store = store();
+    let mut query = OrderQuery {
+        account: ACCOUNT,
+        limit: 3,
+        ..OrderQuery::default()
+    };
+
+    let first = store.orders(&query).await.unwrap();
+    assert_eq!(ids(&first.items), vec![4, 3, 2]);
+    let cursor = first.next.expect("a second page");
+
+    query.after = Some(cursor.to_string().parse().unwrap());
+    let second = store.orders(&query).await.unwrap();
+    assert_eq!(ids(&second.items), vec![1]);
+    assert_eq!(second.next, None);
+}
+
+#[tokio::test]
+async fn breaks_ties_in_creation_time_by_id() {
+    let store = MemoryStore::default();
+    for id in 1..=3 {
+        store.put_order(order(id, "HYPER", SideEnum::Buy, OrdStatusEnum::New, 100));
+    }
+    let mut query = OrderQuery {
+        account: ACCOUNT,
+        limit: 2,
+        ..OrderQuery::default()
+    };
+
+    let first = store.orders(&query).await.unwrap();
+    assert_eq!(ids(&first.items), vec![3, 2]);
+    query.after = first.next;
+    assert_eq!(ids(&store.orders(&query).await.unwrap().items), vec![1]);
+}
+
+#[tokio::test]
+async fn lists_fills_by_order_and_product() {
+    let store = MemoryStore::default();
+    store.add_fill(fill(1, 1, "HYPER", 100));
+    store.add_fill(fill(2, 1, "HYPER", 100));
+    store.add_fill(fill(3, 2, "ENCH", 200));
+
+    let trades = |page: Vec<FillRecord>| page.iter().map(|f| f.trade_id).collect::<Vec<_>>();
+    let of_order = FillQuery {
+        account: ACCOUNT,
+        order_id: Some(Uuid::from_u128(1)),
+        limit: 10,
+        ..FillQuery::default()
+    };
+    assert_eq!(
+        trades(store.fills(&of_order).await.unwrap().items),
+        vec![2, 1]
+    );
+
+    let of_product = FillQuery {
+        account: ACCOUNT,
+        product_id: Some("ENCH".to_owned()),
+        limit: 10,
+        ..FillQuery::default()
+    };
+    assert_eq!(
+        trades(store.fills(&of_product).await.unwrap().items),
+        vec![3]
+    );
+
+    let paged = FillQuery {
+        account: ACCOUNT,
+        limit: 1,
+        ..FillQuery::default()
+    };
+    let first = store.fills(&paged).await.unwrap();
+    assert_eq!(trades(first.items), vec![3]);
+    let second = store
+        .fills(&FillQuery {
+            after: first.next,
+            ..paged
+        })
+        .await
+        .unwrap();
+    assert_eq!(trades(second.items), vec![2]);
+}
+
+#[test]
+fn cursors_round_trip_and_reject_garbage() {
+    let cursor = Cursor {
+        created_at: 1_700_000_000_000_000_000,
+        trade_id: 42,
+        id: Uuid::from_u128(7),
+    };
+    assert_eq!(cursor.to_string().parse::<Cursor>(), Ok(cursor));
+    assert!("".parse::<Cursor>().is_err());
+    assert!("1.2".parse::<Cursor>().is_err());
+    assert!("1.2.not-a-uuid".parse::<Cursor>().is_err());
+}
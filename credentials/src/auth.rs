@@ -0,0 +1,158 @@
+//! Who a request is from. The auth service signs a JWT for each session with keys it publishes
+//! as a JWKS at `/api/auth/jwks`; the gateway verifies tokens offline against a copy of that set
+//! and trades on the SkyBlock account the token's user verified, its `minecraftId`.
+
+use crate::keys::Permission;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+
+use sbe::Uuid;
+
+use serde::Deserialize;
+
+/// The account a request acts for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    /// The auth service's user id.
+    pub user_id: String,
+    /// The SkyBlock account orders are placed for, the user's Minecraft UUID.
+    pub id: Uuid,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuthError {
+    /// The request carries no credentials.
+    Missing,
+    /// The credentials are malformed, expired or not signed by the auth service.
+    Invalid(String),
+    /// The user has not verified a Minecraft account, so has nothing to trade on.
+    Unverified,
+    /// The API key lacks the permission the request needs.
+    NotPermitted(Permission),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("no credentials given"),
+            Self::Invalid(reason) => write!(f, "invalid credentials: {reason}"),
+            Self::Unverified => f.write_str("no verified Minecraft account"),
+            Self::NotPermitted(permission) => {
+                write!(f, "the API key lacks the {permission} permission")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(rename = "minecraftId")]
+    minecraft_id: Option<String>,
+}
+
+struct Key {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+/// Verifies the auth service's session tokens.
+pub struct Verifier {
+    /// By key id; a set with a single key also verifies tokens without one.
+    keys: HashMap<String, Key>,
+}
+
+impl Verifier {
+    /// Trusts the keys of `jwks` for tokens issued by `issuer` for `audience`; empty ones are
+    /// not checked.
+    pub fn new(jwks: &JwkSet, issuer: &str, audience: &str) -> Result<Self, String> {
+        let mut keys = HashMap::new();
+        for jwk in &jwks.keys {
+            let algorithm = match (&jwk.algorithm, jwk.common.key_algorithm) {
+                (_, Some(algorithm)) => algorithm
+                    .to_string()
+                    .parse::<Algorithm>()
+                    .map_err(|err| format!("unsupported key algorithm {algorithm}: {err}"))?,
+                (AlgorithmParameters::OctetKeyPair(_), None) => Algorithm::EdDSA,
+                (AlgorithmParameters::EllipticCurve(_), None) => Algorithm::ES256,
+                (AlgorithmParameters::RSA(_), None) => Algorithm::RS256,
+                (AlgorithmParameters::OctetKey(_), None) => {
+                    return Err("symmetric keys are not accepted".to_owned());
+                }
+            };
+            let key = DecodingKey::from_jwk(jwk).map_err(|err| format!("invalid key: {err}"))?;
+
+            let mut validation = Validation::new(algorithm);
+            if issuer.is_empty() {
+                validation.iss = None;
+            } else {
+                validation.set_issuer(&[issuer]);
+            }
+            if audience.is_empty() {
+                validation.validate_aud = false;
+            } else {
+                validation.set_audience(&[audience]);
+            }
+            validation.set_required_spec_claims(&["exp", "sub"]);
+
+            let kid = jwk.common.key_id.clone().unwrap_or_default();
+            keys.insert(kid, Key { key, validation });
+        }
+        if keys.is_empty() {
+            return Err("the key set has no keys".to_owned());
+        }
+        Ok(Self { keys })
+    }
+
+    /// Reads the key set from a JWKS file.
+    pub fn from_file(path: &Path, issuer: &str, audience: &str) -> Result<Self, String> {
+        let jwks = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        let jwks: JwkSet = serde_json::from_str(&jwks)
+            .map_err(|err| format!("{} is not a JWKS: {err}", path.display()))?;
+        Self::new(&jwks, issuer, audience)
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Account, AuthError> {
+        let header = decode_header(token).map_err(|err| AuthError::Invalid(err.to_string()))?;
+        let key = match header.kid {
+            Some(kid) => self.keys.get(&kid),
+            None if self.keys.len() == 1 => self.keys.values().next(),
+            None => None,
+        }
+        .ok_or_else(|| AuthError::Invalid("signed with an unknown key".to_owned()))?;
+
+        let claims = decode::<Claims>(token, &key.key, &key.validation)
+            .map_err(|err| AuthError::Invalid(err.to_string()))?
+            .claims;
+        let id = claims
+            .minecraft_id
+            .as_deref()
+            .filter(|id| !id.is_empty())
+            .ok_or(AuthError::Unverified)?
+            .parse()
+            .map_err(|_| AuthError::Invalid("malformed minecraftId".to_owned()))?;
+        Ok(Account {
+            user_id: claims.sub,
+            id,
+        })
+    }
+
+    /// Verifies the bearer token of an `Authorization` header value.
+    pub fn verify_bearer(&self, authorization: Option<&str>) -> Result<Account, AuthError> {
+        let authorization = authorization.ok_or(AuthError::Missing)?;
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .or_else(|| authorization.strip_prefix("bearer "))
+            .ok_or_else(|| AuthError::Invalid("expected a bearer token".to_owned()))?;
+        self.verify(token.trim())
+    }
+}
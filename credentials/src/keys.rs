@@ -0,0 +1,358 @@
+//! API keys for programs trading on an account. A key is created with a user's session and has
+//! a secret only its creator sees, once. Each request made with it is signed: its
+//! `SB-ACCESS-SIGN` header is the base64 HMAC-SHA256, keyed with the decoded secret, of the
+//! `SB-ACCESS-TIMESTAMP` (Unix seconds), the method, the path with its query and the body,
+//! concatenated. A signature is only accepted within `window` of its timestamp, and only once.
+
+use crate::auth::{Account, AuthError};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use hmac::{Hmac, KeyInit, Mac};
+
+use sbe::Uuid;
+
+use serde::{Deserialize, Serialize};
+
+use sha2::Sha256;
+
+pub const ACCESS_KEY_HEADER: &str = "sb-access-key";
+pub const ACCESS_SIGN_HEADER: &str = "sb-access-sign";
+pub const ACCESS_TIMESTAMP_HEADER: &str = "sb-access-timestamp";
+
+/// Keys an account may hold at once.
+pub const MAX_KEYS_PER_ACCOUNT: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// Reading orders and fills.
+    View,
+    /// Placing and canceling orders.
+    Trade,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::View => "view",
+            Self::Trade => "trade",
+        })
+    }
+}
+
+/// How a request was authenticated, and so what it may do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// A user's session, which may do anything, including managing keys.
+    Session,
+    Key {
+        id: String,
+        permissions: Vec<Permission>,
+    },
+}
+
+impl Access {
+    pub fn allows(&self, permission: Permission) -> bool {
+        match self {
+            Self::Session => true,
+            Self::Key { permissions, .. } => permissions.contains(&permission),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub user_id: String,
+    pub account: Uuid,
+    pub permissions: Vec<Permission>,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Base64, and only serialized until redacted, so it is shown once, when the key is
+    /// created.
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    secret: String,
+}
+
+impl ApiKey {
+    /// Empty once redacted.
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// The key without its secret, to be listed.
+    pub fn redacted(&self) -> Self {
+        Self {
+            secret: String::new(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KeyError {
+    /// The account already holds `MAX_KEYS_PER_ACCOUNT` keys.
+    TooManyKeys,
+    /// The keys could not be saved.
+    Storage(String),
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyKeys => write!(f, "an account holds at most {MAX_KEYS_PER_ACCOUNT} keys"),
+            Self::Storage(reason) => write!(f, "cannot save API keys: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// What a signed request says about itself.
+pub struct SignedRequest<'a> {
+    pub key: &'a str,
+    pub timestamp: &'a str,
+    pub signature: &'a str,
+    pub method: &'a str,
+    /// Path and query, as sent.
+    pub path: &'a str,
+    pub body: &'a [u8],
+}
+
+/// Signs a request with a key's secret, as clients do.
+pub fn sign(secret: &str, timestamp: &str, method: &str, path: &str, body: &[u8]) -> String {
+    let mac = mac(secret, timestamp, method, path, body).expect("secret is base64");
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+fn mac(
+    secret: &str,
+    timestamp: &str,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> Option<Hmac<Sha256>> {
+    let secret = BASE64.decode(secret).ok()?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&secret).ok()?;
+    mac.update(timestamp.as_bytes());
+    mac.update(method.as_bytes());
+    mac.update(path.as_bytes());
+    mac.update(body);
+    Some(mac)
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], KeyError> {
+    let mut bytes = [0; N];
+    getrandom::fill(&mut bytes).map_err(|err| KeyError::Storage(err.to_string()))?;
+    Ok(bytes)
+}
+
+/// The keys of every account, saved to a JSON file if given one.
+pub struct ApiKeys {
+    path: Option<PathBuf>,
+    window: Duration,
+    keys: Mutex<HashMap<String, ApiKey>>,
+    /// Signatures accepted within the window, with their timestamps, so none is used twice.
+    seen: Mutex<HashMap<String, u64>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn read_keys(path: &Path) -> Result<HashMap<String, ApiKey>, String> {
+    let keys: Vec<ApiKey> = match fs::read_to_string(path) {
+        Ok(saved) => serde_json::from_str(&saved)
+            .map_err(|err| format!("{} is not a key file: {err}", path.display()))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(format!("cannot read {}: {err}", path.display())),
+    };
+    Ok(keys.into_iter().map(|key| (key.id.clone(), key)).collect())
+}
+
+impl ApiKeys {
+    pub fn in_memory(window: Duration) -> Self {
+        Self {
+            path: None,
+            window,
+            keys: Mutex::new(HashMap::new()),
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Loads the keys saved at `path`, which need not exist yet.
+    pub fn open(path: &Path, window: Duration) -> Result<Self, String> {
+        Ok(Self {
+            path: Some(path.to_owned()),
+            keys: Mutex::new(read_keys(path)?),
+            ..Self::in_memory(window)
+        })
+    }
+
+    /// Reads the saved keys again, for a service that checks the keys another one manages.
+    /// Signatures already used stay used.
+    pub fn reload(&self) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            *lock(&self.keys) = read_keys(path)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the keys to a new file that then replaces the old one, so a crash leaves either.
+    /// The file holds the secrets, so only its owner may read it.
+    fn save(&self, keys: &HashMap<String, ApiKey>) -> Result<(), KeyError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut saved: Vec<&ApiKey> = keys.values().collect();
+        saved.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        let json = serde_json::to_string_pretty(&saved)
+            .map_err(|err| KeyError::Storage(err.to_string()))?;
+        let partial = path.with_extension("partial");
+        // The mode only applies to a new file, not to one left behind by a crash.
+        let _ = fs::remove_file(&partial);
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&partial)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .and_then(|()| fs::rename(&partial, path))
+            .map_err(|err| KeyError::Storage(format!("{}: {err}", path.display())))
+    }
+
+    /// Creates a key for `account` and returns it with its secret.
+    pub fn create(
+        &self,
+        account: &Account,
+        name: &str,
+        permissions: &[Permission],
+        now: u64,
+    ) -> Result<ApiKey, KeyError> {
+        let mut keys = lock(&self.keys);
+        let held = keys
+            .values()
+            .filter(|key| key.user_id == account.user_id)
+            .count();
+        if held >= MAX_KEYS_PER_ACCOUNT {
+            return Err(KeyError::TooManyKeys);
+        }
+
+        let mut permissions = permissions.to_vec();
+        permissions.sort();
+        permissions.dedup();
+        let key = ApiKey {
+            id: Uuid::from_u128(u128::from_be_bytes(random_bytes()?)).to_string(),
+            name: name.to_owned(),
+            user_id: account.user_id.clone(),
+            account: account.id,
+            permissions,
+            created_at: now,
+            secret: BASE64.encode(random_bytes::<32>()?),
+        };
+        keys.insert(key.id.clone(), key.clone());
+        if let Err(err) = self.save(&keys) {
+            keys.remove(&key.id);
+            return Err(err);
+        }
+        Ok(key)
+    }
+
+    /// The user's keys, without their secrets, oldest first.
+    pub fn list(&self, account: &Account) -> Vec<ApiKey> {
+        let mut keys: Vec<ApiKey> = lock(&self.keys)
+            .values()
+            .filter(|key| key.user_id == account.user_id)
+            .map(ApiKey::redacted)
+            .collect();
+        keys.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        keys
+    }
+
+    /// Revokes the user's key `id`; false if they have no such key.
+    pub fn revoke(&self, account: &Account, id: &str) -> Result<bool, KeyError> {
+        let mut keys = lock(&self.keys);
+        if keys
+            .get(id)
+            .is_none_or(|key| key.user_id != account.user_id)
+        {
+            return Ok(false);
+        }
+        let revoked = keys.remove(id);
+        if let Err(err) = self.save(&keys) {
+            keys.extend(revoked.map(|key| (key.id.clone(), key)));
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    /// Checks a request's signature and returns the account it acts for and what it may do.
+    /// `now` is in Unix seconds.
+    pub fn verify(
+        &self,
+        request: &SignedRequest,
+        now: u64,
+    ) -> Result<(Account, Access), AuthError> {
+        let invalid = |reason: &str| AuthError::Invalid(reason.to_owned());
+
+        let timestamp: u64 = request
+            .timestamp
+            .parse()
+            .map_err(|_| invalid("the timestamp is not in Unix seconds"))?;
+        if timestamp.abs_diff(now) > self.window.as_secs() {
+            return Err(invalid("the timestamp is outside the signature window"));
+        }
+
+        let key = lock(&self.keys)
+            .get(request.key)
+            .cloned()
+            .ok_or_else(|| invalid("unknown API key"))?;
+        let signature = BASE64
+            .decode(request.signature)
+            .map_err(|_| invalid("the signature is not base64"))?;
+        mac(
+            &key.secret,
+            request.timestamp,
+            request.method,
+            request.path,
+            request.body,
+        )
+        .ok_or_else(|| invalid("the key's secret is corrupt"))?
+        .verify_slice(&signature)
+        .map_err(|_| invalid("the signature does not match"))?;
+
+        let mut seen = lock(&self.seen);
+        let window = self.window.as_secs();
+        seen.retain(|_, &mut seen_at| seen_at.abs_diff(now) <= window);
+        let replay_key = format!("{}:{}", key.id, request.signature);
+        if seen.insert(replay_key, timestamp).is_some() {
+            return Err(invalid("the signature was already used"));
+        }
+
+        Ok((
+            Account {
+                user_id: key.user_id,
+                id: key.account,
+            },
+            Access::Key {
+                id: key.id,
+                permissions: key.permissions,
+            },
+        ))
+    }
+}
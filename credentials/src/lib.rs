@@ -0,0 +1,9 @@
+//! Who a request acts for, shared by the services clients sign in to: `auth::Verifier` checks
+//! the session tokens of the auth service and `keys::ApiKeys` the requests signed with an API
+//! key, both granting an `Account`.
+
+pub mod auth;
+pub mod keys;
+
+pub use auth::{Account, AuthError, Verifier};
+pub use keys::{Access, ApiKey, ApiKeys, KeyError, Permission, SignedRequest};
@@ -0,0 +1,228 @@
+use std::fs::{self, Permissions};
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+
+use credentials::keys::{MAX_KEYS_PER_ACCOUNT, sign};
+use credentials::{
+    Access, Account, ApiKey, ApiKeys, AuthError, KeyError, Permission, SignedRequest,
+};
+
+use sbe::Uuid;
+
+const WINDOW: Duration = Duration::from_secs(30);
+const NOW: u64 = 1_700_000_000;
+const BODY: &[u8] = br#"{"product_id":"HYPER","side":"buy","type":"limit","size":1,"price":10}"#;
+
+fn account(user_id: &str, id: u128) -> Account {
+    Account {
+        user_id: user_id.to_owned(),
+        id: Uuid::from_u128(id),
+    }
+}
+
+fn signed<'a>(key: &'a ApiKey, timestamp: &'a str, signature: &'a str) -> SignedRequest<'a> {
+    SignedRequest {
+        key: &key.id,
+        timestamp,
+        signature,
+        method: "POST",
+        path: "/api/v1/orders",
+        body: BODY,
+    }
+}
+
+fn signature(key: &ApiKey, timestamp: &str) -> String {
+    sign(key.secret(), timestamp, "POST", "/api/v1/orders", BODY)
+}
+
+fn is_invalid<T>(result: Result<T, AuthError>) -> bool {
+    matches!(result, Err(AuthError::Invalid(_)))
+}
+
+#[test]
+fn verifies_requests_signed_with_a_key() {
+    let keys = ApiKeys::in_memory(WINDOW);
+    let owner = account("user-1", 0xA);
+    let key = keys
+        .create(&owner, "bot", &[Permission::Trade, Permission::View], NOW)
+        .unwrap();
+    assert!(!key.secret().is_empty());
+
+    let timestamp = NOW.to_string();
+    let signature = signature(&key, &timestamp);
+    let (account, access) = keys
+        .verify(&signed(&key, &timestamp, &signature), NOW + 5)
+        .unwrap();
+    assert_eq!(account, owner);
+    assert_eq!(
+        access,
+        Access::Key {
+            id: key.id.clone(),
+            permissions: vec![Permission::View, Permission::Trade],
+        }
+    );
+}
+
+#[test]
+fn rejects_bad_signatures_stale_timestamps_and_replays() {
+    let keys = ApiKeys::in_memory(WINDOW);
+    let key = keys
+        .create(&account("user-1", 0xA), "bot", &[Permission::Trade], NOW)
+        .unwrap();
+    let timestamp = NOW.to_string();
+    let signature = signature(&key, &timestamp);
+
+    let mut tampered = signed(&key, &timestamp, &signature);
+    tampered.body = b"{}";
+    assert!(is_invalid(keys.verify(&tampered, NOW)));
+
+    let other_time = (NOW + 1).to_string();
+    assert!(is_invalid(
+        keys.verify(&signed(&key, &other_time, &signature), NOW)
+    ));
+
+    let stale = (NOW - 31).to_string();
+    let stale_signature = sign(key.secret(), &stale, "POST", "/api/v1/orders", BODY);
+    assert!(is_invalid(
+        keys.verify(&signed(&key, &stale, &stale_signature), NOW)
+    ));
+
+    let request = signed(&key, &timestamp, &signature);
+    assert!(keys.verify(&request, NOW).is_ok());
+    assert!(is_invalid(keys.verify(&request, NOW + 1)));
+
+    let mut unknown = signed(&key, &timestamp, &signature);
+    unknown.key = "no-such-key";
+    assert!(is_invalid(keys.verify(&unknown, NOW)));
+}
+
+#[test]
+fn grants_only_a_key_s_permissions() {
+    let viewer = Access::Key {
+        id: "key".to_owned(),
+        permissions: vec![Permission::View],
+    };
+    assert!(viewer.allows(Permission::View));
+    assert!(!viewer.allows(Permission::Trade));
+    assert!(Access::Session.allows(Permission::Trade));
+}
+
+#[test]
+fn lists_and_revokes_only_the_user_s_keys() {
+    let keys = ApiKeys::in_memory(WINDOW);
+    let ours = account("user-1", 0xA);
+    let theirs = account("user-2", 0xB);
+    let key = keys.create(&ours, "bot", &[Permission::View], NOW).unwrap();
+    keys.create(&theirs, "other", &[Permission::View], NOW)
+        .unwrap();
+
+    let listed = keys.list(&ours);
+    assert_eq!(listed, vec![key.redacted()]);
+    assert!(listed[0].secret().is_empty());
+
+    assert_eq!(keys.revoke(&theirs, &key.id), Ok(false));
+    assert_eq!(keys.revoke(&ours, &key.id), Ok(true));
+    assert!(keys.list(&ours).is_empty());
+    assert_eq!(keys.list(&theirs).len(), 1);
+
+    let timestamp = NOW.to_string();
+    let signature = signature(&key, &timestamp);
+    assert!(is_invalid(
+        keys.verify(&signed(&key, &timestamp, &signature), NOW)
+    ));
+}
+
+#[test]
+fn limits_the_keys_a_user_holds() {
+    let keys = ApiKeys::in_memory(WINDOW);
+    let owner = account("user-1", 0xA);
+    for n in 0..MAX_KEYS_PER_ACCOUNT {
+        keys.create(&owner, &format!("bot-{n}"), &[Permission::View], NOW)
+            .unwrap();
+    }
+    assert_eq!(
+        keys.create(&owner, "one-more", &[Permission::View], NOW),
+        Err(KeyError::TooManyKeys)
+    );
+    assert!(
+        keys.create(&account("user-2", 0xB), "bot", &[Permission::View], NOW)
+            .is_ok()
+    );
+}
+
+#[test]
+fn keeps_keys_across_restarts() {
+    let dir = std::env::temp_dir().join(format!("credentials-{}-keys", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("api-keys.json");
+
+    let owner = account("user-1", 0xA);
+    let key = {
+        let keys = ApiKeys::open(&path, WINDOW).unwrap();
+        keys.create(&owner, "bot", &[Permission::Trade], NOW)
+            .unwrap()
+    };
+
+    let keys = ApiKeys::open(&path, WINDOW).unwrap();
+    assert_eq!(keys.list(&owner), vec![key.redacted()]);
+    let timestamp = NOW.to_string();
+    let signature = signature(&key, &timestamp);
+    assert!(
+        keys.verify(&signed(&key, &timestamp, &signature), NOW)
+            .is_ok()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn saves_keys_readable_only_by_their_owner() {
+    let dir = std::env::temp_dir().join(format!("credentials-{}-mode", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("api-keys.json");
+    // Left behind by a crash, readable by everyone.
+    fs::write(path.with_extension("partial"), "[]").unwrap();
+    fs::set_permissions(
+        path.with_extension("partial"),
+        Permissions::from_mode(0o644),
+    )
+    .unwrap();
+
+    let keys = ApiKeys::open(&path, WINDOW).unwrap();
+    keys.create(&account("user-1", 0xA), "bot", &[Permission::View], NOW)
+        .unwrap();
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reloads_keys_saved_by_another_instance() {
+    let dir = std::env::temp_dir().join(format!("credentials-{}-reload", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("api-keys.json");
+
+    let gateway = ApiKeys::open(&path, WINDOW).unwrap();
+    let reader = ApiKeys::open(&path, WINDOW).unwrap();
+    let owner = account("user-1", 0xA);
+    let key = gateway
+        .create(&owner, "bot", &[Permission::View], NOW)
+        .unwrap();
+
+    let timestamp = NOW.to_string();
+    let signature = signature(&key, &timestamp);
+    let request = signed(&key, &timestamp, &signature);
+    assert!(is_invalid(reader.verify(&request, NOW)));
+    reader.reload().unwrap();
+    assert!(reader.verify(&request, NOW).is_ok());
+
+    assert!(gateway.revoke(&owner, &key.id).unwrap());
+    reader.reload().unwrap();
+    assert!(reader.list(&owner).is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use settings::Settings;
+
+use std::time::Duration;
+
+use transport::{ChannelConfig, TransportConfig};
+
+/// The drop copy service's settings. None of them have defaults except the Aeron directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub aeron: TransportConfig,
+    /// Execution reports from the engine; `SUB_CHANNEL`/`SUB_STREAM_ID`.
+    #[serde(rename = "sub")]
+    pub reports: ChannelConfig,
+    /// Published drop copies; `PUB_CHANNEL`/`PUB_STREAM_ID`.
+    #[serde(rename = "pub")]
+    pub drop_copies: ChannelConfig,
+    /// Resend requests; `RESEND_CHANNEL`/`RESEND_STREAM_ID`.
+    pub resend: ChannelConfig,
+    pub ws_addr: String,
+    /// Drop copies kept per account for resends.
+    pub retain_per_account: usize,
+    /// Copy of the auth service's `/api/auth/jwks`, the keys session tokens are checked with.
+    pub jwks_path: String,
+    /// `iss` session tokens must carry; empty to accept any.
+    pub jwt_issuer: String,
+    /// `aud` session tokens must carry; empty to accept any.
+    pub jwt_audience: String,
+    /// The gateway's API key file.
+    pub api_keys_path: String,
+    /// How often the API key file is read again, to pick up keys the gateway created or revoked.
+    pub api_keys_reload_secs: u64,
+    /// How far a signed handshake's timestamp may be from the service's clock.
+    pub signature_window_secs: u64,
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        self.aeron.validate()?;
+        self.reports.validate("sub")?;
+        self.drop_copies.validate("pub")?;
+        self.resend.validate("resend")?;
+        settings::socket_addr("ws_addr", &self.ws_addr)?;
+        if self.retain_per_account == 0 {
+            return Err("retain_per_account must be greater than zero".to_owned());
+        }
+        settings::required("jwks_path", &self.jwks_path)?;
+        settings::required("api_keys_path", &self.api_keys_path)?;
+        if self.api_keys_reload_secs == 0 {
+            return Err("api_keys_reload_secs must be greater than zero".to_owned());
+        }
+        if self.signature_window_secs == 0 {
+            return Err("signature_window_secs must be greater than zero".to_owned());
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    pub const fn signature_window(&self) -> Duration {
+        Duration::from_secs(self.signature_window_secs)
+    }
+
+    pub const fn api_keys_reload(&self) -> Duration {
+        Duration::from_secs(self.api_keys_reload_secs)
+    }
+}
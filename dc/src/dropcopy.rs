@@ -0,0 +1,210 @@
+use crate::publisher::DropCopyPublisher;
+use crate::sequencer::{self, SequencedReport, Sequencer};
+
+use std::sync::{Arc, Mutex};
+
+use sbe::{DropCopyResendRequest, ExecutionReport};
+
+use tokio::sync::broadcast;
+
+use tracing::{error, info};
+
+use transport::MessageSink;
+
+/// Sequences every execution report per account and fans it out to the Aeron drop copy stream and
+/// to WebSocket subscribers.
+pub struct DropCopy<S: MessageSink> {
+    sequencer: Arc<Mutex<Sequencer>>,
+    updates: broadcast::Sender<Arc<SequencedReport>>,
+    publisher: DropCopyPublisher<S>,
+}
+
+impl<S: MessageSink> DropCopy<S> {
+    pub fn new(
+        sequencer: Arc<Mutex<Sequencer>>,
+        updates: broadcast::Sender<Arc<SequencedReport>>,
+        publisher: DropCopyPublisher<S>,
+    ) -> Self {
+        Self {
+            sequencer,
+            updates,
+            publisher,
+        }
+    }
+
+    /// `frame` is the SBE encoded `report`, kept to republish it unchanged.
+    pub fn on_execution_report(&mut self, report: ExecutionReport, frame: &[u8]) {
+        // Sequencing and broadcasting under the same lock lets a WebSocket subscriber replay from
+        // the log and then join the live feed without missing or reordering reports.
+        let sequenced = {
+            let mut sequencer = sequencer::lock(&self.sequencer);
+            let sequenced = sequencer.sequence(report, frame);
+            let _ = self.updates.send(Arc::clone(&sequenced)); // No WebSocket subscribers is fine
+            sequenced
+        };
+
+        self.publisher.publish(&sequenced);
+    }
+
+    pub fn on_resend_request(&mut self, request: DropCopyResendRequest) {
+        let account = request.account;
+        let begin = request.begin_seq_no;
+        let end = request.end_seq_no;
+
+        let reports = sequencer::lock(&self.sequencer).replay(&account, begin, end);
+
+        match reports.first() {
+            Some(first) if first.seq_num > begin => {
+                error!(
+                    begin,
+                    first_available = first.seq_num,
+                    "Resend request reaches past retained drop copies, resending from the oldest retained"
+                );
+            }
+            None => {
+                info!(begin, end, "Resend request matched no retained drop copies");
+            }
+            _ => {}
+        }
+
+        for report in &reports {
+            self.publisher.publish(report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer::tests::report;
+
+    use sbe::{DropCopyHeader, Message, Uuid, decode_message};
+
+    use transport::{Loopback, LoopbackSink, LoopbackSource, MessageSource};
+
+    const SESSION: u64 = 7;
+
+    type Updates = broadcast::Receiver<Arc<SequencedReport>>;
+
+    fn drop_copy(retain_per_account: usize) -> (DropCopy<LoopbackSink>, LoopbackSource, Updates) {
+        let drop_copies = Loopback::new();
+        let source = drop_copies.source();
+        let sequencer = Arc::new(Mutex::new(Sequencer::new(SESSION, retain_per_account)));
+        let (updates, subscriber) = broadcast::channel(16);
+        let drop_copy = DropCopy::new(
+            sequencer,
+            updates,
+            DropCopyPublisher::new(drop_copies.sink()),
+        );
+        (drop_copy, source, subscriber)
+    }
+
+    /// Sequences a report with ExecID `exec_id` for `account`.
+    fn execute(drop_copy: &mut DropCopy<LoopbackSink>, account: u128, exec_id: u64) {
+        let report = report(account, exec_id);
+        let mut frame = vec![0; ExecutionReport::ENCODED_LENGTH];
+        report.encode(&mut frame).unwrap();
+        drop_copy.on_execution_report(report, &frame);
+    }
+
+    /// The published drop copies as their headers and the ExecIDs of their reports.
+    fn drain(source: &mut LoopbackSource) -> Vec<(DropCopyHeader, u64)> {
+        let mut published = Vec::new();
+        source.poll(
+            &mut |frame| {
+                let Ok(Message::DropCopyHeader(header)) = decode_message(frame) else {
+                    panic!("published no DropCopyHeader");
+                };
+                match decode_message(&frame[DropCopyHeader::ENCODED_LENGTH..]) {
+                    Ok(Message::ExecutionReport(report)) => {
+                        assert_eq!(report.account, header.account);
+                        published.push((header, report.exec_id));
+                    }
+                    message => panic!("published {message:?}"),
+                }
+            },
+            usize::MAX,
+        );
+        published
+    }
+
+    fn header(account: u128, msg_seq_num: u64) -> DropCopyHeader {
+        DropCopyHeader {
+            account: Uuid::from_u128(account),
+            msg_seq_num,
+            session_id: Some(SESSION),
+        }
+    }
+
+    #[test]
+    fn publishes_every_report_in_sequence() {
+        let (mut drop_copy, mut source, mut updates) = drop_copy(8);
+        execute(&mut drop_copy, 0xA, 1);
+        execute(&mut drop_copy, 0xB, 2);
+        execute(&mut drop_copy, 0xA, 3);
+
+        assert_eq!(
+            drain(&mut source),
+            [
+                (header(0xA, 1), 1),
+                (header(0xB, 1), 2),
+                (header(0xA, 2), 3)
+            ]
+        );
+        let broadcast: Vec<_> = std::iter::from_fn(|| updates.try_recv().ok())
+            .map(|update| (update.report.account, update.seq_num, update.report.exec_id))
+            .collect();
+        assert_eq!(
+            broadcast,
+            [
+                (Uuid::from_u128(0xA), 1, 1),
+                (Uuid::from_u128(0xB), 1, 2),
+                (Uuid::from_u128(0xA), 2, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn resends_the_requested_range_unchanged() {
+        let (mut drop_copy, mut source, _updates) = drop_copy(8);
+        for exec_id in 1..=4 {
+            execute(&mut drop_copy, 0xA, exec_id);
+        }
+        execute(&mut drop_copy, 0xB, 5);
+        drain(&mut source);
+
+        let resend = |begin_seq_no, end_seq_no| DropCopyResendRequest {
+            account: Uuid::from_u128(0xA),
+            begin_seq_no,
+            end_seq_no,
+        };
+        drop_copy.on_resend_request(resend(2, 3));
+        assert_eq!(
+            drain(&mut source),
+            [(header(0xA, 2), 2), (header(0xA, 3), 3)]
+        );
+        drop_copy.on_resend_request(resend(4, 0));
+        assert_eq!(drain(&mut source), [(header(0xA, 4), 4)]);
+        drop_copy.on_resend_request(resend(5, 0));
+        assert!(drain(&mut source).is_empty());
+    }
+
+    #[test]
+    fn resends_from_the_oldest_retained_report() {
+        let (mut drop_copy, mut source, _updates) = drop_copy(2);
+        for exec_id in 1..=4 {
+            execute(&mut drop_copy, 0xA, exec_id);
+        }
+        drain(&mut source);
+
+        drop_copy.on_resend_request(DropCopyResendRequest {
+            account: Uuid::from_u128(0xA),
+            begin_seq_no: 1,
+            end_seq_no: 0,
+        });
+        assert_eq!(
+            drain(&mut source),
+            [(header(0xA, 3), 3), (header(0xA, 4), 4)]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+mod auth;
+mod config;
+mod dropcopy;
+mod messages;
+mod publisher;
+mod sequencer;
+mod websocket;
+
+use auth::Authenticator;
+use config::Config;
+use dropcopy::DropCopy;
+use publisher::DropCopyPublisher;
+use sequencer::Sequencer;
+
+use std::cell::RefCell;
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use aeron_rs::concurrent::strategies::{BusySpinIdleStrategy, Strategy};
+
+use credentials::{ApiKeys, Verifier};
+
+use sbe::{Message, decode_message};
+
+use tokio::sync::broadcast;
+
+use tracing::subscriber::set_global_default;
+use tracing::{Level, error, info, warn};
+use tracing_subscriber::FmtSubscriber;
+
+use transport::{AeronTransport, MessageSource, TransportError};
+
+const WEBSOCKET_BUFFER: usize = 4096;
+
+/// Returns the decoded message, or `None` if the frame does not hold a valid message.
+fn read_frame(frame: &[u8]) -> Option<Message> {
+    match decode_message(frame) {
+        Ok(message) => Some(message),
+        Err(err) => {
+            warn!(error = ?err, length = frame.len(), "Rejecting malformed message");
+            None
+        }
+    }
+}
+
+fn aeron_setup_failed(err: TransportError) -> ! {
+    error!("Aeron: {}", err);
+    process::exit(1);
+}
+
+fn auth_setup_failed(err: String) -> ! {
+    error!("Auth: {}", err);
+    process::exit(1);
+}
+
+#[tokio::main]
+async fn main() {
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::INFO)
+        .finish();
+
+    set_global_default(subscriber).unwrap_or_else(|err| {
+        error!(target: "setup", kind="tracing_init_failed", error=?err, "Failed to create Tracing subscriber");
+        process::exit(1);
+    });
+
+    let config: Config = settings::load("drop-copy");
+
+    let mut transport =
+        AeronTransport::connect(&config.aeron).unwrap_or_else(|err| aeron_setup_failed(err));
+
+    let mut reports_subscription = transport
+        .add_subscription(&config.reports)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    info!("Aeron: Execution report subscription on {}", config.reports);
+
+    let mut resend_subscription = transport
+        .add_subscription(&config.resend)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    let publication = transport
+        .add_publication(&config.drop_copies)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+
+    let verifier = Verifier::from_file(
+        config.jwks_path.as_ref(),
+        &config.jwt_issuer,
+        &config.jwt_audience,
+    )
+    .unwrap_or_else(|err| auth_setup_failed(err));
+    let api_keys = ApiKeys::open(config.api_keys_path.as_ref(), config.signature_window())
+        .unwrap_or_else(|err| auth_setup_failed(err));
+    let authenticator = Arc::new(Authenticator::new(verifier, api_keys));
+    tokio::spawn(auth::reload_keys(
+        Arc::clone(&authenticator),
+        config.api_keys_reload(),
+    ));
+
+    // Sequence numbers start over with every run, which subscribers tell by the session ID.
+    #[allow(clippy::cast_possible_truncation)]
+    let session_id = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64);
+    info!("Drop copy session {}", session_id);
+
+    let sequencer = Arc::new(Mutex::new(Sequencer::new(
+        session_id,
+        config.retain_per_account,
+    )));
+    let (updates, _) = broadcast::channel(WEBSOCKET_BUFFER);
+
+    tokio::spawn(websocket::serve(
+        config.ws_addr.clone(),
+        authenticator,
+        Arc::clone(&sequencer),
+        updates.clone(),
+    ));
+
+    let drop_copy = RefCell::new(DropCopy::new(
+        sequencer,
+        updates,
+        DropCopyPublisher::new(publication),
+    ));
+
+    let mut report_handler = |frame: &[u8]| {
+        // Cancel rejects and other messages are not drop copied
+        if let Some(Message::ExecutionReport(report)) = read_frame(frame) {
+            drop_copy.borrow_mut().on_execution_report(report, frame);
+        }
+    };
+
+    let mut resend_handler = |frame: &[u8]| match read_frame(frame) {
+        Some(Message::DropCopyResendRequest(request)) => {
+            drop_copy.borrow_mut().on_resend_request(request);
+        }
+        Some(message) => {
+            warn!(
+                template_id = message.template_id(),
+                "Unexpected resend stream message"
+            );
+        }
+        None => {}
+    };
+
+    let poll_idle_strategy = BusySpinIdleStrategy::default();
+
+    info!("Starting Aeron polling loop...");
+
+    loop {
+        let fragments_read = reports_subscription.poll(&mut report_handler, 10)
+            + resend_subscription.poll(&mut resend_handler, 10);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        poll_idle_strategy.idle_opt(fragments_read as i32);
+    }
+}
@@ -0,0 +1,70 @@
+use sbe::{Decimal64, ExecutionReport};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+/// JSON form of a drop copy sent to WebSocket clients. `Decimal64` fields serialize as strings.
+#[derive(Serialize, Debug)]
+pub struct DropCopyUpdate {
+    channel: &'static str,
+    account: String,
+    /// Sequence numbers start over at 1 when this changes.
+    session_id: u64,
+    sequence_num: u64,
+    exec_id: u64,
+    order_id: u64,
+    cl_ord_id: String,
+    trd_match_id: Option<u64>,
+    exec_type: String,
+    ord_status: String,
+    ord_rej_reason: Option<String>,
+    side: String,
+    product_id: String,
+    price: Option<Decimal64>,
+    order_qty: Decimal64,
+    last_qty: Option<Decimal64>,
+    last_px: Option<Decimal64>,
+    leaves_qty: Decimal64,
+    cum_qty: Decimal64,
+    avg_px: Option<Decimal64>,
+    transact_time: String,
+}
+
+impl DropCopyUpdate {
+    pub fn new(session_id: u64, sequence_num: u64, report: &ExecutionReport) -> Self {
+        Self {
+            channel: "drop_copy",
+            account: report.account.to_string(),
+            session_id,
+            sequence_num,
+            exec_id: report.exec_id,
+            order_id: report.order_id,
+            cl_ord_id: report.cl_ord_id.to_string(),
+            trd_match_id: report.trd_match_id,
+            exec_type: report.exec_type.to_string(),
+            ord_status: report.ord_status.to_string(),
+            ord_rej_reason: report.ord_rej_reason.map(|reason| reason.to_string()),
+            side: report.side.to_string(),
+            product_id: format_symbol(&report.symbol),
+            price: report.price,
+            order_qty: report.order_qty,
+            last_qty: report.last_qty,
+            last_px: report.last_px,
+            leaves_qty: report.leaves_qty,
+            cum_qty: report.cum_qty,
+            avg_px: report.avg_px,
+            transact_time: format_nanosecond_timestamp(report.transact_time),
+        }
+    }
+}
+
+fn format_nanosecond_timestamp(timestamp: u64) -> String {
+    let datetime: DateTime<Utc> = DateTime::from_timestamp_nanos(timestamp as i64);
+    datetime.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn format_symbol(symbol_bytes: &[u8; 6]) -> String {
+    String::from_utf8_lossy(symbol_bytes)
+        .trim_end_matches(['\0', ' '])
+        .to_string()
+}
@@ -0,0 +1,65 @@
+use crate::sequencer::SequencedReport;
+
+use aeron_rs::concurrent::strategies::{BusySpinIdleStrategy, Strategy};
+
+use sbe::DropCopyHeader;
+
+use tracing::warn;
+
+use transport::{MessageSink, SendError};
+
+const HEADER_SIZE: usize = DropCopyHeader::ENCODED_LENGTH;
+
+/// Publishes drop copies as a `DropCopyHeader` message immediately followed by the original
+/// `ExecutionReport` message in the same frame.
+pub struct DropCopyPublisher<S: MessageSink> {
+    sink: S,
+    offer_idle_strategy: BusySpinIdleStrategy,
+}
+
+impl<S: MessageSink> DropCopyPublisher<S> {
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            offer_idle_strategy: BusySpinIdleStrategy::default(),
+        }
+    }
+
+    pub fn publish(&mut self, report: &SequencedReport) {
+        let length = HEADER_SIZE + report.frame.len();
+        let mut write = |frame: &mut [u8]| {
+            let (header, body) = frame.split_at_mut(HEADER_SIZE);
+            encode_header(header, report);
+            body.copy_from_slice(&report.frame);
+        };
+
+        self.offer_idle_strategy.reset();
+
+        loop {
+            match self.sink.try_publish(length, &mut write) {
+                Ok(()) => return,
+                Err(SendError::BackPressured | SendError::AdminAction) => {
+                    self.offer_idle_strategy.idle();
+                }
+                Err(SendError::NotConnected) => {
+                    // Nobody is listening; the report stays retained for resend requests.
+                    return;
+                }
+                Err(err) => {
+                    warn!(seq_num = report.seq_num, error = %err, "Failed to publish drop copy");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn encode_header(buffer: &mut [u8], report: &SequencedReport) {
+    DropCopyHeader {
+        account: report.report.account,
+        msg_seq_num: report.seq_num,
+        session_id: Some(report.session_id),
+    }
+    .encode(buffer)
+    .expect("header buffer is sized for a DropCopyHeader");
+}
@@ -0,0 +1,210 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use sbe::{ExecutionReport, Uuid};
+
+/// An execution report with its per-account drop copy sequence number. `frame` holds the original
+/// SBE encoded ExecutionReport, including its message header.
+#[derive(Debug)]
+pub struct SequencedReport {
+    pub session_id: u64,
+    pub seq_num: u64,
+    pub report: ExecutionReport,
+    pub frame: Vec<u8>,
+}
+
+struct AccountLog {
+    last_seq_num: u64,
+    retained: VecDeque<Arc<SequencedReport>>,
+}
+
+/// Assigns gap-free sequence numbers per account, starting at 1, and retains the most recent
+/// reports of every account so that subscribers can recover from gaps.
+///
+/// Sequence numbers are only kept in memory and start over with every `session_id`, which
+/// subscribers compare to tell a restart from a gap.
+pub struct Sequencer {
+    session_id: u64,
+    accounts: HashMap<Uuid, AccountLog>,
+    retain_per_account: usize,
+}
+
+impl Sequencer {
+    pub fn new(session_id: u64, retain_per_account: usize) -> Self {
+        Self {
+            session_id,
+            accounts: HashMap::new(),
+            retain_per_account,
+        }
+    }
+
+    pub fn sequence(&mut self, report: ExecutionReport, frame: &[u8]) -> Arc<SequencedReport> {
+        let log = self
+            .accounts
+            .entry(report.account)
+            .or_insert_with(|| AccountLog {
+                last_seq_num: 0,
+                retained: VecDeque::new(),
+            });
+
+        log.last_seq_num += 1;
+        let sequenced = Arc::new(SequencedReport {
+            session_id: self.session_id,
+            seq_num: log.last_seq_num,
+            report,
+            frame: frame.to_vec(),
+        });
+
+        if log.retained.len() == self.retain_per_account {
+            log.retained.pop_front();
+        }
+        log.retained.push_back(Arc::clone(&sequenced));
+
+        sequenced
+    }
+
+    pub fn last_seq_num(&self, account: &Uuid) -> u64 {
+        self.accounts.get(account).map_or(0, |log| log.last_seq_num)
+    }
+
+    /// Returns the retained reports of `account` with `begin <= seq_num <= end`, where an `end` of
+    /// 0 means up to the latest. Reports that have already been evicted are not returned, so the
+    /// first report may start after `begin`.
+    pub fn replay(&self, account: &Uuid, begin: u64, end: u64) -> Vec<Arc<SequencedReport>> {
+        let Some(log) = self.accounts.get(account) else {
+            return Vec::new();
+        };
+        let end = if end == 0 { u64::MAX } else { end };
+        let first_retained = log.retained.front().map_or(0, |report| report.seq_num);
+        let skip = usize::try_from(begin.saturating_sub(first_retained)).unwrap_or(usize::MAX);
+
+        log.retained
+            .iter()
+            .skip(skip)
+            .take_while(|report| report.seq_num <= end)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Locks the sequencer shared by the Aeron loop and the WebSocket connections. `Sequencer` never
+/// panics part way through an update, so a panic elsewhere while the lock was held leaves it
+/// consistent and poisoning is ignored.
+pub fn lock(sequencer: &Mutex<Sequencer>) -> MutexGuard<'_, Sequencer> {
+    sequencer
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    use sbe::Decimal64;
+    use sbe::exec_type_enum::ExecTypeEnum;
+    use sbe::ord_status_enum::OrdStatusEnum;
+    use sbe::side_enum::SideEnum;
+
+    const SESSION: u64 = 42;
+
+    pub fn report(account: u128, exec_id: u64) -> ExecutionReport {
+        ExecutionReport {
+            account: Uuid::from_u128(account),
+            cl_ord_id: Uuid::from_u128(exec_id.into()),
+            trd_match_id: None,
+            order_id: exec_id,
+            exec_id,
+            transact_time: 0,
+            price: Some(Decimal64::ONE),
+            order_qty: Decimal64::ONE,
+            last_qty: None,
+            last_px: None,
+            leaves_qty: Decimal64::ONE,
+            cum_qty: Decimal64::ZERO,
+            avg_px: None,
+            symbol: *b"JSP\0\0\0",
+            exec_type: ExecTypeEnum::New,
+            ord_status: OrdStatusEnum::New,
+            ord_rej_reason: None,
+            side: SideEnum::Buy,
+            mass_status_req_id: None,
+            tot_num_reports: None,
+        }
+    }
+
+    /// Sequences reports with ExecIDs `exec_ids` for `account`.
+    fn sequence(sequencer: &mut Sequencer, account: u128, exec_ids: impl IntoIterator<Item = u64>) {
+        for exec_id in exec_ids {
+            sequencer.sequence(report(account, exec_id), &exec_id.to_le_bytes());
+        }
+    }
+
+    fn seq_nums(reports: &[Arc<SequencedReport>]) -> Vec<u64> {
+        reports.iter().map(|report| report.seq_num).collect()
+    }
+
+    #[test]
+    fn numbers_each_account_from_one() {
+        let mut sequencer = Sequencer::new(SESSION, 10);
+        let first = sequencer.sequence(report(0xA, 1), b"frame");
+        assert_eq!((first.session_id, first.seq_num), (SESSION, 1));
+        assert_eq!(first.frame, b"frame");
+
+        sequence(&mut sequencer, 0xB, [2, 3]);
+        sequence(&mut sequencer, 0xA, [4]);
+        assert_eq!(sequencer.last_seq_num(&Uuid::from_u128(0xA)), 2);
+        assert_eq!(sequencer.last_seq_num(&Uuid::from_u128(0xB)), 2);
+        assert_eq!(sequencer.last_seq_num(&Uuid::from_u128(0xC)), 0);
+
+        let replayed = sequencer.replay(&Uuid::from_u128(0xA), 1, 0);
+        let exec_ids: Vec<u64> = replayed.iter().map(|r| r.report.exec_id).collect();
+        assert_eq!((seq_nums(&replayed), exec_ids), (vec![1, 2], vec![1, 4]));
+    }
+
+    #[test]
+    fn replays_the_requested_range() {
+        let mut sequencer = Sequencer::new(SESSION, 10);
+        sequence(&mut sequencer, 0xA, 1..=5);
+        let account = Uuid::from_u128(0xA);
+
+        assert_eq!(seq_nums(&sequencer.replay(&account, 2, 4)), [2, 3, 4]);
+        assert_eq!(seq_nums(&sequencer.replay(&account, 4, 0)), [4, 5]);
+        assert_eq!(seq_nums(&sequencer.replay(&account, 0, 1)), [1]);
+        assert_eq!(seq_nums(&sequencer.replay(&account, 5, 9)), [5]);
+        assert!(sequencer.replay(&account, 6, 0).is_empty());
+        assert!(sequencer.replay(&account, 4, 3).is_empty());
+        assert!(sequencer.replay(&Uuid::from_u128(0xB), 1, 0).is_empty());
+    }
+
+    #[test]
+    fn replays_only_the_retained_reports() {
+        let mut sequencer = Sequencer::new(SESSION, 3);
+        sequence(&mut sequencer, 0xA, 1..=5);
+        sequence(&mut sequencer, 0xB, [6]);
+        let account = Uuid::from_u128(0xA);
+
+        assert_eq!(seq_nums(&sequencer.replay(&account, 1, 0)), [3, 4, 5]);
+        assert_eq!(seq_nums(&sequencer.replay(&account, 1, 3)), [3]);
+        assert!(sequencer.replay(&account, 1, 2).is_empty());
+        assert_eq!(seq_nums(&sequencer.replay(&account, 4, 0)), [4, 5]);
+        assert_eq!(sequencer.last_seq_num(&account), 5);
+        assert_eq!(
+            seq_nums(&sequencer.replay(&Uuid::from_u128(0xB), 1, 0)),
+            [1]
+        );
+    }
+
+    #[test]
+    fn survives_a_poisoned_lock() {
+        let sequencer = Mutex::new(Sequencer::new(SESSION, 3));
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = sequencer.lock().unwrap();
+            panic!("poisoned");
+        });
+        assert!(sequencer.is_poisoned());
+
+        let mut sequencer = lock(&sequencer);
+        sequence(&mut sequencer, 0xA, [1]);
+        assert_eq!(sequencer.last_seq_num(&Uuid::from_u128(0xA)), 1);
+    }
+}
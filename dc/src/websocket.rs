@@ -0,0 +1,246 @@
+use crate::auth::Authenticator;
+use crate::messages::DropCopyUpdate;
+use crate::sequencer::{self, SequencedReport, Sequencer};
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use credentials::{Account, AuthError};
+
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::{Error, Message, Result, Utf8Bytes};
+use tokio_tungstenite::{WebSocketStream, accept_hdr_async};
+
+use tracing::{error, info, warn};
+
+use uuid::Uuid;
+
+type Sink = SplitSink<WebSocketStream<TcpStream>, Message>;
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientRequest {
+    /// Starts the feed for the account the client signed in to. With `from_sequence_num` the
+    /// retained reports from that sequence number are replayed before live reports.
+    Subscribe {
+        account: String,
+        from_sequence_num: Option<u64>,
+    },
+    /// Resends retained reports of the subscribed account; `end_sequence_num` defaults to latest.
+    Resend {
+        begin_sequence_num: u64,
+        end_sequence_num: Option<u64>,
+    },
+}
+
+pub async fn serve(
+    addr: String,
+    authenticator: Arc<Authenticator>,
+    sequencer: Arc<Mutex<Sequencer>>,
+    updates: broadcast::Sender<Arc<SequencedReport>>,
+) {
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("WebSocket: Failed to bind to {}: {}", addr, err);
+            return;
+        }
+    };
+    info!("WebSocket: Listening on: {}", addr);
+
+    while let Ok((stream, peer)) = listener.accept().await {
+        let authenticator = Arc::clone(&authenticator);
+        let sequencer = Arc::clone(&sequencer);
+        let updates = updates.clone();
+        tokio::spawn(async move {
+            if let Err(err) =
+                handle_connection(peer, stream, &authenticator, sequencer, updates).await
+            {
+                match err {
+                    // Rejected handshakes are logged when they are rejected
+                    Error::ConnectionClosed
+                    | Error::Protocol(_)
+                    | Error::Utf8(_)
+                    | Error::Http(_) => (),
+                    err => error!("WebSocket: Error processing connection {}: {}", peer, err),
+                }
+            }
+        });
+    }
+}
+
+/// Refuses the handshake of a client that cannot sign in.
+fn reject(err: &AuthError) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(err.to_string()));
+    *response.status_mut() = match err {
+        AuthError::NotPermitted(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::UNAUTHORIZED,
+    };
+    response
+}
+
+async fn handle_connection(
+    peer: SocketAddr,
+    stream: TcpStream,
+    authenticator: &Authenticator,
+    sequencer: Arc<Mutex<Sequencer>>,
+    updates: broadcast::Sender<Arc<SequencedReport>>,
+) -> Result<()> {
+    let mut signed_in: Option<Account> = None;
+    // tungstenite's callback signature, whose error response is large
+    #[allow(clippy::result_large_err)]
+    let sign_in = |request: &Request, response: Response| {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        match authenticator.authenticate(request, now) {
+            Ok(account) => {
+                signed_in = Some(account);
+                Ok(response)
+            }
+            Err(err) => {
+                warn!("WebSocket: Rejected drop copy connection {}: {}", peer, err);
+                Err(reject(&err))
+            }
+        }
+    };
+    let ws_stream = accept_hdr_async(stream, sign_in).await?;
+    let Some(signed_in) = signed_in else {
+        return Ok(());
+    };
+    let (mut sink, mut source) = ws_stream.split();
+    info!(
+        "WebSocket: New drop copy connection: {} for user {}",
+        peer, signed_in.user_id
+    );
+
+    let (account, from_sequence_num) = loop {
+        let Some(message) = source.next().await else {
+            return Ok(());
+        };
+        let message = message?;
+        if message.is_close() {
+            return Ok(());
+        }
+        if !message.is_text() {
+            continue;
+        }
+        match parse_request(&message) {
+            Some(ClientRequest::Subscribe {
+                account,
+                from_sequence_num,
+            }) => match Uuid::parse_str(&account) {
+                Ok(uuid) => {
+                    let (high, low) = uuid.as_u64_pair();
+                    let account = sbe::Uuid::from_u64_pair(high, low);
+                    if account == signed_in.id {
+                        break (account, from_sequence_num);
+                    }
+                    send_error(&mut sink, "Not permitted").await?;
+                }
+                Err(_) => send_error(&mut sink, "Invalid account").await?,
+            },
+            Some(ClientRequest::Resend { .. }) => send_error(&mut sink, "Not subscribed").await?,
+            None => send_error(&mut sink, "Invalid request").await?,
+        }
+    };
+
+    let (mut live, backlog, mut last_sent) = {
+        let sequencer = sequencer::lock(&sequencer);
+        let backlog = from_sequence_num
+            .map(|begin| sequencer.replay(&account, begin, 0))
+            .unwrap_or_default();
+        (
+            updates.subscribe(),
+            backlog,
+            sequencer.last_seq_num(&account),
+        )
+    };
+    send_reports(&mut sink, &backlog).await?;
+
+    loop {
+        tokio::select! {
+            update = live.recv() => match update {
+                Ok(report) => {
+                    if report.report.account == account && report.seq_num > last_sent {
+                        send_reports(&mut sink, std::slice::from_ref(&report)).await?;
+                        last_sent = report.seq_num;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("WebSocket: Subscriber {} lagged by {} reports, recovering from log", peer, skipped);
+                    let missed = sequencer::lock(&sequencer).replay(&account, last_sent + 1, 0);
+                    send_reports(&mut sink, &missed).await?;
+                    last_sent = missed.last().map_or(last_sent, |report| report.seq_num);
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            message = source.next() => {
+                let Some(message) = message else {
+                    return Ok(());
+                };
+                let message = message?;
+                if message.is_close() {
+                    return Ok(());
+                }
+                match parse_request(&message) {
+                    Some(ClientRequest::Resend { begin_sequence_num, end_sequence_num }) => {
+                        let reports = sequencer::lock(&sequencer)
+                            .replay(&account, begin_sequence_num, end_sequence_num.unwrap_or(0));
+                        send_reports(&mut sink, &reports).await?;
+                    }
+                    Some(ClientRequest::Subscribe { .. }) => send_error(&mut sink, "Already subscribed").await?,
+                    None if message.is_text() => send_error(&mut sink, "Invalid request").await?,
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+fn parse_request(message: &Message) -> Option<ClientRequest> {
+    match message {
+        Message::Text(text) => serde_json::from_str(text.as_str()).ok(),
+        _ => None,
+    }
+}
+
+/// Sends the reports in order. A report that cannot be serialized is logged and skipped, which
+/// the client sees as a gap in the sequence numbers.
+async fn send_reports(sink: &mut Sink, reports: &[Arc<SequencedReport>]) -> Result<()> {
+    for report in reports {
+        let update = DropCopyUpdate::new(report.session_id, report.seq_num, &report.report);
+        let update_str = match serde_json::to_string(&update) {
+            Ok(update_str) => update_str,
+            Err(err) => {
+                error!(
+                    seq_num = report.seq_num,
+                    error = %err,
+                    "Failed to serialize drop copy update, skipping it"
+                );
+                continue;
+            }
+        };
+        sink.feed(Message::Text(Utf8Bytes::from(update_str)))
+            .await?;
+    }
+    sink.flush().await
+}
+
+async fn send_error(sink: &mut Sink, message: &str) -> Result<()> {
+    let error = json!({ "type": "error", "message": message });
+    sink.send(Message::Text(Utf8Bytes::from(error.to_string())))
+        .await
+}
@@ -1,26 +1,18 @@
-mod messages;
+mod config;
 mod orderbook;
 mod processors;
-mod transport;
 
-use messages::decode_execution_report;
 use orderbook::OrderBook;
 use processors::execution::process_execution_report;
-use transport::aeron::{build_context, create_subscription, get_aeron_dir};
 
+use std::collections::HashSet;
 use std::process;
-use std::slice;
 
-use aeron_rs::aeron::Aeron;
-use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
-use aeron_rs::concurrent::logbuffer::header::Header;
-use aeron_rs::concurrent::status::status_indicator_reader::channel_status_to_str;
 use aeron_rs::concurrent::strategies::{BusySpinIdleStrategy, Strategy};
-use aeron_rs::fragment_assembler::FragmentAssembler;
-use aeron_rs::utils::types::Index;
 
-use sbe::ReadBuf;
-use sbe::message_header_codec::MessageHeaderDecoder;
+use products::{Product, Products};
+
+use sbe::{Message, decode_message};
 
 use tokio::sync::mpsc;
 
@@ -28,8 +20,14 @@ use tracing::subscriber::set_global_default;
 use tracing::{Level, error, info, warn};
 use tracing_subscriber::FmtSubscriber;
 
+use transport::{AeronTransport, MessageSource};
+
+use config::Config;
+
 #[tokio::main]
 async fn main() {
+    let config: Config = settings::load("level2");
+
     // Initialize tracing
 
     let subscriber = FmtSubscriber::builder()
@@ -41,41 +39,51 @@ async fn main() {
         process::exit(1);
     });
 
+    let products = match Products::load(config.products_path.as_ref()) {
+        Ok(products) => products,
+        Err(e) => {
+            error!("Products: Failed to load: {}", e);
+            return;
+        }
+    };
+    let symbols: HashSet<_> = products.all().map(Product::symbol).collect();
+    info!(
+        "Products: Loaded {} products from {}",
+        symbols.len(),
+        config.products_path
+    );
+
     // --- Aeron and NATS/JetStream Setup ---
-    let aeron_dir = get_aeron_dir();
-    let context = build_context(&aeron_dir);
-    let mut aeron = match Aeron::new(context) {
+    let mut transport = match AeronTransport::connect(&config.aeron) {
         Ok(instance) => instance,
         Err(e) => {
-            error!("Aeron: Failed to create instance: {:?}", e);
+            error!("Aeron: Failed to create instance: {}", e);
             return;
         }
     };
-    // TODO: GET AERON_SUB_CHANNEL FROM ENV VARIABLE
-    let subscription = create_subscription(
-        &mut aeron,
-        //"aeron:udp?endpoint=224.1.1.1:40456|interface=localhost",
-        "aeron:ipc",
-        1002,
-    );
-    let sub_status = subscription.lock().unwrap().channel_status();
-    info!("Aeron: Subscription {}", channel_status_to_str(sub_status));
+    let mut subscription = match recorder::subscribe(&mut transport, &config.sub, &config.replay) {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            error!("Aeron: Failed to add subscription: {}", e);
+            return;
+        }
+    };
+    info!("Aeron: Subscription on {}", config.sub);
 
     // Connect to the NATS server
-    // TODO: GET NATS_SERVER FROM ENV VARIABLE
-    info!("Connecting to NATS server at localhost");
-    let client = async_nats::connect("localhost")
+    info!("Connecting to NATS server at {}", config.nats_url);
+    let client = async_nats::connect(config.nats_url.as_str())
         .await
         .expect("nats connection failed"); // TODO: Replace expect with explicit handle and trace
 
     let (tx, mut rx) = mpsc::channel::<String>(1024);
 
+    let subject = config.nats_subject.clone();
     tokio::spawn(async move {
         info!("Async I/O publisher task started.");
         while let Some(message) = rx.recv().await {
-            // TODO: GET SUBJECT FROM HARDCODED VALUE (DERIVED FROM ENV VAR AT STARTUP)
             client
-                .publish("foo", message.into())
+                .publish(subject.clone(), message.into())
                 .await
                 .expect("JetStream publish failed"); // TODO: Add proper error handling/flow control
         }
@@ -84,46 +92,45 @@ async fn main() {
     let mut book = OrderBook::new();
 
     // Initialize message handler
-    let mut order_message_handler =
-        move |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
-            // SAFETY: This creates a slice from the Aeron buffer for zero-copy message processing.
-            // The buffer is guaranteed to be valid for the specified offset and length by Aeron.
-            // The slice lifetime is bounded by this function scope, ensuring memory safety.
-            let slice_msg = unsafe {
-                slice::from_raw_parts_mut(
-                    buffer.buffer().offset(offset.try_into().expect("")), // TODO: NO EXPECT
-                    length.try_into().expect(""),                         // TODO: NO EXPECT
-                )
-            };
-
-            let read_buf = ReadBuf::new(slice_msg);
-            let header_decoder: MessageHeaderDecoder<ReadBuf<'_>> =
-                MessageHeaderDecoder::default().wrap(read_buf, 0);
-
-            match header_decoder.template_id() {
-                3 => {
-                    let report = decode_execution_report(header_decoder);
-                    if let Some(message) = process_execution_report(&mut book, &report) {
-                        if let Err(e) = tx.try_send(message) {
-                            // TODO: NOTE: For true lossless, you would implement the "pending_report"
-                            // backpressure logic here, but using the String instead.
-                        }
-                    }
-                }
-                template_id => {
-                    panic!("incorrect template_id: {}", template_id)
-                }
+    let mut order_message_handler = move |frame: &[u8]| {
+        let report = match decode_message(frame) {
+            Ok(Message::ExecutionReport(report)) => report,
+            Ok(message) => {
+                warn!(
+                    template_id = message.template_id(),
+                    "Ignoring message with unexpected template ID"
+                );
+                return;
+            }
+            Err(err) => {
+                warn!(error = ?err, length = frame.len(), "Rejecting malformed message");
+                return;
             }
         };
 
-    let mut fragment_assembler = FragmentAssembler::new(&mut order_message_handler, None);
-    let mut fragment_handler = fragment_assembler.handler();
+        if !symbols.contains(&report.symbol) {
+            warn!(
+                order_id = report.order_id,
+                "Ignoring report for a symbol that is not a product"
+            );
+            return;
+        }
+
+        if let Some(message) = process_execution_report(&mut book, &report) {
+            if let Err(e) = tx.try_send(message) {
+                // TODO: NOTE: For true lossless, you would implement the "pending_report"
+                // backpressure logic here, but using the String instead.
+            }
+        }
+    };
+
     let poll_idle_strategy = BusySpinIdleStrategy::default();
 
     info!("Starting Aeron polling loop...");
 
     loop {
-        let fragments_read = subscription.lock().unwrap().poll(&mut fragment_handler, 10);
-        poll_idle_strategy.idle_opt(fragments_read);
+        let fragments_read = subscription.poll(&mut order_message_handler, 10);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        poll_idle_strategy.idle_opt(fragments_read as i32);
     }
 }
@@ -1,18 +1,20 @@
+use sbe::Decimal64;
+
 use serde::Serialize;
 
 #[derive(Serialize)]
 struct L2Update {
     side: String,
     event_time: String,
-    price_level: String,
-    new_quantity: String,
+    price_level: Decimal64,
+    new_quantity: Decimal64,
 }
 
 pub fn format_l2_update_json(
     side: String,
     event_time: String,
-    price_level: String,
-    new_quantity: String,
+    price_level: Decimal64,
+    new_quantity: Decimal64,
     product_id: String,
 ) -> Result<String, serde_json::Error> {
     let l2_data = L2Update {
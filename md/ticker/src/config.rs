@@ -0,0 +1,54 @@
+use recorder::ReplayConfig;
+
+use serde::{Deserialize, Serialize};
+
+use settings::Settings;
+
+use transport::{ChannelConfig, TransportConfig};
+
+/// Execution reports are read from the engine's report stream over IPC by default.
+const DEFAULT_SUB_CHANNEL: &str = "aeron:ipc";
+const DEFAULT_SUB_STREAM_ID: i32 = 1002;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub aeron: TransportConfig,
+    /// Where execution reports are read from; `SUB_CHANNEL`/`SUB_STREAM_ID`.
+    pub sub: ChannelConfig,
+    /// Catching up from the recorder on start.
+    pub replay: ReplayConfig,
+    pub nats_url: String,
+    /// Subject the ticker updates are published on.
+    pub nats_subject: String,
+    /// Instrument the ticker is computed for.
+    pub symbol: String,
+    /// The product definitions shared with the gateway, which must define `symbol`.
+    pub products_path: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aeron: TransportConfig::default(),
+            sub: ChannelConfig::new(DEFAULT_SUB_CHANNEL, DEFAULT_SUB_STREAM_ID),
+            replay: ReplayConfig::default(),
+            nats_url: "localhost".to_owned(),
+            nats_subject: "ticker:JSP".to_owned(),
+            symbol: "JSP".to_owned(),
+            products_path: "products.json".to_owned(),
+        }
+    }
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        self.aeron.validate()?;
+        self.sub.validate("sub")?;
+        self.replay.validate()?;
+        settings::required("nats_url", &self.nats_url)?;
+        settings::required("symbol", &self.symbol)?;
+        settings::required("products_path", &self.products_path)?;
+        settings::required("nats_subject", &self.nats_subject)
+    }
+}
@@ -1,14 +1,13 @@
 use std::collections::BTreeMap;
 use std::collections::btree_map::Entry;
 
-use crate::messages::ExecutionReportMessage;
 use sbe::side_enum::SideEnum;
+use sbe::{Decimal64, ExecutionReport};
 
 #[derive(Clone, Debug)]
 pub struct OrderBook {
-    pub bids: BTreeMap<i64, i64>,
-    pub asks: BTreeMap<i64, i64>,
-    pub last_seen_id: u64,
+    pub bids: BTreeMap<Decimal64, Decimal64>,
+    pub asks: BTreeMap<Decimal64, Decimal64>,
 }
 
 impl OrderBook {
@@ -16,15 +15,15 @@ impl OrderBook {
         Self {
             bids: BTreeMap::new(),
             asks: BTreeMap::new(),
-            last_seen_id: 0,
         }
     }
 
-    pub fn add_order(&mut self, report: &ExecutionReportMessage) -> i64 {
+    pub fn add_order(&mut self, price: Decimal64, report: &ExecutionReport) -> Decimal64 {
+        let order_qty = report.order_qty;
         debug_assert!(
-            report.ord_qty > 0,
-            "add_order called with zero or negative ord_qty: {}",
-            report.ord_qty
+            order_qty.is_positive(),
+            "add_order called with zero or negative order_qty: {}",
+            order_qty
         );
 
         let map = match report.side {
@@ -32,19 +31,20 @@ impl OrderBook {
             SideEnum::Sell => &mut self.asks,
             _ => panic!("Unknown side provided: {}", report.side),
         };
-        let price = report.price;
-        let quantity = map.entry(price).or_insert(0);
-        *quantity += report.ord_qty;
+        let quantity = map.entry(price).or_default();
+        *quantity += order_qty;
         *quantity
     }
 
-    pub fn fill_order(&mut self, report: &ExecutionReportMessage) -> i64 {
+    pub fn fill_order(&mut self, price: Decimal64, report: &ExecutionReport) -> Decimal64 {
+        let last_qty = report.last_qty.unwrap_or_default();
+        let leaves_qty = report.leaves_qty;
+
         let map = match report.side {
             SideEnum::Buy => &mut self.bids,
             SideEnum::Sell => &mut self.asks,
             _ => panic!("Unknown side provided: {}", report.side),
         };
-        let price = report.price;
 
         match map.entry(price) {
             Entry::Occupied(mut entry) => {
@@ -53,36 +53,36 @@ impl OrderBook {
                 // --- Assertions ---
                 // Assumption: A fill must be for a positive quantity.
                 debug_assert!(
-                    report.last_qty > 0,
+                    last_qty.is_positive(),
                     "fill_order called with zero or negative last_qty: {}",
-                    report.last_qty
+                    last_qty
                 );
 
                 // Assumption: The book must have enough quantity to satisfy the fill.
                 // If this fails, you have "phantom liquidity" - the book thinks it has shares that don't exist.
                 debug_assert!(
-                    current_quantity_at_level >= report.last_qty,
+                    current_quantity_at_level >= last_qty,
                     "CRITICAL: fill_order for {} shares but only {} available at price {}",
-                    report.last_qty,
+                    last_qty,
                     current_quantity_at_level,
                     price
                 );
 
                 // Assumption: The 'leaves_qty' from the report should match our calculation.
                 debug_assert_eq!(
-                    current_quantity_at_level - report.last_qty,
-                    report.leaves_qty,
+                    current_quantity_at_level - last_qty,
+                    leaves_qty,
                     "CRITICAL: Mismatch in remaining quantity calculation. Book: {} - Fill: {} != Leaves: {}",
                     current_quantity_at_level,
-                    report.last_qty,
-                    report.leaves_qty
+                    last_qty,
+                    leaves_qty
                 );
 
                 let quantity = entry.get_mut();
-                *quantity -= report.last_qty;
-                if *quantity == 0 {
+                *quantity -= last_qty;
+                if quantity.is_zero() {
                     entry.remove();
-                    0
+                    Decimal64::ZERO
                 } else {
                     *quantity
                 }
@@ -96,13 +96,12 @@ impl OrderBook {
         }
     }
 
-    pub fn remove_order(&mut self, report: &ExecutionReportMessage) -> i64 {
+    pub fn remove_order(&mut self, price: Decimal64, report: &ExecutionReport) -> Decimal64 {
         let map = match report.side {
             SideEnum::Buy => &mut self.bids,
             SideEnum::Sell => &mut self.asks,
             _ => panic!("Unknown side provided: {}", report.side),
         };
-        let price = report.price;
 
         match map.entry(price) {
             Entry::Occupied(entry) => {
@@ -116,7 +115,7 @@ impl OrderBook {
                 );
 
                 entry.remove();
-                0
+                Decimal64::ZERO
             }
             Entry::Vacant(_) => {
                 panic!(
@@ -127,11 +126,11 @@ impl OrderBook {
         }
     }
 
-    pub fn get_best_bid(&self) -> Option<(&i64, &i64)> {
+    pub fn get_best_bid(&self) -> Option<(&Decimal64, &Decimal64)> {
         self.bids.last_key_value()
     }
 
-    pub fn get_best_ask(&self) -> Option<(&i64, &i64)> {
+    pub fn get_best_ask(&self) -> Option<(&Decimal64, &Decimal64)> {
         self.asks.first_key_value()
     }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
-use crate::messages::ExecutionReportMessage;
 use crate::orderbook::OrderBook;
 use crate::processors::ticker::TickerState;
 
+use sbe::ExecutionReport;
 use sbe::exec_type_enum::ExecTypeEnum;
 use sbe::side_enum::SideEnum;
 
@@ -10,67 +10,59 @@ use tracing::info;
 pub fn process_execution_report(
     book: &mut OrderBook,
     ticker_state: &mut TickerState,
-    report: &ExecutionReportMessage,
+    report: &ExecutionReport,
 ) -> Option<String> {
     let mut update_to_send: Option<String> = None;
     match report.exec_type {
         ExecTypeEnum::New => {
-            assert_eq!(
-                report.order_id,
-                book.last_seen_id + 1,
-                "Error: Recieved id {} expected {}",
-                report.order_id,
-                book.last_seen_id + 1
-            );
-            book.last_seen_id += 1;
-
-            if report.price != i64::MIN {
-                book.add_order(&report);
+            if let Some(price) = report.price {
+                book.add_order(price, report);
             }
         }
         ExecTypeEnum::Trade => {
-            if report.price != i64::MIN {
+            if let Some(price) = report.price {
                 let price_level_exists = match report.side {
-                    SideEnum::Buy => book.bids.contains_key(&report.price),
-                    SideEnum::Sell => book.asks.contains_key(&report.price),
+                    SideEnum::Buy => book.bids.contains_key(&price),
+                    SideEnum::Sell => book.asks.contains_key(&price),
                     _ => false,
                 };
 
                 assert!(
                     price_level_exists,
                     "Assumption Violation: fill_order called for non-existent price level {} on side {:?}",
-                    report.price, report.side
+                    price, report.side
                 );
 
-                book.fill_order(&report);
-                ticker_state.update_on_match(&report);
-
-                let ticker = ticker_state.create_ticker(book, report.transact_time);
+                book.fill_order(price, report);
+            }
 
-                update_to_send = Some(
-                    serde_json::to_string(&ticker)
-                    .expect("Failed to serialize L2Update"), // TODO: NO EXPECTS
-                );
+            if ticker_state.update_on_match(&report) {
+                update_to_send = Some(create_ticker_update(book, ticker_state, report));
             }
         }
+        ExecTypeEnum::TradeCancel if ticker_state.update_on_bust(report) => {
+            update_to_send = Some(create_ticker_update(book, ticker_state, report));
+        }
+        ExecTypeEnum::TradeCorrect if ticker_state.update_on_correct(report) => {
+            update_to_send = Some(create_ticker_update(book, ticker_state, report));
+        }
         ExecTypeEnum::Canceled => {
-            if report.price != i64::MIN {
-                book.remove_order(&report);
+            if let Some(price) = report.price {
+                book.remove_order(price, report);
             }
         }
-        ExecTypeEnum::Rejected => {
-            assert_eq!(
-                report.order_id,
-                book.last_seen_id + 1,
-                "Error: Recieved id {} expected {}",
-                report.order_id,
-                book.last_seen_id + 1
-            );
-            book.last_seen_id += 1;
-        }
         _ => {
             // Handle other execution types or ignore
         }
     }
     update_to_send
-}
\ No newline at end of file
+}
+
+fn create_ticker_update(
+    book: &OrderBook,
+    ticker_state: &TickerState,
+    report: &ExecutionReport,
+) -> String {
+    let ticker = ticker_state.create_ticker(book, report.transact_time);
+    serde_json::to_string(&ticker).expect("Failed to serialize Ticker") // TODO: NO EXPECTS
+}
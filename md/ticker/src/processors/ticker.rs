@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::string::ToString;
 
-use crate::messages::{ExecutionReportMessage, SymbolType};
 use crate::orderbook::OrderBook;
 
+use sbe::{Decimal64, ExecutionReport};
+
 use chrono::{Datelike, Duration, TimeZone, Utc, DateTime, SecondsFormat};
 use serde::Serialize;
 
@@ -11,18 +13,18 @@ use tracing::info;
 #[derive(Serialize, Debug, Clone)]
 pub struct Ticker {
     product_id: String,
-    price: String,
-    volume_24_h: String,
-    low_24_h: String,
-    high_24_h: String,
-    open_24_h: String,
-    low_52_w: String,
-    high_52_w: String,
+    price: Decimal64,
+    volume_24_h: Decimal64,
+    low_24_h: Decimal64,
+    high_24_h: Decimal64,
+    open_24_h: Decimal64,
+    low_52_w: Decimal64,
+    high_52_w: Decimal64,
     price_percent_chg_24_h: String,
-    best_bid: String,
-    best_bid_quantity: String,
-    best_ask: String,
-    best_ask_quantity: String,
+    best_bid: Decimal64,
+    best_bid_quantity: Decimal64,
+    best_ask: Decimal64,
+    best_ask_quantity: Decimal64,
     timestamp: String,
 }
 
@@ -31,13 +33,32 @@ pub struct TickerState {
     product_id: String,
     start_24_hr_ts: u64,
     start_52_w_ts: u64,
-    volume_24_h: i64,
-    low_24_h: i64,
-    high_24_h: i64,
-    open_24_hr: i64,
-    low_52_w: i64,
-    high_52_w: i64,
-    last_price: i64,
+    volume_24_h: Decimal64,
+    low_24_h: Decimal64,
+    high_24_h: Decimal64,
+    open_24_hr: Decimal64,
+    low_52_w: Decimal64,
+    high_52_w: Decimal64,
+    last_price: Decimal64,
+    // Trades in the current 24h window keyed by TrdMatchID, kept so busts and corrections can be
+    // reversed. Both sides of a match carry the same TrdMatchID, so each match is counted once.
+    trades_24_h: BTreeMap<u64, WindowTrade>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WindowTrade {
+    price: Decimal64,
+    quantity: Decimal64,
+}
+
+impl WindowTrade {
+    /// Fill price and quantity of a trade report; `None` if the report carries no fill.
+    fn from_report(report: &ExecutionReport) -> Option<Self> {
+        Some(Self {
+            price: report.last_px?,
+            quantity: report.last_qty?,
+        })
+    }
 }
 
 impl TickerState {
@@ -60,13 +81,14 @@ impl TickerState {
             product_id,
             start_24_hr_ts,
             start_52_w_ts,
-            volume_24_h: 0,
-            low_24_h: i64::MAX,
-            high_24_h: i64::MIN,
-            open_24_hr: 0,
-            low_52_w: i64::MAX,
-            high_52_w: i64::MIN,
-            last_price: 0,
+            volume_24_h: Decimal64::ZERO,
+            low_24_h: Decimal64::MAX,
+            high_24_h: Decimal64::MIN,
+            open_24_hr: Decimal64::ZERO,
+            low_52_w: Decimal64::MAX,
+            high_52_w: Decimal64::MIN,
+            last_price: Decimal64::ZERO,
+            trades_24_h: BTreeMap::new(),
         }
     }
 
@@ -75,10 +97,11 @@ impl TickerState {
 
         while current_ts >= next_day_start_ts {
             self.start_24_hr_ts = next_day_start_ts;
-            self.volume_24_h = 0;
-            self.low_24_h = i64::MAX;
-            self.high_24_h = i64::MIN;
+            self.volume_24_h = Decimal64::ZERO;
+            self.low_24_h = Decimal64::MAX;
+            self.high_24_h = Decimal64::MIN;
             self.open_24_hr = self.last_price;
+            self.trades_24_h.clear();
             next_day_start_ts += Duration::days(1).num_nanoseconds().unwrap() as u64;
         }
 
@@ -89,33 +112,107 @@ impl TickerState {
         // while current_ts >= next_year_start_ts {
         //     info!("loopin");
         //     self.start_52_w_ts = next_year_start_ts;
-        //     self.low_52_w = i64::MAX;
-        //     self.high_52_w = i64::MIN;
+        //     self.low_52_w = Decimal64::MAX;
+        //     self.high_52_w = Decimal64::MIN;
         //     year += 1;
         //     next_year_start_ts = Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).unwrap().timestamp();
         // }
     }
 
-    pub fn update_on_match(&mut self, report: &ExecutionReportMessage) {
+    /// Applies a trade to the running statistics. Returns `false` if the match was already
+    /// counted from the report of the other side.
+    pub fn update_on_match(&mut self, report: &ExecutionReport) -> bool {
         self.check_and_reset_windows(report.transact_time);
 
-        self.last_price = report.price;
-        self.volume_24_h += report.last_qty;
+        let (Some(match_id), Some(trade)) = (report.trd_match_id, WindowTrade::from_report(report))
+        else {
+            return false;
+        };
+        if self.trades_24_h.insert(match_id, trade).is_some() {
+            return false;
+        }
+
+        self.last_price = trade.price;
+        self.volume_24_h += trade.quantity;
 
-        self.low_24_h = self.low_24_h.min(report.price);
-        self.high_24_h = self.high_24_h.max(report.price);
-        if self.open_24_hr == 0 {
-            self.open_24_hr = report.price;
+        self.low_24_h = self.low_24_h.min(trade.price);
+        self.high_24_h = self.high_24_h.max(trade.price);
+        if self.open_24_hr.is_zero() {
+            self.open_24_hr = trade.price;
         }
 
-        self.low_52_w = self.low_52_w.min(report.price);
-        self.high_52_w = self.high_52_w.max(report.price);
+        self.low_52_w = self.low_52_w.min(trade.price);
+        self.high_52_w = self.high_52_w.max(trade.price);
+        true
+    }
+
+    /// Removes a busted trade from the 24h window. Returns `false` if the trade is not part of the
+    /// current window or was already removed.
+    pub fn update_on_bust(&mut self, report: &ExecutionReport) -> bool {
+        self.check_and_reset_windows(report.transact_time);
+
+        let Some(match_id) = report.trd_match_id else {
+            return false;
+        };
+        if self.trades_24_h.remove(&match_id).is_none() {
+            return false;
+        }
+
+        self.recompute_24_h();
+        true
+    }
+
+    /// Replaces the price and quantity of a corrected trade in the 24h window. Returns `false` if
+    /// the trade is not part of the current window or the correction was already applied.
+    pub fn update_on_correct(&mut self, report: &ExecutionReport) -> bool {
+        self.check_and_reset_windows(report.transact_time);
+
+        let Some(trade) = report
+            .trd_match_id
+            .and_then(|match_id| self.trades_24_h.get_mut(&match_id))
+        else {
+            return false;
+        };
+        let Some(corrected) = WindowTrade::from_report(report) else {
+            return false;
+        };
+        if *trade == corrected {
+            return false;
+        }
+        *trade = corrected;
+
+        self.recompute_24_h();
+        self.low_52_w = self.low_52_w.min(corrected.price);
+        self.high_52_w = self.high_52_w.max(corrected.price);
+        true
+    }
+
+    // The 52 week range is not recomputed: only the current day of trades is retained.
+    fn recompute_24_h(&mut self) {
+        self.volume_24_h = self.trades_24_h.values().map(|t| t.quantity).sum();
+        self.low_24_h = self
+            .trades_24_h
+            .values()
+            .map(|t| t.price)
+            .min()
+            .unwrap_or(Decimal64::MAX);
+        self.high_24_h = self
+            .trades_24_h
+            .values()
+            .map(|t| t.price)
+            .max()
+            .unwrap_or(Decimal64::MIN);
+        self.last_price = self
+            .trades_24_h
+            .values()
+            .next_back()
+            .map_or(self.open_24_hr, |t| t.price);
     }
 
     fn calculate_price_percent_change(&self) -> f64 {
-        if self.open_24_hr != 0 && self.last_price != 0 {
-            return ((self.last_price as f64 - self.open_24_hr as f64) / self.open_24_hr as f64)
-                * 100.0;
+        if !self.open_24_hr.is_zero() && !self.last_price.is_zero() {
+            let open = self.open_24_hr.to_f64();
+            return ((self.last_price.to_f64() - open) / open) * 100.0;
         }
         0.0
     }
@@ -131,22 +228,22 @@ impl TickerState {
             .unwrap_or_default();
 
         // Handle cases where min/max haven't been updated yet
-        let low_24h = if self.low_24_h == i64::MAX {
+        let low_24h = if self.low_24_h == Decimal64::MAX {
             self.last_price
         } else {
             self.low_24_h
         };
-        let high_24h = if self.high_24_h == i64::MIN {
+        let high_24h = if self.high_24_h == Decimal64::MIN {
             self.last_price
         } else {
             self.high_24_h
         };
-        let low_52w = if self.low_52_w == i64::MAX {
+        let low_52w = if self.low_52_w == Decimal64::MAX {
             self.last_price
         } else {
             self.low_52_w
         };
-        let high_52w = if self.high_52_w == i64::MIN {
+        let high_52w = if self.high_52_w == Decimal64::MIN {
             self.last_price
         } else {
             self.high_52_w
@@ -154,45 +251,23 @@ impl TickerState {
 
         Ticker {
             product_id: self.product_id.clone(),
-            price: format_decimal_with_exponent_neg8(&self.last_price),
-            volume_24_h: format_decimal_with_exponent_neg8(&self.volume_24_h),
-            low_24_h: format_decimal_with_exponent_neg8(&low_24h),
-            high_24_h: format_decimal_with_exponent_neg8(&high_24h),
-            open_24_h: format_decimal_with_exponent_neg8(&self.open_24_hr),
-            low_52_w: format_decimal_with_exponent_neg8(&low_52w),
-            high_52_w: format_decimal_with_exponent_neg8(&high_52w),
+            price: self.last_price,
+            volume_24_h: self.volume_24_h,
+            low_24_h: low_24h,
+            high_24_h: high_24h,
+            open_24_h: self.open_24_hr,
+            low_52_w: low_52w,
+            high_52_w: high_52w,
             price_percent_chg_24_h: self.calculate_price_percent_change().to_string(),
-            best_bid: format_decimal_with_exponent_neg8(&best_bid),
-            best_bid_quantity: format_decimal_with_exponent_neg8(&best_bid_quantity),
-            best_ask: format_decimal_with_exponent_neg8(&best_ask),
-            best_ask_quantity: format_decimal_with_exponent_neg8(&best_ask_quantity),
+            best_bid,
+            best_bid_quantity,
+            best_ask,
+            best_ask_quantity,
             timestamp: format_nanosecond_timestamp(&timestamp),
         }
     }
 }
 
-fn format_decimal_with_exponent_neg8(mantissa: &i64) -> String {
-    const SCALE: i64 = 100_000_000;
-
-    let integral = mantissa / SCALE;
-    let fractional_part = (mantissa % SCALE).abs();
-
-    if fractional_part == 0 {
-        return integral.to_string();
-    }
-
-    let mut fractional_str = format!("{:08}", fractional_part);
-    while fractional_str.ends_with('0') {
-        fractional_str.pop();
-    }
-
-    if fractional_str.is_empty() {
-        integral.to_string()
-    } else {
-        format!("{}.{}", integral, fractional_str)
-    }
-}
-
 fn format_nanosecond_timestamp(timestamp: &u64) -> String {
     let secs = timestamp / 1_000_000_000;
     let nanos = (timestamp % 1_000_000_000) as u32;
@@ -204,4 +279,128 @@ fn format_symbol(symbol_bytes: &[u8; 6]) -> String {
     String::from_utf8_lossy(symbol_bytes)
         .trim_end_matches(['\0', ' ']) // Trim both null characters and spaces.
         .to_string()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use sbe::exec_type_enum::ExecTypeEnum;
+    use sbe::ord_status_enum::OrdStatusEnum;
+    use sbe::side_enum::SideEnum;
+
+    fn dec(value: i64) -> Decimal64 {
+        Decimal64::from_mantissa(value * Decimal64::SCALE)
+    }
+
+    fn trade(state: &TickerState, match_id: u64, price: i64, quantity: i64) -> ExecutionReport {
+        ExecutionReport {
+            account: sbe::Uuid::from_u128(1),
+            cl_ord_id: sbe::Uuid::from_u128(match_id.into()),
+            trd_match_id: Some(match_id),
+            order_id: match_id,
+            exec_id: match_id,
+            transact_time: state.start_24_hr_ts + 1,
+            price: Some(dec(price)),
+            order_qty: dec(quantity),
+            last_qty: Some(dec(quantity)),
+            last_px: Some(dec(price)),
+            leaves_qty: Decimal64::ZERO,
+            cum_qty: dec(quantity),
+            avg_px: Some(dec(price)),
+            symbol: *b"JSP\0\0\0",
+            exec_type: ExecTypeEnum::Trade,
+            ord_status: OrdStatusEnum::Filled,
+            ord_rej_reason: None,
+            side: SideEnum::Buy,
+            mass_status_req_id: None,
+            tot_num_reports: None,
+        }
+    }
+
+    /// A state with matches 1, 2 and 3 at 10, 20 and 15, of 1, 2 and 3 items.
+    fn traded() -> TickerState {
+        let mut state = TickerState::new("JSP".to_string());
+        for (match_id, price, quantity) in [(1, 10, 1), (2, 20, 2), (3, 15, 3)] {
+            let report = trade(&state, match_id, price, quantity);
+            assert!(state.update_on_match(&report));
+        }
+        state
+    }
+
+    #[test]
+    fn bust_takes_the_trade_out_of_the_window() {
+        let mut state = traded();
+        let mut bust = trade(&state, 2, 20, 2);
+        bust.exec_type = ExecTypeEnum::TradeCancel;
+
+        assert!(state.update_on_bust(&bust));
+        assert_eq!(state.volume_24_h, dec(4));
+        assert_eq!((state.low_24_h, state.high_24_h), (dec(10), dec(15)));
+        assert_eq!(state.last_price, dec(15));
+        // The year's range keeps the busted price.
+        assert_eq!(state.high_52_w, dec(20));
+
+        // The other side's report of the same bust changes nothing.
+        assert!(!state.update_on_bust(&bust));
+        assert_eq!(state.volume_24_h, dec(4));
+    }
+
+    #[test]
+    fn bust_of_the_last_trade_reverts_the_price() {
+        let mut state = traded();
+        assert!(state.update_on_bust(&trade(&state, 3, 15, 3)));
+        assert_eq!(state.last_price, dec(20));
+
+        for match_id in [1, 2] {
+            assert!(state.update_on_bust(&trade(&state, match_id, 0, 0)));
+        }
+        assert_eq!(state.volume_24_h, Decimal64::ZERO);
+        assert_eq!(state.last_price, state.open_24_hr);
+    }
+
+    #[test]
+    fn bust_of_an_unknown_trade_is_ignored() {
+        let mut state = traded();
+        assert!(!state.update_on_bust(&trade(&state, 9, 20, 2)));
+        let mut report = trade(&state, 1, 10, 1);
+        report.trd_match_id = None;
+        assert!(!state.update_on_bust(&report));
+        assert_eq!(state.volume_24_h, dec(6));
+    }
+
+    #[test]
+    fn correction_replaces_price_and_quantity() {
+        let mut state = traded();
+        let mut correction = trade(&state, 2, 12, 1);
+        correction.exec_type = ExecTypeEnum::TradeCorrect;
+
+        assert!(state.update_on_correct(&correction));
+        assert_eq!(state.volume_24_h, dec(5));
+        assert_eq!((state.low_24_h, state.high_24_h), (dec(10), dec(15)));
+        assert_eq!(state.last_price, dec(15));
+
+        // Applied once, whichever side reports it.
+        assert!(!state.update_on_correct(&correction));
+        assert_eq!(state.volume_24_h, dec(5));
+    }
+
+    #[test]
+    fn correction_widens_the_year_range() {
+        let mut state = traded();
+        assert!(state.update_on_correct(&trade(&state, 3, 30, 3)));
+        assert_eq!(state.last_price, dec(30));
+        assert_eq!((state.high_24_h, state.high_52_w), (dec(30), dec(30)));
+    }
+
+    #[test]
+    fn correction_of_an_unknown_trade_is_ignored() {
+        let mut state = traded();
+        assert!(!state.update_on_correct(&trade(&state, 9, 30, 3)));
+        let mut report = trade(&state, 1, 30, 3);
+        report.last_px = None;
+        assert!(!state.update_on_correct(&report));
+        assert_eq!(state.volume_24_h, dec(6));
+        assert_eq!(state.high_24_h, dec(20));
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+const BACK_OFF_MAX_SPINS: u32 = 100;
+const BACK_OFF_MAX_YIELDS: u32 = 10;
+const BACK_OFF_MIN_PARK: Duration = Duration::from_micros(1);
+const BACK_OFF_MAX_PARK: Duration = Duration::from_millis(1);
+
+/// How the engine thread waits between attempts to claim space on a backed up publication.
+#[derive(Clone, Copy, Debug)]
+pub enum IdleStrategy {
+    BusySpin,
+    Yielding,
+    Sleeping(Duration),
+    BackOff,
+}
+
+impl FromStr for IdleStrategy {
+    type Err = String;
+
+    /// Accepts `busy_spin`, `yielding`, `back_off` or `sleeping:<micros>`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "busy_spin" => Ok(Self::BusySpin),
+            "yielding" => Ok(Self::Yielding),
+            "back_off" => Ok(Self::BackOff),
+            other => other
+                .strip_prefix("sleeping:")
+                .and_then(|micros| micros.parse::<u64>().ok())
+                .map(|micros| Self::Sleeping(Duration::from_micros(micros)))
+                .ok_or_else(|| format!("unknown idle strategy '{value}'")),
+        }
+    }
+}
+
+/// What a publisher does once a claim has been retried for longer than the policy's `max_spin`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackPressureAction {
+    /// Keep retrying until the claim succeeds; `max_spin` is ignored.
+    Block,
+    /// Give up on the message and log it. Only for the response stream, whose status replies
+    /// and rejected admin requests carry `ExecID` 0: dropped execution reports would leave gaps in the execution IDs.
+    Drop,
+    /// Park the message and every following one in memory and stop polling inbound messages
+    /// until the parked messages have been published.
+    HaltInbound,
+}
+
+impl FromStr for BackPressureAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "block" => Ok(Self::Block),
+            "drop" => Ok(Self::Drop),
+            "halt_inbound" => Ok(Self::HaltInbound),
+            _ => Err(format!("unknown back-pressure action '{value}'")),
+        }
+    }
+}
+
+/// Back-pressure handling for one outbound publication. `NotConnected` and `AdminAction` are
+/// treated the same way as `BackPressured`.
+#[derive(Clone, Copy, Debug)]
+pub struct BackPressurePolicy {
+    pub idle_strategy: IdleStrategy,
+    pub max_spin: Duration,
+    /// Interval after which, and every which, a still backed up publication raises an alert.
+    pub alert_after: Duration,
+    pub action: BackPressureAction,
+}
+
+impl Default for BackPressurePolicy {
+    fn default() -> Self {
+        Self {
+            idle_strategy: IdleStrategy::BusySpin,
+            max_spin: Duration::from_millis(10),
+            alert_after: Duration::from_millis(100),
+            action: BackPressureAction::Block,
+        }
+    }
+}
+
+impl fmt::Display for BackPressurePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "idle={:?} max_spin={:?} alert_after={:?} action={:?}",
+            self.idle_strategy, self.max_spin, self.alert_after, self.action
+        )
+    }
+}
+
+/// Stateful idler for an `IdleStrategy`; `reset` must be called before each new claim.
+pub struct Idler {
+    strategy: IdleStrategy,
+    spins: u32,
+    yields: u32,
+    park: Duration,
+}
+
+impl Idler {
+    pub const fn new(strategy: IdleStrategy) -> Self {
+        Self {
+            strategy,
+            spins: 0,
+            yields: 0,
+            park: BACK_OFF_MIN_PARK,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn reset(&mut self) {
+        self.spins = 0;
+        self.yields = 0;
+        self.park = BACK_OFF_MIN_PARK;
+    }
+
+    #[inline(always)]
+    pub fn idle(&mut self) {
+        match self.strategy {
+            IdleStrategy::BusySpin => std::hint::spin_loop(),
+            IdleStrategy::Yielding => std::thread::yield_now(),
+            IdleStrategy::Sleeping(duration) => std::thread::sleep(duration),
+            IdleStrategy::BackOff => {
+                if self.spins < BACK_OFF_MAX_SPINS {
+                    self.spins += 1;
+                    std::hint::spin_loop();
+                } else if self.yields < BACK_OFF_MAX_YIELDS {
+                    self.yields += 1;
+                    std::thread::yield_now();
+                } else {
+                    std::thread::sleep(self.park);
+                    self.park = (self.park * 2).min(BACK_OFF_MAX_PARK);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_idle_strategies() {
+        assert!(matches!("busy_spin".parse(), Ok(IdleStrategy::BusySpin)));
+        assert!(matches!(" Yielding ".parse(), Ok(IdleStrategy::Yielding)));
+        assert!(matches!("BACK_OFF".parse(), Ok(IdleStrategy::BackOff)));
+        assert!(matches!(
+            "sleeping:250".parse(),
+            Ok(IdleStrategy::Sleeping(duration)) if duration == Duration::from_micros(250)
+        ));
+        for invalid in [
+            "",
+            "spin",
+            "sleeping",
+            "sleeping:",
+            "sleeping:-1",
+            "sleeping:1ms",
+        ] {
+            assert_eq!(
+                invalid.parse::<IdleStrategy>().unwrap_err(),
+                format!("unknown idle strategy '{invalid}'")
+            );
+        }
+    }
+
+    #[test]
+    fn parses_back_pressure_actions() {
+        assert_eq!("block".parse(), Ok(BackPressureAction::Block));
+        assert_eq!(" HALT_INBOUND".parse(), Ok(BackPressureAction::HaltInbound));
+        assert_eq!("drop".parse(), Ok(BackPressureAction::Drop));
+        assert_eq!(
+            "halt".parse::<BackPressureAction>(),
+            Err("unknown back-pressure action 'halt'".to_owned())
+        );
+    }
+
+    #[test]
+    fn backs_off_from_spinning_to_yielding_to_parking() {
+        let mut idler = Idler::new(IdleStrategy::BackOff);
+        for _ in 0..BACK_OFF_MAX_SPINS {
+            idler.idle();
+        }
+        assert_eq!((idler.spins, idler.yields), (BACK_OFF_MAX_SPINS, 0));
+        assert_eq!(idler.park, BACK_OFF_MIN_PARK);
+
+        for _ in 0..BACK_OFF_MAX_YIELDS {
+            idler.idle();
+        }
+        assert_eq!(idler.yields, BACK_OFF_MAX_YIELDS);
+        assert_eq!(idler.park, BACK_OFF_MIN_PARK);
+
+        // Parks for 1, 2, 4 ... microseconds, doubling up to the maximum.
+        let mut park = BACK_OFF_MIN_PARK;
+        while park < BACK_OFF_MAX_PARK {
+            idler.idle();
+            park = (park * 2).min(BACK_OFF_MAX_PARK);
+            assert_eq!(idler.park, park);
+        }
+        idler.idle();
+        assert_eq!(idler.park, BACK_OFF_MAX_PARK);
+
+        idler.reset();
+        assert_eq!((idler.spins, idler.yields), (0, 0));
+        assert_eq!(idler.park, BACK_OFF_MIN_PARK);
+    }
+
+    #[test]
+    fn other_strategies_keep_no_state() {
+        for strategy in [
+            IdleStrategy::BusySpin,
+            IdleStrategy::Yielding,
+            IdleStrategy::Sleeping(Duration::from_micros(1)),
+        ] {
+            let mut idler = Idler::new(strategy);
+            for _ in 0..BACK_OFF_MAX_SPINS + 1 {
+                idler.idle();
+            }
+            assert_eq!((idler.spins, idler.yields), (0, 0));
+            assert_eq!(idler.park, BACK_OFF_MIN_PARK);
+        }
+    }
+}
@@ -1,237 +1,179 @@
-use std::env;
-use std::ffi::CString;
-use std::process;
-use std::sync::{Arc, Mutex};
-use std::thread::sleep;
-use std::time::{Duration, Instant};
-
-use aeron_rs::aeron::Aeron;
-use aeron_rs::concurrent::status::status_indicator_reader::channel_status_to_str;
-
-use aeron_rs::exclusive_publication::ExclusivePublication;
-use aeron_rs::subscription::Subscription;
-use aeron_rs::utils::errors::AeronError;
-
-use tracing::{error, info, warn};
-
-#[allow(clippy::needless_pass_by_value)]
-pub fn error_handler(error: AeronError) {
-    match error {
-        AeronError::SubscriptionNotReady(id) => {
-            warn!(subscription_id = id, "Subscription not ready");
+use crate::backpressure::{BackPressureAction, BackPressurePolicy};
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use settings::Settings;
+
+use transport::{ChannelConfig, TransportConfig};
+
+/// The engine's settings. It has no default streams or book size, so those must be configured,
+/// e.g. with `SUB_CHANNEL`, `PUB_CHANNEL`, `RESP_CHANNEL` and `MAX_ORDERS`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub aeron: TransportConfig,
+    /// Inbound orders and requests.
+    pub sub: ChannelConfig,
+    /// Execution reports.
+    #[serde(rename = "pub")]
+    pub reports: PublicationConfig,
+    /// Responses to status requests.
+    pub resp: PublicationConfig,
+    /// Capacity of the order book.
+    pub max_orders: u64,
+    /// Trades kept to be busted or corrected; beyond this the oldest are forgotten.
+    pub max_trades: u64,
+    /// Address of the Prometheus endpoint.
+    pub metrics_addr: String,
+    /// The product definitions shared with the gateway; only products online are traded.
+    pub products_path: String,
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        self.aeron.validate()?;
+        self.sub.validate("sub")?;
+        self.reports.validate("pub")?;
+        if self.reports.policy().action == BackPressureAction::Drop {
+            return Err(
+                "pub.back_pressure_action cannot be drop: dropped execution reports leave gaps \
+                 in the execution IDs; use block or halt_inbound"
+                    .to_owned(),
+            );
         }
-        AeronError::PublicationNotReady(id) => {
-            warn!(publication_id = id, "Publication not ready");
+        self.resp.validate("resp")?;
+        if self.max_orders == 0 {
+            return Err("max_orders must be greater than zero".to_owned());
         }
-        AeronError::DriverTimeout(_)
-        | AeronError::ClientTimeoutException
-        | AeronError::RegistrationException(_, _)
-        | AeronError::ChannelEndpointException(_, _) => {
-            error!(?error, "Critical Aeron communication failure. Exiting.");
-            process::exit(1);
+        if usize::try_from(self.max_orders).is_err() {
+            return Err(format!("max_orders {} is too large", self.max_orders));
         }
-        _ => {
-            error!(?error, "Unhandled Aeron error. Exiting.");
-            process::exit(1);
+        if self.max_trades == 0 {
+            return Err("max_trades must be greater than zero".to_owned());
         }
+        if usize::try_from(self.max_trades).is_err() {
+            return Err(format!("max_trades {} is too large", self.max_trades));
+        }
+        settings::required("products_path", &self.products_path)?;
+        settings::socket_addr("metrics_addr", &self.metrics_addr)
     }
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn on_new_subscription_handler(channel: CString, stream_id: i32, correlation_id: i64) {
-    let channel_str = channel.to_string_lossy();
-    info!(
-        target: "aeron_callbacks",
-        correlation_id,
-        channel = %channel_str,
-        stream_id,
-        "New subscription successfully established."
-    );
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aeron: TransportConfig::default(),
+            sub: ChannelConfig::default(),
+            reports: PublicationConfig::default(),
+            resp: PublicationConfig::default(),
+            max_orders: 0,
+            max_trades: 1_000_000,
+            metrics_addr: String::new(),
+            products_path: "products.json".to_owned(),
+        }
+    }
 }
 
-#[allow(clippy::needless_pass_by_value)]
-pub fn on_new_exclusive_publication_handler(
-    channel: CString,
-    stream_id: i32,
-    session_id: i32,
-    correlation_id: i64,
-) {
-    let channel_str = channel.to_string_lossy();
-    info!(
-        target: "aeron_callbacks",
-        correlation_id,
-        channel = %channel_str,
-        stream_id,
-        session_id,
-        "New publication successfully established.",
-    );
+/// A stream the engine publishes on and how it waits while the stream is backed up.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PublicationConfig {
+    pub channel: String,
+    pub stream_id: i32,
+    /// `busy_spin`, `yielding`, `back_off` or `sleeping:<micros>`.
+    pub idle_strategy: String,
+    pub max_spin_us: u64,
+    pub alert_after_ms: u64,
+    /// `block` or `halt_inbound`, or `drop` on `resp` only.
+    pub back_pressure_action: String,
 }
 
-fn str_to_c(val: &str) -> CString {
-    CString::new(val).unwrap_or_else(|err| {
-        error!(input_string = %val, error = ?err, "Failed to convert string to CString. Input may contain null bytes.");
-        panic!("Critical error: Failed to convert string"); // TODO: NO PANICS
-    })
-}
+impl PublicationConfig {
+    pub fn channel(&self) -> ChannelConfig {
+        ChannelConfig::new(self.channel.clone(), self.stream_id)
+    }
+
+    /// The back-pressure policy; only valid once `validate` has passed.
+    pub fn policy(&self) -> BackPressurePolicy {
+        self.try_policy()
+            .expect("publication settings are validated on load")
+    }
 
-#[allow(clippy::option_if_let_else)]
-pub fn get_aeron_dir() -> String {
-    let aeron_dir = match env::var("AERON_DIR") {
-        Ok(path_str) => path_str,
-        Err(_) => {
-            #[cfg(target_os = "macos")]
-            {
-                "/Volumes/DevShm/aeron".to_owned()
-            }
-            #[cfg(target_os = "linux")]
-            {
-                "/dev/shm/aeron"
-            }
-            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-            {
-                let os_type = env::consts::OS;
-                error!(
-                    target: "configuration",
-                    os = %os_type,
-                    "AERON_DIR environment variable not set and no default path configured for this OS. Please set AERON_DIR. Supported OS for defaults: macOS, Linux. Exiting."
-                );
-                process::exit(1);
-            }
+    fn validate(&self, name: &str) -> Result<(), String> {
+        self.channel().validate(name)?;
+        if self.alert_after_ms == 0 {
+            return Err(format!("{name}.alert_after_ms must be greater than zero"));
         }
-    };
+        self.try_policy()
+            .map(|_| ())
+            .map_err(|err| format!("{name}: {err}"))
+    }
 
-    info!(target: "configuration", aeron_dir = %aeron_dir, "Using Aeron directory");
-    aeron_dir
+    fn try_policy(&self) -> Result<BackPressurePolicy, String> {
+        Ok(BackPressurePolicy {
+            idle_strategy: self.idle_strategy.parse()?,
+            max_spin: Duration::from_micros(self.max_spin_us),
+            alert_after: Duration::from_millis(self.alert_after_ms),
+            action: self.back_pressure_action.parse()?,
+        })
+    }
 }
 
-fn wait_for<T, F>(mut finder: F, label: &str, timeout_duration: Duration) -> T
-where
-    F: FnMut() -> Option<T>,
-{
-    let start = Instant::now();
-
-    loop {
-        if let Some(found) = finder() {
-            return found;
-        }
-
-        if start.elapsed() > timeout_duration {
-            error!(
-                target: "aeron_setup",
-                timeout_seconds = timeout_duration.as_secs_f64(),
-                resource_label = %label,
-                "Timed out waiting for Aeron resource to become ready. Ensure the Aeron media driver is running and configured correctly. Exiting."
-            );
-            process::exit(1);
+impl Default for PublicationConfig {
+    #[allow(clippy::cast_possible_truncation)]
+    fn default() -> Self {
+        let policy = BackPressurePolicy::default();
+        Self {
+            channel: String::new(),
+            stream_id: 0,
+            idle_strategy: "busy_spin".to_owned(),
+            max_spin_us: policy.max_spin.as_micros() as u64,
+            alert_after_ms: policy.alert_after.as_millis() as u64,
+            back_pressure_action: "block".to_owned(),
         }
-
-        sleep(Duration::from_millis(50));
     }
 }
 
-pub fn create_subscription(aeron: &mut Aeron) -> Arc<Mutex<Subscription>> {
-    info!(target: "aeron_setup", "Creating Aeron subscription.");
-
-    let channel = env::var("SUB_CHANNEL").unwrap_or_else(|err| {
-        error!(target: "configuration", variable = "SUB_CHANNEL", error = ?err, "Required environment variable for subscription channel not set. Exiting.");
-        process::exit(1);
-    });
-    let stream_id_str = env::var("SUB_STREAM_ID").unwrap_or_else(|err| {
-        error!(target: "configuration", variable = "SUB_STREAM_ID", error = ?err, "Required environment variable for subscription stream ID not set. Exiting.");
-        process::exit(1);
-    });
-    let stream_id = stream_id_str.parse::<i32>().unwrap_or_else(|err| {
-        error!(target: "configuration", variable = "SUB_STREAM_ID", value = %stream_id_str, error = ?err, "Failed to parse SUB_STREAM_ID as i32. Exiting.");
-        process::exit(1);
-    });
-
-    info!(target: "aeron_setup", channel = %channel, stream_id = stream_id, "Attempting to add subscription.");
-    let c_channel = str_to_c(&channel);
-    let subscription_id: i64 = match aeron.add_subscription(c_channel, stream_id) {
-        Ok(id) => id,
-        Err(err) => {
-            error!(target: "aeron_setup", kind="add_subscription_failed", error=?err, channel=%channel, stream_id=stream_id, "Failed to add Aeron subscription. See Aeron error details above/below. Exiting.");
-            error_handler(err);
-            unreachable!("error_handler should have exited");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publication(channel: &str, stream_id: i32, action: &str) -> PublicationConfig {
+        PublicationConfig {
+            channel: channel.to_owned(),
+            stream_id,
+            back_pressure_action: action.to_owned(),
+            ..PublicationConfig::default()
         }
-    };
-    info!(target: "aeron_setup", subscription_id, "Subscription added, waiting for it to become available.");
-
-    let subscription = wait_for(
-        || aeron.find_subscription(subscription_id).ok(),
-        &format!("subscription (id: {subscription_id}) on channel '{channel}'"),
-        Duration::from_secs(15),
-    );
-
-    let sub_guard = subscription.lock().unwrap_or_else(|poisoned_err| {
-        error!(target: "concurrency_error", resource = "subscription_mutex", id = subscription_id, error = %poisoned_err, "Mutex for subscription is poisoned. Exiting.");
-        process::exit(1);
-    });
-    let channel_status = sub_guard.channel_status();
-    info!(target: "aeron_status", kind = "subscription_channel_status", status = %channel_status_to_str(channel_status), subscription_id = sub_guard.registration_id(), "Subscription channel status.");
-    drop(sub_guard);
-
-    info!(target: "aeron_setup", "Aeron subscription created.");
-    subscription
-}
+    }
 
-pub fn create_exclusive_publication(aeron: &mut Aeron) -> Arc<Mutex<ExclusivePublication>> {
-    info!(target: "aeron_setup", "Creating Aeron publication.");
-
-    let channel = env::var("PUB_CHANNEL").unwrap_or_else(|e| {
-        error!(target: "configuration", variable = "PUB_CHANNEL", error = ?e, "Required environment variable for publication channel not set. Exiting.");
-        process::exit(1);
-    });
-    let stream_id_str = env::var("PUB_STREAM_ID").unwrap_or_else(|e| {
-        error!(target: "configuration", variable = "PUB_STREAM_ID", error = ?e, "Required environment variable for publication stream ID not set. Exiting.");
-        process::exit(1);
-    });
-    let stream_id = stream_id_str.parse::<i32>().unwrap_or_else(|e| {
-        error!(target: "configuration", variable = "PUB_STREAM_ID", value = %stream_id_str, error = ?e, "Failed to parse PUB_STREAM_ID as i32. Exiting.");
-        process::exit(1);
-    });
-
-    info!(target: "aeron_setup", channel = %channel, stream_id = stream_id, "Attempting to add exclusive publication.");
-    let c_channel = str_to_c(&channel); // str_to_c will exit on failure
-    let publication_id: i64 = match aeron.add_exclusive_publication(c_channel, stream_id) {
-        Ok(id) => id,
-        Err(err) => {
-            error!(target: "aeron_setup", kind="add_publication_failed", error=?err, channel=%channel, stream_id=stream_id, "Failed to add Aeron exclusive publication. See Aeron error details above/below. Exiting.");
-            error_handler(err); // error_handler will now also exit
-            unreachable!("error_handler should have exited"); // Should not be reached if error_handler exits
+    fn config(reports_action: &str, resp_action: &str) -> Config {
+        Config {
+            sub: ChannelConfig::new("aeron:udp?endpoint=localhost:40123", 1001),
+            reports: publication("aeron:ipc", 1002, reports_action),
+            resp: publication("aeron:ipc", 1003, resp_action),
+            max_orders: 16,
+            metrics_addr: "127.0.0.1:9100".to_owned(),
+            ..Config::default()
         }
-    };
-    info!(target: "aeron_setup", publication_id, "Exclusive publication added, waiting for it to become available.");
-
-    let publication = wait_for(
-        // wait_for will exit on timeout
-        || aeron.find_exclusive_publication(publication_id).ok(),
-        &format!("publication (id: {publication_id}) on channel '{channel}'"),
-        Duration::from_secs(15),
-    );
-
-    let pub_guard = publication.lock().unwrap_or_else(|poisoned_err| {
-        error!(target: "concurrency_error", resource = "publication_mutex", id = publication_id, error = %poisoned_err, "Mutex for publication is poisoned. Exiting.");
-        process::exit(1);
-    });
-    let channel_status = pub_guard.channel_status();
-    info!(target: "aeron_status", kind = "publication_channel_status", status = %channel_status_to_str(channel_status), publication_id = pub_guard.registration_id(), "Publication channel status.");
-    drop(pub_guard);
-
-    info!(target: "aeron_setup", "Aeron publication created.");
-
-    publication
-}
+    }
 
-pub fn order_count_max() -> u64 {
-    let max_order_str = env::var("MAX_ORDERS").unwrap_or_else(|e| {
-        // TODO: ERROR HANDLER
-        error!(target: "configuration", variable = "orders_count_max", error = ?e, "Required environment variable for order book max orders not set. Exiting.");
-        process::exit(1);
-    });
-    max_order_str.parse::<u64>().unwrap_or_else(|e| {
-        // TODO: ERROR HANDLER
-        error!(target: "configuration", variable = "orders_count_max", value = %max_order_str, error = ?e, "Failed to parse orders_count_max as usize. Exiting.");
-        process::exit(1);
-    })
+    #[test]
+    fn drops_status_replies_but_not_execution_reports() {
+        assert_eq!(config("halt_inbound", "block").validate(), Ok(()));
+        let config_with_resp_drop = config("block", "drop");
+        assert_eq!(config_with_resp_drop.validate(), Ok(()));
+        assert_eq!(
+            config_with_resp_drop.resp.policy().action,
+            BackPressureAction::Drop
+        );
+
+        let err = config("drop", "block").validate().unwrap_err();
+        assert!(
+            err.starts_with("pub.back_pressure_action cannot be drop"),
+            "{err}"
+        );
+    }
 }
@@ -0,0 +1,135 @@
+use crate::metrics::Metrics;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tracing::{error, info, warn};
+
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves `GET /metrics` in the Prometheus text format from a dedicated thread, so scrapes never
+/// touch the engine thread beyond reading its atomic counters.
+pub fn spawn_metrics_server(addr: &str, metrics: Arc<Metrics>) {
+    let listener = TcpListener::bind(addr).unwrap_or_else(|err| {
+        error!(target: "metrics", address = %addr, error = ?err, "Failed to bind metrics endpoint. Exiting.");
+        process::exit(1);
+    });
+    info!(target: "metrics", address = %addr, "Serving Prometheus metrics on /metrics.");
+
+    thread::Builder::new()
+        .name("metrics-http".to_owned())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        if let Err(err) = handle_connection(stream, &metrics) {
+                            warn!(target: "metrics", error = ?err, "Failed to answer metrics request.");
+                        }
+                    }
+                    Err(err) => {
+                        warn!(target: "metrics", error = ?err, "Failed to accept metrics connection.");
+                    }
+                }
+            }
+        })
+        .unwrap_or_else(|err| {
+            error!(target: "metrics", error = ?err, "Failed to spawn metrics thread. Exiting.");
+            process::exit(1);
+        });
+}
+
+fn handle_connection(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut request = Vec::with_capacity(1024);
+    let mut chunk = [0_u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&chunk[..read]);
+    }
+
+    let request_line = request
+        .split(|&b| b == b'\r' || b == b'\n')
+        .next()
+        .unwrap_or_default();
+    let mut parts = request_line.split(|&b| b == b' ');
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        (b"GET", b"/metrics") => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            metrics.render_prometheus(),
+        ),
+        (b"GET", _) => ("404 Not Found", "text/plain", "not found\n".to_owned()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_owned(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Shutdown;
+
+    /// Sends `request` to a connection answered by `handle_connection` and returns the response.
+    fn exchange(metrics: &Metrics, request: &[u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(request).unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+
+        let (server, _) = listener.accept().unwrap();
+        handle_connection(server, metrics).unwrap();
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_the_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_malformed_message();
+        let response = exchange(&metrics, b"GET /metrics HTTP/1.1\r\nHost: engine\r\n\r\n");
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8"));
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        assert_eq!(body, metrics.render_prometheus());
+        assert!(body.contains("me_messages_total{type=\"malformed\"} 1\n"));
+    }
+
+    #[test]
+    fn answers_other_requests_with_errors() {
+        let metrics = Metrics::new();
+        let response = exchange(&metrics, b"GET / HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        let response = exchange(&metrics, b"POST /metrics HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        // A request cut short is answered all the same.
+        let response = exchange(&metrics, b"GET /metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
@@ -1,23 +1,26 @@
-use crate::config::order_count_max;
 use crate::orderbook::OrderBook;
-use crate::publisher::Publisher;
+use crate::publisher::{Publisher, Status};
 use crate::side::{Buy, Sell, SideSpecificContext};
-use crate::types::{CancelRequest, Order};
+use crate::trades::TradeLedger;
+use crate::types::{CancelRequest, Order, SymbolType};
 
 use std::cmp::min;
+use std::collections::HashSet;
 
-use sbe::ReadBuf;
 use sbe::cxl_rej_reason_enum::CxlRejReasonEnum;
 use sbe::cxl_rej_response_to_enum::CxlRejResponseToEnum;
-use sbe::message_header_codec::MessageHeaderDecoder;
-use sbe::new_order_single_codec::NewOrderSingleDecoder;
 use sbe::ord_rej_reason_enum::OrdRejReasonEnum;
 use sbe::ord_type_enum::OrdTypeEnum;
-use sbe::order_cancel_request_codec::OrderCancelRequestDecoder;
 use sbe::side_enum::SideEnum;
+use sbe::{
+    Decimal64, NewOrderSingle, OrderCancelRequest, OrderMassStatusRequest, OrderStatusRequest,
+    TradeBustRequest, TradeCorrectRequest,
+};
 
 use tracing::error;
 
+use transport::MessageSink;
+
 macro_rules! execute_trade {
     ($self:expr, $aggressor_order:expr, $resting_order:expr) => {{
         let trade_quantity = min(
@@ -30,6 +33,14 @@ macro_rules! execute_trade {
         $resting_order.fill(trade_quantity, trade_px);
 
         $self.counter_match_id += 1;
+        $self.trades.record(
+            $self.counter_match_id,
+            $aggressor_order,
+            $resting_order,
+            trade_quantity,
+            trade_px,
+        );
+
         $self.counter_exec_id += 1;
         $self.publisher.publish_trade(
             $aggressor_order,
@@ -50,33 +61,54 @@ macro_rules! execute_trade {
     }};
 }
 
-pub struct Handler {
+pub struct Handler<P: MessageSink> {
     pub book: OrderBook,
     pub counter_order_id: u64,
     pub counter_exec_id: u64,
     pub counter_match_id: u64,
-    pub publisher: Publisher,
+    pub trades: TradeLedger,
+    /// Symbols orders are accepted for.
+    pub symbols: HashSet<SymbolType>,
+    pub publisher: Publisher<P>,
+    pub responder: Publisher<P>,
 }
 
-impl Handler {
-    pub fn new(publisher: Publisher) -> Self {
-        let order_count_max = match usize::try_from(order_count_max()) {
-            Ok(usize) => usize,
-            Err(error) => panic!("Problem opening the file: {error:?}"), // TODO: NO PANIC
-        };
-
+impl<P: MessageSink> Handler<P> {
+    pub fn new(
+        publisher: Publisher<P>,
+        responder: Publisher<P>,
+        max_orders: usize,
+        max_trades: usize,
+        symbols: HashSet<SymbolType>,
+    ) -> Self {
         Self {
-            book: OrderBook::new(order_count_max),
+            book: OrderBook::new(max_orders),
             counter_order_id: 0,
             counter_exec_id: 0,
             counter_match_id: 0,
+            trades: TradeLedger::new(max_trades),
+            symbols,
             publisher,
+            responder,
         }
     }
 
+    /// True while either publication has reports parked by a `HaltInbound` back-pressure policy.
     #[inline(always)]
-    pub fn process_new_order(&mut self, header_decoder: MessageHeaderDecoder<ReadBuf<'_>>) {
-        let mut order = self.process_new_order_decode(header_decoder);
+    pub fn is_halted(&self) -> bool {
+        self.publisher.is_halted() || self.responder.is_halted()
+    }
+
+    /// Retries parked reports on both publications; returns true once inbound may resume.
+    pub fn flush_parked(&mut self) -> bool {
+        let publisher_flushed = !self.publisher.is_halted() || self.publisher.flush_parked();
+        let responder_flushed = !self.responder.is_halted() || self.responder.flush_parked();
+        publisher_flushed && responder_flushed
+    }
+
+    #[inline(always)]
+    pub fn process_new_order(&mut self, message: &NewOrderSingle) {
+        let mut order = self.process_new_order_decode(message);
 
         if self.book.is_full() {
             self.publish_reject(&order, OrdRejReasonEnum::Other);
@@ -88,6 +120,17 @@ impl Handler {
             return;
         }
 
+        if !self.symbols.contains(&order.symbol) {
+            self.publish_reject(&order, OrdRejReasonEnum::Other);
+            error!(
+                target: "matching_engine",
+                order_id = ?order.client_order_id,
+                symbol = %String::from_utf8_lossy(&order.symbol),
+                "Order rejected: the symbol is not a product that is online",
+            );
+            return;
+        }
+
         if self
             .book
             .order_key_map
@@ -102,11 +145,11 @@ impl Handler {
     }
 
     #[inline(always)]
-    pub fn process_cancel_order(&mut self, header_decoder: MessageHeaderDecoder<ReadBuf<'_>>) {
+    pub fn process_cancel_order(&mut self, message: &OrderCancelRequest) {
         tracing::warn!(target: "INVESTIGATION", "--- PROCESS_CANCEL_ORDER WAS CALLED ---");
 
-        let request = self.process_cancel_order_decode(header_decoder);
-        let order_key = (request.account, request.client_order_id);
+        let request = self.process_cancel_order_decode(message);
+        let order_key = (request.account, request.original_client_order_id);
 
         if !self.book.order_key_map.contains_key(&order_key) {
             self.publish_cancel_reject(
@@ -121,36 +164,210 @@ impl Handler {
         self.publish_cancel(&order);
     }
 
-    #[inline(always)]
-    fn process_new_order_decode(
+    /// Answers with the state of a working order, or of one that is done but still has trades in
+    /// the ledger, or else a rejected status report.
+    pub fn process_order_status(&mut self, request: &OrderStatusRequest) {
+        let client_order_id = request.cl_ord_id.as_u128();
+        let account = request.account.as_u128();
+        let status = Status {
+            mass_status_req_id: u64::MAX,
+            tot_num_reports: 1,
+        };
+
+        let order = self
+            .book
+            .order_key_map
+            .get(&(account, client_order_id))
+            .and_then(|&idx| self.book.pool.get(idx))
+            .copied()
+            .or_else(|| {
+                // Off the book, so filled or cancelled whatever it had left.
+                self.trades
+                    .find((account, client_order_id))
+                    .map(|&order| Order {
+                        leaves_quantity: 0,
+                        ..order
+                    })
+            });
+
+        match order {
+            Some(order) => self.responder.publish_order_status(&order, status),
+            None => self
+                .responder
+                .publish_unknown_order_status(&Order::unknown(account, client_order_id), status),
+        }
+    }
+
+    /// Answers with one status report per working order of the account, each carrying the total
+    /// number of reports. An account without working orders gets a single rejected status report
+    /// with `TotNumReports` 0.
+    pub fn process_order_mass_status(&mut self, request: &OrderMassStatusRequest) {
+        let mass_status_req_id = request.mass_status_req_id;
+        let account = request.account.as_u128();
+
+        let mut orders: Vec<Order> = self
+            .book
+            .pool
+            .iter()
+            .map(|(_, order)| *order)
+            .filter(|order| order.account == account)
+            .collect();
+        orders.sort_unstable_by_key(|order| order.sequence_number);
+
+        let status = Status {
+            mass_status_req_id,
+            tot_num_reports: u32::try_from(orders.len()).unwrap_or(u32::MAX),
+        };
+
+        if orders.is_empty() {
+            self.responder
+                .publish_unknown_order_status(&Order::unknown(account, 0), status);
+            return;
+        }
+
+        for order in &orders {
+            self.responder.publish_order_status(order, status);
+        }
+    }
+
+    /// Reverses the fills of a trade on both orders, or answers with a rejected report on the
+    /// response stream if the `TrdMatchID` is unknown.
+    pub fn process_trade_bust(&mut self, request: &TradeBustRequest) {
+        let match_id = request.trd_match_id;
+
+        let Some(trade) = self.trades.get(match_id) else {
+            error!(
+                target: "matching_engine_admin",
+                match_id,
+                "Trade bust rejected: unknown TrdMatchID",
+            );
+            self.responder.publish_trade_reject(
+                match_id,
+                i64::MIN,
+                i64::MIN,
+                OrdRejReasonEnum::UnknownOrder,
+            );
+            return;
+        };
+
+        for order_id in trade.order_ids {
+            let Some(order) = self.adjust_fill(order_id, -trade.qty, -trade.notional()) else {
+                continue;
+            };
+            self.counter_exec_id += 1;
+            self.publisher.publish_trade_cancel(
+                &order,
+                self.counter_exec_id,
+                match_id,
+                trade.qty,
+                trade.px,
+            );
+        }
+        // Only once the orders are amended, as it forgets those that traded only here.
+        self.trades.remove(match_id);
+    }
+
+    /// Amends the quantity and price of a trade on both orders, or answers with a rejected report
+    /// on the response stream if it cannot.
+    pub fn process_trade_correct(&mut self, request: &TradeCorrectRequest) {
+        let match_id = request.trd_match_id;
+        let qty = request.last_qty.mantissa();
+        let px = request.last_px.mantissa();
+
+        let Some(trade) = self.trades.get(match_id) else {
+            error!(
+                target: "matching_engine_admin",
+                match_id,
+                "Trade correction rejected: unknown TrdMatchID",
+            );
+            self.responder
+                .publish_trade_reject(match_id, qty, px, OrdRejReasonEnum::UnknownOrder);
+            return;
+        };
+
+        // A correction may only reduce the traded quantity, since the leaves quantity of
+        // either order cannot be reinstated to absorb a larger fill.
+        if qty <= 0 || qty > trade.qty || px <= 0 {
+            error!(
+                target: "matching_engine_admin",
+                match_id,
+                qty,
+                px,
+                original_qty = trade.qty,
+                "Trade correction rejected: corrected quantity must be positive and not exceed the original, price must be positive",
+            );
+            self.responder
+                .publish_trade_reject(match_id, qty, px, OrdRejReasonEnum::Other);
+            return;
+        }
+
+        self.trades.update(match_id, qty, px);
+        let notional_delta = i128::from(qty) * i128::from(px) - trade.notional();
+
+        for order_id in trade.order_ids {
+            let Some(order) = self.adjust_fill(order_id, qty - trade.qty, notional_delta) else {
+                continue;
+            };
+            self.counter_exec_id += 1;
+            self.publisher
+                .publish_trade_correct(&order, self.counter_exec_id, match_id, qty, px);
+        }
+    }
+
+    /// Applies a fill amendment to the ledger copy of the order and, if the order is still
+    /// resting, to the live order in the book. Returns the order state to report; orders that are
+    /// no longer working are reported with zero leaves quantity.
+    fn adjust_fill(
         &mut self,
-        header_decoder: MessageHeaderDecoder<ReadBuf<'_>>,
-    ) -> Order {
-        let decoder: NewOrderSingleDecoder<'_> =
-            NewOrderSingleDecoder::default().header(header_decoder, 0);
+        order_id: u64,
+        qty_delta: i64,
+        notional_delta: i128,
+    ) -> Option<Order> {
+        let Some(order) = self.trades.order_mut(order_id) else {
+            error!(
+                target: "matching_engine_admin",
+                order_id,
+                "Trade ledger is missing an order referenced by a trade",
+            );
+            return None;
+        };
+        order.adjust_fill(qty_delta, notional_delta);
+        let mut order = *order;
+
+        let live_order = self
+            .book
+            .order_key_map
+            .get(&order.key())
+            .and_then(|&idx| self.book.pool.get_mut(idx))
+            .filter(|live| live.sequence_number == order_id);
+
+        if let Some(live) = live_order {
+            live.adjust_fill(qty_delta, notional_delta);
+            order.leaves_quantity = live.leaves_quantity;
+        } else {
+            order.leaves_quantity = 0;
+        }
 
+        Some(order)
+    }
+
+    #[inline(always)]
+    fn process_new_order_decode(&mut self, message: &NewOrderSingle) -> Order {
         self.counter_order_id += 1;
-        let quantity = decoder.order_qty_decoder().mantissa();
+        let quantity = message.order_qty.mantissa();
 
         Order {
             prev_order_idx: None,
             next_order_idx: None,
 
-            client_order_id: {
-                let id = decoder.cl_ord_id();
-                (u128::from(id[0]) << 64) | u128::from(id[1])
-            },
-            account: {
-                let id = decoder.account();
-                (u128::from(id[0]) << 64) | u128::from(id[1])
-            },
+            client_order_id: message.cl_ord_id.as_u128(),
+            account: message.account.as_u128(),
 
-            symbol: decoder.symbol(),
-            side: decoder.side(),
-            transact_time: decoder.transact_time_decoder().time(),
+            symbol: message.symbol,
+            side: message.side,
             quantity,
-            r#type: decoder.ord_type(),
-            price: decoder.price_decoder().mantissa(),
+            r#type: message.ord_type,
+            price: Decimal64::to_nullable(message.price),
             sequence_number: self.counter_order_id,
             leaves_quantity: quantity,
             cumulative_quantity: 0,
@@ -159,31 +376,16 @@ impl Handler {
     }
 
     #[inline(always)]
-    fn process_cancel_order_decode(
-        &mut self,
-        header_decoder: MessageHeaderDecoder<ReadBuf<'_>>,
-    ) -> CancelRequest {
-        let decoder: OrderCancelRequestDecoder<'_> =
-            OrderCancelRequestDecoder::default().header(header_decoder, 0);
-
+    fn process_cancel_order_decode(&mut self, message: &OrderCancelRequest) -> CancelRequest {
         self.counter_order_id += 1;
 
         CancelRequest {
-            original_client_order_id: {
-                let id = decoder.orig_cl_ord_id();
-                (u128::from(id[0]) << 64) | u128::from(id[1])
-            },
-            client_order_id: {
-                let id = decoder.cl_ord_id();
-                (u128::from(id[0]) << 64) | u128::from(id[1])
-            },
-            account: {
-                let id = decoder.account();
-                (u128::from(id[0]) << 64) | u128::from(id[1])
-            },
-            // transact_time: decoder.transact_time_decoder().time(),
-            // symbol: decoder.symbol(),
-            // side: decoder.side(),
+            original_client_order_id: message.orig_cl_ord_id.as_u128(),
+            client_order_id: message.cl_ord_id.as_u128(),
+            account: message.account.as_u128(),
+            // transact_time: message.transact_time,
+            // symbol: message.symbol,
+            // side: message.side,
         }
     }
 
@@ -353,3 +555,263 @@ impl Handler {
             .publish_reject(order, self.counter_exec_id, reason);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::Metrics;
+
+    use std::sync::Arc;
+
+    use sbe::exec_type_enum::ExecTypeEnum;
+    use sbe::ord_status_enum::OrdStatusEnum;
+    use sbe::{ExecutionReport, Message, Uuid, decode_message};
+
+    use transport::{Loopback, LoopbackSink, LoopbackSource, MessageSource};
+
+    const SELLER: u128 = 0xA;
+    const BUYER: u128 = 0xB;
+
+    /// A handler publishing reports and responses alike to the returned source.
+    fn handler(max_trades: usize) -> (Handler<LoopbackSink>, LoopbackSource) {
+        let reports = Loopback::new();
+        let source = reports.source();
+        let metrics = Arc::new(Metrics::new());
+        let publisher = Publisher::new(reports.sink(), Default::default(), Arc::clone(&metrics));
+        let responder = Publisher::new(reports.sink(), Default::default(), metrics);
+        let symbols = HashSet::from([*b"JSP\0\0\0"]);
+        let handler = Handler::new(publisher, responder, 16, max_trades, symbols);
+        (handler, source)
+    }
+
+    fn limit(account: u128, cl_ord_id: u128, side: SideEnum, qty: i64, px: i64) -> NewOrderSingle {
+        NewOrderSingle {
+            cl_ord_id: Uuid::from_u128(cl_ord_id),
+            account: Uuid::from_u128(account),
+            transact_time: 0,
+            order_qty: Decimal64::from_mantissa(qty),
+            price: Some(Decimal64::from_mantissa(px)),
+            symbol: *b"JSP\0\0\0",
+            side,
+            ord_type: OrdTypeEnum::Limit,
+        }
+    }
+
+    fn drain(source: &mut LoopbackSource) -> Vec<ExecutionReport> {
+        let mut reports = Vec::new();
+        source.poll(
+            &mut |frame| match decode_message(frame).unwrap() {
+                Message::ExecutionReport(report) => reports.push(report),
+                message => panic!("published {message:?}"),
+            },
+            usize::MAX,
+        );
+        reports
+    }
+
+    /// Rests a sell of 10 at 100 and crosses it with a buy of 4, trading as `TrdMatchID` 1.
+    fn traded(max_trades: usize) -> (Handler<LoopbackSink>, LoopbackSource) {
+        let (mut handler, mut source) = handler(max_trades);
+        handler.process_new_order(&limit(SELLER, 1, SideEnum::Sell, 10, 100));
+        handler.process_new_order(&limit(BUYER, 2, SideEnum::Buy, 4, 100));
+        let trades = drain(&mut source)
+            .into_iter()
+            .filter(|report| report.exec_type == ExecTypeEnum::Trade)
+            .count();
+        assert_eq!(trades, 2);
+        (handler, source)
+    }
+
+    fn bust(match_id: u64) -> TradeBustRequest {
+        TradeBustRequest {
+            trd_match_id: match_id,
+            transact_time: 0,
+        }
+    }
+
+    fn correct(match_id: u64, qty: i64, px: i64) -> TradeCorrectRequest {
+        TradeCorrectRequest {
+            trd_match_id: match_id,
+            transact_time: 0,
+            last_qty: Decimal64::from_mantissa(qty),
+            last_px: Decimal64::from_mantissa(px),
+        }
+    }
+
+    #[test]
+    fn bust_reverses_the_fill_of_both_orders() {
+        let (mut handler, mut source) = traded(8);
+        handler.process_trade_bust(&bust(1));
+
+        let reports = drain(&mut source);
+        assert_eq!(reports.len(), 2);
+        for report in &reports {
+            assert_eq!(report.exec_type, ExecTypeEnum::TradeCancel);
+            assert_eq!(report.trd_match_id, Some(1));
+            assert_eq!(report.last_qty, Some(Decimal64::from_mantissa(4)));
+            assert_eq!(report.cum_qty, Decimal64::ZERO);
+        }
+        // The filled buy left the book; the sell still rests with what it had left.
+        let (buy, sell) = (&reports[0], &reports[1]);
+        assert_eq!(buy.account, Uuid::from_u128(BUYER));
+        assert_eq!(
+            (buy.leaves_qty, buy.ord_status),
+            (Decimal64::ZERO, OrdStatusEnum::Canceled)
+        );
+        assert_eq!(
+            (sell.leaves_qty, sell.ord_status),
+            (Decimal64::from_mantissa(6), OrdStatusEnum::New)
+        );
+
+        // A trade is busted once.
+        handler.process_trade_bust(&bust(1));
+        let reject = drain(&mut source);
+        assert_eq!(reject.len(), 1);
+        assert_eq!(
+            (reject[0].exec_type, reject[0].ord_rej_reason),
+            (ExecTypeEnum::Rejected, Some(OrdRejReasonEnum::UnknownOrder))
+        );
+        assert_eq!((reject[0].trd_match_id, reject[0].exec_id), (Some(1), 0));
+        assert!(handler.trades.get(1).is_none());
+    }
+
+    #[test]
+    fn correction_amends_quantity_and_price() {
+        let (mut handler, mut source) = traded(8);
+        handler.process_trade_correct(&correct(1, 3, 90));
+
+        let reports = drain(&mut source);
+        assert_eq!(reports.len(), 2);
+        for report in &reports {
+            assert_eq!(report.exec_type, ExecTypeEnum::TradeCorrect);
+            assert_eq!(report.last_qty, Some(Decimal64::from_mantissa(3)));
+            assert_eq!(report.last_px, Some(Decimal64::from_mantissa(90)));
+            assert_eq!(report.cum_qty, Decimal64::from_mantissa(3));
+            assert_eq!(report.avg_px, Some(Decimal64::from_mantissa(90)));
+        }
+        assert_eq!(reports[1].ord_status, OrdStatusEnum::PartiallyFilled);
+
+        let trade = handler.trades.get(1).unwrap();
+        assert_eq!((trade.qty, trade.px), (3, 90));
+    }
+
+    #[test]
+    fn rejects_corrections_it_cannot_apply() {
+        let (mut handler, mut source) = traded(8);
+        handler.process_trade_correct(&correct(1, 5, 100));
+        handler.process_trade_correct(&correct(1, 0, 100));
+        handler.process_trade_correct(&correct(1, 2, 0));
+        handler.process_trade_correct(&correct(2, 2, 100));
+
+        let rejects = drain(&mut source);
+        let reasons: Vec<_> = rejects
+            .iter()
+            .map(|report| (report.trd_match_id, report.ord_rej_reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                (Some(1), Some(OrdRejReasonEnum::Other)),
+                (Some(1), Some(OrdRejReasonEnum::Other)),
+                (Some(1), Some(OrdRejReasonEnum::Other)),
+                (Some(2), Some(OrdRejReasonEnum::UnknownOrder)),
+            ]
+        );
+        for report in &rejects {
+            assert_eq!(
+                (report.exec_type, report.ord_status, report.exec_id),
+                (ExecTypeEnum::Rejected, OrdStatusEnum::Rejected, 0)
+            );
+        }
+        assert_eq!(rejects[0].last_qty, Some(Decimal64::from_mantissa(5)));
+        assert_eq!(handler.trades.get(1).unwrap().qty, 4);
+    }
+
+    fn status(
+        handler: &mut Handler<LoopbackSink>,
+        source: &mut LoopbackSource,
+        account: u128,
+        cl_ord_id: u128,
+    ) -> ExecutionReport {
+        handler.process_order_status(&OrderStatusRequest {
+            cl_ord_id: Uuid::from_u128(cl_ord_id),
+            account: Uuid::from_u128(account),
+            transact_time: 0,
+        });
+        let mut reports = drain(source);
+        assert_eq!(reports.len(), 1);
+        reports.remove(0)
+    }
+
+    #[test]
+    fn reports_the_status_of_orders_that_are_done() {
+        let (mut handler, mut source) = traded(8);
+        let sell = status(&mut handler, &mut source, SELLER, 1);
+        assert_eq!(
+            (sell.ord_status, sell.leaves_qty, sell.cum_qty),
+            (
+                OrdStatusEnum::PartiallyFilled,
+                Decimal64::from_mantissa(6),
+                Decimal64::from_mantissa(4)
+            )
+        );
+
+        let buy = status(&mut handler, &mut source, BUYER, 2);
+        assert_eq!(buy.exec_type, ExecTypeEnum::OrderStatus);
+        assert_eq!(
+            (buy.ord_status, buy.leaves_qty, buy.cum_qty),
+            (
+                OrdStatusEnum::Filled,
+                Decimal64::ZERO,
+                Decimal64::from_mantissa(4)
+            )
+        );
+
+        handler.process_cancel_order(&OrderCancelRequest {
+            cl_ord_id: Uuid::from_u128(9),
+            orig_cl_ord_id: Uuid::from_u128(1),
+            account: Uuid::from_u128(SELLER),
+            transact_time: 0,
+            symbol: *b"JSP\0\0\0",
+            side: SideEnum::Sell,
+        });
+        drain(&mut source);
+        let sell = status(&mut handler, &mut source, SELLER, 1);
+        assert_eq!(
+            (sell.ord_status, sell.leaves_qty, sell.cum_qty),
+            (
+                OrdStatusEnum::Canceled,
+                Decimal64::ZERO,
+                Decimal64::from_mantissa(4)
+            )
+        );
+
+        let unknown = status(&mut handler, &mut source, SELLER, 2);
+        assert_eq!(unknown.ord_status, OrdStatusEnum::Rejected);
+    }
+
+    #[test]
+    fn rejects_orders_for_symbols_it_does_not_trade() {
+        let (mut handler, mut source) = handler(8);
+        let mut order = limit(SELLER, 1, SideEnum::Sell, 10, 100);
+        order.symbol = *b"XYZ\0\0\0";
+        handler.process_new_order(&order);
+
+        let reports = drain(&mut source);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].exec_type, ExecTypeEnum::Rejected);
+        assert!(handler.book.order_key_map.is_empty());
+    }
+
+    #[test]
+    fn forgets_trades_beyond_the_ledger_capacity() {
+        let (mut handler, mut source) = traded(1);
+        handler.process_new_order(&limit(BUYER, 3, SideEnum::Buy, 1, 100));
+        drain(&mut source);
+
+        handler.process_trade_bust(&bust(1));
+        assert_eq!(drain(&mut source)[0].exec_type, ExecTypeEnum::Rejected);
+        handler.process_trade_bust(&bust(2));
+        assert_eq!(drain(&mut source).len(), 2);
+    }
+}
@@ -1,36 +1,32 @@
+mod backpressure;
 mod config;
+mod exporter;
 mod handler;
+mod metrics;
 mod orderbook;
 mod publisher;
 mod side;
+mod trades;
 mod types;
 
-use config::{
-    create_exclusive_publication, create_subscription, error_handler, get_aeron_dir,
-    on_new_exclusive_publication_handler, on_new_subscription_handler,
-};
+use config::Config;
 
+use exporter::spawn_metrics_server;
 use handler::Handler;
+use metrics::{Metrics, MessageType};
 use publisher::Publisher;
 
-use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::process;
-use std::slice;
-use std::sync::{Arc, Mutex};
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::Instant;
 
-use aeron_rs::aeron::Aeron;
-use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
-use aeron_rs::concurrent::logbuffer::header::Header;
 use aeron_rs::concurrent::strategies::{BusySpinIdleStrategy, Strategy};
-use aeron_rs::context::Context;
-use aeron_rs::exclusive_publication::ExclusivePublication;
-use aeron_rs::subscription::Subscription;
-use aeron_rs::fragment_assembler::FragmentAssembler;
-use aeron_rs::utils::types::Index;
 
-use sbe::ReadBuf;
-use sbe::message_header_codec::MessageHeaderDecoder;
+use products::{Product, Products, TradingStatus};
+
+use sbe::{Message, SbeErr, SbeResult, decode_message};
 
 use tracing::Level;
 use tracing::subscriber::set_global_default;
@@ -38,92 +34,47 @@ use tracing_subscriber::FmtSubscriber;
 
 use tracing::{error, info};
 
-struct LatencyMetrics {
-    total_messages: u64,
-    total_latency_sum_ns: u128, // Use u128 for sum to prevent overflow with many messages
-    recent_latencies_ns: VecDeque<u64>,
-    window_size: usize,
+use transport::{AeronTransport, MessageSink, MessageSource, TransportError};
+
+fn aeron_setup_failed(err: TransportError) -> ! {
+    error!(target: "aeron_setup", error = %err, "Failed to set up the Aeron transport. Exiting.");
+    process::exit(1);
 }
 
-impl LatencyMetrics {
-    fn new(window_size: usize) -> Self {
-        LatencyMetrics {
-            total_messages: 0,
-            total_latency_sum_ns: 0,
-            recent_latencies_ns: VecDeque::with_capacity(window_size),
-            window_size,
+/// Validates the header and message block of an inbound frame before handing the decoded
+/// message to the handler, so malformed input is never read out of bounds.
+#[inline(always)]
+fn dispatch<P: MessageSink>(handler: &mut Handler<P>, frame: &[u8]) -> SbeResult<MessageType> {
+    match decode_message(frame)? {
+        Message::NewOrderSingle(order) => {
+            handler.process_new_order(&order);
+            Ok(MessageType::NewOrder)
         }
-    }
-
-    fn add_latency(&mut self, latency_ns: u64) {
-        self.total_messages += 1;
-        self.total_latency_sum_ns += u128::from(latency_ns);
-
-        self.recent_latencies_ns.push_back(latency_ns);
-        if self.recent_latencies_ns.len() > self.window_size {
-            self.recent_latencies_ns.pop_front();
+        Message::OrderCancelRequest(request) => {
+            handler.process_cancel_order(&request);
+            Ok(MessageType::CancelOrder)
         }
-    }
-
-    fn get_total_average(&self) -> f64 {
-        if self.total_messages == 0 {
-            0.0
-        } else {
-            self.total_latency_sum_ns as f64 / self.total_messages as f64
+        Message::TradeBustRequest(request) => {
+            handler.process_trade_bust(&request);
+            Ok(MessageType::TradeBust)
         }
-    }
-
-    fn get_recent_average(&self) -> f64 {
-        if self.recent_latencies_ns.is_empty() {
-            0.0
-        } else {
-            let sum: u64 = self.recent_latencies_ns.iter().sum();
-            sum as f64 / self.recent_latencies_ns.len() as f64
+        Message::TradeCorrectRequest(request) => {
+            handler.process_trade_correct(&request);
+            Ok(MessageType::TradeCorrect)
         }
+        Message::OrderStatusRequest(request) => {
+            handler.process_order_status(&request);
+            Ok(MessageType::OrderStatus)
+        }
+        Message::OrderMassStatusRequest(request) => {
+            handler.process_order_mass_status(&request);
+            Ok(MessageType::OrderMassStatus)
+        }
+        // Messages the engine only sends are counted like templates outside the schema
+        message => Err(SbeErr::UnknownTemplateId(message.template_id())),
     }
 }
 
-/// Unwraps an ExclusivePublication from its Arc<Mutex<>> container.
-///
-/// This is safe to call only when we are sure that we hold the only
-/// strong reference to the Arc, which is true in our single-threaded
-/// matching engine context right after creation.
-fn unwrap_exclusive_publication(
-    wrapped_pub: Arc<Mutex<ExclusivePublication>>,
-) -> ExclusivePublication {
-    // Attempt to get the Mutex out of the Arc.
-    // This will only succeed if the strong count is 1.
-    let mutex = Arc::try_unwrap(wrapped_pub).unwrap_or_else(|_| {
-        error!("Could not unwrap Arc for ExclusivePublication, still has multiple owners. This should not happen in the matching engine. Exiting.");
-        process::exit(1);
-    });
-
-    // Get the ExclusivePublication out of the Mutex.
-    // This consumes the mutex, returning the inner data.
-    mutex.into_inner().unwrap_or_else(|poison_err| {
-        error!(
-            "Mutex for ExclusivePublication is poisoned. Exiting. Error: {}",
-            poison_err
-        );
-        process::exit(1);
-    })
-}
-
-/// Unwraps a Subscription from its Arc<Mutex<>> container.
-fn unwrap_subscription(wrapped_sub: Arc<Mutex<Subscription>>) -> Subscription {
-    let mutex = Arc::try_unwrap(wrapped_sub).unwrap_or_else(|_| {
-        error!("Could not unwrap Arc for Subscription, still has multiple owners. This should not happen in the matching engine. Exiting.");
-        process::exit(1);
-    });
-    mutex.into_inner().unwrap_or_else(|poison_err| {
-        error!(
-            "Mutex for Subscription is poisoned. Exiting. Error: {}",
-            poison_err
-        );
-        process::exit(1);
-    })
-}
-
 fn main() -> ! {
     let subscriber = FmtSubscriber::builder()
         .with_max_level(Level::TRACE)
@@ -134,102 +85,94 @@ fn main() -> ! {
         process::exit(1);
     });
 
-    info!(target: "aeron_setup", "Initializing Aeron context.");
-    let mut context: Context = Context::new();
-    context.set_aeron_dir(get_aeron_dir());
-    context.set_new_exclusive_publication_handler(Box::new(on_new_exclusive_publication_handler));
-    context.set_new_subscription_handler(Box::new(on_new_subscription_handler));
-    context.set_error_handler(Box::new(error_handler));
-    context.set_pre_touch_mapped_memory(true);
-
-    let mut aeron = match Aeron::new(context) {
-        Ok(client) => client,
-        Err(err) => {
-            error!(
-                target: "aeron_setup",
-                kind = "aeron_init_failed",
-                error = ?err,
-                "Failed to create Aeron client instance."
-            );
-            error_handler(err);
-            process::exit(1);
-        }
-    };
-
-    let wrapped_publication = create_exclusive_publication(&mut aeron);
-    let publication = unwrap_exclusive_publication(wrapped_publication);
-
-    let wrapped_subscription = create_subscription(&mut aeron);
-    let mut subscription = unwrap_subscription(wrapped_subscription);
-
-    let publisher = Publisher::new(publication);
-    let mut handler = Handler::new(publisher);
+    let config: Config = settings::load("matching-engine");
+    info!(target: "configuration", sub = %config.sub, reports = %config.reports.channel(), responses = %config.resp.channel(), "Using streams");
+    info!(target: "configuration", reports = %config.reports.policy(), responses = %config.resp.policy(), "Using back-pressure policies");
+
+    info!(target: "aeron_setup", "Initializing Aeron transport.");
+    let mut transport =
+        AeronTransport::connect(&config.aeron).unwrap_or_else(|err| aeron_setup_failed(err));
+
+    let publication = transport
+        .add_exclusive_publication(&config.reports.channel())
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    let response_publication = transport
+        .add_exclusive_publication(&config.resp.channel())
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    let mut subscription = transport
+        .add_subscription(&config.sub)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+
+    let products = Products::load(config.products_path.as_ref()).unwrap_or_else(|err| {
+        error!(target: "configuration", error = %err, "Failed to load the products. Exiting.");
+        process::exit(1);
+    });
+    let symbols: HashSet<_> = products
+        .all()
+        .filter(|product| product.status == TradingStatus::Online)
+        .map(Product::symbol)
+        .collect();
+    info!(target: "configuration", products = products.len(), online = symbols.len(), path = %config.products_path, "Loaded products");
+
+    let metrics = Arc::new(Metrics::new());
+    spawn_metrics_server(&config.metrics_addr, Arc::clone(&metrics));
+
+    let publisher = Publisher::new(publication, config.reports.policy(), Arc::clone(&metrics));
+    let responder = Publisher::new(
+        response_publication,
+        config.resp.policy(),
+        Arc::clone(&metrics),
+    );
+    // Validated to fit on load
+    #[allow(clippy::cast_possible_truncation)]
+    let (max_orders, max_trades) = (config.max_orders as usize, config.max_trades as usize);
+    // Shared between the fragment handler and the poll loop, which flushes parked reports while
+    // inbound is halted.
+    let handler = RefCell::new(Handler::new(
+        publisher, responder, max_orders, max_trades, symbols,
+    ));
 
     let poll_idle_strategy = BusySpinIdleStrategy {};
 
-    let metrics = Arc::new(Mutex::new(LatencyMetrics::new(100_000)));
-    let metrics_clone = Arc::clone(&metrics); // Clone for the closure
-
-    let mut order_message_handler =
-        move |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
-            let base_instant = Instant::now();
-
-            // SAFETY: This creates a slice from the Aeron buffer for zero-copy message processing.
-            // The buffer is guaranteed to be valid for the specified offset and length by Aeron.
-            // The slice lifetime is bounded by this function scope, ensuring memory safety.
-            let slice_msg = unsafe {
-                slice::from_raw_parts_mut(
-                    buffer.buffer().offset(offset.try_into().expect("")), // TODO: NO EXPECT
-                    length.try_into().expect(""),                         // TODO: NO EXPECT
-                )
-            };
-
-            let read_buf = ReadBuf::new(slice_msg);
-            let header_decoder: MessageHeaderDecoder<ReadBuf<'_>> =
-                MessageHeaderDecoder::default().wrap(read_buf, 0);
-            match header_decoder.template_id() {
-                1 => {
-                    handler.process_new_order(header_decoder);
-                    let now_instant = Instant::now();
-                    let delta_ns = now_instant.duration_since(base_instant).as_nanos() as u64;
-                    
-                    // Acquire lock, update metrics, and print averages
-                    let mut metrics_guard = metrics_clone.lock().unwrap_or_else(|poisoned| {
-                        error!("LatencyMetrics mutex poisoned: {:?}", poisoned);
-                        process::exit(1); // Exit if the mutex is poisoned, as state is corrupted
-                    });
-                    metrics_guard.add_latency(delta_ns);
-
-                    // Print statistics periodically, e.g., every 1000 messages or so
-                    if metrics_guard.total_messages % 1000 == 0 {
-                        info!(
-                            target: "matching_engine",
-                            total_messages = metrics_guard.total_messages,
-                            last_delta_ns = delta_ns,
-                            recent_avg_ns = metrics_guard.get_recent_average(),
-                            total_avg_ns = metrics_guard.get_total_average(),
-                            "Processing latency metrics"
-                        );
-                    }
-                }
-                2 => {
-                    handler.process_cancel_order(header_decoder);
-                }
-                unknown_id => {
-                    error!(
-                        target: "matching_engine",
-                        template_id = unknown_id,
-                        "Unknown message template ID received, rejecting message"
-                    );
-                }
+    let mut order_message_handler = |frame: &[u8]| {
+        let base_instant = Instant::now();
+
+        let message_type = match dispatch(&mut handler.borrow_mut(), frame) {
+            Ok(message_type) => message_type,
+            Err(SbeErr::UnknownTemplateId(template_id)) => {
+                metrics.record_unknown_message();
+                error!(
+                    target: "matching_engine",
+                    template_id,
+                    "Unknown message template ID received, rejecting message"
+                );
+                return;
+            }
+            Err(err) => {
+                metrics.record_malformed_message();
+                error!(
+                    target: "matching_engine",
+                    error = ?err,
+                    length = frame.len(),
+                    "Malformed message received, rejecting message"
+                );
+                return;
             }
         };
 
-    let mut fragment_assembler = FragmentAssembler::new(&mut order_message_handler, None);
-    let mut fragment_handler = fragment_assembler.handler();
+        #[allow(clippy::cast_possible_truncation)]
+        let delta_ns = base_instant.elapsed().as_nanos() as u64;
+        metrics.record_processing(message_type, delta_ns);
+    };
 
     loop {
-        let fragments_read = subscription.poll(&mut fragment_handler, 10);
-        poll_idle_strategy.idle_opt(fragments_read);
+        if handler.borrow().is_halted() && !handler.borrow_mut().flush_parked() {
+            poll_idle_strategy.idle();
+            continue;
+        }
+
+        let fragments_read = subscription.poll(&mut order_message_handler, 10);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        poll_idle_strategy.idle_opt(fragments_read as i32);
     }
 }
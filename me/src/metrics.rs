@@ -0,0 +1,448 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Values below 2^SUB_BUCKET_BITS are recorded exactly; larger values keep SUB_BUCKET_BITS - 1
+// significant bits, i.e. better than 1.6% relative precision over the whole u64 range.
+const SUB_BUCKET_BITS: u32 = 7;
+const SUB_BUCKET_COUNT: usize = 1 << SUB_BUCKET_BITS;
+const SUB_BUCKET_HALF_COUNT: usize = SUB_BUCKET_COUNT / 2;
+const BUCKET_COUNT: usize = (64 - SUB_BUCKET_BITS as usize + 2) * SUB_BUCKET_HALF_COUNT;
+
+const QUANTILES: [(f64, &str); 3] = [(0.5, "0.5"), (0.99, "0.99"), (0.999, "0.999")];
+
+/// A log-linear (HDR-style) histogram of `u64` values.
+///
+/// Recording is wait-free and assumes a single writer thread (the engine thread); any number of
+/// threads may read concurrently. Readers see a slightly torn but monotonically growing view,
+/// which is fine for reporting.
+pub struct Histogram {
+    counts: Box<[AtomicU64]>,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            counts: (0..BUCKET_COUNT).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn record(&self, value: u64) {
+        increment(&self.counts[bucket_index(value)], 1);
+        increment(&self.sum, value);
+        if value > self.max.load(Ordering::Relaxed) {
+            self.max.store(value, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let counts: Vec<u64> = self
+            .counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect();
+
+        HistogramSnapshot {
+            count: counts.iter().sum(),
+            sum: self.sum.load(Ordering::Relaxed),
+            max: self.max.load(Ordering::Relaxed),
+            counts,
+        }
+    }
+}
+
+// Single writer: a plain load/store avoids a locked read-modify-write on the hot path.
+#[inline(always)]
+fn increment(counter: &AtomicU64, value: u64) {
+    counter.store(
+        counter.load(Ordering::Relaxed).wrapping_add(value),
+        Ordering::Relaxed,
+    );
+}
+
+#[inline(always)]
+const fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKET_COUNT as u64 {
+        return value as usize;
+    }
+    let magnitude = 63 - value.leading_zeros() - (SUB_BUCKET_BITS - 1);
+    (magnitude as usize + 1) * SUB_BUCKET_HALF_COUNT + (value >> magnitude) as usize
+        - SUB_BUCKET_HALF_COUNT
+}
+
+/// Highest value that falls into the same bucket as values recorded at `index`.
+const fn bucket_upper_bound(index: usize) -> u64 {
+    if index < SUB_BUCKET_COUNT {
+        return index as u64;
+    }
+    let magnitude = (index / SUB_BUCKET_HALF_COUNT - 1) as u32;
+    let sub_bucket = (index % SUB_BUCKET_HALF_COUNT + SUB_BUCKET_HALF_COUNT) as u64;
+    // Same as ((sub_bucket + 1) << magnitude) - 1 without overflowing in the last bucket.
+    (sub_bucket << magnitude) | ((1 << magnitude) - 1)
+}
+
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum: u64,
+    pub max: u64,
+    counts: Vec<u64>,
+}
+
+impl HistogramSnapshot {
+    pub fn value_at_quantile(&self, quantile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let target = ((quantile * self.count as f64).ceil() as u64).max(1);
+
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return bucket_upper_bound(index).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+/// Message types handled by the engine, used as the `type` label of the exported metrics.
+#[derive(Clone, Copy)]
+pub enum MessageType {
+    NewOrder,
+    CancelOrder,
+    TradeBust,
+    TradeCorrect,
+    OrderStatus,
+    OrderMassStatus,
+}
+
+impl MessageType {
+    const ALL: [Self; 6] = [
+        Self::NewOrder,
+        Self::CancelOrder,
+        Self::TradeBust,
+        Self::TradeCorrect,
+        Self::OrderStatus,
+        Self::OrderMassStatus,
+    ];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::NewOrder => "new_order",
+            Self::CancelOrder => "cancel_order",
+            Self::TradeBust => "trade_bust",
+            Self::TradeCorrect => "trade_correct",
+            Self::OrderStatus => "order_status",
+            Self::OrderMassStatus => "order_mass_status",
+        }
+    }
+}
+
+/// Engine metrics, written by the engine thread and exported by the metrics HTTP thread.
+pub struct Metrics {
+    processing_latency: [Histogram; MessageType::ALL.len()],
+    unknown_messages: AtomicU64,
+    malformed_messages: AtomicU64,
+    publish_back_pressure: Histogram,
+    back_pressure_alerts: AtomicU64,
+    dropped_reports: AtomicU64,
+    inbound_halts: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            processing_latency: std::array::from_fn(|_| Histogram::new()),
+            unknown_messages: AtomicU64::new(0),
+            malformed_messages: AtomicU64::new(0),
+            publish_back_pressure: Histogram::new(),
+            back_pressure_alerts: AtomicU64::new(0),
+            dropped_reports: AtomicU64::new(0),
+            inbound_halts: AtomicU64::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn record_processing(&self, message_type: MessageType, latency_ns: u64) {
+        self.processing_latency[message_type as usize].record(latency_ns);
+    }
+
+    #[inline(always)]
+    pub fn record_unknown_message(&self) {
+        increment(&self.unknown_messages, 1);
+    }
+
+    #[inline(always)]
+    pub fn record_malformed_message(&self) {
+        increment(&self.malformed_messages, 1);
+    }
+
+    /// Records the time a publication spent back-pressured before a claim succeeded or failed.
+    #[inline(always)]
+    pub fn record_back_pressure(&self, waited_ns: u64) {
+        self.publish_back_pressure.record(waited_ns);
+    }
+
+    pub fn record_back_pressure_alert(&self) {
+        increment(&self.back_pressure_alerts, 1);
+    }
+
+    pub fn record_dropped_report(&self) {
+        increment(&self.dropped_reports, 1);
+    }
+
+    pub fn record_inbound_halt(&self) {
+        increment(&self.inbound_halts, 1);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let snapshots: Vec<(&str, HistogramSnapshot)> = MessageType::ALL
+            .iter()
+            .map(|&t| (t.label(), self.processing_latency[t as usize].snapshot()))
+            .collect();
+
+        out.push_str("# HELP me_messages_total Messages processed by the matching engine.\n");
+        out.push_str("# TYPE me_messages_total counter\n");
+        for (label, snapshot) in &snapshots {
+            let _ = writeln!(
+                out,
+                "me_messages_total{{type=\"{label}\"}} {}",
+                snapshot.count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "me_messages_total{{type=\"unknown\"}} {}",
+            self.unknown_messages.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "me_messages_total{{type=\"malformed\"}} {}",
+            self.malformed_messages.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP me_processing_latency_ns Time to process an inbound message.\n");
+        out.push_str("# TYPE me_processing_latency_ns summary\n");
+        for (label, snapshot) in &snapshots {
+            write_summary(
+                &mut out,
+                "me_processing_latency_ns",
+                &format!("type=\"{label}\""),
+                snapshot,
+            );
+        }
+        write_max(
+            &mut out,
+            "me_processing_latency_ns",
+            snapshots
+                .iter()
+                .map(|(label, s)| (format!("type=\"{label}\""), s.max)),
+        );
+
+        let back_pressure = self.publish_back_pressure.snapshot();
+        out.push_str(
+            "# HELP me_publish_back_pressure_ns Time spent waiting on a back-pressured publication.\n",
+        );
+        out.push_str("# TYPE me_publish_back_pressure_ns summary\n");
+        write_summary(&mut out, "me_publish_back_pressure_ns", "", &back_pressure);
+        write_max(
+            &mut out,
+            "me_publish_back_pressure_ns",
+            std::iter::once((String::new(), back_pressure.max)),
+        );
+
+        for (name, help, counter) in [
+            (
+                "me_publish_back_pressure_alerts_total",
+                "Alerts raised for publications backed up longer than the alert interval.",
+                &self.back_pressure_alerts,
+            ),
+            (
+                "me_publish_dropped_total",
+                "Outbound messages dropped by the back-pressure policy.",
+                &self.dropped_reports,
+            ),
+            (
+                "me_inbound_halts_total",
+                "Times inbound processing was halted by the back-pressure policy.",
+                &self.inbound_halts,
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {}", counter.load(Ordering::Relaxed));
+        }
+
+        out
+    }
+}
+
+fn write_summary(out: &mut String, name: &str, labels: &str, snapshot: &HistogramSnapshot) {
+    let separator = if labels.is_empty() { "" } else { "," };
+    for (quantile, quantile_label) in QUANTILES {
+        let _ = writeln!(
+            out,
+            "{name}{{{labels}{separator}quantile=\"{quantile_label}\"}} {}",
+            snapshot.value_at_quantile(quantile)
+        );
+    }
+    let _ = writeln!(out, "{name}_sum{{{labels}}} {}", snapshot.sum);
+    let _ = writeln!(out, "{name}_count{{{labels}}} {}", snapshot.count);
+}
+
+fn write_max(out: &mut String, name: &str, values: impl Iterator<Item = (String, u64)>) {
+    let _ = writeln!(out, "# HELP {name}_max Largest recorded value.");
+    let _ = writeln!(out, "# TYPE {name}_max gauge");
+    for (labels, max) in values {
+        let _ = writeln!(out, "{name}_max{{{labels}}} {max}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_small_values_exactly() {
+        for value in 0..SUB_BUCKET_COUNT as u64 {
+            assert_eq!(bucket_index(value), value as usize);
+            assert_eq!(bucket_upper_bound(value as usize), value);
+        }
+    }
+
+    #[test]
+    fn buckets_cover_every_value_once() {
+        // Each bucket starts right after the previous one ends.
+        let mut lower = 0;
+        for index in 0..BUCKET_COUNT {
+            let upper = bucket_upper_bound(index);
+            assert!(
+                upper >= lower,
+                "bucket {index} ends at {upper} before {lower}"
+            );
+            assert_eq!(bucket_index(lower), index);
+            assert_eq!(bucket_index(upper), index);
+            if index + 1 < BUCKET_COUNT {
+                lower = upper + 1;
+            } else {
+                assert_eq!(upper, u64::MAX);
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_the_relative_precision() {
+        for value in [128, 129, 255, 256, 1_000, 65_537, 1 << 40, u64::MAX / 3] {
+            let upper = bucket_upper_bound(bucket_index(value));
+            assert!(upper >= value);
+            assert!(
+                (upper - value) as f64 <= value as f64 / 64.0,
+                "{value} in ..{upper}"
+            );
+        }
+    }
+
+    #[test]
+    fn the_largest_values_fall_into_the_last_bucket() {
+        assert_eq!(bucket_index(u64::MAX), BUCKET_COUNT - 1);
+        assert_eq!(bucket_index(1 << 63), BUCKET_COUNT - SUB_BUCKET_HALF_COUNT);
+
+        let histogram = Histogram::new();
+        histogram.record(u64::MAX);
+        let snapshot = histogram.snapshot();
+        assert_eq!((snapshot.count, snapshot.max), (1, u64::MAX));
+        assert_eq!(snapshot.value_at_quantile(0.5), u64::MAX);
+    }
+
+    #[test]
+    fn quantiles_of_an_empty_histogram_are_zero() {
+        let snapshot = Histogram::new().snapshot();
+        assert_eq!((snapshot.count, snapshot.sum, snapshot.max), (0, 0, 0));
+        for (quantile, _) in QUANTILES {
+            assert_eq!(snapshot.value_at_quantile(quantile), 0);
+        }
+    }
+
+    #[test]
+    fn quantiles_are_bounded_by_the_largest_value() {
+        let histogram = Histogram::new();
+        for value in 1..=100 {
+            histogram.record(value);
+        }
+        histogram.record(1_000);
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 101);
+        assert_eq!(snapshot.sum, 5_050 + 1_000);
+        assert_eq!(snapshot.value_at_quantile(0.0), 1);
+        assert_eq!(snapshot.value_at_quantile(0.5), 51);
+        assert_eq!(snapshot.value_at_quantile(0.99), 100);
+        // 1000 is recorded in the bucket ending at 1007, but never reported above the max.
+        assert_eq!(snapshot.value_at_quantile(1.0), 1_000);
+    }
+
+    #[test]
+    fn renders_the_prometheus_text_format() {
+        let metrics = Metrics::new();
+        metrics.record_processing(MessageType::NewOrder, 100);
+        metrics.record_processing(MessageType::NewOrder, 300);
+        metrics.record_processing(MessageType::TradeBust, 7);
+        metrics.record_unknown_message();
+        metrics.record_back_pressure(50);
+        metrics.record_inbound_halt();
+        let text = metrics.render_prometheus();
+
+        for line in [
+            "# TYPE me_messages_total counter",
+            "me_messages_total{type=\"new_order\"} 2",
+            "me_messages_total{type=\"trade_bust\"} 1",
+            "me_messages_total{type=\"cancel_order\"} 0",
+            "me_messages_total{type=\"unknown\"} 1",
+            "me_messages_total{type=\"malformed\"} 0",
+            "# TYPE me_processing_latency_ns summary",
+            "me_processing_latency_ns{type=\"new_order\",quantile=\"0.5\"} 100",
+            "me_processing_latency_ns{type=\"new_order\",quantile=\"0.999\"} 300",
+            "me_processing_latency_ns_sum{type=\"new_order\"} 400",
+            "me_processing_latency_ns_count{type=\"new_order\"} 2",
+            "me_processing_latency_ns_max{type=\"trade_bust\"} 7",
+            "me_publish_back_pressure_ns{quantile=\"0.99\"} 50",
+            "me_publish_back_pressure_ns_count{} 1",
+            "me_publish_back_pressure_ns_max{} 50",
+            "me_publish_dropped_total 0",
+            "me_inbound_halts_total 1",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line:?} in\n{text}"
+            );
+        }
+
+        // Every sample belongs to a family announced by a TYPE line before it.
+        let mut families = Vec::new();
+        for line in text.lines() {
+            if let Some(family) = line.strip_prefix("# TYPE ") {
+                families.push(family.split(' ').next().unwrap().to_owned());
+            } else if !line.starts_with('#') {
+                let name = line.split(['{', ' ']).next().unwrap();
+                let family = name
+                    .strip_suffix("_sum")
+                    .or_else(|| name.strip_suffix("_count"))
+                    .filter(|family| families.iter().any(|f| f == family))
+                    .unwrap_or(name);
+                assert!(families.iter().any(|f| f == family), "{line}");
+            }
+        }
+    }
+}
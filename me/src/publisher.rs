@@ -1,14 +1,12 @@
+use crate::backpressure::{BackPressureAction, BackPressurePolicy, Idler};
+use crate::metrics::Metrics;
 use crate::types::CancelRequest;
 use crate::types::Order;
 
-use std::debug_assert;
-use std::time::{SystemTime};
-
-use aeron_rs::concurrent::logbuffer::buffer_claim::BufferClaim;
-use aeron_rs::concurrent::strategies::BusySpinIdleStrategy;
-use aeron_rs::concurrent::strategies::Strategy;
-use aeron_rs::exclusive_publication::ExclusivePublication;
-use aeron_rs::utils::errors::AeronError;
+use std::collections::VecDeque;
+use std::process;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
 
 use sbe::WriteBuf;
 use sbe::cxl_rej_reason_enum::CxlRejReasonEnum;
@@ -21,17 +19,11 @@ use sbe::ord_status_enum::OrdStatusEnum;
 use sbe::ord_type_enum::OrdTypeEnum;
 use sbe::order_cancel_reject_codec::OrderCancelRejectEncoder;
 
-use tracing::{debug, error, warn};
+use tracing::{error, info, warn};
+
+use transport::{MessageSink, SendError};
 
 const MAX_MESSAGE_SIZE: usize = SBE_BLOCK_LENGTH as usize + ENCODED_LENGTH;
-#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-const MAX_MESSAGE_SIZE_I32: i32 = {
-    assert!(
-        MAX_MESSAGE_SIZE <= i32::MAX as usize,
-        "MAX_MESSAGE_SIZE too big"
-    );
-    MAX_MESSAGE_SIZE as i32
-};
 
 #[derive(Clone, Copy)]
 pub struct Trade {
@@ -45,12 +37,27 @@ pub struct Reject {
     pub reason: OrdRejReasonEnum,
 }
 
+/// Fields identifying a status report as the answer to an `OrderStatusRequest` or an
+/// `OrderMassStatusRequest`.
+#[derive(Clone, Copy)]
+pub struct Status {
+    pub mass_status_req_id: u64, // u64::MAX when answering a single order status request
+    pub tot_num_reports: u32,
+}
+
 #[derive(Clone, Copy)]
 pub enum ExecutionReport {
     New,
     Trade(Trade),
+    TradeCancel(Trade),
+    TradeCorrect(Trade),
     Cancel,
     Reject(Reject),
+    OrderStatus(Status),
+    UnknownOrderStatus(Status),
+    /// A trade bust or correction the engine refused, echoing its `TrdMatchID` and, for a
+    /// correction, the requested quantity and price.
+    TradeReject(Trade, Reject),
 }
 
 impl ExecutionReport {
@@ -59,8 +66,11 @@ impl ExecutionReport {
         match self {
             Self::New => ExecTypeEnum::New,
             Self::Trade(_) => ExecTypeEnum::Trade,
+            Self::TradeCancel(_) => ExecTypeEnum::TradeCancel,
+            Self::TradeCorrect(_) => ExecTypeEnum::TradeCorrect,
             Self::Cancel => ExecTypeEnum::Canceled,
-            Self::Reject(_) => ExecTypeEnum::Rejected,
+            Self::Reject(_) | Self::TradeReject(..) => ExecTypeEnum::Rejected,
+            Self::OrderStatus(_) | Self::UnknownOrderStatus(_) => ExecTypeEnum::OrderStatus,
         }
     }
 
@@ -75,15 +85,32 @@ impl ExecutionReport {
                     OrdStatusEnum::PartiallyFilled
                 }
             }
+            // Status requests are answered for orders that are done, too.
+            Self::TradeCancel(_) | Self::TradeCorrect(_) | Self::OrderStatus(_) => {
+                if order.leaves_quantity > 0 {
+                    if order.cumulative_quantity > 0 {
+                        OrdStatusEnum::PartiallyFilled
+                    } else {
+                        OrdStatusEnum::New
+                    }
+                } else if order.cumulative_quantity == order.quantity {
+                    OrdStatusEnum::Filled
+                } else {
+                    OrdStatusEnum::Canceled
+                }
+            }
             Self::Cancel => OrdStatusEnum::Canceled,
-            Self::Reject(_) => OrdStatusEnum::Rejected,
+            Self::Reject(_) | Self::UnknownOrderStatus(_) | Self::TradeReject(..) => {
+                OrdStatusEnum::Rejected
+            }
         }
     }
 
     #[inline(always)]
     pub const fn ord_rej_reason(&self) -> OrdRejReasonEnum {
         match self {
-            Self::Reject(r) => r.reason,
+            Self::Reject(r) | Self::TradeReject(_, r) => r.reason,
+            Self::UnknownOrderStatus(_) => OrdRejReasonEnum::UnknownOrder,
             _ => OrdRejReasonEnum::NullVal,
         }
     }
@@ -95,11 +122,21 @@ impl ExecutionReport {
         mut encoder: ExecutionReportEncoder<'a>,
         order: &Order,
     ) -> ExecutionReportEncoder<'a> {
-        if let Self::Trade(trade) = self {
+        if let Self::Trade(trade) | Self::TradeCancel(trade) | Self::TradeCorrect(trade) = self {
             encoder.trd_match_id(trade.match_id);
             let encoder = Self::set_last_px(encoder, trade.px);
             let encoder = Self::set_last_qty(encoder, trade.qty);
             Self::set_avg_px(encoder, order.avg_px())
+        } else if let Self::TradeReject(trade, _) = self {
+            encoder.trd_match_id(trade.match_id);
+            let encoder = Self::set_last_px(encoder, trade.px);
+            let encoder = Self::set_last_qty(encoder, trade.qty);
+            Self::set_avg_px(encoder, i64::MIN)
+        } else if let Self::OrderStatus(_) = self {
+            encoder.trd_match_id(u64::MAX);
+            let encoder = Self::set_last_px(encoder, i64::MIN);
+            let encoder = Self::set_last_qty(encoder, i64::MIN);
+            Self::set_avg_px(encoder, order.avg_px())
         } else {
             encoder.trd_match_id(u64::MAX);
             let encoder = Self::set_last_px(encoder, i64::MIN);
@@ -108,6 +145,17 @@ impl ExecutionReport {
         }
     }
 
+    #[inline(always)]
+    fn set_status_fields(self, encoder: &mut ExecutionReportEncoder<'_>) {
+        if let Self::OrderStatus(status) | Self::UnknownOrderStatus(status) = self {
+            encoder.mass_status_req_id(status.mass_status_req_id);
+            encoder.tot_num_reports(status.tot_num_reports);
+        } else {
+            encoder.mass_status_req_id(u64::MAX);
+            encoder.tot_num_reports(u32::MAX);
+        }
+    }
+
     #[inline(always)]
     fn set_last_qty(encoder: ExecutionReportEncoder<'_>, qty: i64) -> ExecutionReportEncoder<'_> {
         let mut last_qty_encoder = encoder.last_qty_encoder();
@@ -136,19 +184,57 @@ impl ExecutionReport {
     }
 }
 
-pub struct Publisher {
-    publication: ExclusivePublication,
-    buffer_claim: BufferClaim,
-    offer_idle_strategy: BusySpinIdleStrategy,
+/// What became of a report after `Publisher::offer`.
+enum Outcome {
+    /// The report was encoded straight into the sink.
+    Published,
+    /// Inbound is halted; the report is encoded into a parked frame published later by
+    /// `flush_parked`.
+    Parked,
+    /// The back-pressure policy dropped the report.
+    Dropped,
 }
 
-impl Publisher {
-    pub fn new(publication: ExclusivePublication) -> Self {
+pub struct Publisher<S: MessageSink> {
+    sink: S,
+    policy: BackPressurePolicy,
+    idler: Idler,
+    parked: VecDeque<Box<[u8; MAX_MESSAGE_SIZE]>>,
+    metrics: Arc<Metrics>,
+}
+
+impl<S: MessageSink> Publisher<S> {
+    pub fn new(sink: S, policy: BackPressurePolicy, metrics: Arc<Metrics>) -> Self {
         Self {
-            publication,
-            buffer_claim: BufferClaim::default(),
-            offer_idle_strategy: BusySpinIdleStrategy {},
+            sink,
+            policy,
+            idler: Idler::new(policy.idle_strategy),
+            parked: VecDeque::new(),
+            metrics,
+        }
+    }
+
+    /// Returns true while reports are parked by a `HaltInbound` policy, in which case the engine
+    /// must not process inbound messages until `flush_parked` succeeds.
+    #[inline(always)]
+    pub fn is_halted(&self) -> bool {
+        !self.parked.is_empty()
+    }
+
+    /// Offers parked reports in order and returns true once none are left.
+    pub fn flush_parked(&mut self) -> bool {
+        while let Some(frame) = self.parked.front() {
+            match self.sink.offer(frame.as_slice()) {
+                Ok(()) => {
+                    self.parked.pop_front();
+                }
+                Err(err) if err.is_transient() => return false,
+                Err(err) => Self::handle_publication_error(err, 0),
+            }
         }
+
+        info!(target: "publication", "Parked reports published, resuming inbound processing.");
+        true
     }
 
     #[inline(always)]
@@ -162,6 +248,30 @@ impl Publisher {
         self.publish_execution_report(&trade_report, order, exec_id);
     }
 
+    pub fn publish_trade_cancel(
+        &mut self,
+        order: &Order,
+        exec_id: u64,
+        match_id: u64,
+        qty: i64,
+        px: i64,
+    ) {
+        let report = ExecutionReport::TradeCancel(Trade { match_id, qty, px });
+        self.publish_execution_report(&report, order, exec_id);
+    }
+
+    pub fn publish_trade_correct(
+        &mut self,
+        order: &Order,
+        exec_id: u64,
+        match_id: u64,
+        qty: i64,
+        px: i64,
+    ) {
+        let report = ExecutionReport::TradeCorrect(Trade { match_id, qty, px });
+        self.publish_execution_report(&report, order, exec_id);
+    }
+
     #[inline(always)]
     pub fn publish_cancel(&mut self, order: &Order, exec_id: u64) {
         self.publish_execution_report(&ExecutionReport::Cancel, order, exec_id);
@@ -175,63 +285,32 @@ impl Publisher {
         reason: CxlRejReasonEnum,
         response_to: CxlRejResponseToEnum,
     ) {
-        // TODO: TURN INTO A REUSABLE METHOD TO OBTAIN A BUFFER CLAIM
-        // -----------------------------------------------------------------------------------------
-        self.offer_idle_strategy.reset(); // TODO: BACKPRESSURE STRATEGY
-
-        loop {
-            let result = self.publication.try_claim(MAX_MESSAGE_SIZE_I32, &mut self.buffer_claim);
-
-            match result {
-                Ok(_) => break,
-                Err(AeronError::BackPressured) => {
-                    self.offer_idle_strategy.idle();
-                }
-                Err(err) => {
-                    Self::handle_publication_error(err, exec_id);
-                    // return;
-                }
-            }
-        }
-
-        let raw_offset = self.buffer_claim.offset();
-        debug_assert!(
-            raw_offset >= 0,
-            "Expected non-negative offset, got {raw_offset}"
-        );
-        #[allow(clippy::cast_sign_loss)]
-        let offset = raw_offset as usize;
-
-        let mut buffer = self.buffer_claim.buffer();
-        let claimed_slice = &mut buffer.as_mutable_slice()[offset..offset + MAX_MESSAGE_SIZE];
-        // -----------------------------------------------------------------------------------------
-
-        let write_buf = WriteBuf::new(claimed_slice);
-        let mut encoder = Self::begin_encoding_cancel_reject(write_buf);
-
-        let client_order_id = {
-            let id = req.client_order_id;
-            [
-                (id >> 64) as u64,                   // high bits
-                (id & 0xFFFF_FFFF_FFFF_FFFF) as u64, // low bits
-            ]
-        };
-        let original_client_order_id = {
-            let id = req.original_client_order_id;
-            [
-                (id >> 64) as u64,                   // high bits
-                (id & 0xFFFF_FFFF_FFFF_FFFF) as u64, // low bits
-            ]
-        };
-
-        encoder.cl_ord_id(&client_order_id);
-        encoder.orig_cl_ord_id(&original_client_order_id);
-        encoder.order_id(u64::MAX); // null
-        encoder.ord_status(OrdStatusEnum::NullVal);
-        encoder.cxl_rej_response_to(response_to);
-        encoder.cxl_rej_reason(reason);
-
-        self.buffer_claim.commit();
+        self.publish_with(exec_id, |frame| {
+            let mut encoder = Self::begin_encoding_cancel_reject(WriteBuf::new(frame));
+
+            let client_order_id = {
+                let id = req.client_order_id;
+                [
+                    (id >> 64) as u64,                   // high bits
+                    (id & 0xFFFF_FFFF_FFFF_FFFF) as u64, // low bits
+                ]
+            };
+            let original_client_order_id = {
+                let id = req.original_client_order_id;
+                [
+                    (id >> 64) as u64,                   // high bits
+                    (id & 0xFFFF_FFFF_FFFF_FFFF) as u64, // low bits
+                ]
+            };
+
+            encoder.cl_ord_id(&client_order_id);
+            encoder.orig_cl_ord_id(&original_client_order_id);
+            encoder.order_id(u64::MAX); // null
+            encoder.ord_status(OrdStatusEnum::NullVal);
+            encoder.cxl_rej_response_to(response_to);
+            encoder.cxl_rej_reason(reason);
+            encoder.exec_id(exec_id);
+        });
     }
 
     #[inline(always)]
@@ -240,95 +319,156 @@ impl Publisher {
         self.publish_execution_report(&reject_report, order, exec_id);
     }
 
+    /// Status reports are not part of the execution stream, so they carry `ExecID` 0 and do not
+    /// consume an execution ID.
+    pub fn publish_order_status(&mut self, order: &Order, status: Status) {
+        self.publish_execution_report(&ExecutionReport::OrderStatus(status), order, 0);
+    }
+
+    pub fn publish_unknown_order_status(&mut self, order: &Order, status: Status) {
+        self.publish_execution_report(&ExecutionReport::UnknownOrderStatus(status), order, 0);
+    }
+
+    /// Answers a trade bust or correction that cannot be applied. The reject names no order and,
+    /// like status reports, carries `ExecID` 0. A bust has no quantity or price, so pass
+    /// `i64::MIN` for both.
+    pub fn publish_trade_reject(
+        &mut self,
+        match_id: u64,
+        qty: i64,
+        px: i64,
+        reason: OrdRejReasonEnum,
+    ) {
+        let report = ExecutionReport::TradeReject(Trade { match_id, qty, px }, Reject { reason });
+        self.publish_execution_report(&report, &Order::unknown(0, 0), 0);
+    }
+
     #[inline(always)]
     fn publish_execution_report(&mut self, report: &ExecutionReport, order: &Order, exec_id: u64) {
-        self.offer_idle_strategy.reset();
+        self.publish_with(exec_id, |frame| {
+            let mut encoder = Self::begin_encoding_execution_report(WriteBuf::new(frame));
+
+            Self::set_common_fields(&mut encoder, order, exec_id);
+            encoder = Self::set_composite_fields(encoder, order);
+            encoder.exec_type(report.exec_type());
+            encoder.ord_status(report.ord_status(order));
+            encoder.ord_rej_reason(report.ord_rej_reason());
+            report.set_status_fields(&mut encoder);
+            report.set_optional_fields(encoder, order);
+        });
+    }
 
-        loop {
-            assert!(
-                i32::try_from(MAX_MESSAGE_SIZE).is_ok(),
-                "MAX_MESSAGE_SIZE too large for i32"
-            );
+    /// Offers one message according to the back-pressure policy and lets `encode` write it,
+    /// either into the sink or into a parked frame.
+    #[inline(always)]
+    fn publish_with(&mut self, exec_id: u64, encode: impl FnOnce(&mut [u8])) {
+        let mut encode = Some(encode);
+        let mut write = |frame: &mut [u8]| {
+            if let Some(encode) = encode.take() {
+                encode(frame);
+            }
+        };
 
-            let result = self.publication.try_claim(MAX_MESSAGE_SIZE_I32, &mut self.buffer_claim);
+        // Reports behind parked ones are parked too so the stream stays in order.
+        let outcome = if self.is_halted() {
+            Outcome::Parked
+        } else {
+            self.offer(exec_id, &mut write)
+        };
 
-            match result {
-                Ok(_) => break,
-                Err(AeronError::BackPressured) => {
-                    self.offer_idle_strategy.idle();
-                }
-                Err(AeronError::NotConnected) => {
-                    warn!(
-                        "Publication not connected for exec_id {}, retrying...",
-                        exec_id
-                    );
-                    std::thread::yield_now();
-                }
-                Err(AeronError::AdminAction) => {
-                    debug!(
-                        "Admin action in progress for exec_id {}, retrying...",
-                        exec_id
-                    );
-                    self.offer_idle_strategy.idle();
-                }
-                Err(err) => {
-                    error!(
-                        "FATAL PUBLISH ERROR: Failed to claim buffer for exec_id {}: {:?}. Aborting publish.",
-                        exec_id, err
-                    );
-                    Self::handle_publication_error(err, exec_id);
-                    // return;
-                }
-            }
+        if let Outcome::Parked = outcome {
+            let mut frame = Box::new([0_u8; MAX_MESSAGE_SIZE]);
+            write(frame.as_mut_slice());
+            self.parked.push_back(frame);
         }
+    }
 
-        let raw_offset = self.buffer_claim.offset();
-        debug_assert!(
-            raw_offset >= 0,
-            "Expected non-negative offset, got {raw_offset}"
-        );
-        #[allow(clippy::cast_sign_loss)]
-        let offset = raw_offset as usize;
+    #[inline(always)]
+    fn offer(&mut self, exec_id: u64, write: &mut dyn FnMut(&mut [u8])) -> Outcome {
+        self.idler.reset();
+        let mut back_pressured_since: Option<Instant> = None;
+        let mut next_alert = self.policy.alert_after;
 
-        let mut buffer = self.buffer_claim.buffer();
-        let claimed_slice = &mut buffer.as_mutable_slice()[offset..offset + MAX_MESSAGE_SIZE];
+        loop {
+            let err = match self.sink.try_publish(MAX_MESSAGE_SIZE, write) {
+                Ok(()) => {
+                    self.record_back_pressure(back_pressured_since);
+                    return Outcome::Published;
+                }
+                Err(err) if err.is_transient() => err,
+                Err(err) => Self::handle_publication_error(err, exec_id),
+            };
+
+            let waited = back_pressured_since
+                .get_or_insert_with(Instant::now)
+                .elapsed();
+            if waited >= next_alert {
+                next_alert += self.policy.alert_after;
+                self.metrics.record_back_pressure_alert();
+                warn!(
+                    target: "publication",
+                    exec_id,
+                    error = ?err,
+                    waited_ms = waited.as_millis(),
+                    action = ?self.policy.action,
+                    "Outbound publication is backed up."
+                );
+            }
 
-        let write_buf = WriteBuf::new(claimed_slice);
-        let mut encoder = Self::begin_encoding_execution_report(write_buf);
+            if waited >= self.policy.max_spin {
+                match self.policy.action {
+                    BackPressureAction::Block => {}
+                    BackPressureAction::Drop => {
+                        self.record_back_pressure(back_pressured_since);
+                        self.metrics.record_dropped_report();
+                        error!(
+                            target: "publication",
+                            exec_id,
+                            error = ?err,
+                            "Dropping outbound report after exceeding the maximum back-pressure wait."
+                        );
+                        return Outcome::Dropped;
+                    }
+                    BackPressureAction::HaltInbound => {
+                        self.record_back_pressure(back_pressured_since);
+                        self.metrics.record_inbound_halt();
+                        warn!(
+                            target: "publication",
+                            exec_id,
+                            error = ?err,
+                            "Halting inbound processing until the outbound publication recovers."
+                        );
+                        return Outcome::Parked;
+                    }
+                }
+            }
 
-        Self::set_common_fields(&mut encoder, order, exec_id);
-        encoder = Self::set_composite_fields(encoder, order);
-        encoder.exec_type(report.exec_type());
-        encoder.ord_status(report.ord_status(order));
-        encoder.ord_rej_reason(report.ord_rej_reason());
-        report.set_optional_fields(encoder, order);
+            self.idler.idle();
+        }
+    }
 
-        self.buffer_claim.commit();
+    /// Records how long a claim waited on a back-pressured publication; uncontended claims are not
+    /// recorded.
+    #[inline(always)]
+    fn record_back_pressure(&self, back_pressured_since: Option<Instant>) {
+        if let Some(since) = back_pressured_since {
+            #[allow(clippy::cast_possible_truncation)]
+            let waited_ns = since.elapsed().as_nanos() as u64;
+            self.metrics.record_back_pressure(waited_ns);
+        }
     }
 
+    /// Errors other than back pressure mean the publication is closed or has exhausted its
+    /// term space; neither recovers, so the engine stops rather than losing reports silently.
     #[allow(clippy::needless_pass_by_value)]
-    fn handle_publication_error(_err: AeronError, _exec_id: u64) {
-        // println!("CONDUCTOR WE HAVE A PROBLEM: {err:?}");
-        // TODO: Add error traces
-        // match err {
-        //     Err(AeronError::AdminAction) => {
-        //         println!("TryClaim: AdminAction encountered, retrying...");
-        //         self.offer_idle_strategy.idle();
-        //         break;
-        //     }
-        //     Err(AeronError::NotConnected) => {
-
-        //     }
-        //     Err(AeronError::PublicationClosed) => {
-        //         return;
-        //     }
-        //     Err(AeronError::MaxPositionExceeded) => {
-
-        //     }
-        //     Err(other_err) => {
-
-        //     }
-        // }
+    fn handle_publication_error(err: SendError, exec_id: u64) -> ! {
+        error!(
+            target: "publication",
+            exec_id,
+            error = ?err,
+            "Unrecoverable publication error. Exiting."
+        );
+        process::exit(1);
     }
 
     #[inline(always)]
@@ -445,3 +585,103 @@ impl Publisher {
             .expect("Failed to get parent after price") // TODO NO EXPECT
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backpressure::IdleStrategy;
+
+    use std::time::Duration;
+
+    use sbe::{Decimal64, Message, decode_message};
+
+    use transport::{Loopback, LoopbackSource, MessageSource};
+
+    /// A publisher that halts inbound as soon as its one-frame stream is full.
+    fn halting(reports: &Loopback, metrics: &Arc<Metrics>) -> Publisher<impl MessageSink> {
+        let policy = BackPressurePolicy {
+            idle_strategy: IdleStrategy::BusySpin,
+            max_spin: Duration::ZERO,
+            alert_after: Duration::from_secs(60),
+            action: BackPressureAction::HaltInbound,
+        };
+        Publisher::new(reports.sink(), policy, Arc::clone(metrics))
+    }
+
+    fn exec_ids(source: &mut LoopbackSource) -> Vec<u64> {
+        let mut exec_ids = Vec::new();
+        source.poll(
+            &mut |frame| match decode_message(frame).unwrap() {
+                Message::ExecutionReport(report) => exec_ids.push(report.exec_id),
+                message => panic!("published {message:?}"),
+            },
+            usize::MAX,
+        );
+        exec_ids
+    }
+
+    #[test]
+    fn parks_reports_in_order_until_the_stream_recovers() {
+        let reports = Loopback::with_capacity(1);
+        let mut source = reports.source();
+        let metrics = Arc::new(Metrics::new());
+        let mut publisher = halting(&reports, &metrics);
+        let order = Order::unknown(1, 2);
+
+        publisher.publish_new_order(&order, 1);
+        assert!(!publisher.is_halted());
+
+        // The stream is full: the second report is parked, and the third behind it.
+        publisher.publish_new_order(&order, 2);
+        assert!(publisher.is_halted());
+        publisher.publish_new_order(&order, 3);
+        assert_eq!(publisher.parked.len(), 2);
+        assert!(!publisher.flush_parked());
+
+        assert_eq!(exec_ids(&mut source), [1]);
+        assert!(!publisher.flush_parked());
+        assert!(publisher.is_halted());
+        assert_eq!(exec_ids(&mut source), [2]);
+        assert!(publisher.flush_parked());
+        assert!(!publisher.is_halted());
+        assert_eq!(exec_ids(&mut source), [3]);
+
+        publisher.publish_new_order(&order, 4);
+        assert_eq!(exec_ids(&mut source), [4]);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("\nme_inbound_halts_total 1\n"), "{text}");
+    }
+
+    #[test]
+    fn parked_frames_hold_the_whole_report() {
+        let reports = Loopback::with_capacity(1);
+        let mut source = reports.source();
+        let metrics = Arc::new(Metrics::new());
+        let mut publisher = halting(&reports, &metrics);
+        let mut order = Order::unknown(7, 8);
+        order.quantity = 5;
+        order.leaves_quantity = 5;
+        order.price = 100;
+
+        publisher.publish_new_order(&order, 1);
+        publisher.publish_new_order(&order, 1);
+        let mut published = Vec::new();
+        while published.len() < 2 {
+            source.poll(
+                &mut |frame| match decode_message(frame).unwrap() {
+                    Message::ExecutionReport(report) => published.push(report),
+                    message => panic!("published {message:?}"),
+                },
+                1,
+            );
+            publisher.flush_parked();
+        }
+
+        // Only the transact time may differ between the report published directly and the
+        // parked one.
+        published[1].transact_time = published[0].transact_time;
+        assert_eq!(published[0], published[1]);
+        assert_eq!(published[0].order_qty, Decimal64::from_mantissa(5));
+    }
+}
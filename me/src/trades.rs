@@ -0,0 +1,176 @@
+use crate::types::{Order, OrderKey};
+
+use std::collections::{BTreeMap, HashMap};
+
+/// A single match between an aggressor and a resting order.
+#[derive(Debug, Clone, Copy)]
+pub struct TradeRecord {
+    pub qty: i64,
+    pub px: i64,
+    pub order_ids: [u64; 2], // aggressor, resting
+}
+
+impl TradeRecord {
+    pub fn notional(&self) -> i128 {
+        i128::from(self.qty) * i128::from(self.px)
+    }
+}
+
+/// The last known state of an order that traded, and how many kept trades refer to it.
+struct TradedOrder {
+    order: Order,
+    trades: usize,
+}
+
+/// Keeps the most recent `capacity` trades by `TrdMatchID`, plus the last known state of each
+/// order in them, so that a trade can later be busted or corrected even after the orders
+/// involved have left the book. Once full, recording a trade forgets the oldest one, and an order
+/// is forgotten with the last trade that refers to it.
+pub struct TradeLedger {
+    capacity: usize,
+    // Match ids only increase, so the first trade is the oldest.
+    trades: BTreeMap<u64, TradeRecord>,
+    orders: HashMap<u64, TradedOrder>,
+    // The latest order to trade under each key, as clients may reuse a key once an order is done.
+    keys: HashMap<OrderKey, u64>,
+}
+
+impl TradeLedger {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            trades: BTreeMap::new(),
+            orders: HashMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn record(&mut self, match_id: u64, aggressor: &Order, resting: &Order, qty: i64, px: i64) {
+        self.trades.insert(
+            match_id,
+            TradeRecord {
+                qty,
+                px,
+                order_ids: [aggressor.sequence_number, resting.sequence_number],
+            },
+        );
+        for order in [aggressor, resting] {
+            self.orders
+                .entry(order.sequence_number)
+                .and_modify(|traded| {
+                    traded.order = *order;
+                    traded.trades += 1;
+                })
+                .or_insert(TradedOrder {
+                    order: *order,
+                    trades: 1,
+                });
+            self.keys.insert(order.key(), order.sequence_number);
+        }
+
+        while self.trades.len() > self.capacity {
+            let Some((_, oldest)) = self.trades.pop_first() else {
+                break;
+            };
+            self.release(&oldest);
+        }
+    }
+
+    pub fn get(&self, match_id: u64) -> Option<TradeRecord> {
+        self.trades.get(&match_id).copied()
+    }
+
+    /// Forgets the trade, and any order only it referred to.
+    pub fn remove(&mut self, match_id: u64) -> Option<TradeRecord> {
+        let trade = self.trades.remove(&match_id)?;
+        self.release(&trade);
+        Some(trade)
+    }
+
+    pub fn update(&mut self, match_id: u64, qty: i64, px: i64) {
+        if let Some(trade) = self.trades.get_mut(&match_id) {
+            trade.qty = qty;
+            trade.px = px;
+        }
+    }
+
+    pub fn order_mut(&mut self, order_id: u64) -> Option<&mut Order> {
+        self.orders
+            .get_mut(&order_id)
+            .map(|traded| &mut traded.order)
+    }
+
+    /// The last known state of the latest order under `key` that traded.
+    pub fn find(&self, key: OrderKey) -> Option<&Order> {
+        self.keys
+            .get(&key)
+            .and_then(|order_id| self.orders.get(order_id))
+            .map(|traded| &traded.order)
+    }
+
+    fn release(&mut self, trade: &TradeRecord) {
+        for order_id in trade.order_ids {
+            let Some(traded) = self.orders.get_mut(&order_id) else {
+                continue;
+            };
+            traded.trades -= 1;
+            if traded.trades == 0 {
+                let key = traded.order.key();
+                self.orders.remove(&order_id);
+                if self.keys.get(&key) == Some(&order_id) {
+                    self.keys.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(sequence_number: u64) -> Order {
+        Order {
+            sequence_number,
+            ..Order::unknown(1, u128::from(sequence_number))
+        }
+    }
+
+    #[test]
+    fn forgets_the_oldest_trades_once_full() {
+        let mut ledger = TradeLedger::new(2);
+        let (a, b, c) = (order(1), order(2), order(3));
+        ledger.record(1, &a, &b, 5, 100);
+        ledger.record(2, &a, &c, 5, 100);
+        assert!(ledger.order_mut(2).is_some());
+
+        ledger.record(3, &c, &a, 5, 100);
+        assert!(ledger.get(1).is_none());
+        assert!(ledger.get(2).is_some() && ledger.get(3).is_some());
+        // Order 2 traded only in the forgotten trade; 1 and 3 are still referred to.
+        assert!(ledger.order_mut(2).is_none());
+        assert!(ledger.order_mut(1).is_some() && ledger.order_mut(3).is_some());
+        assert!(ledger.find(b.key()).is_none());
+        assert_eq!(
+            ledger.find(a.key()).map(|order| order.sequence_number),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn forgets_orders_with_the_last_trade_removed() {
+        let mut ledger = TradeLedger::new(10);
+        let (a, b, c) = (order(1), order(2), order(3));
+        ledger.record(1, &a, &b, 5, 100);
+        ledger.record(2, &a, &c, 5, 100);
+
+        assert_eq!(ledger.remove(1).map(|trade| trade.qty), Some(5));
+        assert!(ledger.remove(1).is_none());
+        assert!(ledger.order_mut(1).is_some());
+        assert!(ledger.order_mut(2).is_none());
+
+        ledger.remove(2);
+        assert!(ledger.order_mut(1).is_none() && ledger.order_mut(3).is_none());
+    }
+}
@@ -20,11 +20,29 @@ pub struct Order {
     // Cold fields (rarely accessed during matching) - subsequent cache lines
     pub client_order_id: UuidType, // 16 bytes - Client Order ID
     pub account: UuidType,         // 16 bytes - Account ID
-    pub transact_time: u64,        // 8 bytes - Time of transaction from client
-    pub symbol: SymbolType, // 6 bytes - Instrument symbol
+    pub symbol: SymbolType,        // 6 bytes - Instrument symbol
 }
 
 impl Order {
+    /// Placeholder used to report on an order the engine does not know about.
+    pub const fn unknown(account: UuidType, client_order_id: UuidType) -> Self {
+        Self {
+            prev_order_idx: None,
+            next_order_idx: None,
+            leaves_quantity: 0,
+            price: i64::MIN,
+            cumulative_quantity: 0,
+            total_notional: 0,
+            sequence_number: 0,
+            quantity: 0,
+            side: SideEnum::NullVal,
+            r#type: OrdTypeEnum::NullVal,
+            client_order_id,
+            account,
+            symbol: [0; 6],
+        }
+    }
+
     pub const fn key(&self) -> OrderKey {
         (self.account, self.client_order_id)
     }
@@ -35,6 +53,13 @@ impl Order {
         self.total_notional += i128::from(qty) * i128::from(price);
     }
 
+    /// Amends the executed quantity and notional of the order after a trade bust or correction.
+    /// The leaves quantity is left untouched: busted quantity is not reinstated on the book.
+    pub fn adjust_fill(&mut self, qty_delta: i64, notional_delta: i128) {
+        self.cumulative_quantity += qty_delta;
+        self.total_notional += notional_delta;
+    }
+
     pub fn avg_px(&self) -> i64 {
         if self.cumulative_quantity == 0 {
             return 0;
@@ -0,0 +1,179 @@
+//! The tradeable SkyBlock items, read from the definition file the services share. Each product
+//! has the symbol orders name it by, the item it is, the increments its prices and sizes move
+//! in, the sizes an order may have and whether it trades.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use sbe::Decimal64;
+
+use serde::{Deserialize, Serialize};
+
+/// Longest symbol, the width of the messages' `Symbol` field.
+pub const MAX_SYMBOL_LEN: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradingStatus {
+    /// Orders are accepted.
+    Online,
+    /// Trading is paused; orders will be accepted again.
+    Halted,
+    /// No longer traded.
+    Delisted,
+}
+
+impl fmt::Display for TradingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Online => "online",
+            Self::Halted => "halted",
+            Self::Delisted => "delisted",
+        })
+    }
+}
+
+/// An instrument. Amounts are decimals, serialized as strings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Product {
+    /// The symbol, e.g. `JSP`.
+    pub id: String,
+    /// Hypixel's id of the item, e.g. `JASPER_CRYSTAL`.
+    pub item_id: String,
+    /// What players call the item.
+    pub item_name: String,
+    /// Prices are whole multiples of this many coins.
+    pub tick_size: Decimal64,
+    /// Sizes are whole multiples of this many items.
+    pub lot_size: Decimal64,
+    pub min_size: Decimal64,
+    pub max_size: Decimal64,
+    pub status: TradingStatus,
+}
+
+fn is_multiple(value: Decimal64, step: Decimal64) -> bool {
+    value.mantissa() % step.mantissa() == 0
+}
+
+impl Product {
+    /// The id as messages carry it in their `Symbol` field, padded with NULs.
+    pub fn symbol(&self) -> [u8; MAX_SYMBOL_LEN] {
+        let mut symbol = [0; MAX_SYMBOL_LEN];
+        symbol[..self.id.len()].copy_from_slice(self.id.as_bytes());
+        symbol
+    }
+
+    /// Checks that an order for `size` items, at `price` if it is a limit order, may be placed:
+    /// the product trades, the price is a whole number of ticks and the size a whole number of
+    /// lots within the product's bounds. `Err` says why not.
+    pub fn check_order(&self, size: Decimal64, price: Option<Decimal64>) -> Result<(), String> {
+        let id = &self.id;
+        if self.status != TradingStatus::Online {
+            return Err(format!("{id} is {} and takes no orders.", self.status));
+        }
+        if let Some(price) = price
+            && !is_multiple(price, self.tick_size)
+        {
+            return Err(format!(
+                "Price of {id} must be a multiple of {}.",
+                self.tick_size
+            ));
+        }
+        if !is_multiple(size, self.lot_size) {
+            return Err(format!(
+                "Size of {id} must be a multiple of {}.",
+                self.lot_size
+            ));
+        }
+        if size < self.min_size || size > self.max_size {
+            return Err(format!(
+                "Size of {id} must be between {} and {}.",
+                self.min_size, self.max_size
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let id = &self.id;
+        if id.is_empty()
+            || id.len() > MAX_SYMBOL_LEN
+            || !id
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        {
+            return Err(format!(
+                "product id {id:?} must be 1 to {MAX_SYMBOL_LEN} capital letters or digits"
+            ));
+        }
+        if self.item_id.trim().is_empty() || self.item_name.trim().is_empty() {
+            return Err(format!("{id}: item_id and item_name are required"));
+        }
+        if !self.tick_size.is_positive() || !self.lot_size.is_positive() {
+            return Err(format!("{id}: tick_size and lot_size must be positive"));
+        }
+        if !self.min_size.is_positive() || self.min_size > self.max_size {
+            return Err(format!(
+                "{id}: min_size must be positive and at most max_size"
+            ));
+        }
+        if !is_multiple(self.min_size, self.lot_size) || !is_multiple(self.max_size, self.lot_size)
+        {
+            return Err(format!(
+                "{id}: min_size and max_size must be multiples of lot_size"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Every product, by id.
+#[derive(Debug, Default)]
+pub struct Products {
+    by_id: BTreeMap<String, Product>,
+}
+
+impl Products {
+    /// Reads the definitions from a JSON array of products, which must be valid and have
+    /// distinct ids.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let products: Vec<Product> =
+            serde_json::from_str(json).map_err(|err| format!("not a product list: {err}"))?;
+        let mut by_id = BTreeMap::new();
+        for product in products {
+            product.validate()?;
+            if by_id.contains_key(&product.id) {
+                return Err(format!("product {} is defined twice", product.id));
+            }
+            by_id.insert(product.id.clone(), product);
+        }
+        Ok(Self { by_id })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        Self::from_json(&json).map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    /// Ids are matched regardless of case, as orders name them.
+    pub fn get(&self, id: &str) -> Option<&Product> {
+        self.by_id.get(&id.to_uppercase())
+    }
+
+    /// In order of id.
+    pub fn all(&self) -> impl Iterator<Item = &Product> {
+        self.by_id.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
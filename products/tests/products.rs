@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use products::{Products, TradingStatus};
+
+use sbe::Decimal64;
+
+const DEFINITIONS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../products.json");
+
+fn product(id: &str, min_size: &str, max_size: &str) -> String {
+    format!(
+        r#"{{"id": "{id}", "item_id": "ITEM", "item_name": "Item", "tick_size": "0.1",
+            "lot_size": "1", "min_size": "{min_size}", "max_size": "{max_size}",
+            "status": "online"}}"#
+    )
+}
+
+#[test]
+fn loads_the_shared_definitions() {
+    let products = Products::load(Path::new(DEFINITIONS)).unwrap();
+    assert!(!products.is_empty());
+
+    let jasper = products.get("JSP").unwrap();
+    assert_eq!(jasper.item_name, "Jasper Crystal");
+    assert_eq!(jasper.tick_size, "0.1".parse::<Decimal64>().unwrap());
+    assert_eq!(jasper.status, TradingStatus::Online);
+    assert_eq!(products.get("jsp"), Some(jasper));
+    assert_eq!(&jasper.symbol(), b"JSP\0\0\0");
+    assert!(products.get("XYZ").is_none());
+}
+
+#[test]
+fn lists_products_by_id() {
+    let json = format!(
+        "[{}, {}]",
+        product("ZZZ", "1", "5"),
+        product("AAA", "1", "5")
+    );
+    let products = Products::from_json(&json).unwrap();
+    let ids: Vec<_> = products.all().map(|product| product.id.as_str()).collect();
+    assert_eq!(ids, ["AAA", "ZZZ"]);
+}
+
+#[test]
+fn serializes_amounts_as_strings() {
+    let products = Products::from_json(&format!("[{}]", product("JSP", "2", "10"))).unwrap();
+    let json = serde_json::to_value(products.get("JSP").unwrap()).unwrap();
+    assert_eq!(json["tick_size"], "0.1");
+    assert_eq!(json["max_size"], "10");
+    assert_eq!(json["status"], "online");
+}
+
+#[test]
+fn rejects_invalid_definitions() {
+    for json in [
+        format!(
+            "[{}, {}]",
+            product("JSP", "1", "5"),
+            product("JSP", "1", "5")
+        ),
+        format!("[{}]", product("jsp", "1", "5")),
+        format!("[{}]", product("TOOLONG", "1", "5")),
+        format!("[{}]", product("JSP", "0", "5")),
+        format!("[{}]", product("JSP", "6", "5")),
+        format!("[{}]", product("JSP", "1.5", "5")),
+        r#"[{"id": "JSP"}]"#.to_owned(),
+    ] {
+        assert!(Products::from_json(&json).is_err(), "{json}");
+    }
+}
+
+#[test]
+fn checks_orders_against_the_product() {
+    let products = Products::from_json(&format!("[{}]", product("JSP", "2", "10"))).unwrap();
+    let jasper = products.get("JSP").unwrap();
+    let amount = |value: &str| value.parse::<Decimal64>().unwrap();
+
+    assert!(
+        jasper
+            .check_order(amount("2"), Some(amount("10.5")))
+            .is_ok()
+    );
+    assert!(jasper.check_order(amount("10"), None).is_ok());
+    for (size, price) in [
+        ("2", Some("10.55")),
+        ("2.5", Some("10.5")),
+        ("1", Some("10.5")),
+        ("11", None),
+    ] {
+        assert!(
+            jasper.check_order(amount(size), price.map(amount)).is_err(),
+            "{size} at {price:?}"
+        );
+    }
+}
+
+#[test]
+fn takes_orders_only_while_online() {
+    for status in ["halted", "delisted"] {
+        let json = format!("[{}]", product("JSP", "1", "5")).replace("online", status);
+        let products = Products::from_json(&json).unwrap();
+        let error = products
+            .get("JSP")
+            .unwrap()
+            .check_order(Decimal64::ONE, None)
+            .unwrap_err();
+        assert_eq!(error, format!("JSP is {status} and takes no orders."));
+    }
+}
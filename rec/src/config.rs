@@ -0,0 +1,59 @@
+use recorder::merge::{
+    DEFAULT_REPLAY_CHANNEL, DEFAULT_REPLAY_STREAM_ID, DEFAULT_REQUEST_CHANNEL,
+    DEFAULT_REQUEST_STREAM_ID,
+};
+
+use serde::{Deserialize, Serialize};
+
+use settings::Settings;
+
+use transport::{ChannelConfig, TransportConfig};
+
+/// Execution reports are recorded from the engine's multicast report stream by default.
+const DEFAULT_SUB_CHANNEL: &str = "aeron:udp?endpoint=224.1.1.1:40456|interface=localhost";
+const DEFAULT_SUB_STREAM_ID: i32 = 1002;
+
+const DEFAULT_SEGMENT_SIZE: u64 = 128 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub aeron: TransportConfig,
+    /// The recorded stream; `SUB_CHANNEL`/`SUB_STREAM_ID`.
+    pub sub: ChannelConfig,
+    /// Replay requests; `REQUESTS_CHANNEL`/`REQUESTS_STREAM_ID`.
+    pub requests: ChannelConfig,
+    /// Replays; `REPLIES_CHANNEL`/`REPLIES_STREAM_ID`.
+    pub replies: ChannelConfig,
+    /// Directory of the segment files.
+    pub dir: String,
+    /// Bytes after which a new segment file is started.
+    pub segment_size: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aeron: TransportConfig::default(),
+            sub: ChannelConfig::new(DEFAULT_SUB_CHANNEL, DEFAULT_SUB_STREAM_ID),
+            requests: ChannelConfig::new(DEFAULT_REQUEST_CHANNEL, DEFAULT_REQUEST_STREAM_ID),
+            replies: ChannelConfig::new(DEFAULT_REPLAY_CHANNEL, DEFAULT_REPLAY_STREAM_ID),
+            dir: "recording".to_owned(),
+            segment_size: DEFAULT_SEGMENT_SIZE,
+        }
+    }
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        self.aeron.validate()?;
+        self.sub.validate("sub")?;
+        self.requests.validate("requests")?;
+        self.replies.validate("replies")?;
+        settings::required("dir", &self.dir)?;
+        if self.segment_size == 0 {
+            return Err("segment_size must be greater than zero".to_owned());
+        }
+        Ok(())
+    }
+}
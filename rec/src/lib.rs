@@ -0,0 +1,15 @@
+//! Recording and replay of the engine's execution report stream. The recorder service appends
+//! every frame of the stream to a `store::Recording` and serves replays of it with a
+//! `replay::Replayer`. Consumers that were down catch up with a `merge::ReplayMerge`, which
+//! replays what they missed and then joins the live stream, and a `sequence::ExecSequencer`
+//! delivers the stream in `ExecID` order, replaying whatever goes missing.
+
+pub mod merge;
+pub mod replay;
+pub mod sequence;
+pub mod store;
+
+pub use merge::{MergeState, ReplayConfig, ReplayFrom, ReplayMerge, ReportStream, subscribe};
+pub use replay::Replayer;
+pub use sequence::{ExecSequencer, SequenceState, exec_id};
+pub use store::{Recording, RecordingReader, StoreError};
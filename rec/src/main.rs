@@ -0,0 +1,122 @@
+mod config;
+
+use config::Config;
+
+use std::process;
+use std::time::{Duration, Instant};
+
+use aeron_rs::concurrent::strategies::{BusySpinIdleStrategy, Strategy};
+
+use recorder::{Recording, Replayer, StoreError};
+
+use sbe::{Message, decode_message};
+
+use tracing::subscriber::set_global_default;
+use tracing::{Level, error, info, warn};
+use tracing_subscriber::FmtSubscriber;
+
+use transport::{AeronTransport, MessageSource, TransportError};
+
+/// How often appended frames are flushed to disk.
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Frames sent per replay each time round the loop, so recording is never held up for long.
+const REPLAY_BATCH: usize = 64;
+
+fn aeron_setup_failed(err: TransportError) -> ! {
+    error!("Aeron: {}", err);
+    process::exit(1);
+}
+
+/// A frame that cannot be recorded leaves a hole in the recording, so the recorder stops.
+fn recording_failed(err: &StoreError) -> ! {
+    error!(error = %err, "Recording failed");
+    process::exit(1);
+}
+
+fn main() {
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::INFO)
+        .finish();
+
+    set_global_default(subscriber).unwrap_or_else(|err| {
+        error!(target: "setup", kind="tracing_init_failed", error=?err, "Failed to create Tracing subscriber");
+        process::exit(1);
+    });
+
+    let config: Config = settings::load("recorder");
+
+    let mut recording = Recording::open(&config.dir, config.segment_size)
+        .unwrap_or_else(|err| recording_failed(&err));
+    info!(
+        dir = config.dir,
+        start = recording.start(),
+        end = recording.end(),
+        frames = recording.len(),
+        "Opened recording"
+    );
+
+    let mut transport =
+        AeronTransport::connect(&config.aeron).unwrap_or_else(|err| aeron_setup_failed(err));
+
+    let mut subscription = transport
+        .add_subscription(&config.sub)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    info!("Aeron: Recording {}", config.sub);
+
+    let mut requests = transport
+        .add_subscription(&config.requests)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    let replies = transport
+        .add_publication(&config.replies)
+        .unwrap_or_else(|err| aeron_setup_failed(err));
+    info!(
+        "Aeron: Replay requests on {}, replays on {}",
+        config.requests, config.replies
+    );
+
+    let mut replayer = Replayer::new(replies);
+    let mut last_sync = Instant::now();
+    let mut unsynced = false;
+
+    let poll_idle_strategy = BusySpinIdleStrategy::default();
+
+    info!("Starting Aeron polling loop...");
+
+    loop {
+        let recorded = subscription.poll(
+            &mut |frame| {
+                if let Err(err) = recording.append(frame, recorder::exec_id(frame).unwrap_or(0)) {
+                    recording_failed(&err);
+                }
+            },
+            1024,
+        );
+        unsynced |= recorded > 0;
+
+        let requested = requests.poll(
+            &mut |frame| match decode_message(frame) {
+                Ok(Message::ReplayRequest(request)) => replayer.request(&recording, &request),
+                Ok(message) => warn!(
+                    template_id = message.template_id(),
+                    "Unexpected replay request stream message"
+                ),
+                Err(err) => warn!(error = ?err, "Rejecting malformed replay request"),
+            },
+            10,
+        );
+
+        let replayed = replayer.replay(&recording, REPLAY_BATCH);
+
+        if unsynced && last_sync.elapsed() >= SYNC_INTERVAL {
+            recording
+                .sync()
+                .unwrap_or_else(|err| recording_failed(&err));
+            last_sync = Instant::now();
+            unsynced = false;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        poll_idle_strategy.idle_opt((recorded + requested + replayed) as i32);
+    }
+}
@@ -0,0 +1,362 @@
+//! Catching up from the recording and then following the live stream. A `ReplayMerge` is
+//! subscribed to the live stream before it asks for a replay, buffering live frames while the
+//! replay runs. Once the replay has caught up, buffered frames the replay already delivered are
+//! recognised by their `ExecID` and skipped, and the rest are delivered before carrying on with
+//! the live stream.
+
+use crate::sequence::{ExecSequencer, exec_id};
+
+use std::collections::VecDeque;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sbe::replay_status_enum::ReplayStatusEnum;
+use sbe::{Message, ReplayHeader, ReplayRequest, decode_message};
+
+use serde::{Deserialize, Serialize};
+
+use tracing::{info, warn};
+
+use transport::{
+    AeronSink, AeronSource, AeronTransport, ChannelConfig, MessageSink, MessageSource,
+    TransportError,
+};
+
+/// Where the recorder takes replay requests and sends replays by default.
+pub const DEFAULT_REQUEST_CHANNEL: &str = "aeron:udp?endpoint=localhost:40126";
+pub const DEFAULT_REQUEST_STREAM_ID: i32 = 1006;
+pub const DEFAULT_REPLAY_CHANNEL: &str = "aeron:udp?endpoint=224.1.1.1:40459|interface=localhost";
+pub const DEFAULT_REPLAY_STREAM_ID: i32 = 1007;
+
+/// How a consumer catches up on start and recovers lost reports; the `[replay]` section of its
+/// configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReplayConfig {
+    /// Replay the recording before following the live stream.
+    pub enabled: bool,
+    /// Replay from the report with this `ExecID` of the engine's current run; 0 replays the
+    /// run from the start.
+    pub from_exec_id: u64,
+    /// Replay reports lost from the live stream. Without it a lost report leaves the stream
+    /// stale.
+    pub recover_gaps: bool,
+    /// Live frames buffered while a replay runs. If more arrive, the replay is continued from
+    /// where it ended instead.
+    pub max_buffered: usize,
+    pub requests: ChannelConfig,
+    pub replies: ChannelConfig,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            from_exec_id: 0,
+            recover_gaps: true,
+            max_buffered: 100_000,
+            requests: ChannelConfig::new(DEFAULT_REQUEST_CHANNEL, DEFAULT_REQUEST_STREAM_ID),
+            replies: ChannelConfig::new(DEFAULT_REPLAY_CHANNEL, DEFAULT_REPLAY_STREAM_ID),
+        }
+    }
+}
+
+impl ReplayConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled && !self.recover_gaps {
+            return Ok(());
+        }
+        self.requests.validate("replay.requests")?;
+        self.replies.validate("replay.replies")?;
+        if self.max_buffered == 0 {
+            return Err("replay.max_buffered must be greater than zero".to_owned());
+        }
+        Ok(())
+    }
+
+    pub const fn from(&self) -> ReplayFrom {
+        match self.from_exec_id {
+            0 => ReplayFrom::Start,
+            exec_id => ReplayFrom::ExecId(exec_id),
+        }
+    }
+}
+
+/// The execution report stream as consumers read it: in `ExecID` order, with lost reports
+/// replayed from the recorder.
+pub type ReportStream = ExecSequencer<AeronSource, AeronSource, AeronSink>;
+
+/// Subscribes to the report stream on `live`, first catching up from the recording if `config`
+/// asks for it.
+pub fn subscribe(
+    transport: &mut AeronTransport,
+    live: &ChannelConfig,
+    config: &ReplayConfig,
+) -> Result<ReportStream, TransportError> {
+    // Subscribed before requesting a replay, so no frame falls between the two.
+    let live = transport.add_subscription(live)?;
+    if !config.enabled && !config.recover_gaps {
+        return Ok(ExecSequencer::new(live, None, config.max_buffered));
+    }
+    let replies = transport.add_subscription(&config.replies)?;
+    let requests = transport.add_publication(&config.requests)?;
+    Ok(if config.enabled {
+        ExecSequencer::catching_up(
+            live,
+            replies,
+            requests,
+            config.from_exec_id,
+            config.max_buffered,
+        )
+    } else {
+        ExecSequencer::new(live, Some((replies, requests)), config.max_buffered)
+    })
+}
+
+/// A `ReplayID` unlikely to be used by another consumer.
+pub fn new_replay_id() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    #[allow(clippy::cast_possible_truncation)]
+    let nanos = nanos as u64;
+    nanos ^ (u64::from(process::id()) << 32)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayFrom {
+    Start,
+    Position(u64),
+    ExecId(u64),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeState {
+    /// The replay request has not been sent yet.
+    Requesting,
+    Replaying,
+    /// Delivering live frames buffered during the replay.
+    Draining,
+    Live,
+    /// The replay was refused or broke off; nothing more is delivered.
+    Failed(String),
+}
+
+pub struct ReplayMerge<L, R, S> {
+    live: L,
+    replies: R,
+    requests: S,
+    replay_id: u64,
+    from: ReplayFrom,
+    state: MergeState,
+    buffered: VecDeque<Vec<u8>>,
+    max_buffered: usize,
+    /// Live frames were dropped because the buffer was full.
+    overflowed: bool,
+    last_exec_id: Option<u64>,
+}
+
+impl<L, R, S> ReplayMerge<L, R, S>
+where
+    L: MessageSource,
+    R: MessageSource,
+    S: MessageSink,
+{
+    /// `live` must already be subscribed. Replays from `from` on the stream of `replies`,
+    /// requested on `requests`.
+    pub fn new(
+        live: L,
+        replies: R,
+        requests: S,
+        replay_id: u64,
+        from: ReplayFrom,
+        max_buffered: usize,
+    ) -> Self {
+        Self {
+            live,
+            replies,
+            requests,
+            replay_id,
+            from,
+            state: MergeState::Requesting,
+            buffered: VecDeque::new(),
+            max_buffered,
+            overflowed: false,
+            last_exec_id: None,
+        }
+    }
+
+    pub const fn state(&self) -> &MergeState {
+        &self.state
+    }
+
+    /// `ExecID` of the last report delivered from the replay.
+    pub const fn last_exec_id(&self) -> Option<u64> {
+        self.last_exec_id
+    }
+
+    fn request(&mut self) {
+        let (position, exec_id) = match self.from {
+            ReplayFrom::Start => (None, None),
+            ReplayFrom::Position(position) => (Some(position), None),
+            ReplayFrom::ExecId(exec_id) => (None, Some(exec_id)),
+        };
+        let request = ReplayRequest {
+            replay_id: self.replay_id,
+            position,
+            exec_id,
+        };
+        let mut frame = [0; ReplayRequest::ENCODED_LENGTH];
+        request
+            .encode(&mut frame)
+            .expect("buffer is sized for a ReplayRequest");
+        match self.requests.offer(&frame) {
+            Ok(()) => {
+                info!(replay_id = self.replay_id, from = ?self.from, "Requested replay");
+                self.state = MergeState::Replaying;
+            }
+            Err(err) if err.is_transient() => {}
+            Err(err) => self.fail(format!("replay request failed: {err}")),
+        }
+    }
+
+    /// Keeps the live stream moving while the replay runs. Once the buffer overflows, live
+    /// frames are dropped until the replay ends.
+    fn buffer_live(&mut self, limit: usize) {
+        let was_overflowed = self.overflowed;
+        let buffered = &mut self.buffered;
+        let overflowed = &mut self.overflowed;
+        let max_buffered = self.max_buffered;
+        self.live.poll(
+            &mut |frame| {
+                if *overflowed {
+                    return;
+                }
+                if buffered.len() < max_buffered {
+                    buffered.push_back(frame.to_vec());
+                } else {
+                    *overflowed = true;
+                    buffered.clear();
+                }
+            },
+            limit,
+        );
+        if self.overflowed && !was_overflowed {
+            warn!(
+                replay_id = self.replay_id,
+                "Live buffer overflowed during replay"
+            );
+        }
+    }
+
+    fn replay(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        let replay_id = self.replay_id;
+        let mut delivered = 0;
+        let mut end = None;
+        let last_exec_id = &mut self.last_exec_id;
+        self.replies.poll(
+            &mut |frame| {
+                if end.is_some() {
+                    return;
+                }
+                match decode_message(frame) {
+                    Ok(Message::ReplayHeader(header)) if header.replay_id == replay_id => {
+                        let frame = &frame[ReplayHeader::ENCODED_LENGTH..];
+                        if let Some(exec_id) = exec_id(frame) {
+                            *last_exec_id = Some(exec_id);
+                        }
+                        handler(frame);
+                        delivered += 1;
+                    }
+                    Ok(Message::ReplayEnd(replay_end)) if replay_end.replay_id == replay_id => {
+                        end = Some(replay_end);
+                    }
+                    _ => {}
+                }
+            },
+            limit,
+        );
+
+        if let Some(end) = end {
+            match end.replay_status {
+                ReplayStatusEnum::Completed if self.overflowed => {
+                    // What the buffer dropped is in the recording by now.
+                    self.overflowed = false;
+                    self.from = ReplayFrom::Position(end.position);
+                    self.state = MergeState::Requesting;
+                }
+                ReplayStatusEnum::Completed => {
+                    self.skip_replayed();
+                    info!(
+                        replay_id,
+                        position = end.position,
+                        buffered = self.buffered.len(),
+                        "Replay caught up, joining the live stream"
+                    );
+                    self.state = MergeState::Draining;
+                }
+                status => self.fail(format!(
+                    "replay ended with {status:?} at position {}",
+                    end.position
+                )),
+            }
+        }
+        delivered
+    }
+
+    /// Drops the buffered frames up to the last one the replay delivered.
+    fn skip_replayed(&mut self) {
+        let Some(last_exec_id) = self.last_exec_id else {
+            return;
+        };
+        let replayed = self
+            .buffered
+            .iter()
+            .rposition(|frame| exec_id(frame).is_some_and(|exec_id| exec_id <= last_exec_id));
+        if let Some(index) = replayed {
+            self.buffered.drain(..=index);
+        }
+    }
+
+    fn fail(&mut self, reason: String) {
+        warn!(replay_id = self.replay_id, reason, "Replay failed");
+        self.buffered.clear();
+        self.state = MergeState::Failed(reason);
+    }
+}
+
+impl<L, R, S> MessageSource for ReplayMerge<L, R, S>
+where
+    L: MessageSource,
+    R: MessageSource,
+    S: MessageSink,
+{
+    fn poll(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        match self.state {
+            MergeState::Requesting => {
+                self.buffer_live(limit);
+                self.request();
+                0
+            }
+            MergeState::Replaying => {
+                self.buffer_live(limit);
+                self.replay(handler, limit)
+            }
+            MergeState::Draining => {
+                let mut delivered = 0;
+                while delivered < limit {
+                    let Some(frame) = self.buffered.pop_front() else {
+                        self.state = MergeState::Live;
+                        return delivered + self.live.poll(handler, limit - delivered);
+                    };
+                    handler(&frame);
+                    delivered += 1;
+                }
+                delivered
+            }
+            MergeState::Live => self.live.poll(handler, limit),
+            // Drained so the publisher is not held up by a consumer that has stopped.
+            MergeState::Failed(_) => self.live.poll(&mut |_| {}, limit),
+        }
+    }
+}
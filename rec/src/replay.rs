@@ -0,0 +1,186 @@
+//! Serves replay requests. Each replay sends the recording from the requested position as
+//! `ReplayHeader` + frame, then a `ReplayEnd` once it has caught up with the recording. Replays
+//! share one stream and are told apart by the requester's `ReplayID`.
+
+use crate::store::{Recording, RecordingReader};
+
+use std::time::{Duration, Instant};
+
+use sbe::replay_status_enum::ReplayStatusEnum;
+use sbe::{ReplayEnd, ReplayHeader, ReplayRequest};
+
+use tracing::{info, warn};
+
+use transport::{MessageSink, SendError};
+
+const HEADER_SIZE: usize = ReplayHeader::ENCODED_LENGTH;
+
+/// A replay that could not send anything for this long is abandoned, as nobody is listening.
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Session {
+    replay_id: u64,
+    reader: Option<RecordingReader>,
+    /// Next frame to send and its position, kept while the stream is back pressured.
+    pending: Option<u64>,
+    frame: Vec<u8>,
+    /// Set once the replay has nothing more to send.
+    status: Option<ReplayStatusEnum>,
+    stalled_since: Option<Instant>,
+}
+
+pub struct Replayer<S: MessageSink> {
+    sink: S,
+    sessions: Vec<Session>,
+}
+
+impl<S: MessageSink> Replayer<S> {
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            sessions: Vec::new(),
+        }
+    }
+
+    /// Number of replays in progress.
+    pub fn replays(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Starts the replay asked for, replacing one with the same `ReplayID`. Requests with
+    /// neither a position nor an `ExecID` replay from the start of the recording.
+    pub fn request(&mut self, recording: &Recording, request: &ReplayRequest) {
+        let position = match (request.position, request.exec_id) {
+            (Some(position), _) => position,
+            (None, Some(exec_id)) => recording.position_of_exec_id(exec_id),
+            (None, None) => recording.start(),
+        };
+
+        let (reader, status) = match recording.reader(position) {
+            Ok(reader) => {
+                info!(replay_id = request.replay_id, position, "Starting replay");
+                (Some(reader), None)
+            }
+            Err(err) => {
+                warn!(replay_id = request.replay_id, error = %err, "Rejecting replay request");
+                (None, Some(ReplayStatusEnum::UnknownPosition))
+            }
+        };
+
+        self.sessions
+            .retain(|session| session.replay_id != request.replay_id);
+        self.sessions.push(Session {
+            replay_id: request.replay_id,
+            reader,
+            pending: None,
+            frame: Vec::new(),
+            status,
+            stalled_since: None,
+        });
+    }
+
+    /// Sends up to `limit` frames of each replay, ending the replays that have caught up, and
+    /// returns the number of frames sent.
+    pub fn replay(&mut self, recording: &Recording, limit: usize) -> usize {
+        let mut sent = 0;
+        let sink = &mut self.sink;
+        self.sessions.retain_mut(|session| {
+            let (frames, done) = replay_session(sink, session, recording, limit);
+            sent += frames;
+            !done
+        });
+        sent
+    }
+}
+
+/// Returns the number of frames sent and whether the session is over.
+fn replay_session<S: MessageSink>(
+    sink: &mut S,
+    session: &mut Session,
+    recording: &Recording,
+    limit: usize,
+) -> (usize, bool) {
+    let mut sent = 0;
+    while session.status.is_none() && sent < limit {
+        let reader = session.reader.as_mut().expect("open replays have a reader");
+        if session.pending.is_none() {
+            match reader.next_frame(recording.end(), &mut session.frame) {
+                Ok(Some(position)) => session.pending = Some(position),
+                Ok(None) => session.status = Some(ReplayStatusEnum::Completed),
+                Err(err) => {
+                    warn!(replay_id = session.replay_id, error = %err, "Replay failed");
+                    session.status = Some(ReplayStatusEnum::Failed);
+                }
+            }
+            continue;
+        }
+
+        let position = session.pending.expect("checked above");
+        let header = ReplayHeader {
+            replay_id: session.replay_id,
+            position,
+        };
+        let frame = &session.frame;
+        let result = sink.try_publish(HEADER_SIZE + frame.len(), &mut |buffer| {
+            let (header_buffer, body) = buffer.split_at_mut(HEADER_SIZE);
+            header
+                .encode(header_buffer)
+                .expect("header buffer is sized for a ReplayHeader");
+            body.copy_from_slice(frame);
+        });
+        match sent_or_stalled(session, result) {
+            Some(true) => {
+                session.pending = None;
+                sent += 1;
+            }
+            Some(false) => return (sent, false),
+            None => return (sent, true),
+        }
+    }
+
+    let Some(replay_status) = session.status else {
+        return (sent, false);
+    };
+    let position = session
+        .reader
+        .as_ref()
+        .map_or_else(|| recording.end(), RecordingReader::position);
+    let end = ReplayEnd {
+        replay_id: session.replay_id,
+        position,
+        replay_status,
+    };
+    let result = sink.try_publish(ReplayEnd::ENCODED_LENGTH, &mut |buffer| {
+        end.encode(buffer).expect("buffer is sized for a ReplayEnd");
+    });
+    match sent_or_stalled(session, result) {
+        Some(true) => {
+            info!(replay_id = session.replay_id, position, status = ?replay_status, "Replay ended");
+            (sent, true)
+        }
+        Some(false) => (sent, false),
+        None => (sent, true),
+    }
+}
+
+/// `Some(true)` if sent, `Some(false)` to retry later and `None` to abandon the replay.
+fn sent_or_stalled(session: &mut Session, result: Result<(), SendError>) -> Option<bool> {
+    match result {
+        Ok(()) => {
+            session.stalled_since = None;
+            Some(true)
+        }
+        Err(err) if err.is_transient() => {
+            let stalled_since = *session.stalled_since.get_or_insert_with(Instant::now);
+            if stalled_since.elapsed() < STALL_TIMEOUT {
+                return Some(false);
+            }
+            warn!(replay_id = session.replay_id, error = %err, "Abandoning stalled replay");
+            None
+        }
+        Err(err) => {
+            warn!(replay_id = session.replay_id, error = %err, "Abandoning replay");
+            None
+        }
+    }
+}
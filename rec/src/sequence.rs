@@ -0,0 +1,373 @@
+//! Delivering the execution report stream in `ExecID` order. The engine numbers every execution
+//! report and cancel reject of a run from 1 without gaps, so an `ExecSequencer` can tell a lost
+//! frame from a repeated one. Repeats are dropped. Frames after a gap are held back while the
+//! missing ones are replayed from the recorder, then delivered in order. A gap the recorder
+//! cannot fill leaves the stream `Stale`: nothing more is delivered, since whatever the consumer
+//! builds from the stream would be wrong.
+//!
+//! Frames without an `ExecID`, such as status reports, are not part of the sequence and are
+//! delivered as they arrive.
+
+use crate::merge::new_replay_id;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use sbe::replay_status_enum::ReplayStatusEnum;
+use sbe::{Message, ReplayHeader, ReplayRequest, decode_message};
+
+use tracing::{debug, info, warn};
+
+use transport::{MessageSink, MessageSource};
+
+/// A recovery without a word from the recorder for this long is given up.
+pub const RECOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The `ExecID` of a frame; status reports and messages outside the sequence have none.
+pub fn exec_id(frame: &[u8]) -> Option<u64> {
+    match decode_message(frame) {
+        Ok(Message::ExecutionReport(report)) if report.exec_id != 0 => Some(report.exec_id),
+        Ok(Message::OrderCancelReject(reject)) => reject.exec_id,
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SequenceState {
+    /// Every frame up to `next` has been delivered and nothing is missing.
+    Live,
+    /// Frames from `next` on are being replayed from the recorder.
+    Recovering { next: u64 },
+    /// Frames went missing and could not be recovered; nothing more is delivered.
+    Stale(String),
+}
+
+/// Asks the recorder to replay the stream and reads the replays.
+struct Recovery<R, Q> {
+    replies: R,
+    requests: Q,
+    replay_id: u64,
+    /// A request is due but has not been sent yet.
+    request_pending: bool,
+    /// When the recorder was last heard from, or the request sent.
+    last_heard: Instant,
+    /// `next` when the current replay was requested, to tell whether it filled anything.
+    requested_from: u64,
+}
+
+pub struct ExecSequencer<L, R, Q> {
+    live: L,
+    recovery: Option<Recovery<R, Q>>,
+    sequence: Sequence,
+}
+
+/// Where the stream is up to, apart from the streams it is read from.
+struct Sequence {
+    state: SequenceState,
+    /// `ExecID` of the next frame to deliver; `None` until the first frame sets it.
+    next: Option<u64>,
+    /// Frames after a gap, by `ExecID`.
+    buffered: BTreeMap<u64, Vec<u8>>,
+    max_buffered: usize,
+    /// Live frames were dropped or the consumer asked to catch up, so the recovery is only
+    /// over once the replay has ended.
+    await_end: bool,
+}
+
+impl<L, R, Q> ExecSequencer<L, R, Q>
+where
+    L: MessageSource,
+    R: MessageSource,
+    Q: MessageSink,
+{
+    /// Sequences `live`, which starts at whichever `ExecID` arrives first. Gaps are recovered
+    /// with replays read from `replies` and requested on `requests`, if given; without them a
+    /// gap leaves the stream stale.
+    pub fn new(live: L, recovery: Option<(R, Q)>, max_buffered: usize) -> Self {
+        Self {
+            live,
+            recovery: recovery.map(|(replies, requests)| Recovery {
+                replies,
+                requests,
+                replay_id: 0,
+                request_pending: false,
+                last_heard: Instant::now(),
+                requested_from: 0,
+            }),
+            sequence: Sequence {
+                state: SequenceState::Live,
+                next: None,
+                buffered: BTreeMap::new(),
+                max_buffered,
+                await_end: false,
+            },
+        }
+    }
+
+    /// Like `new`, but first catches up from the report with `ExecID` `from` of the engine's
+    /// current run, and only goes live once the replay has caught up with the recording.
+    pub fn catching_up(live: L, replies: R, requests: Q, from: u64, max_buffered: usize) -> Self {
+        let mut sequencer = Self::new(live, Some((replies, requests)), max_buffered);
+        let from = from.max(1);
+        sequencer.sequence.next = Some(from);
+        sequencer.sequence.await_end = true;
+        sequencer.sequence.recover(&mut sequencer.recovery, from);
+        sequencer
+    }
+
+    pub const fn state(&self) -> &SequenceState {
+        &self.sequence.state
+    }
+
+    /// `ExecID` of the next frame to be delivered.
+    pub const fn next_exec_id(&self) -> Option<u64> {
+        self.sequence.next
+    }
+
+    /// Frames held back until a gap is filled.
+    pub fn buffered(&self) -> usize {
+        self.sequence.buffered.len()
+    }
+
+    /// Reads the replay, delivering what it fills in, and returns the number of frames read.
+    fn poll_replay(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        let Some(recovery) = self.recovery.as_mut() else {
+            return 0;
+        };
+        let replay_id = recovery.replay_id;
+        let mut frames = Vec::new();
+        let mut end = None;
+        // Read even when not recovering, so that the rest of a replay does not back up.
+        let read = recovery.replies.poll(
+            &mut |frame| match decode_message(frame) {
+                Ok(Message::ReplayHeader(header)) if header.replay_id == replay_id => {
+                    frames.push(frame[ReplayHeader::ENCODED_LENGTH..].to_vec());
+                }
+                Ok(Message::ReplayEnd(replay_end)) if replay_end.replay_id == replay_id => {
+                    end = Some(replay_end.replay_status);
+                }
+                _ => {}
+            },
+            limit,
+        );
+        if !frames.is_empty() || end.is_some() {
+            recovery.last_heard = Instant::now();
+        }
+        if !self.sequence.is_recovering() {
+            return read;
+        }
+
+        for frame in &frames {
+            self.sequence.push(&mut self.recovery, frame, handler, true);
+        }
+        if let Some(status) = end {
+            self.sequence.replay_ended(&mut self.recovery, status);
+        }
+        read
+    }
+}
+
+impl Sequence {
+    const fn is_recovering(&self) -> bool {
+        matches!(self.state, SequenceState::Recovering { .. })
+    }
+
+    /// Delivers `frame` if it is next and whatever it unblocks, buffers it if it is early and
+    /// drops it if it was delivered before.
+    fn push<R: MessageSource, Q: MessageSink>(
+        &mut self,
+        recovery: &mut Option<Recovery<R, Q>>,
+        frame: &[u8],
+        handler: &mut dyn FnMut(&[u8]),
+        from_replay: bool,
+    ) {
+        if let SequenceState::Stale(_) = self.state {
+            return;
+        }
+        let Some(exec_id) = exec_id(frame) else {
+            // Only live status reports are of interest; the replay repeats old ones.
+            if !from_replay {
+                handler(frame);
+            }
+            return;
+        };
+        let next = *self.next.get_or_insert(exec_id);
+
+        if exec_id == 1 && next > 2 && !from_replay {
+            warn!(
+                expected = next,
+                "Engine restarted, sequencing its new run from the start"
+            );
+            self.next = Some(1);
+            self.buffered.clear();
+            self.await_end = false;
+            self.state = SequenceState::Live;
+            self.push(recovery, frame, handler, false);
+            return;
+        }
+
+        if exec_id < next {
+            debug!(exec_id, expected = next, "Dropping repeated report");
+            return;
+        }
+        if exec_id > next {
+            if self.buffered.len() < self.max_buffered {
+                self.buffered.insert(exec_id, frame.to_vec());
+            } else if !self.await_end {
+                warn!(
+                    exec_id,
+                    max_buffered = self.max_buffered,
+                    "Recovery buffer full, relying on the replay"
+                );
+                self.await_end = true;
+            }
+            if self.state == SequenceState::Live {
+                warn!(exec_id, expected = next, "Gap in the report stream");
+                self.recover(recovery, next);
+            }
+            return;
+        }
+
+        handler(frame);
+        let mut next = next + 1;
+        while let Some(frame) = self.buffered.remove(&next) {
+            handler(&frame);
+            next += 1;
+        }
+        // Buffered copies of frames the replay has delivered since.
+        self.buffered = self.buffered.split_off(&next);
+        self.next = Some(next);
+
+        if self.is_recovering() {
+            self.state = SequenceState::Recovering { next };
+            if self.buffered.is_empty() && !self.await_end {
+                info!(next, "Recovered the report stream");
+                self.state = SequenceState::Live;
+            }
+        }
+    }
+
+    fn recover<R: MessageSource, Q: MessageSink>(
+        &mut self,
+        recovery: &mut Option<Recovery<R, Q>>,
+        next: u64,
+    ) {
+        let Some(recovery) = recovery.as_mut() else {
+            self.stale(format!(
+                "reports from ExecID {next} are missing and recovery is disabled"
+            ));
+            return;
+        };
+        recovery.request_pending = true;
+        recovery.last_heard = Instant::now();
+        self.state = SequenceState::Recovering { next };
+        self.send_request(recovery);
+    }
+
+    fn send_request<R: MessageSource, Q: MessageSink>(&mut self, recovery: &mut Recovery<R, Q>) {
+        let Some(next) = self.next else {
+            return;
+        };
+        let replay_id = new_replay_id();
+        let request = ReplayRequest {
+            replay_id,
+            position: None,
+            exec_id: Some(next),
+        };
+        let mut frame = [0; ReplayRequest::ENCODED_LENGTH];
+        request
+            .encode(&mut frame)
+            .expect("buffer is sized for a ReplayRequest");
+        match recovery.requests.offer(&frame) {
+            Ok(()) => {
+                info!(
+                    replay_id,
+                    from = next,
+                    "Requested replay of missing reports"
+                );
+                recovery.replay_id = replay_id;
+                recovery.request_pending = false;
+                recovery.requested_from = next;
+            }
+            Err(err) if err.is_transient() => {}
+            Err(err) => self.stale(format!("replay request failed: {err}")),
+        }
+    }
+
+    fn replay_ended<R: MessageSource, Q: MessageSink>(
+        &mut self,
+        recovery: &mut Option<Recovery<R, Q>>,
+        status: ReplayStatusEnum,
+    ) {
+        let Some(next) = self.next else {
+            return;
+        };
+        if status != ReplayStatusEnum::Completed {
+            self.stale(format!("replay from ExecID {next} ended with {status:?}"));
+            return;
+        }
+        let was_awaiting_end = self.await_end;
+        self.await_end = false;
+        let Some(&first_buffered) = self.buffered.keys().next() else {
+            if self.is_recovering() {
+                info!(next, "Recovered the report stream");
+                self.state = SequenceState::Live;
+            }
+            return;
+        };
+        let requested_from = recovery
+            .as_ref()
+            .map_or(next, |recovery| recovery.requested_from);
+        if next > requested_from || was_awaiting_end {
+            // Dropped or newly recorded frames may fill the rest.
+            self.recover(recovery, next);
+        } else {
+            self.stale(format!(
+                "reports {next} to {} are missing from the recording",
+                first_buffered - 1
+            ));
+        }
+    }
+
+    fn stale(&mut self, reason: String) {
+        warn!(reason, "Report stream is stale");
+        self.buffered.clear();
+        self.state = SequenceState::Stale(reason);
+    }
+}
+
+impl<L, R, Q> MessageSource for ExecSequencer<L, R, Q>
+where
+    L: MessageSource,
+    R: MessageSource,
+    Q: MessageSink,
+{
+    fn poll(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        if let SequenceState::Stale(_) = self.sequence.state {
+            // Drained so the publisher is not held up by a consumer that has stopped.
+            return self.live.poll(&mut |_| {}, limit);
+        }
+
+        let mut read = self.poll_replay(handler, limit);
+        if let (Some(recovery), SequenceState::Recovering { next }) =
+            (self.recovery.as_mut(), &self.sequence.state)
+        {
+            if recovery.last_heard.elapsed() >= RECOVERY_TIMEOUT {
+                let next = *next;
+                self.sequence.stale(format!(
+                    "the recorder did not replay the reports from ExecID {next}"
+                ));
+            } else if recovery.request_pending {
+                self.sequence.send_request(recovery);
+            }
+        }
+
+        let sequence = &mut self.sequence;
+        let recovery = &mut self.recovery;
+        read += self.live.poll(
+            &mut |frame| sequence.push(recovery, frame, handler, false),
+            limit,
+        );
+        read
+    }
+}
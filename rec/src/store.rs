@@ -0,0 +1,396 @@
+//! The recording on disk: a directory of segment files, each named after the position of its
+//! first frame, e.g. `00000000000134217728.rec`. A segment holds frames in the order they were
+//! recorded, each preceded by its length as a little-endian `u32`, so `sbe-dump stream` reads it
+//! as is. Positions count bytes from the start of the recording and a frame's position is that of
+//! its length prefix.
+//!
+//! Next to each segment an `.idx` file lists the position and `ExecID` of each of its frames as
+//! little-endian `u64` pairs, 0 for frames without one. An index entry is written before its
+//! frame, so after a crash the frames are trimmed to the last one that is complete and indexed.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const SEGMENT_EXTENSION: &str = "rec";
+const INDEX_EXTENSION: &str = "idx";
+const LENGTH_PREFIX: u64 = size_of::<u32>() as u64;
+const INDEX_ENTRY: usize = 2 * size_of::<u64>();
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// The files on disk do not form a recording, e.g. a segment is missing.
+    Corrupt {
+        path: PathBuf,
+        reason: String,
+    },
+    FrameTooLong {
+        length: usize,
+    },
+    /// The position is not the start of a recorded frame.
+    UnknownPosition {
+        position: u64,
+    },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Self::Corrupt { path, reason } => {
+                write!(f, "{} is not a valid recording: {reason}", path.display())
+            }
+            Self::FrameTooLong { length } => write!(f, "frame of {length} bytes is too long"),
+            Self::UnknownPosition { position } => {
+                write!(
+                    f,
+                    "position {position} is not the start of a recorded frame"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> StoreError + '_ {
+    move |error| StoreError::Io {
+        path: path.to_owned(),
+        error,
+    }
+}
+
+/// A recording being appended to. Readers see every frame appended before they read.
+pub struct Recording {
+    dir: PathBuf,
+    segment_size: u64,
+    /// Start position of each segment, in order.
+    segments: Vec<u64>,
+    segment: File,
+    index_file: File,
+    /// Position of every frame, in order.
+    positions: Vec<u64>,
+    /// `ExecID` and position of every frame that has one, in order.
+    exec_ids: Vec<(u64, u64)>,
+    /// Where the engine's current run starts in `exec_ids`. The engine numbers executions from 1
+    /// again when it restarts, so only the latest run is searched.
+    run_start: usize,
+    end: u64,
+    record: Vec<u8>,
+}
+
+impl Recording {
+    /// Opens the recording in `dir`, creating it if needed, and recovers from an interrupted
+    /// append. A new segment is started once the current one would exceed `segment_size` bytes.
+    pub fn open(dir: impl Into<PathBuf>, segment_size: u64) -> Result<Self, StoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(io_error(&dir))?;
+
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&dir).map_err(io_error(&dir))? {
+            let path = entry.map_err(io_error(&dir))?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == SEGMENT_EXTENSION)
+            {
+                let start = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                    .ok_or_else(|| StoreError::Corrupt {
+                        path: path.clone(),
+                        reason: "segment is not named after its position".to_owned(),
+                    })?;
+                segments.push(start);
+            }
+        }
+        segments.sort_unstable();
+        if segments.is_empty() {
+            segments.push(0);
+        }
+
+        let mut recording = Self {
+            segment: create(&segment_path(&dir, segments[0]))?,
+            index_file: create(&index_path(&dir, segments[0]))?,
+            dir,
+            segment_size,
+            segments: Vec::new(),
+            positions: Vec::new(),
+            exec_ids: Vec::new(),
+            run_start: 0,
+            end: segments[0],
+            record: Vec::new(),
+        };
+        let last = segments.len() - 1;
+        for (number, &start) in segments.iter().enumerate() {
+            recording.load_segment(start, number == last)?;
+        }
+        Ok(recording)
+    }
+
+    /// Position of the first frame.
+    pub fn start(&self) -> u64 {
+        self.segments[0]
+    }
+
+    /// Position the next frame will be recorded at.
+    pub const fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Records `frame` with its `ExecID`, 0 if it has none, and returns its position.
+    pub fn append(&mut self, frame: &[u8], exec_id: u64) -> Result<u64, StoreError> {
+        let length = u32::try_from(frame.len()).map_err(|_| StoreError::FrameTooLong {
+            length: frame.len(),
+        })?;
+        let record_length = LENGTH_PREFIX + u64::from(length);
+        let segment_start = *self.segments.last().expect("a recording has a segment");
+        if self.end > segment_start && self.end - segment_start + record_length > self.segment_size
+        {
+            self.start_segment(self.end)?;
+        }
+
+        let position = self.end;
+        let mut entry = [0; INDEX_ENTRY];
+        entry[..8].copy_from_slice(&position.to_le_bytes());
+        entry[8..].copy_from_slice(&exec_id.to_le_bytes());
+        self.index_file
+            .write_all(&entry)
+            .map_err(io_error(&index_path(&self.dir, segment_start)))?;
+
+        self.record.clear();
+        self.record.extend_from_slice(&length.to_le_bytes());
+        self.record.extend_from_slice(frame);
+        self.segment
+            .write_all(&self.record)
+            .map_err(io_error(&segment_path(&self.dir, segment_start)))?;
+
+        self.push(position, exec_id);
+        self.end += record_length;
+        Ok(position)
+    }
+
+    /// Flushes the current segment and its index to disk.
+    pub fn sync(&self) -> Result<(), StoreError> {
+        let segment_start = *self.segments.last().expect("a recording has a segment");
+        self.index_file
+            .sync_data()
+            .map_err(io_error(&index_path(&self.dir, segment_start)))?;
+        self.segment
+            .sync_data()
+            .map_err(io_error(&segment_path(&self.dir, segment_start)))
+    }
+
+    /// True if `position` is the start of a recorded frame or the end of the recording.
+    pub fn contains(&self, position: u64) -> bool {
+        position == self.end || self.positions.binary_search(&position).is_ok()
+    }
+
+    /// Position of the first frame of the engine's current run with an `ExecID` of at least
+    /// `exec_id`, or the end if there is none yet.
+    pub fn position_of_exec_id(&self, exec_id: u64) -> u64 {
+        let run = &self.exec_ids[self.run_start..];
+        let index = run.partition_point(|&(recorded, _)| recorded < exec_id);
+        run.get(index).map_or(self.end, |&(_, position)| position)
+    }
+
+    /// Reads the recording from `position`, which must start a frame or be the end.
+    pub fn reader(&self, position: u64) -> Result<RecordingReader, StoreError> {
+        if !self.contains(position) {
+            return Err(StoreError::UnknownPosition { position });
+        }
+        let segment = self.segments.partition_point(|&start| start <= position) - 1;
+        Ok(RecordingReader {
+            dir: self.dir.clone(),
+            segment_start: self.segments[segment],
+            position,
+            file: None,
+        })
+    }
+
+    fn push(&mut self, position: u64, exec_id: u64) {
+        self.positions.push(position);
+        if exec_id != 0 {
+            if self
+                .exec_ids
+                .last()
+                .is_some_and(|&(last, _)| exec_id <= last)
+            {
+                self.run_start = self.exec_ids.len();
+            }
+            self.exec_ids.push((exec_id, position));
+        }
+    }
+
+    fn start_segment(&mut self, start: u64) -> Result<(), StoreError> {
+        self.segment = create(&segment_path(&self.dir, start))?;
+        self.index_file = create(&index_path(&self.dir, start))?;
+        self.segments.push(start);
+        Ok(())
+    }
+
+    /// Loads a segment's index. The last segment is checked against its frames and trimmed to
+    /// the last complete, indexed one, then opened for appending.
+    fn load_segment(&mut self, start: u64, last: bool) -> Result<(), StoreError> {
+        let path = segment_path(&self.dir, start);
+        if start != self.end {
+            return Err(StoreError::Corrupt {
+                path,
+                reason: format!("segment should start at {}", self.end),
+            });
+        }
+
+        let index_path = index_path(&self.dir, start);
+        let index = match fs::read(&index_path) {
+            Ok(index) => index,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(io_error(&index_path)(err)),
+        };
+        let entries: Vec<(u64, u64)> = index
+            .chunks_exact(INDEX_ENTRY)
+            .map(|entry| {
+                let (position, exec_id) = entry.split_at(8);
+                (
+                    u64::from_le_bytes(position.try_into().expect("8 bytes")),
+                    u64::from_le_bytes(exec_id.try_into().expect("8 bytes")),
+                )
+            })
+            .collect();
+
+        let data = fs::read(&path).map_err(io_error(&path))?;
+        let mut offset = 0;
+        let mut frames = 0;
+        if last {
+            // Count the complete frames that have an index entry in the right place.
+            while let Some(&(position, _)) = entries.get(frames) {
+                let Some(prefix) = data.get(offset..offset + 4) else {
+                    break;
+                };
+                let length = u32::from_le_bytes(prefix.try_into().expect("4 bytes")) as usize;
+                if position != start + offset as u64 || data.len() < offset + 4 + length {
+                    break;
+                }
+                offset += 4 + length;
+                frames += 1;
+            }
+            truncate(&path, offset as u64)?;
+            truncate(&index_path, (frames * INDEX_ENTRY) as u64)?;
+        } else {
+            offset = data.len();
+            frames = entries.len();
+        }
+
+        for &(position, exec_id) in &entries[..frames] {
+            self.push(position, exec_id);
+        }
+        self.segments.push(start);
+        self.end = start + offset as u64;
+        if last {
+            self.segment = create(&path)?;
+            self.index_file = create(&index_path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads frames in order from a position of a recording.
+pub struct RecordingReader {
+    dir: PathBuf,
+    segment_start: u64,
+    position: u64,
+    file: Option<BufReader<File>>,
+}
+
+impl RecordingReader {
+    /// Position of the next frame.
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Reads the next frame into `frame` and returns its position, or `None` once `end`, the
+    /// recording's end when called, is reached.
+    pub fn next_frame(&mut self, end: u64, frame: &mut Vec<u8>) -> Result<Option<u64>, StoreError> {
+        if self.position >= end {
+            return Ok(None);
+        }
+
+        let mut prefix = [0; 4];
+        let mut reopened = false;
+        loop {
+            let path = segment_path(&self.dir, self.segment_start);
+            if self.file.is_none() {
+                let mut file = File::open(&path).map_err(io_error(&path))?;
+                file.seek(SeekFrom::Start(self.position - self.segment_start))
+                    .map_err(io_error(&path))?;
+                self.file = Some(BufReader::new(file));
+            }
+            let file = self.file.as_mut().expect("opened above");
+            match file.read_exact(&mut prefix) {
+                Ok(()) => break,
+                // The segment is used up, so the next one starts here.
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof && !reopened => {
+                    self.file = None;
+                    self.segment_start = self.position;
+                    reopened = true;
+                }
+                Err(err) => return Err(io_error(&path)(err)),
+            }
+        }
+
+        let length = u32::from_le_bytes(prefix) as usize;
+        frame.resize(length, 0);
+        let path = segment_path(&self.dir, self.segment_start);
+        self.file
+            .as_mut()
+            .expect("opened above")
+            .read_exact(frame)
+            .map_err(io_error(&path))?;
+
+        let position = self.position;
+        self.position += LENGTH_PREFIX + length as u64;
+        Ok(Some(position))
+    }
+}
+
+fn segment_path(dir: &Path, start: u64) -> PathBuf {
+    dir.join(format!("{start:020}.{SEGMENT_EXTENSION}"))
+}
+
+fn index_path(dir: &Path, start: u64) -> PathBuf {
+    dir.join(format!("{start:020}.{INDEX_EXTENSION}"))
+}
+
+fn create(path: &Path) -> Result<File, StoreError> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(io_error(path))
+}
+
+fn truncate(path: &Path, length: u64) -> Result<(), StoreError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .map_err(io_error(path))?;
+    if file.metadata().map_err(io_error(path))?.len() > length {
+        file.set_len(length).map_err(io_error(path))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::PathBuf;
+
+use recorder::{MergeState, Recording, ReplayFrom, ReplayMerge, Replayer};
+
+use sbe::exec_type_enum::ExecTypeEnum;
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::side_enum::SideEnum;
+use sbe::{Decimal64, ExecutionReport, Message, ReplayRequest, Uuid, decode_message};
+
+use transport::{Loopback, LoopbackSink, LoopbackSource, MessageSink, MessageSource};
+
+const REPLAY_ID: u64 = 77;
+
+fn report(exec_id: u64) -> Vec<u8> {
+    let report = ExecutionReport {
+        account: Uuid::from_u128(1),
+        cl_ord_id: Uuid::from_u64_pair(1, exec_id),
+        trd_match_id: None,
+        order_id: exec_id,
+        exec_id,
+        transact_time: 1_700_000_000_000_000_000,
+        price: Some(Decimal64::from_mantissa(150_000_000)),
+        order_qty: Decimal64::from_mantissa(100_000_000),
+        last_qty: None,
+        last_px: None,
+        leaves_qty: Decimal64::from_mantissa(100_000_000),
+        cum_qty: Decimal64::from_mantissa(0),
+        avg_px: None,
+        symbol: *b"BTCUSD",
+        exec_type: ExecTypeEnum::New,
+        ord_status: OrdStatusEnum::New,
+        ord_rej_reason: None,
+        side: SideEnum::Buy,
+        mass_status_req_id: None,
+        tot_num_reports: None,
+    };
+    let mut frame = vec![0; ExecutionReport::ENCODED_LENGTH];
+    report.encode(&mut frame).unwrap();
+    frame
+}
+
+fn exec_ids(frames: &[Vec<u8>]) -> Vec<u64> {
+    frames
+        .iter()
+        .map(|frame| match decode_message(frame) {
+            Ok(Message::ExecutionReport(report)) => report.exec_id,
+            other => panic!("expected an execution report, got {other:?}"),
+        })
+        .collect()
+}
+
+/// The engine's report stream with a recorder on it, and a consumer catching up from it.
+struct Harness {
+    dir: PathBuf,
+    recording: Recording,
+    live: LoopbackSink,
+    requests: LoopbackSource,
+    replayer: Replayer<LoopbackSink>,
+    merge: ReplayMerge<LoopbackSource, LoopbackSource, LoopbackSink>,
+    delivered: Vec<Vec<u8>>,
+}
+
+impl Harness {
+    /// Records `recorded` before the consumer starts.
+    fn new(name: &str, recorded: &[u64], from: ReplayFrom, max_buffered: usize) -> Self {
+        let dir = std::env::temp_dir().join(format!("recorder-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut recording = Recording::open(&dir, 1 << 20).unwrap();
+        for &exec_id in recorded {
+            recording.append(&report(exec_id), exec_id).unwrap();
+        }
+
+        let live = Loopback::new();
+        let requests = Loopback::new();
+        let replies = Loopback::new();
+        let merge = ReplayMerge::new(
+            live.source(),
+            replies.source(),
+            requests.sink(),
+            REPLAY_ID,
+            from,
+            max_buffered,
+        );
+        Self {
+            dir,
+            recording,
+            live: live.sink(),
+            requests: requests.source(),
+            replayer: Replayer::new(replies.sink()),
+            merge,
+            delivered: Vec::new(),
+        }
+    }
+
+    /// The engine publishes a report, which the recorder records.
+    fn publish(&mut self, exec_id: u64) {
+        let frame = report(exec_id);
+        self.recording.append(&frame, exec_id).unwrap();
+        self.live.offer(&frame).unwrap();
+    }
+
+    fn poll_consumer(&mut self) {
+        let delivered = &mut self.delivered;
+        self.merge
+            .poll(&mut |frame| delivered.push(frame.to_vec()), usize::MAX);
+    }
+
+    fn poll_recorder(&mut self) {
+        let mut requests = Vec::new();
+        self.requests.poll(
+            &mut |frame| match decode_message(frame) {
+                Ok(Message::ReplayRequest(request)) => requests.push(request),
+                other => panic!("expected a replay request, got {other:?}"),
+            },
+            usize::MAX,
+        );
+        for request in &requests {
+            self.replayer.request(&self.recording, request);
+        }
+        self.replayer.replay(&self.recording, usize::MAX);
+    }
+
+    fn run_until_settled(&mut self) {
+        for _ in 0..10 {
+            self.poll_consumer();
+            self.poll_recorder();
+        }
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn replays_the_recording_then_joins_the_live_stream_without_duplicates() {
+    let mut harness = Harness::new("join", &[1, 2], ReplayFrom::Start, 100);
+    // Live while the consumer is starting, so both in the replay and buffered.
+    harness.publish(3);
+    harness.publish(4);
+
+    harness.poll_consumer();
+    assert_eq!(harness.merge.state(), &MergeState::Replaying);
+    harness.poll_recorder();
+    assert_eq!(harness.replayer.replays(), 0);
+    // Live after the replay caught up, so only buffered.
+    harness.publish(5);
+
+    harness.run_until_settled();
+    harness.publish(6);
+    harness.poll_consumer();
+
+    assert_eq!(harness.merge.state(), &MergeState::Live);
+    assert_eq!(harness.merge.last_exec_id(), Some(4));
+    assert_eq!(exec_ids(&harness.delivered), vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn replays_from_an_exec_id() {
+    let mut harness = Harness::new("exec-id", &[1, 2, 3], ReplayFrom::ExecId(2), 100);
+    harness.publish(4);
+
+    harness.run_until_settled();
+
+    assert_eq!(harness.merge.state(), &MergeState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![2, 3, 4]);
+}
+
+#[test]
+fn fails_on_an_unknown_position() {
+    let mut harness = Harness::new("unknown", &[1], ReplayFrom::Position(5), 100);
+    harness.publish(2);
+
+    harness.run_until_settled();
+
+    assert!(matches!(harness.merge.state(), MergeState::Failed(_)));
+    assert!(harness.delivered.is_empty());
+}
+
+#[test]
+fn continues_the_replay_when_the_live_buffer_overflows() {
+    let mut harness = Harness::new("overflow", &[1], ReplayFrom::Start, 1);
+    harness.publish(2);
+    harness.publish(3);
+
+    harness.poll_consumer();
+    harness.poll_recorder();
+    // Dropped by the consumer, which has to get it from a second replay.
+    harness.publish(4);
+    harness.poll_consumer();
+    assert_eq!(harness.merge.state(), &MergeState::Requesting);
+
+    harness.run_until_settled();
+    harness.publish(5);
+    harness.poll_consumer();
+
+    assert_eq!(harness.merge.state(), &MergeState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn replayer_reuses_the_session_of_a_repeated_request() {
+    let dir = std::env::temp_dir().join(format!("recorder-{}-repeat", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let mut recording = Recording::open(&dir, 1 << 20).unwrap();
+    recording.append(&report(1), 1).unwrap();
+
+    let replies = Loopback::new();
+    let mut source = replies.source();
+    let mut replayer = Replayer::new(replies.sink());
+    let request = ReplayRequest {
+        replay_id: REPLAY_ID,
+        position: None,
+        exec_id: None,
+    };
+    replayer.request(&recording, &request);
+    replayer.request(&recording, &request);
+    assert_eq!(replayer.replays(), 1);
+
+    assert_eq!(replayer.replay(&recording, usize::MAX), 1);
+    let mut frames = Vec::new();
+    source.poll(&mut |frame| frames.push(frame.to_vec()), usize::MAX);
+    assert!(matches!(
+        decode_message(&frames[0]),
+        Ok(Message::ReplayHeader(header)) if header.replay_id == REPLAY_ID && header.position == 0
+    ));
+    assert!(matches!(
+        decode_message(&frames[1]),
+        Ok(Message::ReplayEnd(end)) if end.position == recording.end()
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,296 @@
+use std::fs;
+use std::path::PathBuf;
+
+use recorder::{ExecSequencer, Recording, Replayer, SequenceState};
+
+use sbe::cxl_rej_reason_enum::CxlRejReasonEnum;
+use sbe::cxl_rej_response_to_enum::CxlRejResponseToEnum;
+use sbe::exec_type_enum::ExecTypeEnum;
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::side_enum::SideEnum;
+use sbe::{Decimal64, ExecutionReport, Message, OrderCancelReject, Uuid, decode_message};
+
+use transport::{Loopback, LoopbackSink, LoopbackSource, MessageSink, MessageSource};
+
+/// An execution report; `ExecID` 0 makes it a status report.
+fn report(exec_id: u64) -> Vec<u8> {
+    let (exec_type, ord_status) = if exec_id == 0 {
+        (ExecTypeEnum::OrderStatus, OrdStatusEnum::New)
+    } else {
+        (ExecTypeEnum::New, OrdStatusEnum::New)
+    };
+    let report = ExecutionReport {
+        account: Uuid::from_u128(1),
+        cl_ord_id: Uuid::from_u64_pair(1, exec_id),
+        trd_match_id: None,
+        order_id: exec_id,
+        exec_id,
+        transact_time: 1_700_000_000_000_000_000,
+        price: Some(Decimal64::from_mantissa(150_000_000)),
+        order_qty: Decimal64::from_mantissa(100_000_000),
+        last_qty: None,
+        last_px: None,
+        leaves_qty: Decimal64::from_mantissa(100_000_000),
+        cum_qty: Decimal64::from_mantissa(0),
+        avg_px: None,
+        symbol: *b"BTCUSD",
+        exec_type,
+        ord_status,
+        ord_rej_reason: None,
+        side: SideEnum::Buy,
+        mass_status_req_id: None,
+        tot_num_reports: None,
+    };
+    let mut frame = vec![0; ExecutionReport::ENCODED_LENGTH];
+    report.encode(&mut frame).unwrap();
+    frame
+}
+
+fn cancel_reject(exec_id: u64) -> Vec<u8> {
+    let reject = OrderCancelReject {
+        cl_ord_id: Uuid::from_u64_pair(2, exec_id),
+        orig_cl_ord_id: Uuid::from_u64_pair(1, exec_id),
+        order_id: None,
+        ord_status: None,
+        cxl_rej_response_to: CxlRejResponseToEnum::OrderCancelRequest,
+        cxl_rej_reason: CxlRejReasonEnum::UnknownOrder,
+        exec_id: Some(exec_id),
+    };
+    let mut frame = vec![0; OrderCancelReject::ENCODED_LENGTH];
+    reject.encode(&mut frame).unwrap();
+    frame
+}
+
+/// `ExecID`s of the delivered frames, 0 for status reports.
+fn exec_ids(frames: &[Vec<u8>]) -> Vec<u64> {
+    frames
+        .iter()
+        .map(|frame| match decode_message(frame) {
+            Ok(Message::ExecutionReport(report)) => report.exec_id,
+            Ok(Message::OrderCancelReject(reject)) => reject.exec_id.unwrap(),
+            other => panic!("expected a report, got {other:?}"),
+        })
+        .collect()
+}
+
+type Sequencer = ExecSequencer<LoopbackSource, LoopbackSource, LoopbackSink>;
+
+/// The engine's report stream with a recorder on it, and a sequenced consumer of it.
+struct Harness {
+    dir: PathBuf,
+    recording: Recording,
+    live: LoopbackSink,
+    requests: LoopbackSource,
+    replayer: Replayer<LoopbackSink>,
+    sequencer: Sequencer,
+    delivered: Vec<Vec<u8>>,
+}
+
+impl Harness {
+    fn new(
+        name: &str,
+        make: impl FnOnce(LoopbackSource, LoopbackSource, LoopbackSink) -> Sequencer,
+    ) -> Self {
+        let dir = std::env::temp_dir().join(format!("recorder-{}-seq-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let live = Loopback::new();
+        let requests = Loopback::new();
+        let replies = Loopback::new();
+        // Subscribed first, so the sequencer's request can be sent when it is made.
+        let request_source = requests.source();
+        let sequencer = make(live.source(), replies.source(), requests.sink());
+        Self {
+            recording: Recording::open(&dir, 1 << 20).unwrap(),
+            dir,
+            live: live.sink(),
+            requests: request_source,
+            replayer: Replayer::new(replies.sink()),
+            sequencer,
+            delivered: Vec::new(),
+        }
+    }
+
+    /// Without recovery.
+    fn unrecovered(name: &str) -> Self {
+        Self::new(name, |live, _, _| ExecSequencer::new(live, None, 100))
+    }
+
+    fn recovered(name: &str) -> Self {
+        Self::new(name, |live, replies, requests| {
+            ExecSequencer::new(live, Some((replies, requests)), 100)
+        })
+    }
+
+    /// The engine publishes a frame, which the recorder records.
+    fn publish(&mut self, frame: &[u8]) {
+        self.record(frame);
+        self.live.offer(frame).unwrap();
+    }
+
+    /// A frame only the recorder received.
+    fn record(&mut self, frame: &[u8]) {
+        let exec_id = recorder::exec_id(frame).unwrap_or(0);
+        self.recording.append(frame, exec_id).unwrap();
+    }
+
+    fn poll_consumer(&mut self) {
+        let delivered = &mut self.delivered;
+        self.sequencer
+            .poll(&mut |frame| delivered.push(frame.to_vec()), usize::MAX);
+    }
+
+    fn poll_recorder(&mut self) {
+        let mut requests = Vec::new();
+        self.requests.poll(
+            &mut |frame| match decode_message(frame) {
+                Ok(Message::ReplayRequest(request)) => requests.push(request),
+                other => panic!("expected a replay request, got {other:?}"),
+            },
+            usize::MAX,
+        );
+        for request in &requests {
+            self.replayer.request(&self.recording, request);
+        }
+        self.replayer.replay(&self.recording, usize::MAX);
+    }
+
+    fn run_until_settled(&mut self) {
+        for _ in 0..10 {
+            self.poll_consumer();
+            self.poll_recorder();
+        }
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn delivers_in_order_and_drops_repeats() {
+    let mut harness = Harness::unrecovered("repeats");
+    for exec_id in [5, 6, 6, 7, 5] {
+        harness.publish(&report(exec_id));
+    }
+
+    harness.poll_consumer();
+
+    assert_eq!(harness.sequencer.state(), &SequenceState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![5, 6, 7]);
+    assert_eq!(harness.sequencer.next_exec_id(), Some(8));
+}
+
+#[test]
+fn sequences_cancel_rejects_and_passes_status_reports_through() {
+    let mut harness = Harness::unrecovered("rejects");
+    harness.publish(&report(1));
+    harness.publish(&report(0));
+    harness.publish(&cancel_reject(2));
+    harness.publish(&report(3));
+
+    harness.poll_consumer();
+
+    assert_eq!(harness.sequencer.state(), &SequenceState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![1, 0, 2, 3]);
+}
+
+#[test]
+fn goes_stale_on_a_gap_without_recovery() {
+    let mut harness = Harness::unrecovered("stale");
+    harness.publish(&report(1));
+    harness.record(&report(2));
+    harness.publish(&report(3));
+    harness.poll_consumer();
+    harness.publish(&report(4));
+    harness.poll_consumer();
+
+    assert!(matches!(harness.sequencer.state(), SequenceState::Stale(_)));
+    assert_eq!(exec_ids(&harness.delivered), vec![1]);
+}
+
+#[test]
+fn recovers_lost_reports_from_the_recorder() {
+    let mut harness = Harness::recovered("recover");
+    harness.publish(&report(1));
+    harness.record(&report(2));
+    harness.record(&cancel_reject(3));
+    harness.publish(&report(4));
+    harness.poll_consumer();
+    assert_eq!(
+        harness.sequencer.state(),
+        &SequenceState::Recovering { next: 2 }
+    );
+    assert_eq!(harness.sequencer.buffered(), 1);
+
+    harness.publish(&report(5));
+    harness.run_until_settled();
+    harness.publish(&report(6));
+    harness.poll_consumer();
+
+    assert_eq!(harness.sequencer.state(), &SequenceState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(harness.sequencer.buffered(), 0);
+}
+
+#[test]
+fn goes_stale_when_the_recording_misses_the_reports_too() {
+    let mut harness = Harness::recovered("unrecoverable");
+    harness.publish(&report(1));
+    // 2 and 3 are lost by the recorder as well.
+    harness.publish(&report(4));
+
+    harness.poll_consumer();
+    harness.poll_consumer();
+    harness.run_until_settled();
+
+    assert_eq!(
+        harness.sequencer.state(),
+        &SequenceState::Stale("reports 2 to 3 are missing from the recording".to_owned())
+    );
+}
+
+#[test]
+fn catches_up_from_an_exec_id_before_going_live() {
+    let mut harness = Harness::new("catch-up", |live, replies, requests| {
+        ExecSequencer::catching_up(live, replies, requests, 2, 100)
+    });
+    harness.record(&report(1));
+    harness.record(&report(2));
+    harness.record(&report(3));
+    harness.publish(&report(4));
+
+    harness.poll_recorder();
+    harness.run_until_settled();
+
+    assert_eq!(harness.sequencer.state(), &SequenceState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![2, 3, 4]);
+}
+
+#[test]
+fn catching_up_on_a_quiet_stream_goes_live_once_the_replay_ends() {
+    let mut harness = Harness::new("quiet", |live, replies, requests| {
+        ExecSequencer::catching_up(live, replies, requests, 0, 100)
+    });
+    harness.record(&report(1));
+
+    harness.run_until_settled();
+
+    assert_eq!(harness.sequencer.state(), &SequenceState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![1]);
+    assert_eq!(harness.sequencer.next_exec_id(), Some(2));
+}
+
+#[test]
+fn starts_over_when_the_engine_restarts() {
+    let mut harness = Harness::unrecovered("restart");
+    for exec_id in [1, 2, 3, 1, 2] {
+        harness.publish(&report(exec_id));
+    }
+
+    harness.poll_consumer();
+
+    assert_eq!(harness.sequencer.state(), &SequenceState::Live);
+    assert_eq!(exec_ids(&harness.delivered), vec![1, 2, 3, 1, 2]);
+}
@@ -0,0 +1,215 @@
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+use recorder::{Recording, StoreError};
+
+fn recording_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("recorder-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn read_all(recording: &Recording, position: u64) -> Vec<(u64, Vec<u8>)> {
+    let mut reader = recording.reader(position).unwrap();
+    let mut frame = Vec::new();
+    let mut frames = Vec::new();
+    while let Some(position) = reader.next_frame(recording.end(), &mut frame).unwrap() {
+        frames.push((position, frame.clone()));
+    }
+    frames
+}
+
+#[test]
+fn reads_back_appended_frames_from_any_position() {
+    let dir = recording_dir("read");
+    let mut recording = Recording::open(&dir, 1024).unwrap();
+    assert!(recording.is_empty());
+
+    let first = recording.append(b"first", 1).unwrap();
+    let second = recording.append(b"second", 0).unwrap();
+    let third = recording.append(b"third", 2).unwrap();
+    assert_eq!((first, second, third), (0, 9, 19));
+    assert_eq!(recording.end(), 28);
+    assert_eq!(recording.len(), 3);
+
+    assert_eq!(
+        read_all(&recording, first),
+        vec![
+            (0, b"first".to_vec()),
+            (9, b"second".to_vec()),
+            (19, b"third".to_vec()),
+        ]
+    );
+    assert_eq!(read_all(&recording, third), vec![(19, b"third".to_vec())]);
+    assert_eq!(read_all(&recording, recording.end()), Vec::new());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn segment_files_are_length_prefixed_frames() {
+    let dir = recording_dir("format");
+    let mut recording = Recording::open(&dir, 1024).unwrap();
+    recording.append(b"abc", 0).unwrap();
+
+    let segment = fs::read(dir.join("00000000000000000000.rec")).unwrap();
+    assert_eq!(segment, b"\x03\x00\x00\x00abc");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rejects_positions_inside_frames() {
+    let dir = recording_dir("unknown");
+    let mut recording = Recording::open(&dir, 1024).unwrap();
+    recording.append(b"frame", 0).unwrap();
+
+    assert!(recording.contains(0));
+    assert!(recording.contains(9));
+    assert!(matches!(
+        recording.reader(3),
+        Err(StoreError::UnknownPosition { position: 3 })
+    ));
+    assert!(matches!(
+        recording.reader(100),
+        Err(StoreError::UnknownPosition { position: 100 })
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rolls_over_to_new_segments() {
+    let dir = recording_dir("rollover");
+    let mut recording = Recording::open(&dir, 20).unwrap();
+    for frame in [b"aaaaaaaa", b"bbbbbbbb", b"cccccccc"] {
+        recording.append(frame, 0).unwrap();
+    }
+
+    let mut segments: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".rec"))
+        .collect();
+    segments.sort();
+    assert_eq!(
+        segments,
+        vec![
+            "00000000000000000000.rec",
+            "00000000000000000012.rec",
+            "00000000000000000024.rec",
+        ]
+    );
+
+    let frames: Vec<Vec<u8>> = read_all(&recording, 0)
+        .into_iter()
+        .map(|(_, frame)| frame)
+        .collect();
+    assert_eq!(
+        frames,
+        vec![
+            b"aaaaaaaa".to_vec(),
+            b"bbbbbbbb".to_vec(),
+            b"cccccccc".to_vec()
+        ]
+    );
+
+    drop(recording);
+    let recording = Recording::open(&dir, 20).unwrap();
+    assert_eq!(recording.len(), 3);
+    assert_eq!(recording.end(), 36);
+    assert_eq!(read_all(&recording, 12).len(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn readers_follow_frames_appended_after_they_caught_up() {
+    let dir = recording_dir("follow");
+    let mut recording = Recording::open(&dir, 20).unwrap();
+    recording.append(b"aaaaaaaa", 0).unwrap();
+
+    let mut reader = recording.reader(0).unwrap();
+    let mut frame = Vec::new();
+    assert_eq!(
+        reader.next_frame(recording.end(), &mut frame).unwrap(),
+        Some(0)
+    );
+    assert_eq!(
+        reader.next_frame(recording.end(), &mut frame).unwrap(),
+        None
+    );
+
+    recording.append(b"bbbbbbbb", 0).unwrap();
+    assert_eq!(
+        reader.next_frame(recording.end(), &mut frame).unwrap(),
+        Some(12)
+    );
+    assert_eq!(frame, b"bbbbbbbb");
+    assert_eq!(reader.position(), 24);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn recovers_from_a_partly_written_frame() {
+    let dir = recording_dir("recover");
+    let mut recording = Recording::open(&dir, 1024).unwrap();
+    recording.append(b"whole", 1).unwrap();
+    recording.append(b"torn", 2).unwrap();
+    drop(recording);
+
+    // Cut the last frame short, as a crash during the write would.
+    let segment = dir.join("00000000000000000000.rec");
+    let file = OpenOptions::new().write(true).open(&segment).unwrap();
+    file.set_len(11).unwrap();
+    drop(file);
+
+    let mut recording = Recording::open(&dir, 1024).unwrap();
+    assert_eq!(recording.len(), 1);
+    assert_eq!(recording.end(), 9);
+    assert_eq!(recording.position_of_exec_id(2), 9);
+    assert_eq!(fs::metadata(&segment).unwrap().len(), 9);
+    assert_eq!(
+        fs::metadata(dir.join("00000000000000000000.idx"))
+            .unwrap()
+            .len(),
+        16
+    );
+
+    assert_eq!(recording.append(b"again", 2).unwrap(), 9);
+    assert_eq!(
+        read_all(&recording, 0),
+        vec![(0, b"whole".to_vec()), (9, b"again".to_vec())]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn finds_frames_by_exec_id_in_the_latest_run() {
+    let dir = recording_dir("exec-id");
+    let mut recording = Recording::open(&dir, 1024).unwrap();
+    let mut positions = Vec::new();
+    // A status report without an ExecID, then a restarted engine numbering from 1 again.
+    for (frame, exec_id) in [
+        (b"a", 1),
+        (b"b", 2),
+        (b"-", 0),
+        (b"c", 4),
+        (b"d", 1),
+        (b"e", 2),
+    ] {
+        positions.push(recording.append(frame, exec_id).unwrap());
+    }
+
+    assert_eq!(recording.position_of_exec_id(1), positions[4]);
+    assert_eq!(recording.position_of_exec_id(2), positions[5]);
+    assert_eq!(recording.position_of_exec_id(3), recording.end());
+
+    drop(recording);
+    let recording = Recording::open(&dir, 1024).unwrap();
+    assert_eq!(recording.position_of_exec_id(2), positions[5]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,128 @@
+mod recording;
+mod render;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+use recording::Frames;
+use render::Format;
+
+use sbe::{Message, decode_message};
+
+const USAGE: &str = "usage:
+  sbe-dump [--json] hex <bytes>         decode a frame written as hex
+  sbe-dump [--json] file <path>         decode a frame stored as raw bytes
+  sbe-dump [--json] stream <path>       decode every frame of a recorded stream
+  sbe-dump encode <json|-> [<path>]     encode a JSON message, printing hex or writing it to <path>";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (format, args) = match args.as_slice() {
+        ["--json", rest @ ..] => (Format::Json, rest),
+        rest => (Format::Text, rest),
+    };
+
+    match (format, args) {
+        (_, ["hex", hex]) => dump_frame(format, &parse_hex(hex)),
+        (_, ["file", path]) => dump_frame(format, &read_file(path)),
+        (_, ["stream", path]) => dump_stream(format, &read_file(path)),
+        (Format::Text, ["encode", json]) => println!("{}", to_hex(&encode(json))),
+        (Format::Text, ["encode", json, path]) => {
+            fs::write(path, encode(json)).unwrap_or_else(|err| fail(&format!("{path}: {err}")));
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("sbe-dump: {message}");
+    process::exit(1);
+}
+
+fn read_file(path: &str) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|err| fail(&format!("{path}: {err}")))
+}
+
+/// Accepts hex with or without a `0x` prefix; whitespace between digits is ignored.
+fn parse_hex(hex: &str) -> Vec<u8> {
+    let digits: Vec<u8> = hex
+        .trim_start_matches("0x")
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        fail("hex input has an odd number of digits");
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .unwrap_or_else(|| fail("hex input contains a non-hex digit"))
+        })
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn dump_frame(format: Format, frame: &[u8]) {
+    match decode_message(frame) {
+        Ok(message) => println!("{}", render::render(format, &message, frame.len())),
+        Err(err) => fail(&format!("cannot decode {} bytes: {err:?}", frame.len())),
+    }
+}
+
+/// Decodes every frame of a recording, reporting frames that fail to decode and carrying on.
+fn dump_stream(format: Format, data: &[u8]) {
+    for (index, frame) in Frames::new(data).enumerate() {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(offset) => fail(&format!("truncated frame {index} at offset {offset}")),
+        };
+        match decode_message(frame.bytes) {
+            Ok(message) => match format {
+                Format::Text => println!(
+                    "#{index} @{}  {}",
+                    frame.offset,
+                    render::render(format, &message, frame.bytes.len())
+                ),
+                Format::Json => println!("{}", render::to_json(&message)),
+            },
+            Err(err) => eprintln!(
+                "#{index} @{}: cannot decode {} bytes: {err:?}",
+                frame.offset,
+                frame.bytes.len()
+            ),
+        }
+    }
+}
+
+/// Encodes a JSON message, read from stdin when `json` is `-`.
+fn encode(json: &str) -> Vec<u8> {
+    let json = if json == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .unwrap_or_else(|err| fail(&format!("stdin: {err}")));
+        input
+    } else {
+        json.to_owned()
+    };
+    let message: Message =
+        serde_json::from_str(&json).unwrap_or_else(|err| fail(&format!("invalid message: {err}")));
+
+    let mut frame = vec![0; message.encoded_length()];
+    message
+        .encode(&mut frame)
+        .unwrap_or_else(|err| fail(&format!("cannot encode message: {err:?}")));
+    frame
+}
@@ -0,0 +1,47 @@
+//! Recorded stream files: every frame taken off the stream, in order, each preceded by its length
+//! as a little-endian `u32`.
+
+/// Length prefix in front of every recorded frame.
+pub const LENGTH_PREFIX: usize = size_of::<u32>();
+
+/// A frame of a recording and the offset of its length prefix within the file.
+pub struct Frame<'a> {
+    pub offset: usize,
+    pub bytes: &'a [u8],
+}
+
+/// Iterates over the frames of a recording. A truncated last frame is returned as an error with
+/// its offset, after which iteration stops.
+pub struct Frames<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Frame<'a>, usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.data[self.offset..];
+        if rest.is_empty() {
+            return None;
+        }
+        let offset = self.offset;
+        let Some((prefix, rest)) = rest.split_first_chunk::<LENGTH_PREFIX>() else {
+            self.offset = self.data.len();
+            return Some(Err(offset));
+        };
+        let length = u32::from_le_bytes(*prefix) as usize;
+        let Some(bytes) = rest.get(..length) else {
+            self.offset = self.data.len();
+            return Some(Err(offset));
+        };
+        self.offset += LENGTH_PREFIX + length;
+        Some(Ok(Frame { offset, bytes }))
+    }
+}
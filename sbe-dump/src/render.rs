@@ -0,0 +1,50 @@
+//! Text and JSON rendering of decoded messages. Both go through the messages' serde form, so
+//! decimals, UUIDs, enums and symbols read the same in either output.
+
+use std::fmt::Write as _;
+
+use sbe::Message;
+
+use serde_json::Value;
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    /// Message name and length, then one field per line with the values aligned.
+    Text,
+    Json,
+}
+
+pub fn render(format: Format, message: &Message, length: usize) -> String {
+    match format {
+        Format::Text => to_text(message, length),
+        Format::Json => serde_json::to_string_pretty(message).expect("messages serialize to JSON"),
+    }
+}
+
+/// Single line JSON, used for streams so every frame is one line.
+pub fn to_json(message: &Message) -> String {
+    serde_json::to_string(message).expect("messages serialize to JSON")
+}
+
+fn to_text(message: &Message, length: usize) -> String {
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(message) else {
+        unreachable!("messages serialize to JSON objects");
+    };
+    let name = fields.shift_remove("message").unwrap_or(Value::Null);
+
+    let mut out = format!(
+        "{} (template {}, {length} bytes)",
+        name.as_str().unwrap_or("?"),
+        message.template_id()
+    );
+    let width = fields.keys().map(String::len).max().unwrap_or(0);
+    for (field, value) in &fields {
+        let value = match value {
+            Value::Null => "-".to_owned(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let _ = write!(out, "\n  {field:<width$}  {value}");
+    }
+    out
+}
@@ -0,0 +1,79 @@
+use std::fs;
+use std::process::{Command, Output};
+
+const ORDER: &str = r#"{
+    "message": "NewOrderSingle",
+    "cl_ord_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+    "account": "00000000-0000-0001-0000-000000000002",
+    "transact_time": 1700000000000000000,
+    "order_qty": "0.25",
+    "price": "101.5",
+    "symbol": "BTCUSD",
+    "side": "Sell",
+    "ord_type": "Limit"
+}"#;
+
+fn sbe_dump(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sbe-dump"))
+        .args(args)
+        .output()
+        .expect("sbe-dump runs")
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).expect("utf-8 output")
+}
+
+#[test]
+fn encoded_json_decodes_to_the_same_json() {
+    let hex = stdout(sbe_dump(&["encode", ORDER]));
+
+    let json = stdout(sbe_dump(&["--json", "hex", hex.trim()]));
+
+    let expected: serde_json::Value = serde_json::from_str(ORDER).unwrap();
+    let decoded: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn text_output_aligns_fields_and_renders_values() {
+    let hex = stdout(sbe_dump(&["encode", ORDER]));
+
+    let text = stdout(sbe_dump(&["hex", hex.trim()]));
+
+    assert!(text.starts_with("NewOrderSingle (template 1, "), "{text}");
+    assert!(text.contains("\n  cl_ord_id      67e55044-10b1-426f-9247-bb680e5fe0c8\n"));
+    assert!(text.contains("\n  price          101.5\n"));
+    assert!(text.contains("\n  symbol         BTCUSD\n"));
+}
+
+#[test]
+fn stream_decodes_every_frame_and_reports_bad_ones() {
+    let dir = std::env::temp_dir().join(format!("sbe-dump-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let frame_path = dir.join("order.bin");
+    let stream_path = dir.join("stream.rec");
+
+    assert!(
+        sbe_dump(&["encode", ORDER, frame_path.to_str().unwrap()])
+            .status
+            .success()
+    );
+    let frame = fs::read(&frame_path).unwrap();
+    let mut stream = Vec::new();
+    for frame in [&frame[..], &[0xff; 4], &frame[..]] {
+        stream.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        stream.extend_from_slice(frame);
+    }
+    fs::write(&stream_path, stream).unwrap();
+
+    let output = sbe_dump(&["--json", "stream", stream_path.to_str().unwrap()]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    let lines: Vec<String> = stdout(output).lines().map(str::to_owned).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|line| line.contains(r#""price":"101.5""#)));
+    assert!(stderr.starts_with("#1 @"), "{stderr}");
+}
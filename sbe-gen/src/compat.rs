@@ -0,0 +1,255 @@
+use std::fmt;
+
+use crate::schema::{EnumType, Field, FieldType, Message, Presence, Schema};
+
+/// A difference between two schema revisions that would break a service still running the
+/// older one, e.g. a field that moved or a field added without a newer `sinceVersion`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BreakingChange {
+    pub location: String,
+    pub reason: String,
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.reason)
+    }
+}
+
+/// Lists every change from `old` to `new` that is not a backwards compatible extension.
+///
+/// Compatible extensions are new messages, new enum values and fields appended after the old
+/// block, each tagged with a `sinceVersion` newer than `old.version` in a schema whose version
+/// was bumped. Messages are matched by template id, fields by name.
+pub fn breaking_changes(old: &Schema, new: &Schema) -> Vec<BreakingChange> {
+    let mut changes = Changes::default();
+
+    if old.id != new.id {
+        changes.push(
+            "schema",
+            format!("id changed from {} to {}", old.id, new.id),
+        );
+    }
+    if old.byte_order != new.byte_order {
+        changes.push(
+            "schema",
+            format!(
+                "byteOrder changed from {} to {}",
+                old.byte_order, new.byte_order
+            ),
+        );
+    }
+    if new.version < old.version {
+        changes.push(
+            "schema",
+            format!("version went back from {} to {}", old.version, new.version),
+        );
+    }
+
+    for old_message in &old.messages {
+        match new.messages.iter().find(|m| m.id == old_message.id) {
+            Some(new_message) => compare_message(old, new, old_message, new_message, &mut changes),
+            None => changes.push(
+                &old_message.name,
+                format!("message with template id {} was removed", old_message.id),
+            ),
+        }
+    }
+    for new_message in &new.messages {
+        if !old.messages.iter().any(|m| m.id == new_message.id) {
+            changes.added(old, new, &new_message.name, new_message.since_version);
+        }
+    }
+
+    changes.0
+}
+
+#[derive(Default)]
+struct Changes(Vec<BreakingChange>);
+
+impl Changes {
+    fn push(&mut self, location: &str, reason: String) {
+        self.0.push(BreakingChange {
+            location: location.to_owned(),
+            reason,
+        });
+    }
+
+    /// Additions must be introduced by a version the old schema did not have yet.
+    fn added(&mut self, old: &Schema, new: &Schema, location: &str, since_version: u16) {
+        if since_version <= old.version {
+            self.push(
+                location,
+                format!(
+                    "added with sinceVersion {since_version}, must be newer than the previous schema version {}",
+                    old.version
+                ),
+            );
+        } else if since_version > new.version {
+            self.push(
+                location,
+                format!(
+                    "sinceVersion {since_version} is newer than the schema version {}",
+                    new.version
+                ),
+            );
+        }
+    }
+}
+
+fn compare_message(
+    old: &Schema,
+    new: &Schema,
+    old_message: &Message,
+    new_message: &Message,
+    changes: &mut Changes,
+) {
+    let name = &old_message.name;
+    if new_message.block_length < old_message.block_length {
+        changes.push(
+            name,
+            format!(
+                "blockLength shrank from {} to {}",
+                old_message.block_length, new_message.block_length
+            ),
+        );
+    }
+
+    for old_field in &old_message.fields {
+        let location = format!("{name}.{}", old_field.name);
+        let Some(new_field) = new_message.fields.iter().find(|f| f.name == old_field.name) else {
+            changes.push(&location, "field was removed".to_owned());
+            continue;
+        };
+        compare_field(old, new, old_field, new_field, &location, changes);
+    }
+
+    for new_field in &new_message.fields {
+        if old_message.fields.iter().any(|f| f.name == new_field.name) {
+            continue;
+        }
+        let location = format!("{name}.{}", new_field.name);
+        changes.added(old, new, &location, new_field.since_version);
+        if new_field.offset < old_message.block_length {
+            changes.push(
+                &location,
+                format!(
+                    "added at offset {} inside the previous block of {} bytes",
+                    new_field.offset, old_message.block_length
+                ),
+            );
+        }
+    }
+}
+
+fn compare_field(
+    old: &Schema,
+    new: &Schema,
+    old_field: &Field,
+    new_field: &Field,
+    location: &str,
+    changes: &mut Changes,
+) {
+    if old_field.offset != new_field.offset {
+        changes.push(
+            location,
+            format!(
+                "offset changed from {} to {}",
+                old_field.offset, new_field.offset
+            ),
+        );
+    }
+    if old_field.since_version != new_field.since_version {
+        changes.push(
+            location,
+            format!(
+                "sinceVersion changed from {} to {}",
+                old_field.since_version, new_field.since_version
+            ),
+        );
+    }
+
+    let old_layout = layout(old, old_field);
+    let new_layout = layout(new, new_field);
+    if old_layout != new_layout {
+        changes.push(
+            location,
+            format!("encoding changed from {old_layout} to {new_layout}"),
+        );
+    }
+
+    if old.field_presence(old_field) == Presence::Required
+        && new.field_presence(new_field) == Presence::Optional
+    {
+        changes.push(
+            location,
+            "became optional, older decoders would read its null value as data".to_owned(),
+        );
+    }
+
+    if let (Ok(FieldType::Enum(old_enum)), Ok(FieldType::Enum(new_enum))) =
+        (old.field_type(old_field), new.field_type(new_field))
+    {
+        compare_enum(old, new, old_enum, new_enum, location, changes);
+    }
+}
+
+fn compare_enum(
+    old: &Schema,
+    new: &Schema,
+    old_enum: &EnumType,
+    new_enum: &EnumType,
+    location: &str,
+    changes: &mut Changes,
+) {
+    for old_value in &old_enum.values {
+        match new_enum.values.iter().find(|v| v.name == old_value.name) {
+            Some(new_value) if new_value.value != old_value.value => changes.push(
+                &format!("{location}={}", old_value.name),
+                format!(
+                    "value changed from {} to {}",
+                    old_value.value, new_value.value
+                ),
+            ),
+            Some(_) => {}
+            None => changes.push(
+                &format!("{location}={}", old_value.name),
+                "enum value was removed".to_owned(),
+            ),
+        }
+    }
+    for new_value in &new_enum.values {
+        if !old_enum.values.iter().any(|v| v.name == new_value.name) {
+            changes.added(
+                old,
+                new,
+                &format!("{location}={}", new_value.name),
+                new_value.since_version,
+            );
+        }
+    }
+}
+
+/// Describes how a field is laid out on the wire, ignoring names that do not affect encoding.
+fn layout(schema: &Schema, field: &Field) -> String {
+    match schema.field_type(field) {
+        Ok(FieldType::Primitive(primitive, 1, _)) => primitive.sbe_name().to_owned(),
+        Ok(FieldType::Primitive(primitive, length, _)) => {
+            format!("{}[{length}]", primitive.sbe_name())
+        }
+        Ok(FieldType::Enum(e)) => format!("enum of {}", e.encoding.sbe_name()),
+        Ok(FieldType::Composite(c)) => {
+            let members: Vec<String> = c
+                .members
+                .iter()
+                .map(|m| match m.presence {
+                    Presence::Constant => format!("{} constant", m.primitive.sbe_name()),
+                    _ if m.length > 1 => format!("{}[{}]", m.primitive.sbe_name(), m.length),
+                    _ => m.primitive.sbe_name().to_owned(),
+                })
+                .collect();
+            format!("composite {{{}}}", members.join(", "))
+        }
+        Err(_) => format!("unresolved type {}", field.type_name),
+    }
+}
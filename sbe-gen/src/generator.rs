@@ -0,0 +1,963 @@
+use std::fmt::Write as _;
+
+use crate::owned::generate_messages;
+use crate::schema::{
+    Composite, EncodedType, EnumType, Field, FieldType, Message, Presence, Primitive, Schema,
+    TypeDef,
+};
+
+const MAX_WIDTH: usize = 100;
+const ARRAY_WIDTH: usize = 60;
+const CHAIN_WIDTH: usize = 60;
+
+/// Generates every codec module for `schema`, followed by the crate's `lib.rs`, as
+/// `(file name, contents)` pairs.
+pub fn generate(schema: &Schema) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+
+    for def in &schema.types {
+        match def {
+            TypeDef::Enum(e) if is_referenced(schema, &e.name) => {
+                files.push((format!("{}.rs", snake_case(&e.name)), generate_enum(e)));
+            }
+            TypeDef::Composite(c) => {
+                files.push((
+                    format!("{}_codec.rs", snake_case(&c.name)),
+                    generate_composite(schema, c),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    for message in &schema.messages {
+        files.push((
+            format!("{}_codec.rs", snake_case(&message.name)),
+            generate_message(schema, message),
+        ));
+    }
+
+    files.push(("messages.rs".to_owned(), generate_messages(schema)));
+    files.push(("decimal64.rs".to_owned(), DECIMAL64.to_owned()));
+    files.push(("types.rs".to_owned(), TYPES.to_owned()));
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files.push(("lib.rs".to_owned(), generate_lib(schema, &files)));
+    files
+}
+
+/// Runtime support (buffers, reader/writer traits) shared by every generated codec.
+const RUNTIME: &str = include_str!("runtime.rs.in");
+
+/// `Decimal64` fixed-point type backing the `decimal64` composite.
+const DECIMAL64: &str = include_str!("decimal64.rs.in");
+
+/// `Uuid` value type used by the owned messages.
+const TYPES: &str = include_str!("types.rs.in");
+
+fn generate_lib(schema: &Schema, modules: &[(String, String)]) -> String {
+    let mut out = String::from(
+        "#![forbid(unsafe_code)]\n\
+         #![allow(clippy::all)]\n\
+         #![allow(non_camel_case_types)]\n\
+         #![allow(ambiguous_glob_reexports)]\n\
+         \n\
+         use ::core::convert::TryInto;\n\
+         \n",
+    );
+    for (file_name, _) in modules {
+        let _ = writeln!(out, "pub mod {};", file_name.trim_end_matches(".rs"));
+    }
+    out.push_str("\npub use decimal64::*;\npub use messages::*;\npub use types::*;\n");
+    let _ = write!(
+        out,
+        "\npub const SBE_SCHEMA_ID: u16 = {};\n\
+         pub const SBE_SCHEMA_VERSION: u16 = {};\n\
+         pub const SBE_SEMANTIC_VERSION: &str = \"{}\";\n\n",
+        schema.id, schema.version, schema.semantic_version
+    );
+    out.push_str(RUNTIME);
+    out
+}
+
+/// Enums are only emitted when a message field or composite member encodes them;
+/// enums that exist purely as `valueRef` targets for constants are skipped.
+fn is_referenced(schema: &Schema, name: &str) -> bool {
+    schema
+        .messages
+        .iter()
+        .flat_map(|m| &m.fields)
+        .any(|f| f.type_name == name)
+}
+
+/// Converts a schema identifier into the snake case sbetool uses for modules and methods.
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if i > 0 {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied();
+            let boundary = (c.is_ascii_uppercase()
+                && (prev.is_ascii_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_ascii_uppercase()
+                        && next.is_some_and(|n| n.is_ascii_lowercase()))))
+                || (c.is_ascii_digit() && prev.is_ascii_alphabetic());
+            if boundary {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// Converts a schema identifier into the type name used for generated structs and enums.
+pub fn type_name(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_ascii_uppercase().to_string() + chars.as_str()
+    })
+}
+
+fn offset_expr(offset: usize) -> String {
+    if offset == 0 {
+        "self.offset".to_owned()
+    } else {
+        format!("self.offset + {offset}")
+    }
+}
+
+/// Lays out a method signature the way rustfmt would at the given indent.
+pub(crate) fn signature(indent: &str, head: &str, params: &[&str], ret: Option<&str>) -> String {
+    let ret = ret.map(|r| format!(" -> {r}")).unwrap_or_default();
+    let single = format!("{indent}{head}({}){ret} {{", params.join(", "));
+    if single.len() <= MAX_WIDTH {
+        return single;
+    }
+    let mut out = format!("{indent}{head}(\n");
+    for param in params {
+        let _ = writeln!(out, "{indent}    {param},");
+    }
+    let _ = write!(out, "{indent}){ret} {{");
+    out
+}
+
+fn enum_literal(e: &EnumType, raw: &str) -> String {
+    let rust_type = e.encoding.rust_type();
+    if e.encoding == Primitive::Char {
+        let code = raw.chars().next().map_or(0, |c| c as u32);
+        format!("{code}_{rust_type}")
+    } else {
+        let value: u64 = raw.parse().unwrap_or_default();
+        format!("0x{value:x}_{rust_type}")
+    }
+}
+
+fn enum_null(e: &EnumType) -> String {
+    if e.encoding == Primitive::Char {
+        "0_u8".to_owned()
+    } else {
+        e.encoding.null_value().to_owned()
+    }
+}
+
+pub fn generate_enum(e: &EnumType) -> String {
+    let name = type_name(&e.name);
+    let repr = e.encoding.rust_type();
+    let null = enum_null(e);
+    let mut out = String::new();
+
+    out.push_str("use serde::{Deserialize, Serialize};\n\n");
+    out.push_str(
+        "#[derive(\n\
+         \x20   Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,\n\
+         )]\n",
+    );
+    let _ = writeln!(out, "#[repr({repr})]");
+    let _ = writeln!(out, "pub enum {name} {{");
+    for v in &e.values {
+        let _ = writeln!(out, "    {} = {},", v.name, enum_literal(e, &v.value));
+    }
+    out.push_str("    #[default]\n");
+    let _ = writeln!(out, "    NullVal = {null},");
+    out.push_str("}\n");
+
+    let _ = writeln!(out, "impl From<{repr}> for {name} {{");
+    out.push_str("    #[inline]\n");
+    let _ = writeln!(out, "    fn from(v: {repr}) -> Self {{");
+    out.push_str("        match v {\n");
+    for v in &e.values {
+        let _ = writeln!(
+            out,
+            "            {} => Self::{},",
+            enum_literal(e, &v.value),
+            v.name
+        );
+    }
+    out.push_str("            _ => Self::NullVal,\n        }\n    }\n}\n");
+
+    let _ = writeln!(out, "impl From<{name}> for {repr} {{");
+    out.push_str("    #[inline]\n");
+    let _ = writeln!(out, "    fn from(v: {name}) -> Self {{");
+    out.push_str("        match v {\n");
+    for v in &e.values {
+        let _ = writeln!(
+            out,
+            "            {name}::{} => {},",
+            v.name,
+            enum_literal(e, &v.value)
+        );
+    }
+    let _ = writeln!(out, "            {name}::NullVal => {null},");
+    out.push_str("        }\n    }\n}\n");
+
+    let _ = writeln!(out, "impl core::str::FromStr for {name} {{");
+    out.push_str("    type Err = ();\n\n    #[inline]\n");
+    out.push_str("    fn from_str(v: &str) -> core::result::Result<Self, Self::Err> {\n");
+    out.push_str("        match v {\n");
+    for v in &e.values {
+        let _ = writeln!(out, "            \"{0}\" => Ok(Self::{0}),", v.name);
+    }
+    out.push_str("            _ => Ok(Self::NullVal),\n        }\n    }\n}\n");
+
+    let _ = writeln!(out, "impl core::fmt::Display for {name} {{");
+    out.push_str("    #[inline]\n");
+    out.push_str("    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n");
+    out.push_str("        match self {\n");
+    for v in &e.values {
+        let _ = writeln!(out, "            Self::{0} => write!(f, \"{0}\"),", v.name);
+    }
+    out.push_str("            Self::NullVal => write!(f, \"NullVal\"),\n        }\n    }\n}\n");
+
+    out
+}
+
+struct FieldDoc<'a> {
+    kind: &'a str,
+    name: &'a str,
+    primitive: Primitive,
+    character_encoding: Option<&'a str>,
+    semantic_type: Option<&'a str>,
+    offset: usize,
+    length: usize,
+    version: u16,
+}
+
+impl FieldDoc<'_> {
+    fn render(&self, suffix: &str) -> String {
+        let indent = "        ";
+        let mut out = String::new();
+        let _ = writeln!(out, "{indent}/// {} '{}'{suffix}", self.kind, self.name);
+        let _ = writeln!(
+            out,
+            "{indent}/// - min value: {}",
+            self.primitive.min_value()
+        );
+        let _ = writeln!(
+            out,
+            "{indent}/// - max value: {}",
+            self.primitive.max_value()
+        );
+        let _ = writeln!(
+            out,
+            "{indent}/// - null value: {}",
+            self.primitive.null_value()
+        );
+        let _ = writeln!(
+            out,
+            "{indent}/// - characterEncoding: {}",
+            self.character_encoding.unwrap_or("null")
+        );
+        let _ = writeln!(
+            out,
+            "{indent}/// - semanticType: {}",
+            self.semantic_type.unwrap_or("null")
+        );
+        let _ = writeln!(out, "{indent}/// - encodedOffset: {}", self.offset);
+        let _ = writeln!(out, "{indent}/// - encodedLength: {}", self.length);
+        let _ = writeln!(out, "{indent}/// - version: {}", self.version);
+        out
+    }
+}
+
+fn constant_value(schema: &Schema, member: &EncodedType) -> String {
+    if let Some(value) = &member.constant_value {
+        return value.clone();
+    }
+    if let Some(reference) = &member.value_ref
+        && let Some((enum_name, value_name)) = reference.split_once('.')
+        && let Some(TypeDef::Enum(e)) = schema.find_type(enum_name)
+        && let Some(v) = e.values.iter().find(|v| v.name == value_name)
+    {
+        return v.value.clone();
+    }
+    String::new()
+}
+
+pub fn generate_composite(schema: &Schema, composite: &Composite) -> String {
+    let name = type_name(&composite.name);
+    let mut out = String::new();
+
+    out.push_str("use crate::*;\n\n");
+    let _ = writeln!(out, "pub use decoder::{name}Decoder;");
+    let _ = writeln!(out, "pub use encoder::{name}Encoder;\n");
+    let _ = writeln!(
+        out,
+        "pub const ENCODED_LENGTH: usize = {};\n",
+        composite.encoded_length()
+    );
+
+    out.push_str("pub mod encoder {\n    use super::*;\n\n");
+    out.push_str("    #[derive(Debug, Default)]\n");
+    let _ = writeln!(out, "    pub struct {name}Encoder<P> {{");
+    out.push_str("        parent: Option<P>,\n        offset: usize,\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a, P> Writer<'a> for {name}Encoder<P>");
+    out.push_str("    where\n        P: Writer<'a> + Default,\n    {\n");
+    out.push_str("        #[inline]\n");
+    out.push_str("        fn get_buf_mut(&mut self) -> &mut WriteBuf<'a> {\n");
+    out.push_str("            if let Some(parent) = self.parent.as_mut() {\n");
+    out.push_str("                parent.get_buf_mut()\n");
+    out.push_str("            } else {\n");
+    out.push_str("                panic!(\"parent was None\")\n");
+    out.push_str("            }\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a, P> {name}Encoder<P>");
+    out.push_str("    where\n        P: Writer<'a> + Default,\n    {\n");
+    out.push_str("        pub fn wrap(mut self, parent: P, offset: usize) -> Self {\n");
+    out.push_str("            self.parent = Some(parent);\n");
+    out.push_str("            self.offset = offset;\n");
+    out.push_str("            self\n        }\n\n");
+    out.push_str("        #[inline]\n");
+    out.push_str("        pub fn parent(&mut self) -> SbeResult<P> {\n");
+    out.push_str("            self.parent.take().ok_or(SbeErr::ParentNotSet)\n        }\n");
+
+    let mut offset = 0;
+    for member in &composite.members {
+        out.push('\n');
+        if member.presence == Presence::Constant {
+            let _ = writeln!(out, "        // skipping CONSTANT {}", member.name);
+            continue;
+        }
+        let rust_type = member.primitive.rust_type();
+        out.push_str(
+            &FieldDoc {
+                kind: "primitive field",
+                name: &member.name,
+                primitive: member.primitive,
+                character_encoding: member.character_encoding.as_deref(),
+                semantic_type: member.semantic_type.as_deref(),
+                offset,
+                length: member.encoded_length(),
+                version: member.since_version,
+            }
+            .render(""),
+        );
+        out.push_str("        #[inline]\n");
+        let _ = writeln!(
+            out,
+            "        pub fn {}(&mut self, value: {rust_type}) {{",
+            snake_case(&member.name)
+        );
+        let _ = writeln!(out, "            let offset = {};", offset_expr(offset));
+        let _ = writeln!(
+            out,
+            "            self.get_buf_mut().put_{rust_type}_at(offset, value);"
+        );
+        out.push_str("        }\n");
+        offset += member.encoded_length();
+    }
+    out.push_str("    }\n} // end encoder mod\n\n");
+
+    out.push_str("pub mod decoder {\n    use super::*;\n\n");
+    out.push_str("    #[derive(Debug, Default)]\n");
+    let _ = writeln!(out, "    pub struct {name}Decoder<P> {{");
+    out.push_str("        parent: Option<P>,\n        offset: usize,\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a, P> ActingVersion for {name}Decoder<P>");
+    out.push_str("    where\n        P: Reader<'a> + ActingVersion + Default,\n    {\n");
+    out.push_str("        #[inline]\n");
+    out.push_str("        fn acting_version(&self) -> u16 {\n");
+    out.push_str(
+        "            self.parent.as_ref().unwrap().acting_version()\n        }\n    }\n\n",
+    );
+    let _ = writeln!(out, "    impl<'a, P> Reader<'a> for {name}Decoder<P>");
+    out.push_str("    where\n        P: Reader<'a> + Default,\n    {\n");
+    out.push_str("        #[inline]\n");
+    out.push_str("        fn get_buf(&self) -> &ReadBuf<'a> {\n");
+    out.push_str("            self.parent.as_ref().expect(\"parent missing\").get_buf()\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a, P> {name}Decoder<P>");
+    out.push_str("    where\n        P: Reader<'a> + Default,\n    {\n");
+    out.push_str("        pub fn wrap(mut self, parent: P, offset: usize) -> Self {\n");
+    out.push_str("            self.parent = Some(parent);\n");
+    out.push_str("            self.offset = offset;\n");
+    out.push_str("            self\n        }\n\n");
+    out.push_str("        #[inline]\n");
+    out.push_str("        pub fn parent(&mut self) -> SbeResult<P> {\n");
+    out.push_str("            self.parent.take().ok_or(SbeErr::ParentNotSet)\n        }\n");
+
+    let mut offset = 0;
+    for member in &composite.members {
+        out.push('\n');
+        let rust_type = member.primitive.rust_type();
+        let method = snake_case(&member.name);
+        if member.presence == Presence::Constant {
+            out.push_str("        /// CONSTANT\n        #[inline]\n");
+            let _ = writeln!(out, "        pub fn {method}(&self) -> {rust_type} {{");
+            let _ = writeln!(out, "            {}", constant_value(schema, member));
+            out.push_str("        }\n");
+            continue;
+        }
+        out.push_str("        /// primitive field - 'REQUIRED'\n        #[inline]\n");
+        let _ = writeln!(out, "        pub fn {method}(&self) -> {rust_type} {{");
+        let _ = writeln!(
+            out,
+            "            self.get_buf().get_{rust_type}_at({})",
+            offset_expr(offset)
+        );
+        out.push_str("        }\n");
+        offset += member.encoded_length();
+    }
+    out.push_str("    }\n");
+    if composite.name == schema.header_type {
+        out.push_str(&checked_header_wrap(&name));
+    }
+    out.push_str("} // end decoder mod\n");
+
+    out
+}
+
+/// Validating entry point for untrusted buffers, emitted on the message header decoder only.
+fn checked_header_wrap(name: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "\n    impl<'a> {name}Decoder<ReadBuf<'a>> {{");
+    out.push_str(
+        "        /// Wraps `buf` after checking that it holds a complete header of this schema. Any\n\
+         \x20       /// version is accepted: message decoders use it as their acting version, reading\n\
+         \x20       /// older messages with null values for newer fields and skipping fields added\n\
+         \x20       /// after this crate was generated.\n",
+    );
+    out.push_str(
+        "        pub fn wrap_checked(self, buf: ReadBuf<'a>, offset: usize) -> SbeResult<Self> {\n",
+    );
+    out.push_str("            buf.check_len(offset + ENCODED_LENGTH)?;\n");
+    out.push_str("            let header = self.wrap(buf, offset);\n\n");
+    out.push_str("            let schema_id = header.schema_id();\n");
+    out.push_str("            if schema_id != SBE_SCHEMA_ID {\n");
+    out.push_str("                return Err(SbeErr::SchemaIdMismatch {\n");
+    out.push_str("                    expected: SBE_SCHEMA_ID,\n");
+    out.push_str("                    actual: schema_id,\n                });\n            }\n\n");
+    out.push_str("            Ok(header)\n        }\n    }\n");
+    out
+}
+
+pub fn generate_message(schema: &Schema, message: &Message) -> String {
+    let name = type_name(&message.name);
+    let mut out = String::new();
+
+    out.push_str("use crate::*;\n\n");
+    let _ = writeln!(out, "pub use decoder::{name}Decoder;");
+    let _ = writeln!(out, "pub use encoder::{name}Encoder;\n");
+    out.push_str("pub use crate::SBE_SCHEMA_ID;\n");
+    out.push_str("pub use crate::SBE_SCHEMA_VERSION;\n");
+    out.push_str("pub use crate::SBE_SEMANTIC_VERSION;\n\n");
+    let _ = writeln!(
+        out,
+        "pub const SBE_BLOCK_LENGTH: u16 = {};",
+        message.block_length
+    );
+    let _ = writeln!(out, "pub const SBE_TEMPLATE_ID: u16 = {};\n", message.id);
+
+    out.push_str("pub mod encoder {\n    use super::*;\n    use message_header_codec::*;\n\n");
+    out.push_str("    #[derive(Debug, Default)]\n");
+    let _ = writeln!(out, "    pub struct {name}Encoder<'a> {{");
+    out.push_str("        buf: WriteBuf<'a>,\n        initial_offset: usize,\n");
+    out.push_str("        offset: usize,\n        limit: usize,\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a> Writer<'a> for {name}Encoder<'a> {{");
+    out.push_str("        #[inline]\n");
+    out.push_str("        fn get_buf_mut(&mut self) -> &mut WriteBuf<'a> {\n");
+    out.push_str("            &mut self.buf\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a> Encoder<'a> for {name}Encoder<'a> {{");
+    out.push_str("        #[inline]\n        fn get_limit(&self) -> usize {\n");
+    out.push_str("            self.limit\n        }\n\n");
+    out.push_str("        #[inline]\n        fn set_limit(&mut self, limit: usize) {\n");
+    out.push_str("            self.limit = limit;\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a> {name}Encoder<'a> {{");
+    out.push_str("        pub fn wrap(mut self, buf: WriteBuf<'a>, offset: usize) -> Self {\n");
+    out.push_str("            let limit = offset + SBE_BLOCK_LENGTH as usize;\n");
+    out.push_str("            self.buf = buf;\n");
+    out.push_str("            self.initial_offset = offset;\n");
+    out.push_str("            self.offset = offset;\n");
+    out.push_str("            self.limit = limit;\n");
+    out.push_str("            self\n        }\n\n");
+    out.push_str("        #[inline]\n        pub fn encoded_length(&self) -> usize {\n");
+    out.push_str("            self.limit - self.offset\n        }\n\n");
+    out.push_str("        pub fn header(self, offset: usize) -> MessageHeaderEncoder<Self> {\n");
+    out.push_str(
+        "            let mut header = MessageHeaderEncoder::default().wrap(self, offset);\n",
+    );
+    out.push_str("            header.block_length(SBE_BLOCK_LENGTH);\n");
+    out.push_str("            header.template_id(SBE_TEMPLATE_ID);\n");
+    out.push_str("            header.schema_id(SBE_SCHEMA_ID);\n");
+    out.push_str("            header.version(SBE_SCHEMA_VERSION);\n");
+    out.push_str("            header\n        }\n");
+
+    for field in &message.fields {
+        out.push_str(&encode_field(schema, field));
+    }
+    out.push_str("    }\n} // end encoder\n\n");
+
+    out.push_str("pub mod decoder {\n    use super::*;\n    use message_header_codec::*;\n\n");
+    out.push_str("    #[derive(Clone, Copy, Debug, Default)]\n");
+    let _ = writeln!(out, "    pub struct {name}Decoder<'a> {{");
+    out.push_str("        buf: ReadBuf<'a>,\n        initial_offset: usize,\n");
+    out.push_str("        offset: usize,\n        limit: usize,\n");
+    out.push_str(
+        "        pub acting_block_length: u16,\n        pub acting_version: u16,\n    }\n\n",
+    );
+    let _ = writeln!(out, "    impl ActingVersion for {name}Decoder<'_> {{");
+    out.push_str("        #[inline]\n        fn acting_version(&self) -> u16 {\n");
+    out.push_str("            self.acting_version\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a> Reader<'a> for {name}Decoder<'a> {{");
+    out.push_str("        #[inline]\n        fn get_buf(&self) -> &ReadBuf<'a> {\n");
+    out.push_str("            &self.buf\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a> Decoder<'a> for {name}Decoder<'a> {{");
+    out.push_str("        #[inline]\n        fn get_limit(&self) -> usize {\n");
+    out.push_str("            self.limit\n        }\n\n");
+    out.push_str("        #[inline]\n        fn set_limit(&mut self, limit: usize) {\n");
+    out.push_str("            self.limit = limit;\n        }\n    }\n\n");
+    let _ = writeln!(out, "    impl<'a> {name}Decoder<'a> {{");
+    out.push_str("        pub fn wrap(\n            mut self,\n            buf: ReadBuf<'a>,\n");
+    out.push_str("            offset: usize,\n            acting_block_length: u16,\n");
+    out.push_str("            acting_version: u16,\n        ) -> Self {\n");
+    out.push_str("            let limit = offset + acting_block_length as usize;\n");
+    out.push_str("            self.buf = buf;\n");
+    out.push_str("            self.initial_offset = offset;\n");
+    out.push_str("            self.offset = offset;\n");
+    out.push_str("            self.limit = limit;\n");
+    out.push_str("            self.acting_block_length = acting_block_length;\n");
+    out.push_str("            self.acting_version = acting_version;\n");
+    out.push_str("            self\n        }\n\n");
+    out.push_str("        #[inline]\n        pub fn encoded_length(&self) -> usize {\n");
+    out.push_str("            self.limit - self.offset\n        }\n\n");
+    out.push_str(
+        "        pub fn header(self, mut header: MessageHeaderDecoder<ReadBuf<'a>>, offset: usize) -> Self {\n",
+    );
+    out.push_str("            debug_assert_eq!(SBE_TEMPLATE_ID, header.template_id());\n");
+    out.push_str("            let acting_block_length = header.block_length();\n");
+    out.push_str("            let acting_version = header.version();\n\n");
+    out.push_str("            self.wrap(\n                header.parent().unwrap(),\n");
+    out.push_str("                offset + message_header_codec::ENCODED_LENGTH,\n");
+    out.push_str("                acting_block_length,\n                acting_version,\n");
+    out.push_str("            )\n        }\n\n");
+    out.push_str(
+        "        /// Like `header`, but checks the template id, that blockLength covers every field of\n\
+         \x20       /// the header's acting version and that the buffer holds the block, so malformed\n\
+         \x20       /// input is rejected instead of read out of bounds. The header itself should come\n\
+         \x20       /// from `MessageHeaderDecoder::wrap_checked`.\n",
+    );
+    out.push_str("        pub fn header_checked(\n            self,\n");
+    out.push_str("            header: MessageHeaderDecoder<ReadBuf<'a>>,\n");
+    out.push_str("            offset: usize,\n        ) -> SbeResult<Self> {\n");
+    out.push_str("            let template_id = header.template_id();\n");
+    out.push_str("            if template_id != SBE_TEMPLATE_ID {\n");
+    out.push_str("                return Err(SbeErr::TemplateIdMismatch {\n");
+    out.push_str("                    expected: SBE_TEMPLATE_ID,\n");
+    out.push_str("                    actual: template_id,\n                });\n            }\n");
+    out.push_str(&required_block_length(schema, message));
+    out.push_str("            let block_length = header.block_length();\n");
+    out.push_str("            if block_length < required_block_length {\n");
+    out.push_str("                return Err(SbeErr::BlockLengthTooShort {\n");
+    out.push_str("                    required: required_block_length,\n");
+    out.push_str("                    actual: block_length,\n                });\n            }\n");
+    out.push_str(
+        "            let required = offset + message_header_codec::ENCODED_LENGTH + block_length as usize;\n",
+    );
+    out.push_str("            header.get_buf().check_len(required)?;\n\n");
+    out.push_str("            Ok(self.header(header, offset))\n        }\n");
+
+    for field in &message.fields {
+        out.push_str(&decode_field(schema, field));
+    }
+    out.push_str("    }\n} // end decoder\n");
+
+    out
+}
+
+fn encode_field(schema: &Schema, field: &Field) -> String {
+    let indent = "        ";
+    let method = snake_case(&field.name);
+    let offset = offset_expr(field.offset);
+    let mut out = String::new();
+
+    match schema
+        .field_type(field)
+        .expect("field types are resolved at parse time")
+    {
+        FieldType::Primitive(primitive, length, encoding) if length > 1 => {
+            let rust_type = primitive.rust_type();
+            let size = primitive.size();
+            let index = |var: &str| {
+                if size == 1 {
+                    format!("offset + {var}")
+                } else {
+                    format!("offset + {var} * {size}")
+                }
+            };
+            let doc = |suffix: &str| {
+                FieldDoc {
+                    kind: "primitive array field",
+                    name: &field.name,
+                    primitive,
+                    character_encoding: encoding,
+                    semantic_type: field.semantic_type.as_deref(),
+                    offset: field.offset,
+                    length: size * length,
+                    version: field.since_version,
+                }
+                .render(suffix)
+            };
+
+            out.push_str("\n        #[inline]\n");
+            let _ = writeln!(
+                out,
+                "        pub fn {method}_at(&mut self, index: usize, value: {rust_type}) {{"
+            );
+            let _ = writeln!(out, "            let offset = {offset};");
+            out.push_str("            let buf = self.get_buf_mut();\n");
+            let _ = writeln!(
+                out,
+                "            buf.put_{rust_type}_at({}, value);",
+                index("index")
+            );
+            out.push_str("        }\n");
+
+            out.push('\n');
+            out.push_str(&doc(""));
+            out.push_str("        #[inline]\n");
+            let _ = writeln!(
+                out,
+                "        pub fn {method}(&mut self, value: &[{rust_type}]) {{"
+            );
+            let _ = writeln!(out, "            debug_assert_eq!({length}, value.len());");
+            let _ = writeln!(out, "            let offset = {offset};");
+            out.push_str("            let buf = self.get_buf_mut();\n");
+            if primitive == Primitive::Char {
+                out.push_str("            buf.put_slice_at(offset, value);\n");
+            } else {
+                for i in 0..length {
+                    let at = if i == 0 {
+                        "offset".to_owned()
+                    } else {
+                        format!("offset + {}", i * size)
+                    };
+                    let _ = writeln!(out, "            buf.put_{rust_type}_at({at}, value[{i}]);");
+                }
+            }
+            out.push_str("        }\n");
+
+            out.push('\n');
+            out.push_str(&doc(" from an Iterator"));
+            out.push_str("        #[inline]\n");
+            let _ = writeln!(
+                out,
+                "        pub fn {method}_from_iter(&mut self, iter: impl Iterator<Item = {rust_type}>) {{"
+            );
+            let _ = writeln!(out, "            let offset = {offset};");
+            out.push_str("            let buf = self.get_buf_mut();\n");
+            out.push_str("            for (i, v) in iter.enumerate() {\n");
+            let _ = writeln!(
+                out,
+                "                buf.put_{rust_type}_at({}, v);",
+                index("i")
+            );
+            out.push_str("            }\n        }\n");
+
+            out.push('\n');
+            out.push_str(&doc(" with zero padding"));
+            out.push_str("        #[inline]\n");
+            let _ = writeln!(
+                out,
+                "        pub fn {method}_zero_padded(&mut self, value: &[{rust_type}]) {{"
+            );
+            let chain = format!(
+                "value.iter().copied().chain(std::iter::repeat(0_{rust_type})).take({length})"
+            );
+            if chain.len() <= CHAIN_WIDTH {
+                let _ = writeln!(out, "            let iter = {chain};");
+            } else {
+                out.push_str("            let iter = value\n");
+                out.push_str("                .iter()\n");
+                out.push_str("                .copied()\n");
+                let _ = writeln!(
+                    out,
+                    "                .chain(std::iter::repeat(0_{rust_type}))"
+                );
+                let _ = writeln!(out, "                .take({length});");
+            }
+            let _ = writeln!(out, "            self.{method}_from_iter(iter);");
+            out.push_str("        }\n");
+        }
+        FieldType::Primitive(primitive, _, encoding) => {
+            let rust_type = primitive.rust_type();
+            out.push('\n');
+            out.push_str(
+                &FieldDoc {
+                    kind: "primitive field",
+                    name: &field.name,
+                    primitive,
+                    character_encoding: encoding,
+                    semantic_type: field.semantic_type.as_deref(),
+                    offset: field.offset,
+                    length: primitive.size(),
+                    version: field.since_version,
+                }
+                .render(""),
+            );
+            out.push_str("        #[inline]\n");
+            let _ = writeln!(
+                out,
+                "        pub fn {method}(&mut self, value: {rust_type}) {{"
+            );
+            let _ = writeln!(out, "            let offset = {offset};");
+            let _ = writeln!(
+                out,
+                "            self.get_buf_mut().put_{rust_type}_at(offset, value);"
+            );
+            out.push_str("        }\n");
+        }
+        FieldType::Composite(composite) => {
+            let module = format!("{}_codec", snake_case(&composite.name));
+            let ret = format!("{module}::{}Encoder<Self>", type_name(&composite.name));
+            out.push_str("\n        /// COMPOSITE ENCODER\n        #[inline]\n");
+            out.push_str(&signature(
+                indent,
+                &format!("pub fn {method}_encoder"),
+                &["self"],
+                Some(&ret),
+            ));
+            out.push('\n');
+            let _ = writeln!(out, "            let offset = {offset};");
+            let _ = writeln!(
+                out,
+                "            {module}::{}Encoder::default().wrap(self, offset)",
+                type_name(&composite.name)
+            );
+            out.push_str("        }\n");
+        }
+        FieldType::Enum(e) => {
+            let enum_type = format!("{}::{}", snake_case(&e.name), type_name(&e.name));
+            let value_param = format!("value: {enum_type}");
+            out.push_str("\n        /// REQUIRED enum\n        #[inline]\n");
+            out.push_str(&signature(
+                indent,
+                &format!("pub fn {method}"),
+                &["&mut self", &value_param],
+                None,
+            ));
+            out.push('\n');
+            let _ = writeln!(out, "            let offset = {offset};");
+            let _ = writeln!(
+                out,
+                "            self.get_buf_mut().put_{}_at(offset, value as {})",
+                e.encoding.rust_type(),
+                e.encoding.rust_type()
+            );
+            out.push_str("        }\n");
+        }
+    }
+
+    out
+}
+
+fn decode_field(schema: &Schema, field: &Field) -> String {
+    let indent = "        ";
+    let method = snake_case(&field.name);
+    let offset = offset_expr(field.offset);
+    let mut out = String::new();
+
+    match schema
+        .field_type(field)
+        .expect("field types are resolved at parse time")
+    {
+        FieldType::Primitive(primitive, length, _) if length > 1 => {
+            let rust_type = primitive.rust_type();
+            out.push_str("\n        #[inline]\n");
+            let _ = writeln!(
+                out,
+                "        pub fn {method}(&self) -> [{rust_type}; {length}] {{"
+            );
+            out.push_str(&version_guard(
+                field,
+                &format!("[{}; {length}]", primitive.null_value()),
+            ));
+            out.push_str("            let buf = self.get_buf();\n");
+            if primitive == Primitive::Char {
+                let _ = writeln!(out, "            ReadBuf::get_bytes_at(buf.data, {offset})");
+            } else {
+                let items: Vec<String> = (0..length)
+                    .map(|i| {
+                        if i == 0 {
+                            format!("buf.get_{rust_type}_at({offset})")
+                        } else {
+                            format!(
+                                "buf.get_{rust_type}_at({offset} + {})",
+                                i * primitive.size()
+                            )
+                        }
+                    })
+                    .collect();
+                let inner = items.join(", ");
+                if inner.len() <= ARRAY_WIDTH {
+                    let _ = writeln!(out, "            [{inner}]");
+                } else {
+                    out.push_str("            [\n");
+                    for item in &items {
+                        let _ = writeln!(out, "                {item},");
+                    }
+                    out.push_str("            ]\n");
+                }
+            }
+            out.push_str("        }\n");
+        }
+        FieldType::Primitive(primitive, _, _) => {
+            let rust_type = primitive.rust_type();
+            let null = primitive.null_value();
+            out.push('\n');
+            if schema.field_presence(field) == Presence::Optional {
+                let _ = writeln!(
+                    out,
+                    "        /// primitive field - 'OPTIONAL' {{ null_value: '{null}' }}"
+                );
+                out.push_str("        #[inline]\n");
+                let _ = writeln!(
+                    out,
+                    "        pub fn {method}(&self) -> Option<{rust_type}> {{"
+                );
+                out.push_str(&version_guard(field, "None"));
+                let _ = writeln!(
+                    out,
+                    "            let value = self.get_buf().get_{rust_type}_at({offset});"
+                );
+                let _ = writeln!(out, "            if value == {null} {{");
+                out.push_str("                None\n            } else {\n");
+                out.push_str("                Some(value)\n            }\n        }\n");
+            } else {
+                out.push_str("        /// primitive field - 'REQUIRED'\n        #[inline]\n");
+                let _ = writeln!(out, "        pub fn {method}(&self) -> {rust_type} {{");
+                out.push_str(&version_guard(field, null));
+                let _ = writeln!(
+                    out,
+                    "            self.get_buf().get_{rust_type}_at({offset})"
+                );
+                out.push_str("        }\n");
+            }
+        }
+        FieldType::Composite(composite) => {
+            let module = format!("{}_codec", snake_case(&composite.name));
+            let decoder = format!("{module}::{}Decoder<Self>", type_name(&composite.name));
+            // Composites added after version 0 are only present from their sinceVersion on.
+            let versioned = field.since_version > 0;
+            let ret = if versioned {
+                format!("Either<Self, {decoder}>")
+            } else {
+                decoder
+            };
+            out.push_str("\n        /// COMPOSITE DECODER\n        #[inline]\n");
+            out.push_str(&signature(
+                indent,
+                &format!("pub fn {method}_decoder"),
+                &["self"],
+                Some(&ret),
+            ));
+            out.push('\n');
+            out.push_str(&version_guard(field, "Either::Left(self)"));
+            let _ = writeln!(out, "            let offset = {offset};");
+            let wrap = format!(
+                "{module}::{}Decoder::default().wrap(self, offset)",
+                type_name(&composite.name)
+            );
+            if versioned {
+                let _ = writeln!(out, "            Either::Right({wrap})");
+            } else {
+                let _ = writeln!(out, "            {wrap}");
+            }
+            out.push_str("        }\n");
+        }
+        FieldType::Enum(e) => {
+            let enum_type = format!("{}::{}", snake_case(&e.name), type_name(&e.name));
+            out.push_str("\n        /// REQUIRED enum\n        #[inline]\n");
+            out.push_str(&signature(
+                indent,
+                &format!("pub fn {method}"),
+                &["&self"],
+                Some(&enum_type),
+            ));
+            out.push('\n');
+            out.push_str(&version_guard(field, &format!("{enum_type}::NullVal")));
+            let _ = writeln!(
+                out,
+                "            self.get_buf().get_{}_at({offset}).into()",
+                e.encoding.rust_type()
+            );
+            out.push_str("        }\n");
+        }
+    }
+
+    out
+}
+
+/// Early return for fields added after version 0, so decoding a message encoded at an older
+/// acting version yields `fallback` instead of reading past the sender's block.
+fn version_guard(field: &Field, fallback: &str) -> String {
+    if field.since_version == 0 {
+        return String::new();
+    }
+    format!(
+        "            if self.acting_version() < {} {{\n                return {fallback};\n            }}\n",
+        field.since_version
+    )
+}
+
+/// Shortest block a sender at each acting version can produce: a `match` arm per range of
+/// versions sharing the same set of fields, with the current block length for everything newer.
+fn required_block_length(schema: &Schema, message: &Message) -> String {
+    let mut versions: Vec<u16> = message
+        .fields
+        .iter()
+        .map(|f| f.since_version)
+        .filter(|&v| v > 0)
+        .collect();
+    versions.sort_unstable();
+    versions.dedup();
+
+    if versions.is_empty() {
+        return "            let required_block_length = SBE_BLOCK_LENGTH;\n".to_owned();
+    }
+
+    let mut out =
+        String::from("            let required_block_length = match header.version() {\n");
+    let mut from = 0;
+    for &since in &versions {
+        let block_length = message
+            .fields
+            .iter()
+            .filter(|f| f.since_version < since)
+            .map(|f| {
+                f.offset
+                    + schema
+                        .field_length(f)
+                        .expect("field types are resolved at parse time")
+            })
+            .max()
+            .unwrap_or(0);
+        let range = if since - 1 == from {
+            from.to_string()
+        } else {
+            format!("{from}..={}", since - 1)
+        };
+        let _ = writeln!(out, "                {range} => {block_length},");
+        from = since;
+    }
+    out.push_str("                _ => SBE_BLOCK_LENGTH,\n            };\n");
+    out
+}
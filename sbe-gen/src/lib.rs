@@ -0,0 +1,71 @@
+//! Rust code generator for the SBE schema in `messages.xml`.
+//!
+//! Emits the same `*_codec.rs`/`*_enum.rs` modules and `lib.rs` that sbetool produced for the
+//! `sbe` crate, so the checked-in codecs can be regenerated without the Java tool.
+
+pub mod compat;
+pub mod generator;
+mod owned;
+pub mod schema;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub use compat::{BreakingChange, breaking_changes};
+pub use schema::{Schema, SchemaError};
+
+/// Parses `schema_xml` and returns every generated file as `(file name, contents)`.
+pub fn generate(schema_xml: &str) -> Result<Vec<(String, String)>, SchemaError> {
+    let schema = Schema::parse(schema_xml)?;
+    Ok(generator::generate(&schema))
+}
+
+/// A generated file whose committed copy is missing, stale or no longer generated.
+#[derive(Debug)]
+pub enum Drift {
+    Missing(PathBuf),
+    Changed(PathBuf),
+    Extra(PathBuf),
+}
+
+/// Compares the generated files with the `.rs` files in `out_dir`.
+pub fn check(files: &[(String, String)], out_dir: &Path) -> io::Result<Vec<Drift>> {
+    let mut drift = Vec::new();
+
+    for (name, contents) in files {
+        let path = out_dir.join(name);
+        match fs::read_to_string(&path) {
+            Ok(committed) if committed == *contents => {}
+            Ok(_) => drift.push(Drift::Changed(path)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => drift.push(Drift::Missing(path)),
+            Err(err) => return Err(err),
+        }
+    }
+
+    for entry in fs::read_dir(out_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if path.extension().is_some_and(|ext| ext == "rs") && !files.iter().any(|(f, _)| f == name)
+        {
+            drift.push(Drift::Extra(path));
+        }
+    }
+
+    Ok(drift)
+}
+
+/// Writes the generated files into `out_dir` and removes `.rs` files it no longer generates.
+pub fn write(files: &[(String, String)], out_dir: &Path) -> io::Result<()> {
+    for drift in check(files, out_dir)? {
+        if let Drift::Extra(path) = drift {
+            fs::remove_file(path)?;
+        }
+    }
+    for (name, contents) in files {
+        fs::write(out_dir.join(name), contents)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use sbe_gen::{Drift, Schema};
+
+const USAGE: &str = "usage:
+  sbe-gen <messages.xml> <output dir>          generate the codecs
+  sbe-gen --check <messages.xml> <output dir>  fail if the codecs are out of date
+  sbe-gen --compat <old.xml> <new.xml>         fail on backwards incompatible changes";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["--check", schema_path, out_dir] => check(schema_path, Path::new(out_dir)),
+        ["--compat", old_path, new_path] => compat(old_path, new_path),
+        [schema_path, out_dir] if !schema_path.starts_with("--") => {
+            generate(schema_path, Path::new(out_dir));
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    }
+}
+
+fn read_schema(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("failed to read {path}: {err}");
+        process::exit(1);
+    })
+}
+
+fn generated_files(schema_path: &str) -> Vec<(String, String)> {
+    sbe_gen::generate(&read_schema(schema_path)).unwrap_or_else(|err| {
+        eprintln!("invalid schema {schema_path}: {err}");
+        process::exit(1);
+    })
+}
+
+fn generate(schema_path: &str, out_dir: &Path) {
+    let files = generated_files(schema_path);
+    sbe_gen::write(&files, out_dir).unwrap_or_else(|err| {
+        eprintln!("failed to write {}: {err}", out_dir.display());
+        process::exit(1);
+    });
+    println!("generated {} files in {}", files.len(), out_dir.display());
+}
+
+fn check(schema_path: &str, out_dir: &Path) {
+    let files = generated_files(schema_path);
+    let drift = sbe_gen::check(&files, out_dir).unwrap_or_else(|err| {
+        eprintln!("failed to read {}: {err}", out_dir.display());
+        process::exit(1);
+    });
+    for entry in &drift {
+        match entry {
+            Drift::Missing(path) => eprintln!("missing: {}", path.display()),
+            Drift::Changed(path) => eprintln!("out of date: {}", path.display()),
+            Drift::Extra(path) => eprintln!("not in schema: {}", path.display()),
+        }
+    }
+    if !drift.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn compat(old_path: &str, new_path: &str) {
+    let parse = |path: &str| {
+        Schema::parse(&read_schema(path)).unwrap_or_else(|err| {
+            eprintln!("invalid schema {path}: {err}");
+            process::exit(1);
+        })
+    };
+    let (old, new) = (parse(old_path), parse(new_path));
+
+    let changes = sbe_gen::breaking_changes(&old, &new);
+    for change in &changes {
+        eprintln!("breaking: {change}");
+    }
+    if !changes.is_empty() {
+        process::exit(1);
+    }
+    println!(
+        "schema version {} can be rolled out next to version {}",
+        new.version, old.version
+    );
+}
@@ -0,0 +1,368 @@
+//! Generates `messages.rs`: an owned struct per message, the `Message` enum over all of them and
+//! `decode_message`, so services can work with plain values instead of flyweight codecs.
+
+use std::fmt::Write as _;
+
+use crate::generator::{signature, snake_case, type_name};
+use crate::schema::{
+    Composite, EncodedType, EnumType, Field, FieldType, Message, Presence, Primitive, Schema,
+    TypeDef,
+};
+
+const MAX_WIDTH: usize = 100;
+
+/// How a message field is represented in its owned struct.
+enum Owned<'s> {
+    Primitive(Primitive),
+    Array(Primitive, usize),
+    Uuid,
+    Enum(&'s EnumType),
+    /// `decimal64`-shaped composite: an `int64` mantissa with a constant exponent of -8.
+    Decimal,
+    /// Composite with a single encoded member, such as a timestamp, represented by that member.
+    Member(&'s EncodedType),
+}
+
+struct OwnedField<'s> {
+    field: &'s Field,
+    name: String,
+    kind: Owned<'s>,
+    optional: bool,
+}
+
+impl<'s> OwnedField<'s> {
+    fn new(schema: &'s Schema, field: &'s Field) -> Self {
+        let field_type = schema
+            .field_type(field)
+            .expect("field types are resolved at parse time");
+        let kind = match field_type {
+            FieldType::Primitive(primitive, 1, _) => Owned::Primitive(primitive),
+            FieldType::Primitive(Primitive::UInt64, 2, _) if field.type_name == "uuid" => {
+                Owned::Uuid
+            }
+            FieldType::Primitive(primitive, length, _) => Owned::Array(primitive, length),
+            FieldType::Enum(e) => Owned::Enum(e),
+            FieldType::Composite(c) if is_decimal(c) => Owned::Decimal,
+            FieldType::Composite(c) => {
+                let mut encoded = c
+                    .members
+                    .iter()
+                    .filter(|m| m.presence != Presence::Constant);
+                match (encoded.next(), encoded.next()) {
+                    (Some(member), None) if member.length == 1 => Owned::Member(member),
+                    _ => panic!("composite '{}' has no owned representation", c.name),
+                }
+            }
+        };
+        Self {
+            field,
+            name: snake_case(&field.name),
+            kind,
+            optional: schema.field_presence(field) == Presence::Optional,
+        }
+    }
+
+    fn rust_type(&self) -> String {
+        let base = match &self.kind {
+            Owned::Primitive(primitive) => primitive.rust_type().to_owned(),
+            Owned::Array(primitive, length) => format!("[{}; {length}]", primitive.rust_type()),
+            Owned::Uuid => "Uuid".to_owned(),
+            Owned::Enum(e) => enum_path(e),
+            Owned::Decimal => "Decimal64".to_owned(),
+            Owned::Member(member) => member.primitive.rust_type().to_owned(),
+        };
+        if self.optional && !matches!(self.kind, Owned::Array(..) | Owned::Uuid) {
+            format!("Option<{base}>")
+        } else {
+            base
+        }
+    }
+
+    /// Expression reading the field from a message decoder bound to `decoder`.
+    fn decode_expr(&self) -> String {
+        let name = &self.name;
+        match &self.kind {
+            // Primitive decoders already map the null value of optional fields to `None`.
+            Owned::Primitive(_) | Owned::Array(..) => format!("decoder.{name}()"),
+            Owned::Uuid => format!("Uuid::from_words(decoder.{name}())"),
+            Owned::Enum(_) if self.optional => format!("non_null(decoder.{name}())"),
+            Owned::Enum(_) => format!("decoder.{name}()"),
+            Owned::Decimal | Owned::Member(_) if self.field.since_version > 0 => {
+                format!(
+                    "match decoder.{name}_decoder() {{\n\
+                     \x20               Either::Left(_) => {},\n\
+                     \x20               Either::Right(composite) => {},\n\
+                     \x20           }}",
+                    self.absent(),
+                    self.wrap_member("composite")
+                )
+            }
+            Owned::Decimal | Owned::Member(_) => {
+                self.wrap_member(&format!("decoder.{name}_decoder()"))
+            }
+        }
+    }
+
+    /// Converts the encoded member of a composite field read through `composite`.
+    fn wrap_member(&self, composite: &str) -> String {
+        match &self.kind {
+            Owned::Decimal if self.optional => {
+                format!("Decimal64::from_nullable({composite}.mantissa())")
+            }
+            Owned::Decimal => format!("Decimal64::from_mantissa({composite}.mantissa())"),
+            Owned::Member(member) if self.optional => {
+                let method = snake_case(&member.name);
+                let null = member.primitive.null_value();
+                format!("Some({composite}.{method}()).filter(|&value| value != {null})")
+            }
+            Owned::Member(member) => format!("{composite}.{}()", snake_case(&member.name)),
+            _ => unreachable!("only composite fields have members"),
+        }
+    }
+
+    /// Value of a composite field that the sender's acting version does not carry.
+    fn absent(&self) -> String {
+        match &self.kind {
+            _ if self.optional => "None".to_owned(),
+            Owned::Decimal => "Decimal64::from_mantissa(Decimal64::NULL_MANTISSA)".to_owned(),
+            Owned::Member(member) => member.primitive.null_value().to_owned(),
+            _ => unreachable!("only composite fields have members"),
+        }
+    }
+
+    /// Statements writing the field through a message encoder bound to `encoder`. Composite
+    /// encoders hand the message encoder back unless the field is the `last` one.
+    fn encode_stmts(&self, last: bool) -> String {
+        let name = &self.name;
+        let indent = "        ";
+        match &self.kind {
+            Owned::Primitive(primitive) if self.optional => format!(
+                "{indent}encoder.{name}(self.{name}.unwrap_or({}));\n",
+                primitive.null_value()
+            ),
+            Owned::Primitive(_) => format!("{indent}encoder.{name}(self.{name});\n"),
+            Owned::Array(..) => format!("{indent}encoder.{name}(&self.{name});\n"),
+            Owned::Uuid => format!("{indent}encoder.{name}(&self.{name}.words());\n"),
+            Owned::Enum(_) if self.optional => {
+                format!("{indent}encoder.{name}(self.{name}.unwrap_or_default());\n")
+            }
+            Owned::Enum(_) => format!("{indent}encoder.{name}(self.{name});\n"),
+            Owned::Decimal | Owned::Member(_) => {
+                let (method, value) = match &self.kind {
+                    Owned::Decimal if self.optional => (
+                        "mantissa".to_owned(),
+                        format!("Decimal64::to_nullable(self.{name})"),
+                    ),
+                    Owned::Decimal => ("mantissa".to_owned(), format!("self.{name}.mantissa()")),
+                    Owned::Member(member) if self.optional => (
+                        snake_case(&member.name),
+                        format!("self.{name}.unwrap_or({})", member.primitive.null_value()),
+                    ),
+                    Owned::Member(member) => (snake_case(&member.name), format!("self.{name}")),
+                    _ => unreachable!(),
+                };
+                let mut out = String::new();
+                let _ = writeln!(out, "{indent}let mut {name} = encoder.{name}_encoder();");
+                let _ = writeln!(out, "{indent}{name}.{method}({value});");
+                if !last {
+                    let _ = writeln!(out, "{indent}encoder = {name}.parent()?;");
+                }
+                out
+            }
+        }
+    }
+}
+
+fn is_decimal(composite: &Composite) -> bool {
+    let mantissa = composite
+        .members
+        .iter()
+        .any(|m| m.name == "mantissa" && m.primitive == Primitive::Int64 && m.length == 1);
+    let exponent = composite.members.iter().any(|m| {
+        m.name == "exponent"
+            && m.presence == Presence::Constant
+            && m.constant_value.as_deref() == Some("-8")
+    });
+    mantissa && exponent && composite.members.len() == 2
+}
+
+fn header_length(schema: &Schema) -> usize {
+    match schema.find_type(&schema.header_type) {
+        Some(TypeDef::Composite(header)) => header.encoded_length(),
+        _ => panic!(
+            "message header type '{}' is not a composite",
+            schema.header_type
+        ),
+    }
+}
+
+fn enum_path(e: &EnumType) -> String {
+    format!("{}::{}", snake_case(&e.name), type_name(&e.name))
+}
+
+fn codec_module(message: &Message) -> String {
+    format!("{}_codec", snake_case(&message.name))
+}
+
+pub fn generate_messages(schema: &Schema) -> String {
+    let mut out = String::new();
+
+    out.push_str(
+        "//! Owned, typed form of every message in the schema, decoded from or encoded into a\n\
+         //! complete frame (message header followed by the block).\n\n",
+    );
+    out.push_str("use crate::*;\n\n");
+    out.push_str("use serde::{Deserialize, Serialize};\n\n");
+
+    for message in &schema.messages {
+        out.push_str(&generate_struct(schema, message));
+    }
+
+    out.push_str(
+        "/// Serialized with the message name in a `message` field next to the message's own fields.\n\
+         #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]\n\
+         #[serde(tag = \"message\")]\n\
+         pub enum Message {\n",
+    );
+    for message in &schema.messages {
+        let name = type_name(&message.name);
+        let _ = writeln!(out, "    {name}({name}),");
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("impl Message {\n");
+    out.push_str("    pub fn template_id(&self) -> u16 {\n        match self {\n");
+    for message in &schema.messages {
+        let _ = writeln!(
+            out,
+            "            Self::{}(_) => {}::SBE_TEMPLATE_ID,",
+            type_name(&message.name),
+            codec_module(message)
+        );
+    }
+    out.push_str("        }\n    }\n\n");
+    out.push_str("    /// Length of the encoded message including its header.\n");
+    out.push_str("    pub fn encoded_length(&self) -> usize {\n        match self {\n");
+    for message in &schema.messages {
+        let name = type_name(&message.name);
+        let _ = writeln!(
+            out,
+            "            Self::{name}(_) => {name}::ENCODED_LENGTH,"
+        );
+    }
+    out.push_str("        }\n    }\n\n");
+    out.push_str(
+        "    /// Encodes the message with its header at the start of `buf` and returns the number of\n\
+         \x20   /// bytes written.\n",
+    );
+    out.push_str("    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {\n");
+    out.push_str("        match self {\n");
+    for message in &schema.messages {
+        let _ = writeln!(
+            out,
+            "            Self::{}(message) => message.encode(buf),",
+            type_name(&message.name)
+        );
+    }
+    out.push_str("        }\n    }\n}\n\n");
+
+    out.push_str(
+        "/// Decodes the message framed at the start of `buf`, validating the header, template id\n\
+         /// and block length so malformed input is rejected instead of read out of bounds.\n",
+    );
+    out.push_str("pub fn decode_message(buf: &[u8]) -> SbeResult<Message> {\n");
+    out.push_str("    let buf = ReadBuf::new(buf);\n");
+    out.push_str(
+        "    let header = message_header_codec::MessageHeaderDecoder::default().wrap_checked(buf, 0)?;\n",
+    );
+    out.push_str("    Ok(match header.template_id() {\n");
+    for message in &schema.messages {
+        let name = type_name(&message.name);
+        let module = codec_module(message);
+        let _ = writeln!(out, "        {module}::SBE_TEMPLATE_ID => Message::{name}(");
+        let _ = writeln!(out, "            {module}::{name}Decoder::default()");
+        out.push_str("                .header_checked(header, 0)?\n");
+        out.push_str("                .into(),\n        ),\n");
+    }
+    out.push_str("        template_id => return Err(SbeErr::UnknownTemplateId(template_id)),\n");
+    out.push_str("    })\n}\n\n");
+
+    out.push_str(
+        "/// Optional enums decode their null value as `NullVal`, which is also their default.\n\
+         fn non_null<T: Default + PartialEq>(value: T) -> Option<T> {\n\
+         \x20   if value == T::default() {\n\
+         \x20       None\n\
+         \x20   } else {\n\
+         \x20       Some(value)\n\
+         \x20   }\n\
+         }\n",
+    );
+    out
+}
+
+fn generate_struct(schema: &Schema, message: &Message) -> String {
+    let name = type_name(&message.name);
+    let module = codec_module(message);
+    let fields: Vec<OwnedField<'_>> = message
+        .fields
+        .iter()
+        .map(|f| OwnedField::new(schema, f))
+        .collect();
+    let mut out = String::new();
+
+    out.push_str("#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]\n");
+    let _ = writeln!(out, "pub struct {name} {{");
+    for field in &fields {
+        if let Owned::Array(Primitive::Char, _) = field.kind {
+            out.push_str("    #[serde(with = \"crate::types::char_array\")]\n");
+        }
+        let _ = writeln!(out, "    pub {}: {},", field.name, field.rust_type());
+    }
+    out.push_str("}\n\n");
+
+    let _ = writeln!(out, "impl {name} {{");
+    out.push_str("    /// Length of the encoded message including its header.\n");
+    let _ = writeln!(
+        out,
+        "    pub const ENCODED_LENGTH: usize = {};",
+        header_length(schema) + message.block_length
+    );
+    out.push('\n');
+    out.push_str(
+        "    /// Encodes the message with its header at the start of `buf` and returns the number of\n\
+         \x20   /// bytes written.\n",
+    );
+    out.push_str("    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {\n");
+    out.push_str("        if buf.len() < Self::ENCODED_LENGTH {\n");
+    out.push_str("            return Err(SbeErr::BufferTooShort {\n");
+    out.push_str("                required: Self::ENCODED_LENGTH,\n");
+    out.push_str("                actual: buf.len(),\n            });\n        }\n");
+    let _ = writeln!(
+        out,
+        "        let mut encoder = {module}::{name}Encoder::default()"
+    );
+    out.push_str("            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)\n");
+    out.push_str("            .header(0)\n            .parent()?;\n");
+    for (i, field) in fields.iter().enumerate() {
+        out.push_str(&field.encode_stmts(i + 1 == fields.len()));
+    }
+    out.push_str("        Ok(Self::ENCODED_LENGTH)\n    }\n}\n\n");
+
+    let decoder = format!("{module}::{name}Decoder<'_>");
+    let head = format!("impl From<{decoder}> for {name} {{");
+    if head.len() <= MAX_WIDTH {
+        let _ = writeln!(out, "{head}");
+    } else {
+        let _ = writeln!(out, "impl From<{decoder}>\n    for {name}\n{{");
+    }
+    let param = format!("decoder: {decoder}");
+    out.push_str(&signature("    ", "fn from", &[&param], Some("Self")));
+    out.push('\n');
+    out.push_str("        Self {\n");
+    for field in &fields {
+        let expr = field.decode_expr();
+        let _ = writeln!(out, "            {}: {expr},", field.name);
+    }
+    out.push_str("        }\n    }\n}\n\n");
+    out
+}
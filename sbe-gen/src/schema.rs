@@ -0,0 +1,510 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+    Char,
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Float,
+    Double,
+}
+
+impl Primitive {
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" => Self::Char,
+            "int8" => Self::Int8,
+            "uint8" => Self::UInt8,
+            "int16" => Self::Int16,
+            "uint16" => Self::UInt16,
+            "int32" => Self::Int32,
+            "uint32" => Self::UInt32,
+            "int64" => Self::Int64,
+            "uint64" => Self::UInt64,
+            "float" => Self::Float,
+            "double" => Self::Double,
+            _ => return None,
+        })
+    }
+
+    /// Name of the primitive in the schema's `primitiveType` attribute.
+    pub const fn sbe_name(self) -> &'static str {
+        match self {
+            Self::Char => "char",
+            Self::Int8 => "int8",
+            Self::UInt8 => "uint8",
+            Self::Int16 => "int16",
+            Self::UInt16 => "uint16",
+            Self::Int32 => "int32",
+            Self::UInt32 => "uint32",
+            Self::Int64 => "int64",
+            Self::UInt64 => "uint64",
+            Self::Float => "float",
+            Self::Double => "double",
+        }
+    }
+
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Char | Self::Int8 | Self::UInt8 => 1,
+            Self::Int16 | Self::UInt16 => 2,
+            Self::Int32 | Self::UInt32 | Self::Float => 4,
+            Self::Int64 | Self::UInt64 | Self::Double => 8,
+        }
+    }
+
+    pub const fn rust_type(self) -> &'static str {
+        match self {
+            Self::Char | Self::UInt8 => "u8",
+            Self::Int8 => "i8",
+            Self::Int16 => "i16",
+            Self::UInt16 => "u16",
+            Self::Int32 => "i32",
+            Self::UInt32 => "u32",
+            Self::Int64 => "i64",
+            Self::UInt64 => "u64",
+            Self::Float => "f32",
+            Self::Double => "f64",
+        }
+    }
+
+    pub const fn min_value(self) -> &'static str {
+        match self {
+            Self::Char => "32",
+            Self::Int8 => "-127",
+            Self::Int16 => "-32767",
+            Self::Int32 => "-2147483647",
+            Self::Int64 => "-9223372036854775807",
+            Self::UInt8 | Self::UInt16 | Self::UInt32 | Self::UInt64 => "0",
+            Self::Float => "-3.4028235e+38",
+            Self::Double => "-1.7976931348623157e+308",
+        }
+    }
+
+    pub const fn max_value(self) -> &'static str {
+        match self {
+            Self::Char => "126",
+            Self::Int8 => "127",
+            Self::UInt8 => "254",
+            Self::Int16 => "32767",
+            Self::UInt16 => "65534",
+            Self::Int32 => "2147483647",
+            Self::UInt32 => "4294967294",
+            Self::Int64 => "9223372036854775807",
+            Self::UInt64 => "18446744073709551614",
+            Self::Float => "3.4028235e+38",
+            Self::Double => "1.7976931348623157e+308",
+        }
+    }
+
+    pub const fn null_value(self) -> &'static str {
+        match self {
+            Self::Char => "0_u8",
+            Self::Int8 => "-128_i8",
+            Self::UInt8 => "0xff_u8",
+            Self::Int16 => "-32768_i16",
+            Self::UInt16 => "0xffff_u16",
+            Self::Int32 => "-2147483648_i32",
+            Self::UInt32 => "0xffffffff_u32",
+            Self::Int64 => "-9223372036854775808_i64",
+            Self::UInt64 => "0xffffffffffffffff_u64",
+            Self::Float => "f32::NAN",
+            Self::Double => "f64::NAN",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Presence {
+    Required,
+    Optional,
+    Constant,
+}
+
+impl Presence {
+    fn parse(value: Option<&str>) -> Result<Self, SchemaError> {
+        match value {
+            None | Some("required") => Ok(Self::Required),
+            Some("optional") => Ok(Self::Optional),
+            Some("constant") => Ok(Self::Constant),
+            Some(other) => Err(SchemaError::new(format!("unknown presence '{other}'"))),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EncodedType {
+    pub name: String,
+    pub primitive: Primitive,
+    pub length: usize,
+    pub presence: Presence,
+    pub character_encoding: Option<String>,
+    pub semantic_type: Option<String>,
+    pub constant_value: Option<String>,
+    pub value_ref: Option<String>,
+    pub since_version: u16,
+}
+
+impl EncodedType {
+    pub const fn encoded_length(&self) -> usize {
+        match self.presence {
+            Presence::Constant => 0,
+            _ => self.primitive.size() * self.length,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidValue {
+    pub name: String,
+    pub value: String,
+    pub since_version: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnumType {
+    pub name: String,
+    pub encoding: Primitive,
+    pub values: Vec<ValidValue>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Composite {
+    pub name: String,
+    pub members: Vec<EncodedType>,
+}
+
+impl Composite {
+    pub fn encoded_length(&self) -> usize {
+        self.members.iter().map(EncodedType::encoded_length).sum()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TypeDef {
+    Encoded(EncodedType),
+    Enum(EnumType),
+    Composite(Composite),
+}
+
+impl TypeDef {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Encoded(t) => &t.name,
+            Self::Enum(e) => &e.name,
+            Self::Composite(c) => &c.name,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Field {
+    pub name: String,
+    pub id: u16,
+    pub type_name: String,
+    pub semantic_type: Option<String>,
+    pub presence: Option<Presence>,
+    pub since_version: u16,
+    pub offset: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Message {
+    pub name: String,
+    pub id: u16,
+    pub block_length: usize,
+    pub semantic_type: Option<String>,
+    pub since_version: u16,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Schema {
+    pub package: String,
+    pub id: u16,
+    pub version: u16,
+    pub semantic_version: String,
+    pub description: String,
+    pub header_type: String,
+    pub byte_order: String,
+    pub types: Vec<TypeDef>,
+    pub messages: Vec<Message>,
+}
+
+/// Resolved view of what a message field is encoded as.
+pub enum FieldType<'s> {
+    Primitive(Primitive, usize, Option<&'s str>),
+    Enum(&'s EnumType),
+    Composite(&'s Composite),
+}
+
+impl Schema {
+    pub fn parse(text: &str) -> Result<Self, SchemaError> {
+        let doc = roxmltree::Document::parse(text).map_err(|e| SchemaError::new(e.to_string()))?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "messageSchema" {
+            return Err(SchemaError::new("root element is not messageSchema"));
+        }
+
+        let mut schema = Self {
+            package: root.attribute("package").unwrap_or_default().to_owned(),
+            id: parse_u16(root.attribute("id"), "schema id")?,
+            version: parse_u16_or(root.attribute("version"), 0, "schema version")?,
+            semantic_version: root
+                .attribute("semanticVersion")
+                .unwrap_or_default()
+                .to_owned(),
+            description: root.attribute("description").unwrap_or_default().to_owned(),
+            header_type: root
+                .attribute("headerType")
+                .unwrap_or("messageHeader")
+                .to_owned(),
+            byte_order: root
+                .attribute("byteOrder")
+                .unwrap_or("littleEndian")
+                .to_owned(),
+            types: Vec::new(),
+            messages: Vec::new(),
+        };
+
+        for types in root.children().filter(|n| n.has_tag_name("types")) {
+            for node in types.children().filter(roxmltree::Node::is_element) {
+                let def = match node.tag_name().name() {
+                    "type" => TypeDef::Encoded(parse_encoded_type(node)?),
+                    "enum" => TypeDef::Enum(parse_enum(node, &schema)?),
+                    "composite" => TypeDef::Composite(parse_composite(node)?),
+                    other => {
+                        return Err(SchemaError::new(format!(
+                            "unsupported type element '{other}'"
+                        )));
+                    }
+                };
+                schema.types.push(def);
+            }
+        }
+
+        for node in root.children().filter(|n| n.has_tag_name("message")) {
+            let message = parse_message(node, &schema)?;
+            schema.messages.push(message);
+        }
+
+        Ok(schema)
+    }
+
+    pub fn find_type(&self, name: &str) -> Option<&TypeDef> {
+        self.types.iter().find(|t| t.name() == name)
+    }
+
+    pub fn field_type(&self, field: &Field) -> Result<FieldType<'_>, SchemaError> {
+        if let Some(primitive) = Primitive::parse(&field.type_name) {
+            return Ok(FieldType::Primitive(primitive, 1, None));
+        }
+        match self.find_type(&field.type_name) {
+            Some(TypeDef::Encoded(t)) => Ok(FieldType::Primitive(
+                t.primitive,
+                t.length,
+                t.character_encoding.as_deref(),
+            )),
+            Some(TypeDef::Enum(e)) => Ok(FieldType::Enum(e)),
+            Some(TypeDef::Composite(c)) => Ok(FieldType::Composite(c)),
+            None => Err(SchemaError::new(format!(
+                "field '{}' references unknown type '{}'",
+                field.name, field.type_name
+            ))),
+        }
+    }
+
+    pub fn field_length(&self, field: &Field) -> Result<usize, SchemaError> {
+        Ok(match self.field_type(field)? {
+            FieldType::Primitive(p, len, _) => p.size() * len,
+            FieldType::Enum(e) => e.encoding.size(),
+            FieldType::Composite(c) => c.encoded_length(),
+        })
+    }
+
+    /// Presence of a field, falling back to the presence declared on its type.
+    pub fn field_presence(&self, field: &Field) -> Presence {
+        if let Some(presence) = field.presence {
+            return presence;
+        }
+        match self.find_type(&field.type_name) {
+            Some(TypeDef::Encoded(t)) => t.presence,
+            _ => Presence::Required,
+        }
+    }
+}
+
+fn parse_u16(value: Option<&str>, what: &str) -> Result<u16, SchemaError> {
+    value
+        .ok_or_else(|| SchemaError::new(format!("missing {what}")))?
+        .parse()
+        .map_err(|_| SchemaError::new(format!("invalid {what}")))
+}
+
+fn parse_u16_or(value: Option<&str>, default: u16, what: &str) -> Result<u16, SchemaError> {
+    value.map_or(Ok(default), |v| parse_u16(Some(v), what))
+}
+
+fn parse_encoded_type(node: roxmltree::Node<'_, '_>) -> Result<EncodedType, SchemaError> {
+    let name = node
+        .attribute("name")
+        .ok_or_else(|| SchemaError::new("type without a name"))?;
+    let primitive_name = node.attribute("primitiveType").unwrap_or_default();
+    let primitive = Primitive::parse(primitive_name).ok_or_else(|| {
+        SchemaError::new(format!(
+            "type '{name}' has unknown primitiveType '{primitive_name}'"
+        ))
+    })?;
+    let presence = Presence::parse(node.attribute("presence"))?;
+    let length = match node.attribute("length") {
+        Some(len) => len
+            .parse()
+            .map_err(|_| SchemaError::new(format!("type '{name}' has an invalid length")))?,
+        None => 1,
+    };
+    let constant_value = node
+        .text()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_owned);
+
+    Ok(EncodedType {
+        name: name.to_owned(),
+        primitive,
+        length,
+        presence,
+        character_encoding: node.attribute("characterEncoding").map(str::to_owned),
+        semantic_type: node.attribute("semanticType").map(str::to_owned),
+        constant_value,
+        value_ref: node.attribute("valueRef").map(str::to_owned),
+        since_version: parse_u16_or(node.attribute("sinceVersion"), 0, "sinceVersion")?,
+    })
+}
+
+fn parse_enum(node: roxmltree::Node<'_, '_>, schema: &Schema) -> Result<EnumType, SchemaError> {
+    let name = node
+        .attribute("name")
+        .ok_or_else(|| SchemaError::new("enum without a name"))?;
+    let encoding_name = node.attribute("encodingType").unwrap_or_default();
+    let encoding = match Primitive::parse(encoding_name) {
+        Some(p) => p,
+        None => match schema.find_type(encoding_name) {
+            Some(TypeDef::Encoded(t)) => t.primitive,
+            _ => {
+                return Err(SchemaError::new(format!(
+                    "enum '{name}' has unknown encodingType '{encoding_name}'"
+                )));
+            }
+        },
+    };
+
+    let mut values = Vec::new();
+    for value in node.children().filter(|n| n.has_tag_name("validValue")) {
+        values.push(ValidValue {
+            name: value.attribute("name").unwrap_or_default().to_owned(),
+            value: value.text().unwrap_or_default().trim().to_owned(),
+            since_version: parse_u16_or(value.attribute("sinceVersion"), 0, "sinceVersion")?,
+        });
+    }
+
+    Ok(EnumType {
+        name: name.to_owned(),
+        encoding,
+        values,
+    })
+}
+
+fn parse_composite(node: roxmltree::Node<'_, '_>) -> Result<Composite, SchemaError> {
+    let name = node
+        .attribute("name")
+        .ok_or_else(|| SchemaError::new("composite without a name"))?;
+    let mut members = Vec::new();
+    for member in node.children().filter(roxmltree::Node::is_element) {
+        if !member.has_tag_name("type") {
+            return Err(SchemaError::new(format!(
+                "composite '{name}' has unsupported member '{}'",
+                member.tag_name().name()
+            )));
+        }
+        members.push(parse_encoded_type(member)?);
+    }
+    Ok(Composite {
+        name: name.to_owned(),
+        members,
+    })
+}
+
+fn parse_message(node: roxmltree::Node<'_, '_>, schema: &Schema) -> Result<Message, SchemaError> {
+    let name = node
+        .attribute("name")
+        .ok_or_else(|| SchemaError::new("message without a name"))?;
+
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    for child in node.children().filter(roxmltree::Node::is_element) {
+        if !child.has_tag_name("field") {
+            return Err(SchemaError::new(format!(
+                "message '{name}' has unsupported element '{}'",
+                child.tag_name().name()
+            )));
+        }
+        let field = Field {
+            name: child.attribute("name").unwrap_or_default().to_owned(),
+            id: parse_u16(child.attribute("id"), "field id")?,
+            type_name: child.attribute("type").unwrap_or_default().to_owned(),
+            semantic_type: child.attribute("semanticType").map(str::to_owned),
+            presence: child
+                .attribute("presence")
+                .map(|p| Presence::parse(Some(p)))
+                .transpose()?,
+            since_version: parse_u16_or(child.attribute("sinceVersion"), 0, "sinceVersion")?,
+            offset,
+        };
+        offset += schema.field_length(&field)?;
+        fields.push(field);
+    }
+
+    let block_length = match node.attribute("blockLength") {
+        Some(len) => len.parse().map_err(|_| {
+            SchemaError::new(format!("message '{name}' has an invalid blockLength"))
+        })?,
+        None => offset,
+    };
+    if block_length < offset {
+        return Err(SchemaError::new(format!(
+            "message '{name}' declares blockLength {block_length} but its fields need {offset} bytes"
+        )));
+    }
+
+    Ok(Message {
+        name: name.to_owned(),
+        id: parse_u16(node.attribute("id"), "message id")?,
+        block_length,
+        semantic_type: node.attribute("semanticType").map(str::to_owned),
+        since_version: parse_u16_or(node.attribute("sinceVersion"), 0, "sinceVersion")?,
+        fields,
+    })
+}
+
+#[derive(Debug)]
+pub struct SchemaError(String);
+
+impl SchemaError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SchemaError {}
@@ -0,0 +1,15 @@
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn committed_codecs_match_schema() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let xml = fs::read_to_string(root.join("messages.xml")).expect("read messages.xml");
+    let files = sbe_gen::generate(&xml).expect("messages.xml is a valid schema");
+
+    let drift = sbe_gen::check(&files, &root.join("sbe/src")).expect("read sbe/src");
+    assert!(
+        drift.is_empty(),
+        "sbe/src disagrees with messages.xml, run `cargo run -p sbe-gen -- messages.xml sbe/src`: {drift:?}"
+    );
+}
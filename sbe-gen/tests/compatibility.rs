@@ -0,0 +1,61 @@
+use sbe_gen::{Schema, breaking_changes};
+
+fn schema(version: u16, fields: &str, block_length: usize) -> Schema {
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<sbe:messageSchema xmlns:sbe="http://fixprotocol.io/2016/sbe" package="sbe" id="100" version="{version}" byteOrder="littleEndian">
+    <types>
+        <composite name="messageHeader">
+            <type name="blockLength" primitiveType="uint16"/>
+            <type name="templateId" primitiveType="uint16"/>
+            <type name="schemaId" primitiveType="uint16"/>
+            <type name="version" primitiveType="uint16"/>
+        </composite>
+    </types>
+    <sbe:message name="Report" id="1" blockLength="{block_length}">
+        {fields}
+    </sbe:message>
+</sbe:messageSchema>"#
+    );
+    Schema::parse(&xml).expect("valid test schema")
+}
+
+const V0_FIELDS: &str = r#"<field name="OrderID" id="37" type="uint64"/>"#;
+
+#[test]
+fn appended_versioned_field_is_compatible() {
+    let old = schema(0, V0_FIELDS, 8);
+    let new = schema(
+        1,
+        r#"<field name="OrderID" id="37" type="uint64"/>
+           <field name="ExecID" id="17" type="uint64" sinceVersion="1"/>"#,
+        16,
+    );
+
+    assert_eq!(breaking_changes(&old, &new), Vec::new());
+}
+
+#[test]
+fn unversioned_or_removed_fields_are_breaking() {
+    let old = schema(0, V0_FIELDS, 8);
+    let unversioned = schema(
+        0,
+        r#"<field name="OrderID" id="37" type="uint64"/>
+           <field name="ExecID" id="17" type="uint64"/>"#,
+        16,
+    );
+    let removed = schema(1, r#"<field name="ExecID" id="17" type="uint64"/>"#, 8);
+
+    let locations = |new: &Schema| -> Vec<String> {
+        breaking_changes(&old, new)
+            .into_iter()
+            .map(|change| change.location)
+            .collect()
+    };
+    assert_eq!(locations(&unversioned), ["Report.ExecID"]);
+    // ExecID takes over OrderID's offset without a newer sinceVersion
+    assert_eq!(
+        locations(&removed),
+        ["Report.OrderID", "Report.ExecID", "Report.ExecID"]
+    );
+}
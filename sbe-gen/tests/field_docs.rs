@@ -0,0 +1,33 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use sbe_gen::schema::Primitive;
+
+fn range<T: FromStr<Err: Debug>>(primitive: Primitive) -> (T, T) {
+    let parse = |value: &str| {
+        value.parse().unwrap_or_else(|err| {
+            panic!(
+                "{} documents {value}, not a {}: {err:?}",
+                primitive.sbe_name(),
+                primitive.rust_type()
+            )
+        })
+    };
+    (parse(primitive.min_value()), parse(primitive.max_value()))
+}
+
+/// Every documented range fits the field's Rust type and leaves out its null value.
+#[test]
+fn documented_ranges_fit_the_type() {
+    assert_eq!(range::<u8>(Primitive::Char), (32, 126));
+    assert_eq!(range::<i8>(Primitive::Int8), (i8::MIN + 1, i8::MAX));
+    assert_eq!(range::<u8>(Primitive::UInt8), (0, u8::MAX - 1));
+    assert_eq!(range::<i16>(Primitive::Int16), (i16::MIN + 1, i16::MAX));
+    assert_eq!(range::<u16>(Primitive::UInt16), (0, u16::MAX - 1));
+    assert_eq!(range::<i32>(Primitive::Int32), (i32::MIN + 1, i32::MAX));
+    assert_eq!(range::<u32>(Primitive::UInt32), (0, u32::MAX - 1));
+    assert_eq!(range::<i64>(Primitive::Int64), (i64::MIN + 1, i64::MAX));
+    assert_eq!(range::<u64>(Primitive::UInt64), (0, u64::MAX - 1));
+    assert_eq!(range::<f32>(Primitive::Float), (f32::MIN, f32::MAX));
+    assert_eq!(range::<f64>(Primitive::Double), (f64::MIN, f64::MAX));
+}
@@ -0,0 +1,376 @@
+//! Fixed-point decimal carried by the `decimal64` composite.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use core::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// Decimal with eight fractional digits, encoded as the `decimal64` composite: a signed mantissa
+/// with a constant exponent of -8.
+///
+/// `i64::MIN` is the composite's null value, so it is never produced by parsing or arithmetic;
+/// an absent optional decimal is `None`. The operators panic on overflow like the primitive
+/// integers do in debug builds, the `checked_*` methods return `None` instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal64(i64);
+
+/// How a result with more than eight fractional digits is brought back to the decimal's scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Toward zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// Toward negative infinity.
+    Floor,
+    /// Toward positive infinity.
+    Ceiling,
+    /// To the nearest value, ties away from zero.
+    HalfUp,
+    /// To the nearest value, ties to the even neighbour.
+    HalfEven,
+}
+
+/// Why a string or number could not be converted into a [`Decimal64`] exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    Empty,
+    Invalid,
+    /// More than eight significant fractional digits.
+    TooPrecise,
+    Overflow,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParseDecimalError::Empty => "cannot parse decimal from empty string",
+            ParseDecimalError::Invalid => "invalid decimal literal",
+            ParseDecimalError::TooPrecise => "decimal has more than 8 fractional digits",
+            ParseDecimalError::Overflow => "decimal is out of range",
+        })
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl Decimal64 {
+    pub const EXPONENT: i8 = -8;
+    /// Mantissa of one, `10^-EXPONENT`.
+    pub const SCALE: i64 = 100_000_000;
+    /// Mantissa that encodes an absent optional decimal.
+    pub const NULL_MANTISSA: i64 = i64::MIN;
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(Self::SCALE);
+    pub const MIN: Self = Self(i64::MIN + 1);
+    pub const MAX: Self = Self(i64::MAX);
+
+    #[inline]
+    pub const fn from_mantissa(mantissa: i64) -> Self {
+        Self(mantissa)
+    }
+
+    #[inline]
+    pub const fn mantissa(self) -> i64 {
+        self.0
+    }
+
+    /// Decodes an optional decimal, mapping the null mantissa to `None`.
+    #[inline]
+    pub const fn from_nullable(mantissa: i64) -> Option<Self> {
+        if mantissa == Self::NULL_MANTISSA {
+            None
+        } else {
+            Some(Self(mantissa))
+        }
+    }
+
+    /// Encodes an optional decimal, mapping `None` to the null mantissa.
+    #[inline]
+    pub const fn to_nullable(value: Option<Self>) -> i64 {
+        match value {
+            Some(decimal) => decimal.0,
+            None => Self::NULL_MANTISSA,
+        }
+    }
+
+    /// The decimal with the given integral value, or `None` if it is out of range.
+    #[inline]
+    pub const fn from_integer(value: i64) -> Option<Self> {
+        match value.checked_mul(Self::SCALE) {
+            Some(mantissa) => Some(Self(mantissa)),
+            None => None,
+        }
+    }
+
+    #[inline]
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub const fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    #[inline]
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    #[inline]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).and_then(Self::non_null)
+    }
+
+    #[inline]
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).and_then(Self::non_null)
+    }
+
+    #[inline]
+    pub fn checked_neg(self) -> Option<Self> {
+        self.0.checked_neg().map(Self)
+    }
+
+    /// `self * rhs`, rounded to eight fractional digits with `mode`.
+    pub fn checked_mul(self, rhs: Self, mode: RoundingMode) -> Option<Self> {
+        let product = i128::from(self.0) * i128::from(rhs.0);
+        Self::from_wide(div_round(product, i128::from(Self::SCALE), mode))
+    }
+
+    /// `self / rhs`, rounded to eight fractional digits with `mode`; `None` if `rhs` is zero.
+    pub fn checked_div(self, rhs: Self, mode: RoundingMode) -> Option<Self> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        let dividend = i128::from(self.0) * i128::from(Self::SCALE);
+        Self::from_wide(div_round(dividend, i128::from(rhs.0), mode))
+    }
+
+    /// Rounds to `decimal_places` fractional digits with `mode`. Values already at that
+    /// precision, and any precision of eight or more, are returned unchanged.
+    pub fn checked_round(self, decimal_places: u32, mode: RoundingMode) -> Option<Self> {
+        if decimal_places >= 8 {
+            return Some(self);
+        }
+        let step = 10_i128.pow(8 - decimal_places);
+        let steps = div_round(i128::from(self.0), step, mode);
+        Self::from_wide(steps * step)
+    }
+
+    /// Nearest `f64`, for consumers that store or compute in floating point.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    #[inline]
+    fn non_null(mantissa: i64) -> Option<Self> {
+        Self::from_nullable(mantissa)
+    }
+
+    #[inline]
+    fn from_wide(mantissa: i128) -> Option<Self> {
+        i64::try_from(mantissa).ok().and_then(Self::non_null)
+    }
+}
+
+/// `numerator / denominator` rounded with `mode`. `denominator` must not be zero.
+fn div_round(numerator: i128, denominator: i128, mode: RoundingMode) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return quotient;
+    }
+
+    let negative = (numerator < 0) != (denominator < 0);
+    let away_from_zero = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::Floor => negative,
+        RoundingMode::Ceiling => !negative,
+        RoundingMode::HalfUp | RoundingMode::HalfEven => {
+            match (remainder.unsigned_abs() * 2).cmp(&denominator.unsigned_abs()) {
+                Ordering::Less => false,
+                Ordering::Greater => true,
+                Ordering::Equal => mode == RoundingMode::HalfUp || quotient % 2 != 0,
+            }
+        }
+    };
+
+    match (away_from_zero, negative) {
+        (false, _) => quotient,
+        (true, false) => quotient + 1,
+        (true, true) => quotient - 1,
+    }
+}
+
+impl fmt::Display for Decimal64 {
+    /// Plain notation without trailing zeros, e.g. `1.5` or `-0.00000001`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const SCALE: u64 = Decimal64::SCALE as u64;
+
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let integral = self.0.unsigned_abs() / SCALE;
+        let fraction = self.0.unsigned_abs() % SCALE;
+        if fraction == 0 {
+            return write!(f, "{sign}{integral}");
+        }
+        let digits = format!("{fraction:08}");
+        write!(f, "{sign}{integral}.{}", digits.trim_end_matches('0'))
+    }
+}
+
+impl FromStr for Decimal64 {
+    type Err = ParseDecimalError;
+
+    /// Parses plain decimal notation (`-12.5`, `+0.00000001`, `3`) without rounding: more than
+    /// eight non-zero fractional digits is an error rather than a rounded value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseDecimalError::Empty);
+        }
+        let (negative, unsigned) = match s.as_bytes()[0] {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integral, fraction) = match unsigned.split_once('.') {
+            Some((integral, fraction)) if !fraction.is_empty() => (integral, fraction),
+            Some(_) => return Err(ParseDecimalError::Invalid),
+            None => (unsigned, ""),
+        };
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if integral.is_empty() || !is_digits(integral) || !is_digits(fraction) {
+            return Err(ParseDecimalError::Invalid);
+        }
+
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > 8 {
+            return Err(ParseDecimalError::TooPrecise);
+        }
+
+        let mut mantissa: i64 = 0;
+        for digit in integral.bytes().chain(fraction.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(i64::from(digit - b'0')))
+                .ok_or(ParseDecimalError::Overflow)?;
+        }
+        mantissa = mantissa
+            .checked_mul(10_i64.pow(8 - fraction.len() as u32))
+            .ok_or(ParseDecimalError::Overflow)?;
+
+        Ok(Self(if negative { -mantissa } else { mantissa }))
+    }
+}
+
+impl TryFrom<f64> for Decimal64 {
+    type Error = ParseDecimalError;
+
+    /// Converts the shortest decimal representation of `value`, so `0.1` becomes exactly `0.1`.
+    /// Values needing more than eight fractional digits, and NaN or infinities, are rejected.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(ParseDecimalError::Invalid);
+        }
+        value.to_string().parse()
+    }
+}
+
+impl Add for Decimal64 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect("decimal addition overflowed")
+    }
+}
+
+impl Sub for Decimal64 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs)
+            .expect("decimal subtraction overflowed")
+    }
+}
+
+impl Neg for Decimal64 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self.checked_neg().expect("decimal negation overflowed")
+    }
+}
+
+impl AddAssign for Decimal64 {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Decimal64 {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sum for Decimal64 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+/// Serialized as a string in plain notation so no precision is lost in JSON.
+impl Serialize for Decimal64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Accepts a string in plain notation or a number. Numbers that cannot be represented exactly
+/// are rejected rather than rounded.
+impl<'de> Deserialize<'de> for Decimal64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(Decimal64Visitor)
+    }
+}
+
+struct Decimal64Visitor;
+
+impl Visitor<'_> for Decimal64Visitor {
+    type Value = Decimal64;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal with at most 8 fractional digits")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Decimal64, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Decimal64, E> {
+        Decimal64::from_integer(value).ok_or_else(|| E::custom(ParseDecimalError::Overflow))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Decimal64, E> {
+        i64::try_from(value)
+            .ok()
+            .and_then(Decimal64::from_integer)
+            .ok_or_else(|| E::custom(ParseDecimalError::Overflow))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Decimal64, E> {
+        Decimal64::try_from(value).map_err(E::custom)
+    }
+}
@@ -1,10 +1,17 @@
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 #[repr(u8)]
 pub enum ExecTypeEnum {
     New = 48_u8,
     Canceled = 52_u8,
     Rejected = 56_u8,
     Trade = 70_u8,
+    TradeCorrect = 71_u8,
+    TradeCancel = 72_u8,
+    OrderStatus = 73_u8,
     #[default]
     NullVal = 0_u8,
 }
@@ -16,6 +23,9 @@ impl From<u8> for ExecTypeEnum {
             52_u8 => Self::Canceled,
             56_u8 => Self::Rejected,
             70_u8 => Self::Trade,
+            71_u8 => Self::TradeCorrect,
+            72_u8 => Self::TradeCancel,
+            73_u8 => Self::OrderStatus,
             _ => Self::NullVal,
         }
     }
@@ -28,6 +38,9 @@ impl From<ExecTypeEnum> for u8 {
             ExecTypeEnum::Canceled => 52_u8,
             ExecTypeEnum::Rejected => 56_u8,
             ExecTypeEnum::Trade => 70_u8,
+            ExecTypeEnum::TradeCorrect => 71_u8,
+            ExecTypeEnum::TradeCancel => 72_u8,
+            ExecTypeEnum::OrderStatus => 73_u8,
             ExecTypeEnum::NullVal => 0_u8,
         }
     }
@@ -42,6 +55,9 @@ impl core::str::FromStr for ExecTypeEnum {
             "Canceled" => Ok(Self::Canceled),
             "Rejected" => Ok(Self::Rejected),
             "Trade" => Ok(Self::Trade),
+            "TradeCorrect" => Ok(Self::TradeCorrect),
+            "TradeCancel" => Ok(Self::TradeCancel),
+            "OrderStatus" => Ok(Self::OrderStatus),
             _ => Ok(Self::NullVal),
         }
     }
@@ -54,6 +70,9 @@ impl core::fmt::Display for ExecTypeEnum {
             Self::Canceled => write!(f, "Canceled"),
             Self::Rejected => write!(f, "Rejected"),
             Self::Trade => write!(f, "Trade"),
+            Self::TradeCorrect => write!(f, "TradeCorrect"),
+            Self::TradeCancel => write!(f, "TradeCancel"),
+            Self::OrderStatus => write!(f, "OrderStatus"),
             Self::NullVal => write!(f, "NullVal"),
         }
     }
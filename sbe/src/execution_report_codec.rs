@@ -7,7 +7,7 @@ pub use crate::SBE_SCHEMA_ID;
 pub use crate::SBE_SCHEMA_VERSION;
 pub use crate::SBE_SEMANTIC_VERSION;
 
-pub const SBE_BLOCK_LENGTH: u16 = 130;
+pub const SBE_BLOCK_LENGTH: u16 = 142;
 pub const SBE_TEMPLATE_ID: u16 = 3;
 
 pub mod encoder {
@@ -74,7 +74,7 @@ pub mod encoder {
 
         /// primitive array field 'Account'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -92,7 +92,7 @@ pub mod encoder {
 
         /// primitive array field 'Account' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -110,7 +110,7 @@ pub mod encoder {
 
         /// primitive array field 'Account' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -136,7 +136,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -154,7 +154,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -172,7 +172,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -191,7 +191,7 @@ pub mod encoder {
 
         /// primitive field 'TrdMatchID'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: int
@@ -206,7 +206,7 @@ pub mod encoder {
 
         /// primitive field 'OrderID'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: int
@@ -221,7 +221,7 @@ pub mod encoder {
 
         /// primitive field 'ExecID'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: int
@@ -376,6 +376,36 @@ pub mod encoder {
             let offset = self.offset + 129;
             self.get_buf_mut().put_u8_at(offset, value as u8)
         }
+
+        /// primitive field 'MassStatusReqID'
+        /// - min value: 0
+        /// - max value: 18446744073709551614
+        /// - null value: 0xffffffffffffffff_u64
+        /// - characterEncoding: null
+        /// - semanticType: int
+        /// - encodedOffset: 130
+        /// - encodedLength: 8
+        /// - version: 1
+        #[inline]
+        pub fn mass_status_req_id(&mut self, value: u64) {
+            let offset = self.offset + 130;
+            self.get_buf_mut().put_u64_at(offset, value);
+        }
+
+        /// primitive field 'TotNumReports'
+        /// - min value: 0
+        /// - max value: 4294967294
+        /// - null value: 0xffffffff_u32
+        /// - characterEncoding: null
+        /// - semanticType: int
+        /// - encodedOffset: 138
+        /// - encodedLength: 4
+        /// - version: 1
+        #[inline]
+        pub fn tot_num_reports(&mut self, value: u32) {
+            let offset = self.offset + 138;
+            self.get_buf_mut().put_u32_at(offset, value);
+        }
     }
 } // end encoder
 
@@ -455,6 +485,39 @@ pub mod decoder {
             )
         }
 
+        /// Like `header`, but checks the template id, that blockLength covers every field of
+        /// the header's acting version and that the buffer holds the block, so malformed
+        /// input is rejected instead of read out of bounds. The header itself should come
+        /// from `MessageHeaderDecoder::wrap_checked`.
+        pub fn header_checked(
+            self,
+            header: MessageHeaderDecoder<ReadBuf<'a>>,
+            offset: usize,
+        ) -> SbeResult<Self> {
+            let template_id = header.template_id();
+            if template_id != SBE_TEMPLATE_ID {
+                return Err(SbeErr::TemplateIdMismatch {
+                    expected: SBE_TEMPLATE_ID,
+                    actual: template_id,
+                });
+            }
+            let required_block_length = match header.version() {
+                0 => 130,
+                _ => SBE_BLOCK_LENGTH,
+            };
+            let block_length = header.block_length();
+            if block_length < required_block_length {
+                return Err(SbeErr::BlockLengthTooShort {
+                    required: required_block_length,
+                    actual: block_length,
+                });
+            }
+            let required = offset + message_header_codec::ENCODED_LENGTH + block_length as usize;
+            header.get_buf().check_len(required)?;
+
+            Ok(self.header(header, offset))
+        }
+
         #[inline]
         pub fn account(&self) -> [u64; 2] {
             let buf = self.get_buf();
@@ -580,5 +643,33 @@ pub mod decoder {
         pub fn side(&self) -> side_enum::SideEnum {
             self.get_buf().get_u8_at(self.offset + 129).into()
         }
+
+        /// primitive field - 'OPTIONAL' { null_value: '0xffffffffffffffff_u64' }
+        #[inline]
+        pub fn mass_status_req_id(&self) -> Option<u64> {
+            if self.acting_version() < 1 {
+                return None;
+            }
+            let value = self.get_buf().get_u64_at(self.offset + 130);
+            if value == 0xffffffffffffffff_u64 {
+                None
+            } else {
+                Some(value)
+            }
+        }
+
+        /// primitive field - 'OPTIONAL' { null_value: '0xffffffff_u32' }
+        #[inline]
+        pub fn tot_num_reports(&self) -> Option<u32> {
+            if self.acting_version() < 1 {
+                return None;
+            }
+            let value = self.get_buf().get_u32_at(self.offset + 138);
+            if value == 0xffffffff_u32 {
+                None
+            } else {
+                Some(value)
+            }
+        }
     }
 } // end decoder
@@ -7,21 +7,38 @@ use ::core::convert::TryInto;
 
 pub mod cxl_rej_reason_enum;
 pub mod cxl_rej_response_to_enum;
+pub mod decimal64;
 pub mod decimal_64_codec;
+pub mod drop_copy_header_codec;
+pub mod drop_copy_resend_request_codec;
 pub mod exec_type_enum;
 pub mod execution_report_codec;
 pub mod message_header_codec;
+pub mod messages;
 pub mod new_order_single_codec;
 pub mod ord_rej_reason_enum;
 pub mod ord_status_enum;
 pub mod ord_type_enum;
 pub mod order_cancel_reject_codec;
 pub mod order_cancel_request_codec;
+pub mod order_mass_status_request_codec;
+pub mod order_status_request_codec;
+pub mod replay_end_codec;
+pub mod replay_header_codec;
+pub mod replay_request_codec;
+pub mod replay_status_enum;
 pub mod side_enum;
+pub mod trade_bust_request_codec;
+pub mod trade_correct_request_codec;
+pub mod types;
 pub mod utc_timestamp_nanos_codec;
 
+pub use decimal64::*;
+pub use messages::*;
+pub use types::*;
+
 pub const SBE_SCHEMA_ID: u16 = 100;
-pub const SBE_SCHEMA_VERSION: u16 = 0;
+pub const SBE_SCHEMA_VERSION: u16 = 4;
 pub const SBE_SEMANTIC_VERSION: &str = "";
 
 pub type SbeResult<T> = core::result::Result<T, SbeErr>;
@@ -29,6 +46,28 @@ pub type SbeResult<T> = core::result::Result<T, SbeErr>;
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SbeErr {
     ParentNotSet,
+    /// The buffer ends before the header or message block being decoded.
+    BufferTooShort {
+        required: usize,
+        actual: usize,
+    },
+    /// The header carries another schema's id.
+    SchemaIdMismatch {
+        expected: u16,
+        actual: u16,
+    },
+    /// The header's template id does not belong to any message of the schema.
+    UnknownTemplateId(u16),
+    /// The header's template id is not the one of the message being decoded.
+    TemplateIdMismatch {
+        expected: u16,
+        actual: u16,
+    },
+    /// The header's blockLength is shorter than the message's block at the header's version.
+    BlockLengthTooShort {
+        required: u16,
+        actual: u16,
+    },
 }
 impl core::fmt::Display for SbeErr {
     #[inline]
@@ -83,6 +122,28 @@ impl<'a> ReadBuf<'a> {
         Self { data }
     }
 
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Fails unless the buffer holds at least `required` bytes.
+    #[inline]
+    pub fn check_len(&self, required: usize) -> SbeResult<()> {
+        if self.data.len() < required {
+            return Err(SbeErr::BufferTooShort {
+                required,
+                actual: self.data.len(),
+            });
+        }
+        Ok(())
+    }
+
     #[inline]
     pub(crate) fn get_bytes_at<const N: usize>(slice: &[u8], index: usize) -> [u8; N] {
         slice[index..index + N]
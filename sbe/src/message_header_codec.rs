@@ -173,4 +173,25 @@ pub mod decoder {
             self.get_buf().get_u16_at(self.offset + 6)
         }
     }
+
+    impl<'a> MessageHeaderDecoder<ReadBuf<'a>> {
+        /// Wraps `buf` after checking that it holds a complete header of this schema. Any
+        /// version is accepted: message decoders use it as their acting version, reading
+        /// older messages with null values for newer fields and skipping fields added
+        /// after this crate was generated.
+        pub fn wrap_checked(self, buf: ReadBuf<'a>, offset: usize) -> SbeResult<Self> {
+            buf.check_len(offset + ENCODED_LENGTH)?;
+            let header = self.wrap(buf, offset);
+
+            let schema_id = header.schema_id();
+            if schema_id != SBE_SCHEMA_ID {
+                return Err(SbeErr::SchemaIdMismatch {
+                    expected: SBE_SCHEMA_ID,
+                    actual: schema_id,
+                });
+            }
+
+            Ok(header)
+        }
+    }
 } // end decoder mod
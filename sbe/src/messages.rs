@@ -0,0 +1,824 @@
+//! Owned, typed form of every message in the schema, decoded from or encoded into a
+//! complete frame (message header followed by the block).
+
+use crate::*;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewOrderSingle {
+    pub cl_ord_id: Uuid,
+    pub account: Uuid,
+    pub transact_time: u64,
+    pub order_qty: Decimal64,
+    pub price: Option<Decimal64>,
+    #[serde(with = "crate::types::char_array")]
+    pub symbol: [u8; 6],
+    pub side: side_enum::SideEnum,
+    pub ord_type: ord_type_enum::OrdTypeEnum,
+}
+
+impl NewOrderSingle {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 72;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = new_order_single_codec::NewOrderSingleEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.cl_ord_id(&self.cl_ord_id.words());
+        encoder.account(&self.account.words());
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        encoder = transact_time.parent()?;
+        let mut order_qty = encoder.order_qty_encoder();
+        order_qty.mantissa(self.order_qty.mantissa());
+        encoder = order_qty.parent()?;
+        let mut price = encoder.price_encoder();
+        price.mantissa(Decimal64::to_nullable(self.price));
+        encoder = price.parent()?;
+        encoder.symbol(&self.symbol);
+        encoder.side(self.side);
+        encoder.ord_type(self.ord_type);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<new_order_single_codec::NewOrderSingleDecoder<'_>> for NewOrderSingle {
+    fn from(decoder: new_order_single_codec::NewOrderSingleDecoder<'_>) -> Self {
+        Self {
+            cl_ord_id: Uuid::from_words(decoder.cl_ord_id()),
+            account: Uuid::from_words(decoder.account()),
+            transact_time: decoder.transact_time_decoder().time(),
+            order_qty: Decimal64::from_mantissa(decoder.order_qty_decoder().mantissa()),
+            price: Decimal64::from_nullable(decoder.price_decoder().mantissa()),
+            symbol: decoder.symbol(),
+            side: decoder.side(),
+            ord_type: decoder.ord_type(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderCancelRequest {
+    pub orig_cl_ord_id: Uuid,
+    pub cl_ord_id: Uuid,
+    pub account: Uuid,
+    pub transact_time: u64,
+    #[serde(with = "crate::types::char_array")]
+    pub symbol: [u8; 6],
+    pub side: side_enum::SideEnum,
+}
+
+impl OrderCancelRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 71;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = order_cancel_request_codec::OrderCancelRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.orig_cl_ord_id(&self.orig_cl_ord_id.words());
+        encoder.cl_ord_id(&self.cl_ord_id.words());
+        encoder.account(&self.account.words());
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        encoder = transact_time.parent()?;
+        encoder.symbol(&self.symbol);
+        encoder.side(self.side);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<order_cancel_request_codec::OrderCancelRequestDecoder<'_>> for OrderCancelRequest {
+    fn from(decoder: order_cancel_request_codec::OrderCancelRequestDecoder<'_>) -> Self {
+        Self {
+            orig_cl_ord_id: Uuid::from_words(decoder.orig_cl_ord_id()),
+            cl_ord_id: Uuid::from_words(decoder.cl_ord_id()),
+            account: Uuid::from_words(decoder.account()),
+            transact_time: decoder.transact_time_decoder().time(),
+            symbol: decoder.symbol(),
+            side: decoder.side(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    pub account: Uuid,
+    pub cl_ord_id: Uuid,
+    pub trd_match_id: Option<u64>,
+    pub order_id: u64,
+    pub exec_id: u64,
+    pub transact_time: u64,
+    pub price: Option<Decimal64>,
+    pub order_qty: Decimal64,
+    pub last_qty: Option<Decimal64>,
+    pub last_px: Option<Decimal64>,
+    pub leaves_qty: Decimal64,
+    pub cum_qty: Decimal64,
+    pub avg_px: Option<Decimal64>,
+    #[serde(with = "crate::types::char_array")]
+    pub symbol: [u8; 6],
+    pub exec_type: exec_type_enum::ExecTypeEnum,
+    pub ord_status: ord_status_enum::OrdStatusEnum,
+    pub ord_rej_reason: Option<ord_rej_reason_enum::OrdRejReasonEnum>,
+    pub side: side_enum::SideEnum,
+    pub mass_status_req_id: Option<u64>,
+    pub tot_num_reports: Option<u32>,
+}
+
+impl ExecutionReport {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 150;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = execution_report_codec::ExecutionReportEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.account(&self.account.words());
+        encoder.cl_ord_id(&self.cl_ord_id.words());
+        encoder.trd_match_id(self.trd_match_id.unwrap_or(0xffffffffffffffff_u64));
+        encoder.order_id(self.order_id);
+        encoder.exec_id(self.exec_id);
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        encoder = transact_time.parent()?;
+        let mut price = encoder.price_encoder();
+        price.mantissa(Decimal64::to_nullable(self.price));
+        encoder = price.parent()?;
+        let mut order_qty = encoder.order_qty_encoder();
+        order_qty.mantissa(self.order_qty.mantissa());
+        encoder = order_qty.parent()?;
+        let mut last_qty = encoder.last_qty_encoder();
+        last_qty.mantissa(Decimal64::to_nullable(self.last_qty));
+        encoder = last_qty.parent()?;
+        let mut last_px = encoder.last_px_encoder();
+        last_px.mantissa(Decimal64::to_nullable(self.last_px));
+        encoder = last_px.parent()?;
+        let mut leaves_qty = encoder.leaves_qty_encoder();
+        leaves_qty.mantissa(self.leaves_qty.mantissa());
+        encoder = leaves_qty.parent()?;
+        let mut cum_qty = encoder.cum_qty_encoder();
+        cum_qty.mantissa(self.cum_qty.mantissa());
+        encoder = cum_qty.parent()?;
+        let mut avg_px = encoder.avg_px_encoder();
+        avg_px.mantissa(Decimal64::to_nullable(self.avg_px));
+        encoder = avg_px.parent()?;
+        encoder.symbol(&self.symbol);
+        encoder.exec_type(self.exec_type);
+        encoder.ord_status(self.ord_status);
+        encoder.ord_rej_reason(self.ord_rej_reason.unwrap_or_default());
+        encoder.side(self.side);
+        encoder.mass_status_req_id(self.mass_status_req_id.unwrap_or(0xffffffffffffffff_u64));
+        encoder.tot_num_reports(self.tot_num_reports.unwrap_or(0xffffffff_u32));
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<execution_report_codec::ExecutionReportDecoder<'_>> for ExecutionReport {
+    fn from(decoder: execution_report_codec::ExecutionReportDecoder<'_>) -> Self {
+        Self {
+            account: Uuid::from_words(decoder.account()),
+            cl_ord_id: Uuid::from_words(decoder.cl_ord_id()),
+            trd_match_id: decoder.trd_match_id(),
+            order_id: decoder.order_id(),
+            exec_id: decoder.exec_id(),
+            transact_time: decoder.transact_time_decoder().time(),
+            price: Decimal64::from_nullable(decoder.price_decoder().mantissa()),
+            order_qty: Decimal64::from_mantissa(decoder.order_qty_decoder().mantissa()),
+            last_qty: Decimal64::from_nullable(decoder.last_qty_decoder().mantissa()),
+            last_px: Decimal64::from_nullable(decoder.last_px_decoder().mantissa()),
+            leaves_qty: Decimal64::from_mantissa(decoder.leaves_qty_decoder().mantissa()),
+            cum_qty: Decimal64::from_mantissa(decoder.cum_qty_decoder().mantissa()),
+            avg_px: Decimal64::from_nullable(decoder.avg_px_decoder().mantissa()),
+            symbol: decoder.symbol(),
+            exec_type: decoder.exec_type(),
+            ord_status: decoder.ord_status(),
+            ord_rej_reason: non_null(decoder.ord_rej_reason()),
+            side: decoder.side(),
+            mass_status_req_id: decoder.mass_status_req_id(),
+            tot_num_reports: decoder.tot_num_reports(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderCancelReject {
+    pub cl_ord_id: Uuid,
+    pub orig_cl_ord_id: Uuid,
+    pub order_id: Option<u64>,
+    pub ord_status: Option<ord_status_enum::OrdStatusEnum>,
+    pub cxl_rej_response_to: cxl_rej_response_to_enum::CxlRejResponseToEnum,
+    pub cxl_rej_reason: cxl_rej_reason_enum::CxlRejReasonEnum,
+    pub exec_id: Option<u64>,
+}
+
+impl OrderCancelReject {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 59;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = order_cancel_reject_codec::OrderCancelRejectEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.cl_ord_id(&self.cl_ord_id.words());
+        encoder.orig_cl_ord_id(&self.orig_cl_ord_id.words());
+        encoder.order_id(self.order_id.unwrap_or(0xffffffffffffffff_u64));
+        encoder.ord_status(self.ord_status.unwrap_or_default());
+        encoder.cxl_rej_response_to(self.cxl_rej_response_to);
+        encoder.cxl_rej_reason(self.cxl_rej_reason);
+        encoder.exec_id(self.exec_id.unwrap_or(0xffffffffffffffff_u64));
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<order_cancel_reject_codec::OrderCancelRejectDecoder<'_>> for OrderCancelReject {
+    fn from(decoder: order_cancel_reject_codec::OrderCancelRejectDecoder<'_>) -> Self {
+        Self {
+            cl_ord_id: Uuid::from_words(decoder.cl_ord_id()),
+            orig_cl_ord_id: Uuid::from_words(decoder.orig_cl_ord_id()),
+            order_id: decoder.order_id(),
+            ord_status: non_null(decoder.ord_status()),
+            cxl_rej_response_to: decoder.cxl_rej_response_to(),
+            cxl_rej_reason: decoder.cxl_rej_reason(),
+            exec_id: decoder.exec_id(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderStatusRequest {
+    pub cl_ord_id: Uuid,
+    pub account: Uuid,
+    pub transact_time: u64,
+}
+
+impl OrderStatusRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 48;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = order_status_request_codec::OrderStatusRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.cl_ord_id(&self.cl_ord_id.words());
+        encoder.account(&self.account.words());
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<order_status_request_codec::OrderStatusRequestDecoder<'_>> for OrderStatusRequest {
+    fn from(decoder: order_status_request_codec::OrderStatusRequestDecoder<'_>) -> Self {
+        Self {
+            cl_ord_id: Uuid::from_words(decoder.cl_ord_id()),
+            account: Uuid::from_words(decoder.account()),
+            transact_time: decoder.transact_time_decoder().time(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderMassStatusRequest {
+    pub mass_status_req_id: u64,
+    pub account: Uuid,
+    pub transact_time: u64,
+}
+
+impl OrderMassStatusRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 40;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = order_mass_status_request_codec::OrderMassStatusRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.mass_status_req_id(self.mass_status_req_id);
+        encoder.account(&self.account.words());
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<order_mass_status_request_codec::OrderMassStatusRequestDecoder<'_>>
+    for OrderMassStatusRequest
+{
+    fn from(decoder: order_mass_status_request_codec::OrderMassStatusRequestDecoder<'_>) -> Self {
+        Self {
+            mass_status_req_id: decoder.mass_status_req_id(),
+            account: Uuid::from_words(decoder.account()),
+            transact_time: decoder.transact_time_decoder().time(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeBustRequest {
+    pub trd_match_id: u64,
+    pub transact_time: u64,
+}
+
+impl TradeBustRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 24;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = trade_bust_request_codec::TradeBustRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.trd_match_id(self.trd_match_id);
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<trade_bust_request_codec::TradeBustRequestDecoder<'_>> for TradeBustRequest {
+    fn from(decoder: trade_bust_request_codec::TradeBustRequestDecoder<'_>) -> Self {
+        Self {
+            trd_match_id: decoder.trd_match_id(),
+            transact_time: decoder.transact_time_decoder().time(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeCorrectRequest {
+    pub trd_match_id: u64,
+    pub transact_time: u64,
+    pub last_qty: Decimal64,
+    pub last_px: Decimal64,
+}
+
+impl TradeCorrectRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 40;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = trade_correct_request_codec::TradeCorrectRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.trd_match_id(self.trd_match_id);
+        let mut transact_time = encoder.transact_time_encoder();
+        transact_time.time(self.transact_time);
+        encoder = transact_time.parent()?;
+        let mut last_qty = encoder.last_qty_encoder();
+        last_qty.mantissa(self.last_qty.mantissa());
+        encoder = last_qty.parent()?;
+        let mut last_px = encoder.last_px_encoder();
+        last_px.mantissa(self.last_px.mantissa());
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<trade_correct_request_codec::TradeCorrectRequestDecoder<'_>> for TradeCorrectRequest {
+    fn from(decoder: trade_correct_request_codec::TradeCorrectRequestDecoder<'_>) -> Self {
+        Self {
+            trd_match_id: decoder.trd_match_id(),
+            transact_time: decoder.transact_time_decoder().time(),
+            last_qty: Decimal64::from_mantissa(decoder.last_qty_decoder().mantissa()),
+            last_px: Decimal64::from_mantissa(decoder.last_px_decoder().mantissa()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DropCopyHeader {
+    pub account: Uuid,
+    pub msg_seq_num: u64,
+    pub session_id: Option<u64>,
+}
+
+impl DropCopyHeader {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 40;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = drop_copy_header_codec::DropCopyHeaderEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.account(&self.account.words());
+        encoder.msg_seq_num(self.msg_seq_num);
+        encoder.session_id(self.session_id.unwrap_or(0xffffffffffffffff_u64));
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<drop_copy_header_codec::DropCopyHeaderDecoder<'_>> for DropCopyHeader {
+    fn from(decoder: drop_copy_header_codec::DropCopyHeaderDecoder<'_>) -> Self {
+        Self {
+            account: Uuid::from_words(decoder.account()),
+            msg_seq_num: decoder.msg_seq_num(),
+            session_id: decoder.session_id(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DropCopyResendRequest {
+    pub account: Uuid,
+    pub begin_seq_no: u64,
+    pub end_seq_no: u64,
+}
+
+impl DropCopyResendRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 40;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = drop_copy_resend_request_codec::DropCopyResendRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.account(&self.account.words());
+        encoder.begin_seq_no(self.begin_seq_no);
+        encoder.end_seq_no(self.end_seq_no);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<drop_copy_resend_request_codec::DropCopyResendRequestDecoder<'_>>
+    for DropCopyResendRequest
+{
+    fn from(decoder: drop_copy_resend_request_codec::DropCopyResendRequestDecoder<'_>) -> Self {
+        Self {
+            account: Uuid::from_words(decoder.account()),
+            begin_seq_no: decoder.begin_seq_no(),
+            end_seq_no: decoder.end_seq_no(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayRequest {
+    pub replay_id: u64,
+    pub position: Option<u64>,
+    pub exec_id: Option<u64>,
+}
+
+impl ReplayRequest {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 32;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = replay_request_codec::ReplayRequestEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.replay_id(self.replay_id);
+        encoder.position(self.position.unwrap_or(0xffffffffffffffff_u64));
+        encoder.exec_id(self.exec_id.unwrap_or(0xffffffffffffffff_u64));
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<replay_request_codec::ReplayRequestDecoder<'_>> for ReplayRequest {
+    fn from(decoder: replay_request_codec::ReplayRequestDecoder<'_>) -> Self {
+        Self {
+            replay_id: decoder.replay_id(),
+            position: decoder.position(),
+            exec_id: decoder.exec_id(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub replay_id: u64,
+    pub position: u64,
+}
+
+impl ReplayHeader {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 24;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = replay_header_codec::ReplayHeaderEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.replay_id(self.replay_id);
+        encoder.position(self.position);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<replay_header_codec::ReplayHeaderDecoder<'_>> for ReplayHeader {
+    fn from(decoder: replay_header_codec::ReplayHeaderDecoder<'_>) -> Self {
+        Self {
+            replay_id: decoder.replay_id(),
+            position: decoder.position(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayEnd {
+    pub replay_id: u64,
+    pub position: u64,
+    pub replay_status: replay_status_enum::ReplayStatusEnum,
+}
+
+impl ReplayEnd {
+    /// Length of the encoded message including its header.
+    pub const ENCODED_LENGTH: usize = 25;
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        if buf.len() < Self::ENCODED_LENGTH {
+            return Err(SbeErr::BufferTooShort {
+                required: Self::ENCODED_LENGTH,
+                actual: buf.len(),
+            });
+        }
+        let mut encoder = replay_end_codec::ReplayEndEncoder::default()
+            .wrap(WriteBuf::new(buf), message_header_codec::ENCODED_LENGTH)
+            .header(0)
+            .parent()?;
+        encoder.replay_id(self.replay_id);
+        encoder.position(self.position);
+        encoder.replay_status(self.replay_status);
+        Ok(Self::ENCODED_LENGTH)
+    }
+}
+
+impl From<replay_end_codec::ReplayEndDecoder<'_>> for ReplayEnd {
+    fn from(decoder: replay_end_codec::ReplayEndDecoder<'_>) -> Self {
+        Self {
+            replay_id: decoder.replay_id(),
+            position: decoder.position(),
+            replay_status: decoder.replay_status(),
+        }
+    }
+}
+
+/// Serialized with the message name in a `message` field next to the message's own fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "message")]
+pub enum Message {
+    NewOrderSingle(NewOrderSingle),
+    OrderCancelRequest(OrderCancelRequest),
+    ExecutionReport(ExecutionReport),
+    OrderCancelReject(OrderCancelReject),
+    OrderStatusRequest(OrderStatusRequest),
+    OrderMassStatusRequest(OrderMassStatusRequest),
+    TradeBustRequest(TradeBustRequest),
+    TradeCorrectRequest(TradeCorrectRequest),
+    DropCopyHeader(DropCopyHeader),
+    DropCopyResendRequest(DropCopyResendRequest),
+    ReplayRequest(ReplayRequest),
+    ReplayHeader(ReplayHeader),
+    ReplayEnd(ReplayEnd),
+}
+
+impl Message {
+    pub fn template_id(&self) -> u16 {
+        match self {
+            Self::NewOrderSingle(_) => new_order_single_codec::SBE_TEMPLATE_ID,
+            Self::OrderCancelRequest(_) => order_cancel_request_codec::SBE_TEMPLATE_ID,
+            Self::ExecutionReport(_) => execution_report_codec::SBE_TEMPLATE_ID,
+            Self::OrderCancelReject(_) => order_cancel_reject_codec::SBE_TEMPLATE_ID,
+            Self::OrderStatusRequest(_) => order_status_request_codec::SBE_TEMPLATE_ID,
+            Self::OrderMassStatusRequest(_) => order_mass_status_request_codec::SBE_TEMPLATE_ID,
+            Self::TradeBustRequest(_) => trade_bust_request_codec::SBE_TEMPLATE_ID,
+            Self::TradeCorrectRequest(_) => trade_correct_request_codec::SBE_TEMPLATE_ID,
+            Self::DropCopyHeader(_) => drop_copy_header_codec::SBE_TEMPLATE_ID,
+            Self::DropCopyResendRequest(_) => drop_copy_resend_request_codec::SBE_TEMPLATE_ID,
+            Self::ReplayRequest(_) => replay_request_codec::SBE_TEMPLATE_ID,
+            Self::ReplayHeader(_) => replay_header_codec::SBE_TEMPLATE_ID,
+            Self::ReplayEnd(_) => replay_end_codec::SBE_TEMPLATE_ID,
+        }
+    }
+
+    /// Length of the encoded message including its header.
+    pub fn encoded_length(&self) -> usize {
+        match self {
+            Self::NewOrderSingle(_) => NewOrderSingle::ENCODED_LENGTH,
+            Self::OrderCancelRequest(_) => OrderCancelRequest::ENCODED_LENGTH,
+            Self::ExecutionReport(_) => ExecutionReport::ENCODED_LENGTH,
+            Self::OrderCancelReject(_) => OrderCancelReject::ENCODED_LENGTH,
+            Self::OrderStatusRequest(_) => OrderStatusRequest::ENCODED_LENGTH,
+            Self::OrderMassStatusRequest(_) => OrderMassStatusRequest::ENCODED_LENGTH,
+            Self::TradeBustRequest(_) => TradeBustRequest::ENCODED_LENGTH,
+            Self::TradeCorrectRequest(_) => TradeCorrectRequest::ENCODED_LENGTH,
+            Self::DropCopyHeader(_) => DropCopyHeader::ENCODED_LENGTH,
+            Self::DropCopyResendRequest(_) => DropCopyResendRequest::ENCODED_LENGTH,
+            Self::ReplayRequest(_) => ReplayRequest::ENCODED_LENGTH,
+            Self::ReplayHeader(_) => ReplayHeader::ENCODED_LENGTH,
+            Self::ReplayEnd(_) => ReplayEnd::ENCODED_LENGTH,
+        }
+    }
+
+    /// Encodes the message with its header at the start of `buf` and returns the number of
+    /// bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> SbeResult<usize> {
+        match self {
+            Self::NewOrderSingle(message) => message.encode(buf),
+            Self::OrderCancelRequest(message) => message.encode(buf),
+            Self::ExecutionReport(message) => message.encode(buf),
+            Self::OrderCancelReject(message) => message.encode(buf),
+            Self::OrderStatusRequest(message) => message.encode(buf),
+            Self::OrderMassStatusRequest(message) => message.encode(buf),
+            Self::TradeBustRequest(message) => message.encode(buf),
+            Self::TradeCorrectRequest(message) => message.encode(buf),
+            Self::DropCopyHeader(message) => message.encode(buf),
+            Self::DropCopyResendRequest(message) => message.encode(buf),
+            Self::ReplayRequest(message) => message.encode(buf),
+            Self::ReplayHeader(message) => message.encode(buf),
+            Self::ReplayEnd(message) => message.encode(buf),
+        }
+    }
+}
+
+/// Decodes the message framed at the start of `buf`, validating the header, template id
+/// and block length so malformed input is rejected instead of read out of bounds.
+pub fn decode_message(buf: &[u8]) -> SbeResult<Message> {
+    let buf = ReadBuf::new(buf);
+    let header = message_header_codec::MessageHeaderDecoder::default().wrap_checked(buf, 0)?;
+    Ok(match header.template_id() {
+        new_order_single_codec::SBE_TEMPLATE_ID => Message::NewOrderSingle(
+            new_order_single_codec::NewOrderSingleDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        order_cancel_request_codec::SBE_TEMPLATE_ID => Message::OrderCancelRequest(
+            order_cancel_request_codec::OrderCancelRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        execution_report_codec::SBE_TEMPLATE_ID => Message::ExecutionReport(
+            execution_report_codec::ExecutionReportDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        order_cancel_reject_codec::SBE_TEMPLATE_ID => Message::OrderCancelReject(
+            order_cancel_reject_codec::OrderCancelRejectDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        order_status_request_codec::SBE_TEMPLATE_ID => Message::OrderStatusRequest(
+            order_status_request_codec::OrderStatusRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        order_mass_status_request_codec::SBE_TEMPLATE_ID => Message::OrderMassStatusRequest(
+            order_mass_status_request_codec::OrderMassStatusRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        trade_bust_request_codec::SBE_TEMPLATE_ID => Message::TradeBustRequest(
+            trade_bust_request_codec::TradeBustRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        trade_correct_request_codec::SBE_TEMPLATE_ID => Message::TradeCorrectRequest(
+            trade_correct_request_codec::TradeCorrectRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        drop_copy_header_codec::SBE_TEMPLATE_ID => Message::DropCopyHeader(
+            drop_copy_header_codec::DropCopyHeaderDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        drop_copy_resend_request_codec::SBE_TEMPLATE_ID => Message::DropCopyResendRequest(
+            drop_copy_resend_request_codec::DropCopyResendRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        replay_request_codec::SBE_TEMPLATE_ID => Message::ReplayRequest(
+            replay_request_codec::ReplayRequestDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        replay_header_codec::SBE_TEMPLATE_ID => Message::ReplayHeader(
+            replay_header_codec::ReplayHeaderDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        replay_end_codec::SBE_TEMPLATE_ID => Message::ReplayEnd(
+            replay_end_codec::ReplayEndDecoder::default()
+                .header_checked(header, 0)?
+                .into(),
+        ),
+        template_id => return Err(SbeErr::UnknownTemplateId(template_id)),
+    })
+}
+
+/// Optional enums decode their null value as `NullVal`, which is also their default.
+fn non_null<T: Default + PartialEq>(value: T) -> Option<T> {
+    if value == T::default() {
+        None
+    } else {
+        Some(value)
+    }
+}
@@ -7,7 +7,7 @@ pub use crate::SBE_SCHEMA_ID;
 pub use crate::SBE_SCHEMA_VERSION;
 pub use crate::SBE_SEMANTIC_VERSION;
 
-pub const SBE_BLOCK_LENGTH: u16 = 43;
+pub const SBE_BLOCK_LENGTH: u16 = 51;
 pub const SBE_TEMPLATE_ID: u16 = 4;
 
 pub mod encoder {
@@ -74,7 +74,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -92,7 +92,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -110,7 +110,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -136,7 +136,7 @@ pub mod encoder {
 
         /// primitive array field 'OrigClOrdID'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -154,7 +154,7 @@ pub mod encoder {
 
         /// primitive array field 'OrigClOrdID' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -172,7 +172,7 @@ pub mod encoder {
 
         /// primitive array field 'OrigClOrdID' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -191,7 +191,7 @@ pub mod encoder {
 
         /// primitive field 'OrderID'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: uint64
@@ -227,6 +227,21 @@ pub mod encoder {
             let offset = self.offset + 42;
             self.get_buf_mut().put_u8_at(offset, value as u8)
         }
+
+        /// primitive field 'ExecID'
+        /// - min value: 0
+        /// - max value: 18446744073709551614
+        /// - null value: 0xffffffffffffffff_u64
+        /// - characterEncoding: null
+        /// - semanticType: int
+        /// - encodedOffset: 43
+        /// - encodedLength: 8
+        /// - version: 3
+        #[inline]
+        pub fn exec_id(&mut self, value: u64) {
+            let offset = self.offset + 43;
+            self.get_buf_mut().put_u64_at(offset, value);
+        }
     }
 } // end encoder
 
@@ -306,6 +321,39 @@ pub mod decoder {
             )
         }
 
+        /// Like `header`, but checks the template id, that blockLength covers every field of
+        /// the header's acting version and that the buffer holds the block, so malformed
+        /// input is rejected instead of read out of bounds. The header itself should come
+        /// from `MessageHeaderDecoder::wrap_checked`.
+        pub fn header_checked(
+            self,
+            header: MessageHeaderDecoder<ReadBuf<'a>>,
+            offset: usize,
+        ) -> SbeResult<Self> {
+            let template_id = header.template_id();
+            if template_id != SBE_TEMPLATE_ID {
+                return Err(SbeErr::TemplateIdMismatch {
+                    expected: SBE_TEMPLATE_ID,
+                    actual: template_id,
+                });
+            }
+            let required_block_length = match header.version() {
+                0..=2 => 43,
+                _ => SBE_BLOCK_LENGTH,
+            };
+            let block_length = header.block_length();
+            if block_length < required_block_length {
+                return Err(SbeErr::BlockLengthTooShort {
+                    required: required_block_length,
+                    actual: block_length,
+                });
+            }
+            let required = offset + message_header_codec::ENCODED_LENGTH + block_length as usize;
+            header.get_buf().check_len(required)?;
+
+            Ok(self.header(header, offset))
+        }
+
         #[inline]
         pub fn cl_ord_id(&self) -> [u64; 2] {
             let buf = self.get_buf();
@@ -349,5 +397,19 @@ pub mod decoder {
         pub fn cxl_rej_reason(&self) -> cxl_rej_reason_enum::CxlRejReasonEnum {
             self.get_buf().get_u8_at(self.offset + 42).into()
         }
+
+        /// primitive field - 'OPTIONAL' { null_value: '0xffffffffffffffff_u64' }
+        #[inline]
+        pub fn exec_id(&self) -> Option<u64> {
+            if self.acting_version() < 3 {
+                return None;
+            }
+            let value = self.get_buf().get_u64_at(self.offset + 43);
+            if value == 0xffffffffffffffff_u64 {
+                None
+            } else {
+                Some(value)
+            }
+        }
     }
 } // end decoder
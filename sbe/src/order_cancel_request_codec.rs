@@ -74,7 +74,7 @@ pub mod encoder {
 
         /// primitive array field 'OrigClOrdID'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -92,7 +92,7 @@ pub mod encoder {
 
         /// primitive array field 'OrigClOrdID' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -110,7 +110,7 @@ pub mod encoder {
 
         /// primitive array field 'OrigClOrdID' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -136,7 +136,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -154,7 +154,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -172,7 +172,7 @@ pub mod encoder {
 
         /// primitive array field 'ClOrdId' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -198,7 +198,7 @@ pub mod encoder {
 
         /// primitive array field 'Account'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -216,7 +216,7 @@ pub mod encoder {
 
         /// primitive array field 'Account' from an Iterator
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -234,7 +234,7 @@ pub mod encoder {
 
         /// primitive array field 'Account' with zero padding
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: String
@@ -402,6 +402,36 @@ pub mod decoder {
             )
         }
 
+        /// Like `header`, but checks the template id, that blockLength covers every field of
+        /// the header's acting version and that the buffer holds the block, so malformed
+        /// input is rejected instead of read out of bounds. The header itself should come
+        /// from `MessageHeaderDecoder::wrap_checked`.
+        pub fn header_checked(
+            self,
+            header: MessageHeaderDecoder<ReadBuf<'a>>,
+            offset: usize,
+        ) -> SbeResult<Self> {
+            let template_id = header.template_id();
+            if template_id != SBE_TEMPLATE_ID {
+                return Err(SbeErr::TemplateIdMismatch {
+                    expected: SBE_TEMPLATE_ID,
+                    actual: template_id,
+                });
+            }
+            let required_block_length = SBE_BLOCK_LENGTH;
+            let block_length = header.block_length();
+            if block_length < required_block_length {
+                return Err(SbeErr::BlockLengthTooShort {
+                    required: required_block_length,
+                    actual: block_length,
+                });
+            }
+            let required = offset + message_header_codec::ENCODED_LENGTH + block_length as usize;
+            header.get_buf().check_len(required)?;
+
+            Ok(self.header(header, offset))
+        }
+
         #[inline]
         pub fn orig_cl_ord_id(&self) -> [u64; 2] {
             let buf = self.get_buf();
@@ -0,0 +1,286 @@
+use crate::*;
+
+pub use decoder::OrderMassStatusRequestDecoder;
+pub use encoder::OrderMassStatusRequestEncoder;
+
+pub use crate::SBE_SCHEMA_ID;
+pub use crate::SBE_SCHEMA_VERSION;
+pub use crate::SBE_SEMANTIC_VERSION;
+
+pub const SBE_BLOCK_LENGTH: u16 = 32;
+pub const SBE_TEMPLATE_ID: u16 = 8;
+
+pub mod encoder {
+    use super::*;
+    use message_header_codec::*;
+
+    #[derive(Debug, Default)]
+    pub struct OrderMassStatusRequestEncoder<'a> {
+        buf: WriteBuf<'a>,
+        initial_offset: usize,
+        offset: usize,
+        limit: usize,
+    }
+
+    impl<'a> Writer<'a> for OrderMassStatusRequestEncoder<'a> {
+        #[inline]
+        fn get_buf_mut(&mut self) -> &mut WriteBuf<'a> {
+            &mut self.buf
+        }
+    }
+
+    impl<'a> Encoder<'a> for OrderMassStatusRequestEncoder<'a> {
+        #[inline]
+        fn get_limit(&self) -> usize {
+            self.limit
+        }
+
+        #[inline]
+        fn set_limit(&mut self, limit: usize) {
+            self.limit = limit;
+        }
+    }
+
+    impl<'a> OrderMassStatusRequestEncoder<'a> {
+        pub fn wrap(mut self, buf: WriteBuf<'a>, offset: usize) -> Self {
+            let limit = offset + SBE_BLOCK_LENGTH as usize;
+            self.buf = buf;
+            self.initial_offset = offset;
+            self.offset = offset;
+            self.limit = limit;
+            self
+        }
+
+        #[inline]
+        pub fn encoded_length(&self) -> usize {
+            self.limit - self.offset
+        }
+
+        pub fn header(self, offset: usize) -> MessageHeaderEncoder<Self> {
+            let mut header = MessageHeaderEncoder::default().wrap(self, offset);
+            header.block_length(SBE_BLOCK_LENGTH);
+            header.template_id(SBE_TEMPLATE_ID);
+            header.schema_id(SBE_SCHEMA_ID);
+            header.version(SBE_SCHEMA_VERSION);
+            header
+        }
+
+        /// primitive field 'MassStatusReqID'
+        /// - min value: 0
+        /// - max value: 18446744073709551614
+        /// - null value: 0xffffffffffffffff_u64
+        /// - characterEncoding: null
+        /// - semanticType: int
+        /// - encodedOffset: 0
+        /// - encodedLength: 8
+        /// - version: 0
+        #[inline]
+        pub fn mass_status_req_id(&mut self, value: u64) {
+            let offset = self.offset;
+            self.get_buf_mut().put_u64_at(offset, value);
+        }
+
+        #[inline]
+        pub fn account_at(&mut self, index: usize, value: u64) {
+            let offset = self.offset + 8;
+            let buf = self.get_buf_mut();
+            buf.put_u64_at(offset + index * 8, value);
+        }
+
+        /// primitive array field 'Account'
+        /// - min value: 0
+        /// - max value: 18446744073709551614
+        /// - null value: 0xffffffffffffffff_u64
+        /// - characterEncoding: null
+        /// - semanticType: String
+        /// - encodedOffset: 8
+        /// - encodedLength: 16
+        /// - version: 0
+        #[inline]
+        pub fn account(&mut self, value: &[u64]) {
+            debug_assert_eq!(2, value.len());
+            let offset = self.offset + 8;
+            let buf = self.get_buf_mut();
+            buf.put_u64_at(offset, value[0]);
+            buf.put_u64_at(offset + 8, value[1]);
+        }
+
+        /// primitive array field 'Account' from an Iterator
+        /// - min value: 0
+        /// - max value: 18446744073709551614
+        /// - null value: 0xffffffffffffffff_u64
+        /// - characterEncoding: null
+        /// - semanticType: String
+        /// - encodedOffset: 8
+        /// - encodedLength: 16
+        /// - version: 0
+        #[inline]
+        pub fn account_from_iter(&mut self, iter: impl Iterator<Item = u64>) {
+            let offset = self.offset + 8;
+            let buf = self.get_buf_mut();
+            for (i, v) in iter.enumerate() {
+                buf.put_u64_at(offset + i * 8, v);
+            }
+        }
+
+        /// primitive array field 'Account' with zero padding
+        /// - min value: 0
+        /// - max value: 18446744073709551614
+        /// - null value: 0xffffffffffffffff_u64
+        /// - characterEncoding: null
+        /// - semanticType: String
+        /// - encodedOffset: 8
+        /// - encodedLength: 16
+        /// - version: 0
+        #[inline]
+        pub fn account_zero_padded(&mut self, value: &[u64]) {
+            let iter = value
+                .iter()
+                .copied()
+                .chain(std::iter::repeat(0_u64))
+                .take(2);
+            self.account_from_iter(iter);
+        }
+
+        /// COMPOSITE ENCODER
+        #[inline]
+        pub fn transact_time_encoder(
+            self,
+        ) -> utc_timestamp_nanos_codec::UTCTimestampNanosEncoder<Self> {
+            let offset = self.offset + 24;
+            utc_timestamp_nanos_codec::UTCTimestampNanosEncoder::default().wrap(self, offset)
+        }
+    }
+} // end encoder
+
+pub mod decoder {
+    use super::*;
+    use message_header_codec::*;
+
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct OrderMassStatusRequestDecoder<'a> {
+        buf: ReadBuf<'a>,
+        initial_offset: usize,
+        offset: usize,
+        limit: usize,
+        pub acting_block_length: u16,
+        pub acting_version: u16,
+    }
+
+    impl ActingVersion for OrderMassStatusRequestDecoder<'_> {
+        #[inline]
+        fn acting_version(&self) -> u16 {
+            self.acting_version
+        }
+    }
+
+    impl<'a> Reader<'a> for OrderMassStatusRequestDecoder<'a> {
+        #[inline]
+        fn get_buf(&self) -> &ReadBuf<'a> {
+            &self.buf
+        }
+    }
+
+    impl<'a> Decoder<'a> for OrderMassStatusRequestDecoder<'a> {
+        #[inline]
+        fn get_limit(&self) -> usize {
+            self.limit
+        }
+
+        #[inline]
+        fn set_limit(&mut self, limit: usize) {
+            self.limit = limit;
+        }
+    }
+
+    impl<'a> OrderMassStatusRequestDecoder<'a> {
+        pub fn wrap(
+            mut self,
+            buf: ReadBuf<'a>,
+            offset: usize,
+            acting_block_length: u16,
+            acting_version: u16,
+        ) -> Self {
+            let limit = offset + acting_block_length as usize;
+            self.buf = buf;
+            self.initial_offset = offset;
+            self.offset = offset;
+            self.limit = limit;
+            self.acting_block_length = acting_block_length;
+            self.acting_version = acting_version;
+            self
+        }
+
+        #[inline]
+        pub fn encoded_length(&self) -> usize {
+            self.limit - self.offset
+        }
+
+        pub fn header(self, mut header: MessageHeaderDecoder<ReadBuf<'a>>, offset: usize) -> Self {
+            debug_assert_eq!(SBE_TEMPLATE_ID, header.template_id());
+            let acting_block_length = header.block_length();
+            let acting_version = header.version();
+
+            self.wrap(
+                header.parent().unwrap(),
+                offset + message_header_codec::ENCODED_LENGTH,
+                acting_block_length,
+                acting_version,
+            )
+        }
+
+        /// Like `header`, but checks the template id, that blockLength covers every field of
+        /// the header's acting version and that the buffer holds the block, so malformed
+        /// input is rejected instead of read out of bounds. The header itself should come
+        /// from `MessageHeaderDecoder::wrap_checked`.
+        pub fn header_checked(
+            self,
+            header: MessageHeaderDecoder<ReadBuf<'a>>,
+            offset: usize,
+        ) -> SbeResult<Self> {
+            let template_id = header.template_id();
+            if template_id != SBE_TEMPLATE_ID {
+                return Err(SbeErr::TemplateIdMismatch {
+                    expected: SBE_TEMPLATE_ID,
+                    actual: template_id,
+                });
+            }
+            let required_block_length = SBE_BLOCK_LENGTH;
+            let block_length = header.block_length();
+            if block_length < required_block_length {
+                return Err(SbeErr::BlockLengthTooShort {
+                    required: required_block_length,
+                    actual: block_length,
+                });
+            }
+            let required = offset + message_header_codec::ENCODED_LENGTH + block_length as usize;
+            header.get_buf().check_len(required)?;
+
+            Ok(self.header(header, offset))
+        }
+
+        /// primitive field - 'REQUIRED'
+        #[inline]
+        pub fn mass_status_req_id(&self) -> u64 {
+            self.get_buf().get_u64_at(self.offset)
+        }
+
+        #[inline]
+        pub fn account(&self) -> [u64; 2] {
+            let buf = self.get_buf();
+            [
+                buf.get_u64_at(self.offset + 8),
+                buf.get_u64_at(self.offset + 8 + 8),
+            ]
+        }
+
+        /// COMPOSITE DECODER
+        #[inline]
+        pub fn transact_time_decoder(
+            self,
+        ) -> utc_timestamp_nanos_codec::UTCTimestampNanosDecoder<Self> {
+            let offset = self.offset + 24;
+            utc_timestamp_nanos_codec::UTCTimestampNanosDecoder::default().wrap(self, offset)
+        }
+    }
+} // end decoder
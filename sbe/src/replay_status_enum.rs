@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[repr(u8)]
+pub enum ReplayStatusEnum {
+    Completed = 48_u8,
+    UnknownPosition = 49_u8,
+    Failed = 50_u8,
+    #[default]
+    NullVal = 0_u8,
+}
+impl From<u8> for ReplayStatusEnum {
+    #[inline]
+    fn from(v: u8) -> Self {
+        match v {
+            48_u8 => Self::Completed,
+            49_u8 => Self::UnknownPosition,
+            50_u8 => Self::Failed,
+            _ => Self::NullVal,
+        }
+    }
+}
+impl From<ReplayStatusEnum> for u8 {
+    #[inline]
+    fn from(v: ReplayStatusEnum) -> Self {
+        match v {
+            ReplayStatusEnum::Completed => 48_u8,
+            ReplayStatusEnum::UnknownPosition => 49_u8,
+            ReplayStatusEnum::Failed => 50_u8,
+            ReplayStatusEnum::NullVal => 0_u8,
+        }
+    }
+}
+impl core::str::FromStr for ReplayStatusEnum {
+    type Err = ();
+
+    #[inline]
+    fn from_str(v: &str) -> core::result::Result<Self, Self::Err> {
+        match v {
+            "Completed" => Ok(Self::Completed),
+            "UnknownPosition" => Ok(Self::UnknownPosition),
+            "Failed" => Ok(Self::Failed),
+            _ => Ok(Self::NullVal),
+        }
+    }
+}
+impl core::fmt::Display for ReplayStatusEnum {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Completed => write!(f, "Completed"),
+            Self::UnknownPosition => write!(f, "UnknownPosition"),
+            Self::Failed => write!(f, "Failed"),
+            Self::NullVal => write!(f, "NullVal"),
+        }
+    }
+}
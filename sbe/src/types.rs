@@ -0,0 +1,147 @@
+//! Identifier type and serde helpers used by the owned messages in `messages`.
+
+use core::fmt;
+use core::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+/// 128-bit identifier encoded as the `uuid` type: two `uint64` words, most significant first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u64; 2]);
+
+impl Uuid {
+    pub const NIL: Self = Self([0; 2]);
+
+    #[inline]
+    pub const fn from_words(words: [u64; 2]) -> Self {
+        Self(words)
+    }
+
+    #[inline]
+    pub const fn words(self) -> [u64; 2] {
+        self.0
+    }
+
+    #[inline]
+    pub const fn from_u64_pair(high: u64, low: u64) -> Self {
+        Self([high, low])
+    }
+
+    #[inline]
+    pub const fn from_u128(value: u128) -> Self {
+        Self([(value >> 64) as u64, value as u64])
+    }
+
+    #[inline]
+    pub const fn as_u128(self) -> u128 {
+        ((self.0[0] as u128) << 64) | self.0[1] as u128
+    }
+}
+
+impl From<[u64; 2]> for Uuid {
+    #[inline]
+    fn from(words: [u64; 2]) -> Self {
+        Self(words)
+    }
+}
+
+impl From<Uuid> for [u64; 2] {
+    #[inline]
+    fn from(uuid: Uuid) -> Self {
+        uuid.0
+    }
+}
+
+impl fmt::Display for Uuid {
+    /// Lowercase hyphenated form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [high, low] = self.0;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff
+        )
+    }
+}
+
+/// The string is not a UUID in hyphenated or simple (32 hex digit) form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseUuidError;
+
+impl fmt::Display for ParseUuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid UUID, expected 32 hex digits optionally grouped 8-4-4-4-12")
+    }
+}
+
+impl std::error::Error for ParseUuidError {}
+
+impl FromStr for Uuid {
+    type Err = ParseUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hyphenated = s.len() == 36
+            && s.char_indices()
+                .all(|(i, c)| (c == '-') == matches!(i, 8 | 13 | 18 | 23));
+        let digits: String = if hyphenated {
+            s.chars().filter(|&c| c != '-').collect()
+        } else {
+            s.to_owned()
+        };
+        if digits.len() != 32 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseUuidError);
+        }
+        u128::from_str_radix(&digits, 16)
+            .map(Self::from_u128)
+            .map_err(|_| ParseUuidError)
+    }
+}
+
+/// Serialized in the lowercase hyphenated form.
+impl Serialize for Uuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uuid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Serde representation of fixed-length `char` arrays as strings, with the NUL padding trimmed
+/// on serialization and restored on deserialization.
+pub mod char_array {
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        serializer.serialize_str(&String::from_utf8_lossy(&bytes[..end]))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if s.len() > N {
+            return Err(de::Error::custom(format_args!(
+                "expected at most {N} bytes, got {}",
+                s.len()
+            )));
+        }
+        let mut bytes = [0; N];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        Ok(bytes)
+    }
+}
@@ -45,7 +45,7 @@ pub mod encoder {
 
         /// primitive field 'time'
         /// - min value: 0
-        /// - max value: -2
+        /// - max value: 18446744073709551614
         /// - null value: 0xffffffffffffffff_u64
         /// - characterEncoding: null
         /// - semanticType: null
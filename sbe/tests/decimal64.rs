@@ -0,0 +1,130 @@
+use sbe::{Decimal64, ParseDecimalError, RoundingMode};
+
+fn dec(s: &str) -> Decimal64 {
+    s.parse().expect("valid decimal")
+}
+
+#[test]
+fn parses_and_formats_exactly() {
+    assert_eq!(dec("1.5"), Decimal64::from_mantissa(150_000_000));
+    assert_eq!(dec("-0.00000001"), Decimal64::from_mantissa(-1));
+    assert_eq!(
+        dec("+42.100000000000"),
+        Decimal64::from_mantissa(4_210_000_000)
+    );
+    assert_eq!(dec("92233720368.54775807"), Decimal64::MAX);
+
+    for s in ["0", "1.5", "-2", "-0.00000001", "92233720368.54775807"] {
+        assert_eq!(dec(s).to_string(), s);
+    }
+}
+
+#[test]
+fn rejects_inexact_or_malformed_strings() {
+    assert_eq!("".parse::<Decimal64>(), Err(ParseDecimalError::Empty));
+    for s in ["-", ".5", "1.", "1e5", "1,5", " 1", "0x10"] {
+        assert_eq!(
+            s.parse::<Decimal64>(),
+            Err(ParseDecimalError::Invalid),
+            "{s}"
+        );
+    }
+    assert_eq!(
+        "0.000000001".parse::<Decimal64>(),
+        Err(ParseDecimalError::TooPrecise)
+    );
+    assert_eq!(
+        "92233720368.54775808".parse::<Decimal64>(),
+        Err(ParseDecimalError::Overflow)
+    );
+}
+
+#[test]
+fn arithmetic_is_checked_and_never_yields_the_null_value() {
+    assert_eq!(dec("0.1").checked_add(dec("0.2")), Some(dec("0.3")));
+    assert_eq!(
+        Decimal64::MAX.checked_add(Decimal64::from_mantissa(1)),
+        None
+    );
+    assert_eq!(
+        Decimal64::MIN.checked_sub(Decimal64::from_mantissa(1)),
+        None
+    );
+    assert_eq!(
+        dec("1").checked_div(Decimal64::ZERO, RoundingMode::Down),
+        None
+    );
+    assert_eq!(
+        Decimal64::MAX.checked_mul(dec("2"), RoundingMode::Down),
+        None
+    );
+}
+
+#[test]
+fn multiplication_and_division_round_with_the_given_mode() {
+    let third = |mode| dec("1").checked_div(dec("3"), mode).unwrap();
+    assert_eq!(third(RoundingMode::Down), dec("0.33333333"));
+    assert_eq!(third(RoundingMode::Up), dec("0.33333334"));
+
+    let tiny = dec("0.00000001");
+    let half = dec("0.5");
+    assert_eq!(tiny.checked_mul(half, RoundingMode::HalfUp), Some(tiny));
+    assert_eq!(
+        tiny.checked_mul(half, RoundingMode::HalfEven),
+        Some(Decimal64::ZERO)
+    );
+    assert_eq!((-tiny).checked_mul(half, RoundingMode::Floor), Some(-tiny));
+    assert_eq!(
+        (-tiny).checked_mul(half, RoundingMode::Ceiling),
+        Some(Decimal64::ZERO)
+    );
+
+    assert_eq!(
+        dec("2.5").checked_round(0, RoundingMode::HalfEven),
+        Some(dec("2"))
+    );
+    assert_eq!(
+        dec("-2.5").checked_round(0, RoundingMode::HalfUp),
+        Some(dec("-3"))
+    );
+    assert_eq!(
+        dec("1.23456789").checked_round(2, RoundingMode::Down),
+        Some(dec("1.23"))
+    );
+}
+
+#[test]
+fn null_mantissa_maps_to_none() {
+    assert_eq!(Decimal64::from_nullable(i64::MIN), None);
+    assert_eq!(Decimal64::to_nullable(None), i64::MIN);
+    assert_eq!(
+        Decimal64::from_nullable(5),
+        Some(Decimal64::from_mantissa(5))
+    );
+}
+
+#[test]
+fn converts_floats_through_their_shortest_representation() {
+    assert_eq!(Decimal64::try_from(0.1), Ok(dec("0.1")));
+    assert_eq!(
+        Decimal64::try_from(1e-9),
+        Err(ParseDecimalError::TooPrecise)
+    );
+    assert_eq!(
+        Decimal64::try_from(f64::NAN),
+        Err(ParseDecimalError::Invalid)
+    );
+    assert_eq!(Decimal64::try_from(1e20), Err(ParseDecimalError::Overflow));
+}
+
+#[test]
+fn serializes_as_a_string_and_deserializes_strings_or_numbers() {
+    assert_eq!(serde_json::to_string(&dec("1.5")).unwrap(), r#""1.5""#);
+
+    let parse = |json: &str| serde_json::from_str::<Decimal64>(json);
+    assert_eq!(parse(r#""0.1""#).unwrap(), dec("0.1"));
+    assert_eq!(parse("0.1").unwrap(), dec("0.1"));
+    assert_eq!(parse("3").unwrap(), dec("3"));
+    assert!(parse("0.000000001").is_err());
+    assert!(parse("100000000000").is_err());
+}
@@ -0,0 +1,107 @@
+use sbe::exec_type_enum::ExecTypeEnum;
+use sbe::ord_rej_reason_enum::OrdRejReasonEnum;
+use sbe::ord_status_enum::OrdStatusEnum;
+use sbe::side_enum::SideEnum;
+use sbe::{decode_message, Decimal64, ExecutionReport, Message, SbeErr, Uuid};
+
+fn report() -> ExecutionReport {
+    ExecutionReport {
+        account: Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8),
+        cl_ord_id: Uuid::from_u64_pair(1, 2),
+        trd_match_id: Some(7),
+        order_id: 42,
+        exec_id: 99,
+        transact_time: 1_700_000_000_000_000_000,
+        price: Some(Decimal64::from_mantissa(150_000_000)),
+        order_qty: Decimal64::from_mantissa(200_000_000),
+        last_qty: Some(Decimal64::from_mantissa(50_000_000)),
+        last_px: Some(Decimal64::from_mantissa(150_000_000)),
+        leaves_qty: Decimal64::from_mantissa(150_000_000),
+        cum_qty: Decimal64::from_mantissa(50_000_000),
+        avg_px: None,
+        symbol: *b"BTCUSD",
+        exec_type: ExecTypeEnum::Trade,
+        ord_status: OrdStatusEnum::PartiallyFilled,
+        ord_rej_reason: None,
+        side: SideEnum::Buy,
+        mass_status_req_id: None,
+        tot_num_reports: None,
+    }
+}
+
+#[test]
+fn encoded_message_decodes_to_the_same_value() {
+    let message = Message::ExecutionReport(report());
+    let mut buf = [0u8; 256];
+
+    let length = message.encode(&mut buf).expect("buffer fits the report");
+
+    assert_eq!(length, ExecutionReport::ENCODED_LENGTH);
+    assert_eq!(decode_message(&buf[..length]), Ok(message));
+}
+
+#[test]
+fn optional_enums_round_trip_through_their_null_value() {
+    let rejected = ExecutionReport {
+        ord_rej_reason: Some(OrdRejReasonEnum::DuplicateOrder),
+        ..report()
+    };
+    let mut buf = [0u8; ExecutionReport::ENCODED_LENGTH];
+
+    rejected.encode(&mut buf).expect("buffer fits the report");
+    assert_eq!(decode_message(&buf), Ok(Message::ExecutionReport(rejected)));
+
+    report().encode(&mut buf).expect("buffer fits the report");
+    let Ok(Message::ExecutionReport(decoded)) = decode_message(&buf) else {
+        panic!("expected an execution report");
+    };
+    assert_eq!(decoded.ord_rej_reason, None);
+}
+
+#[test]
+fn short_or_unknown_frames_are_rejected() {
+    let mut buf = [0u8; ExecutionReport::ENCODED_LENGTH];
+    assert_eq!(
+        report().encode(&mut buf[..20]),
+        Err(SbeErr::BufferTooShort {
+            required: ExecutionReport::ENCODED_LENGTH,
+            actual: 20,
+        })
+    );
+
+    report().encode(&mut buf).expect("buffer fits the report");
+    assert!(matches!(
+        decode_message(&buf[..40]),
+        Err(SbeErr::BufferTooShort { .. })
+    ));
+
+    buf[2..4].copy_from_slice(&999_u16.to_le_bytes());
+    assert_eq!(decode_message(&buf), Err(SbeErr::UnknownTemplateId(999)));
+}
+
+#[test]
+fn values_format_like_their_json_representation() {
+    assert_eq!(Decimal64::from_mantissa(150_000_000).to_string(), "1.5");
+    assert_eq!(Decimal64::from_mantissa(-1).to_string(), "-0.00000001");
+    assert_eq!(Decimal64::from_mantissa(-200_000_000).to_string(), "-2");
+    assert_eq!(
+        Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8).to_string(),
+        "67e55044-10b1-426f-9247-bb680e5fe0c8"
+    );
+}
+
+#[test]
+fn json_names_the_message_and_round_trips() {
+    let message = Message::ExecutionReport(report());
+
+    let json = serde_json::to_value(message).expect("messages serialize");
+    assert_eq!(json["message"], "ExecutionReport");
+    assert_eq!(json["account"], "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    assert_eq!(json["price"], "1.5");
+    assert_eq!(json["avg_px"], serde_json::Value::Null);
+    assert_eq!(json["symbol"], "BTCUSD");
+    assert_eq!(json["side"], "Buy");
+
+    let decoded: Message = serde_json::from_value(json).expect("messages deserialize");
+    assert_eq!(decoded, message);
+}
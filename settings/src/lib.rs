@@ -0,0 +1,331 @@
+//! Layered configuration shared by the services. A service describes its settings as a serde
+//! struct whose `Default` holds the built-in values; each layer below overrides the one before:
+//!
+//! 1. the defaults,
+//! 2. the TOML file given by `--config <path>` or `CONFIG_FILE`,
+//! 3. environment variables, named after the setting's path: `pub.stream_id` is
+//!    `PUB_STREAM_ID`,
+//! 4. command line flags, named after the path: `--pub.stream_id 1001`.
+//!
+//! `--print-config` prints the resolved configuration as TOML and `--help` lists every setting.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::net::ToSocketAddrs;
+use std::process;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use toml::{Table, Value};
+
+/// Names the config file when `--config` is not given.
+pub const CONFIG_FILE_VAR: &str = "CONFIG_FILE";
+
+/// A service's configuration. Every struct in it should use `#[serde(default)]`, so files and
+/// overrides may leave settings out, and `#[serde(deny_unknown_fields)]`, so misspelled settings
+/// are rejected.
+pub trait Settings: Serialize + DeserializeOwned + Default {
+    /// Checks what the types alone cannot, e.g. that required settings are present.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    /// The command line could not be parsed.
+    Usage(String),
+    Read {
+        path: String,
+        reason: String,
+    },
+    File {
+        path: String,
+        reason: String,
+    },
+    /// A flag names a setting the service does not have.
+    UnknownSetting(String),
+    /// An environment variable or flag holds a value of the wrong type.
+    InvalidValue {
+        source: String,
+        value: String,
+        expected: &'static str,
+    },
+    /// The resolved configuration failed validation.
+    Invalid(String),
+}
+
+impl SettingsError {
+    /// Usage errors exit with 2, like the other tools; configuration errors with 1.
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) | Self::UnknownSetting(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(message) => f.write_str(message),
+            Self::Read { path, reason } => write!(f, "cannot read {path}: {reason}"),
+            Self::File { path, reason } => write!(f, "invalid config file {path}: {reason}"),
+            Self::UnknownSetting(flag) => write!(f, "unknown setting {flag}"),
+            Self::InvalidValue {
+                source,
+                value,
+                expected,
+            } => write!(f, "{source} must be {expected}, not {value:?}"),
+            Self::Invalid(message) => write!(f, "invalid configuration: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// What the command line asked the service to do.
+#[derive(Debug)]
+pub enum Invocation<T> {
+    Run(T),
+    PrintConfig(T),
+    Help,
+}
+
+/// Resolves and validates the configuration from the process's arguments and environment.
+/// Prints the configuration or usage and exits when asked to, and exits on errors. A printed
+/// configuration that fails validation is followed by the error.
+pub fn load<T: Settings>(service: &str) -> T {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = match resolve::<T>(&args, &|name| env::var(name).ok()) {
+        Ok(Invocation::Run(config)) => config,
+        Ok(Invocation::PrintConfig(config)) => {
+            print!("{}", to_toml(&config));
+            if let Err(reason) = config.validate() {
+                exit(service, &SettingsError::Invalid(reason));
+            }
+            process::exit(0);
+        }
+        Ok(Invocation::Help) => {
+            print!("{}", usage::<T>(service));
+            process::exit(0);
+        }
+        Err(err) => exit(service, &err),
+    };
+    if let Err(reason) = config.validate() {
+        exit(service, &SettingsError::Invalid(reason));
+    }
+    config
+}
+
+fn exit(service: &str, err: &SettingsError) -> ! {
+    eprintln!("{service}: {err}");
+    if err.exit_code() == 2 {
+        eprintln!("Run {service} --help to list the settings.");
+    }
+    process::exit(err.exit_code());
+}
+
+/// Resolves the configuration from `args`, without the program name, and the variables
+/// returned by `env`. The result is not validated.
+pub fn resolve<T: Settings>(
+    args: &[String],
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<Invocation<T>, SettingsError> {
+    let mut print_config = false;
+    let mut config_path = None;
+    let mut flags = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(SettingsError::Usage(format!("unexpected argument {arg:?}")));
+        };
+        let (name, inline_value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_owned())),
+            None => (flag, None),
+        };
+        match name {
+            "help" => return Ok(Invocation::Help),
+            "print-config" => print_config = true,
+            _ => {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| SettingsError::Usage(format!("--{name} needs a value")))?;
+                if name == "config" {
+                    config_path = Some(value);
+                } else {
+                    flags.push((name.to_owned(), value));
+                }
+            }
+        }
+    }
+
+    let mut tree = defaults::<T>();
+
+    if let Some(path) = config_path.or_else(|| env(CONFIG_FILE_VAR)) {
+        let text = fs::read_to_string(&path).map_err(|err| SettingsError::Read {
+            path: path.clone(),
+            reason: err.to_string(),
+        })?;
+        // Parsed on its own first so errors point at the line in the file.
+        toml::from_str::<T>(&text).map_err(|err| SettingsError::File {
+            path: path.clone(),
+            reason: err.to_string(),
+        })?;
+        let file: Table = toml::from_str(&text).map_err(|err| SettingsError::File {
+            path,
+            reason: err.to_string(),
+        })?;
+        merge(&mut tree, file);
+    }
+
+    for path in leaves(&tree) {
+        let name = env_name(&path);
+        if let Some(value) = env(&name) {
+            set(&mut tree, &path, &value, &name)?;
+        }
+    }
+
+    for (name, value) in flags {
+        let path: Vec<String> = name.split('.').map(str::to_owned).collect();
+        if !leaves(&tree).contains(&path) {
+            return Err(SettingsError::UnknownSetting(format!("--{name}")));
+        }
+        set(&mut tree, &path, &value, &format!("--{name}"))?;
+    }
+
+    let config = Value::Table(tree)
+        .try_into()
+        .map_err(|err: toml::de::Error| SettingsError::Invalid(err.message().to_owned()))?;
+    Ok(if print_config {
+        Invocation::PrintConfig(config)
+    } else {
+        Invocation::Run(config)
+    })
+}
+
+/// Lists every setting with its flag, environment variable and default.
+pub fn usage<T: Settings>(service: &str) -> String {
+    let tree = defaults::<T>();
+    let rows: Vec<(String, String, String)> = leaves(&tree)
+        .into_iter()
+        .map(|path| {
+            let default = get(&tree, &path).map_or_else(String::new, ToString::to_string);
+            (format!("--{}", path.join(".")), env_name(&path), default)
+        })
+        .collect();
+    let flag_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let env_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+
+    let mut text = format!(
+        "usage: {service} [--config <path>] [--print-config] [--<setting> <value>]...\n\n\
+         Settings are taken from the config file (--config or {CONFIG_FILE_VAR}), then the\n\
+         environment, then flags.\n\n"
+    );
+    for (flag, env_name, default) in rows {
+        text.push_str(&format!(
+            "  {flag:<flag_width$}  {env_name:<env_width$}  {default}\n"
+        ));
+    }
+    text
+}
+
+pub fn to_toml<T: Serialize>(config: &T) -> String {
+    toml::to_string_pretty(config).expect("configurations serialize to TOML")
+}
+
+/// Fails unless `value` is set; for settings without a usable default.
+pub fn required(setting: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("{setting} is required"));
+    }
+    Ok(())
+}
+
+/// Fails unless `value` is a `host:port` address that resolves.
+pub fn socket_addr(setting: &str, value: &str) -> Result<(), String> {
+    required(setting, value)?;
+    match value.to_socket_addrs() {
+        Ok(addrs) if addrs.len() > 0 => Ok(()),
+        _ => Err(format!("{setting} {value:?} is not a host:port address")),
+    }
+}
+
+fn defaults<T: Settings>() -> Table {
+    Table::try_from(T::default()).expect("default configurations serialize to TOML tables")
+}
+
+fn env_name(path: &[String]) -> String {
+    path.join("_").to_uppercase()
+}
+
+fn merge(tree: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (tree.get_mut(&key), value) {
+            (Some(Value::Table(table)), Value::Table(overrides)) => merge(table, overrides),
+            (_, value) => {
+                tree.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Paths of the settings in `tree`, in order.
+fn leaves(tree: &Table) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    for (key, value) in tree {
+        match value {
+            Value::Table(table) => {
+                for mut path in leaves(table) {
+                    path.insert(0, key.clone());
+                    paths.push(path);
+                }
+            }
+            _ => paths.push(vec![key.clone()]),
+        }
+    }
+    paths
+}
+
+fn get<'a>(tree: &'a Table, path: &[String]) -> Option<&'a Value> {
+    let (last, parents) = path.split_last()?;
+    let mut table = tree;
+    for key in parents {
+        table = table.get(key)?.as_table()?;
+    }
+    table.get(last)
+}
+
+/// Replaces the setting at `path` with `raw` parsed as the type of its current value.
+fn set(tree: &mut Table, path: &[String], raw: &str, source: &str) -> Result<(), SettingsError> {
+    let (last, parents) = path.split_last().expect("setting paths are not empty");
+    let mut table = tree;
+    for key in parents {
+        table = table
+            .get_mut(key)
+            .and_then(Value::as_table_mut)
+            .expect("paths are taken from the tree");
+    }
+    let current = table.get(last).expect("paths are taken from the tree");
+
+    let invalid = |expected| SettingsError::InvalidValue {
+        source: source.to_owned(),
+        value: raw.to_owned(),
+        expected,
+    };
+    let value = match current {
+        Value::String(_) => Value::String(raw.to_owned()),
+        Value::Integer(_) => Value::Integer(raw.trim().parse().map_err(|_| invalid("an integer"))?),
+        Value::Float(_) => Value::Float(raw.trim().parse().map_err(|_| invalid("a number"))?),
+        Value::Boolean(_) => {
+            Value::Boolean(raw.trim().parse().map_err(|_| invalid("true or false"))?)
+        }
+        Value::Array(_) | Value::Table(_) | Value::Datetime(_) => {
+            return Err(invalid("set in the config file"));
+        }
+    };
+    table.insert(last.clone(), value);
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use settings::{CONFIG_FILE_VAR, Invocation, Settings, SettingsError, resolve, usage};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    bind_addr: String,
+    verbose: bool,
+    ratio: f64,
+    sub: Stream,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Stream {
+    channel: String,
+    stream_id: i32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind_addr: "127.0.0.1:8080".to_owned(),
+            verbose: false,
+            ratio: 0.5,
+            sub: Stream::default(),
+        }
+    }
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Self {
+            channel: "aeron:ipc".to_owned(),
+            stream_id: 1002,
+        }
+    }
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        settings::required("sub.channel", &self.sub.channel)
+    }
+}
+
+fn run(args: &[&str], env: &[(&str, &str)]) -> Result<Config, SettingsError> {
+    let args: Vec<String> = args.iter().map(|&arg| arg.to_owned()).collect();
+    let env: HashMap<String, String> = env
+        .iter()
+        .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+        .collect();
+    match resolve::<Config>(&args, &|name| env.get(name).cloned())? {
+        Invocation::Run(config) => Ok(config),
+        invocation => panic!("unexpected {invocation:?}"),
+    }
+}
+
+/// Writes `text` to a file unique to the calling test.
+fn config_file(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("settings-{}-{name}.toml", std::process::id()));
+    fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn uses_the_defaults_without_overrides() {
+    assert_eq!(run(&[], &[]).unwrap(), Config::default());
+}
+
+#[test]
+fn later_layers_override_earlier_ones() {
+    let path = config_file(
+        "layers",
+        "bind_addr = \"0.0.0.0:1\"\n[sub]\nchannel = \"aeron:udp?endpoint=localhost:40123\"\nstream_id = 1\n",
+    );
+    let config = run(
+        &["--sub.stream_id", "3"],
+        &[
+            (CONFIG_FILE_VAR, path.to_str().unwrap()),
+            ("SUB_STREAM_ID", "2"),
+            ("VERBOSE", "true"),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(config.bind_addr, "0.0.0.0:1");
+    assert_eq!(config.sub.channel, "aeron:udp?endpoint=localhost:40123");
+    assert_eq!(config.sub.stream_id, 3);
+    assert!(config.verbose);
+    assert!((config.ratio - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn config_flag_takes_precedence_over_the_variable() {
+    let flag = config_file("flag", "ratio = 2.0\n");
+    let config = run(
+        &[&format!("--config={}", flag.display())],
+        &[(CONFIG_FILE_VAR, "/nonexistent.toml")],
+    )
+    .unwrap();
+    assert!((config.ratio - 2.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn parses_overrides_by_the_type_of_the_default() {
+    let err = run(&[], &[("SUB_STREAM_ID", "ten")]).unwrap_err();
+    assert!(
+        matches!(err, SettingsError::InvalidValue { ref source, .. } if source == "SUB_STREAM_ID"),
+        "{err}"
+    );
+    let err = run(&["--verbose=yes"], &[]).unwrap_err();
+    assert!(matches!(err, SettingsError::InvalidValue { .. }), "{err}");
+    assert_eq!(run(&["--ratio", "1"], &[]).unwrap().ratio, 1.0);
+}
+
+#[test]
+fn rejects_unknown_settings() {
+    let err = run(&["--sub.stream", "1"], &[]).unwrap_err();
+    assert!(matches!(err, SettingsError::UnknownSetting(_)), "{err}");
+    assert_eq!(err.exit_code(), 2);
+
+    let path = config_file("unknown", "[sub]\nstream = 1\n");
+    let err = run(&["--config", path.to_str().unwrap()], &[]).unwrap_err();
+    assert!(matches!(err, SettingsError::File { .. }), "{err}");
+    assert_eq!(err.exit_code(), 1);
+}
+
+#[test]
+fn rejects_malformed_command_lines() {
+    assert!(matches!(
+        run(&["positional"], &[]),
+        Err(SettingsError::Usage(_))
+    ));
+    assert!(matches!(
+        run(&["--bind_addr"], &[]),
+        Err(SettingsError::Usage(_))
+    ));
+}
+
+#[test]
+fn print_config_and_help_are_recognised() {
+    let args = ["--print-config".to_owned(), "--sub.channel=".to_owned()];
+    let Ok(Invocation::PrintConfig(config)) = resolve::<Config>(&args, &|_| None) else {
+        panic!("expected --print-config");
+    };
+    assert!(config.validate().is_err());
+    assert!(settings::to_toml(&config).contains("[sub]"));
+
+    let args = [
+        "--bind_addr".to_owned(),
+        "x".to_owned(),
+        "--help".to_owned(),
+    ];
+    assert!(matches!(
+        resolve::<Config>(&args, &|_| None),
+        Ok(Invocation::Help)
+    ));
+}
+
+#[test]
+fn usage_lists_every_setting() {
+    let text = usage::<Config>("test");
+    for line in [
+        "--bind_addr",
+        "BIND_ADDR",
+        "--sub.stream_id",
+        "SUB_STREAM_ID",
+        "1002",
+    ] {
+        assert!(text.contains(line), "{line} missing from\n{text}");
+    }
+}
+
+#[test]
+fn validates_socket_addresses() {
+    assert!(settings::socket_addr("bind_addr", "127.0.0.1:8080").is_ok());
+    assert!(settings::socket_addr("bind_addr", "8080").is_err());
+    assert!(settings::socket_addr("bind_addr", "").is_err());
+}
@@ -0,0 +1,60 @@
+use recorder::ReplayConfig;
+
+use serde::{Deserialize, Serialize};
+
+use settings::Settings;
+
+use transport::{ChannelConfig, TransportConfig};
+
+/// Execution reports are read from the engine's multicast report stream by default.
+const DEFAULT_SUB_CHANNEL: &str = "aeron:udp?endpoint=224.1.1.1:40456|interface=localhost";
+const DEFAULT_SUB_STREAM_ID: i32 = 1002;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub aeron: TransportConfig,
+    /// Where execution reports are read from; `SUB_CHANNEL`/`SUB_STREAM_ID`.
+    pub sub: ChannelConfig,
+    /// Catching up from the recorder on start.
+    pub replay: ReplayConfig,
+    /// QuestDB client configuration string.
+    pub questdb_conf: String,
+    /// Rows buffered before they are flushed to QuestDB.
+    pub flush_every: u64,
+    /// Trades whose fills are kept to reverse busts and corrections; beyond this the oldest are
+    /// forgotten.
+    pub max_trades: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            aeron: TransportConfig::default(),
+            sub: ChannelConfig::new(DEFAULT_SUB_CHANNEL, DEFAULT_SUB_STREAM_ID),
+            replay: ReplayConfig::default(),
+            questdb_conf: "http::addr=localhost:9000;".to_owned(),
+            flush_every: 1000,
+            max_trades: 1_000_000,
+        }
+    }
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        self.aeron.validate()?;
+        self.sub.validate("sub")?;
+        self.replay.validate()?;
+        settings::required("questdb_conf", &self.questdb_conf)?;
+        if self.flush_every == 0 {
+            return Err("flush_every must be greater than zero".to_owned());
+        }
+        if self.max_trades == 0 {
+            return Err("max_trades must be greater than zero".to_owned());
+        }
+        if usize::try_from(self.max_trades).is_err() {
+            return Err(format!("max_trades {} is too large", self.max_trades));
+        }
+        Ok(())
+    }
+}
@@ -1,171 +1,267 @@
-mod aeron_handler;
-use aeron_handler::{build_context, create_subscription, get_aeron_dir};
+mod config;
+
 use sbe::ord_status_enum::OrdStatusEnum;
 
+use std::collections::BTreeMap;
 use std::time::Instant;
 
-use aeron_rs::aeron::Aeron;
-use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
-use aeron_rs::concurrent::status::status_indicator_reader::channel_status_to_str;
 use sbe::exec_type_enum::ExecTypeEnum;
 use sbe::side_enum::SideEnum;
 
-use log::{error, info};
+use log::{error, info, warn};
 
-use std::slice;
+use sbe::{Decimal64, ExecutionReport, Message, decode_message};
 
-use aeron_rs::concurrent::logbuffer::header::Header;
-use aeron_rs::utils::types::Index;
+use questdb::ingress::{Buffer, Sender, TimestampNanos};
 
-use sbe::ReadBuf;
-use sbe::execution_report_codec::ExecutionReportDecoder;
-use sbe::message_header_codec::MessageHeaderDecoder;
+use transport::{AeronTransport, MessageSource};
 
-const DECIMAL_NULL_VAL: i64 = i64::MIN;
+use config::Config;
 
-use uuid::Uuid;
+/// Last reported quantity and price of the fills of the most recent trades, keyed by
+/// (TrdMatchID, OrderID), so that a trade bust or correction can write a row reversing the
+/// original fill. Once full, recording a fill forgets the oldest one.
+pub struct FillLedger {
+    /// Two fills per trade, one for each side.
+    capacity: usize,
+    // Match ids only increase, so the first fill is the oldest.
+    fills: BTreeMap<(u64, u64), (Decimal64, Decimal64)>,
+}
 
-use questdb::ingress::{Buffer, Sender, TimestampNanos};
+/// A signed row of the `fills` table.
+#[derive(Debug, PartialEq, Eq)]
+struct Fill {
+    exec_type: &'static str,
+    qty: Decimal64,
+    px: Decimal64,
+}
+
+impl FillLedger {
+    /// Keeps the fills of the last `max_trades` trades.
+    pub fn new(max_trades: usize) -> Self {
+        Self {
+            capacity: max_trades.saturating_mul(2),
+            fills: BTreeMap::new(),
+        }
+    }
+
+    /// The rows a report of a fill adds: the fill of a trade, the reversal of a busted fill, and
+    /// the reversal of a corrected fill followed by the corrected one. Busts and corrections of
+    /// fills that were never seen, were already busted or have been forgotten reverse nothing.
+    fn apply(
+        &mut self,
+        exec_type: ExecTypeEnum,
+        key: (u64, u64),
+        (qty, px): (Decimal64, Decimal64),
+    ) -> Vec<Fill> {
+        let fill = |exec_type, qty, px| Fill { exec_type, qty, px };
+        match exec_type {
+            ExecTypeEnum::Trade => {
+                self.record(key, (qty, px));
+                vec![fill("trade", qty, px)]
+            }
+            ExecTypeEnum::TradeCancel => self
+                .fills
+                .remove(&key)
+                .map(|(old_qty, old_px)| fill("trade_cancel", -old_qty, old_px))
+                .into_iter()
+                .collect(),
+            ExecTypeEnum::TradeCorrect => {
+                let mut rows: Vec<Fill> = self
+                    .record(key, (qty, px))
+                    .map(|(old_qty, old_px)| fill("trade_correct", -old_qty, old_px))
+                    .into_iter()
+                    .collect();
+                rows.push(fill("trade_correct", qty, px));
+                rows
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Records a fill and returns the one it replaces.
+    fn record(
+        &mut self,
+        key: (u64, u64),
+        fill: (Decimal64, Decimal64),
+    ) -> Option<(Decimal64, Decimal64)> {
+        let replaced = self.fills.insert(key, fill);
+        while self.fills.len() > self.capacity {
+            self.fills.pop_first();
+        }
+        replaced
+    }
+}
 
 pub fn read_message(
-    buffer: &AtomicBuffer,
-    offset: Index,
-    length: Index,
-    _header: &Header,
+    frame: &[u8],
     qdb_buffer: &mut Buffer,
     qdb_sender: &mut Sender,
+    fills: &mut FillLedger,
 ) {
-    let slice_msg = unsafe {
-        slice::from_raw_parts_mut(buffer.buffer().offset(offset as isize), length as usize)
-    };
-    let read_buf = ReadBuf::new(slice_msg);
-    let header_decoder: MessageHeaderDecoder<ReadBuf<'_>> =
-        MessageHeaderDecoder::default().wrap(read_buf, 0);
-
-    match header_decoder.template_id() {
-        3 => {
-            let _result = write_to_db(header_decoder, qdb_buffer, qdb_sender);
+    match decode_message(frame) {
+        Ok(Message::ExecutionReport(report)) => {
+            let _result = write_to_db(&report, qdb_buffer, qdb_sender, fills);
             // println!("RESULT: {:?}", result);
         }
-        4 => {}
-        _ => {}
+        Ok(_) => {}
+        Err(err) => {
+            warn!(
+                "Rejecting malformed message of {} bytes: {err:?}",
+                frame.len()
+            );
+        }
     }
 }
 
 fn write_to_db(
-    header_decoder: MessageHeaderDecoder<ReadBuf<'_>>,
+    report: &ExecutionReport,
     qdb_buffer: &mut Buffer,
     qdb_sender: &mut Sender,
+    fills: &mut FillLedger,
 ) -> questdb::Result<()> {
-    let report: ExecutionReportDecoder<'_> =
-        ExecutionReportDecoder::default().header(header_decoder, 0);
-
-    let exec_type = report.exec_type();
-    if !(exec_type == ExecTypeEnum::New || exec_type == ExecTypeEnum::Trade) {
+    let exec_type = report.exec_type;
+    if !matches!(
+        exec_type,
+        ExecTypeEnum::New
+            | ExecTypeEnum::Canceled
+            | ExecTypeEnum::Trade
+            | ExecTypeEnum::TradeCancel
+            | ExecTypeEnum::TradeCorrect
+    ) {
         return Ok(());
     }
 
-    let account = &report.account()[..];
-    let account = std::str::from_utf8(account).unwrap_or("");
-
-    let cl_ord_id = &report.account()[..];
-    let cl_ord_id = std::str::from_utf8(cl_ord_id).unwrap_or("");
+    let account = report.account.to_string();
+    let cl_ord_id = report.cl_ord_id.to_string();
 
-    // let account_bytes = report.account();
-    // let account = Uuid::from_bytes(account_bytes).to_string();
-
-    // let cl_ord_id_bytes = report.cl_ord_id();
-    // let cl_ord_id = Uuid::from_bytes(cl_ord_id_bytes).to_string();
-
-    let price_m = report.price_decoder().mantissa();
-    let avg_px_m = report.avg_px_decoder().mantissa();
-
-    let price = if price_m != DECIMAL_NULL_VAL {
-        Some(price_m as f64 / 1e8)
-    } else {
-        None
-    };
-
-    let avg_px = if avg_px_m != DECIMAL_NULL_VAL {
-        Some(avg_px_m as f64 / 1e8)
-    } else {
-        None
-    };
-
-    let leaves_qty = report.leaves_qty_decoder().mantissa() as f64 / 1e8;
-    let cum_qty = report.cum_qty_decoder().mantissa() as f64 / 1e8;
-
-    let ord_status = match report.ord_status() {
+    let ord_status = match report.ord_status {
         OrdStatusEnum::New => "new",
         OrdStatusEnum::PartiallyFilled => "partially_filled",
+        OrdStatusEnum::Filled => "filled",
         OrdStatusEnum::Canceled => "canceled",
         _ => return Ok(()),
     };
 
-    let side_bool = match report.side() {
+    let side_bool = match report.side {
         SideEnum::Buy => true,
         SideEnum::Sell => false,
         _ => return Ok(()),
     };
 
-    let symbol_raw = report.symbol();
-    let symbol = match str::from_utf8(&symbol_raw) {
+    let symbol = match str::from_utf8(&report.symbol) {
         Ok(s) => s.trim_end_matches('\0'), // strip padding nulls
         Err(_) => return Ok(()),           // ignore invalid UTF-8
     };
 
-    let timestamp = TimestampNanos::new(report.transact_time_decoder().time() as i64);
+    let timestamp = TimestampNanos::new(report.transact_time as i64);
 
     let mut builder = qdb_buffer
         .table("orders")?
         .symbol("symbol", symbol)?
         .symbol("ord_status", ord_status)?
-        .column_str("account", account)?
-        .column_str("cl_ord_id", cl_ord_id)?
-        .column_f64("leaves_qty", leaves_qty)?
-        .column_f64("cum_qty", cum_qty)?
-        .column_bool("side", side_bool)?;
-
-    if let Some(p) = price {
-        builder = builder.column_f64("price", p)?;
+        .column_str("account", &account)?
+        .column_str("cl_ord_id", &cl_ord_id)?;
+    builder = column_amount(builder, "order_qty", report.order_qty)?;
+    builder = column_amount(builder, "leaves_qty", report.leaves_qty)?;
+    builder = column_amount(builder, "cum_qty", report.cum_qty)?;
+    builder = builder.column_bool("side", side_bool)?;
+
+    if let Some(p) = report.price {
+        builder = column_amount(builder, "price", p)?;
     }
-    if let Some(a) = avg_px {
-        builder = builder.column_f64("avg_px", a)?;
+    if let Some(a) = report.avg_px {
+        builder = column_amount(builder, "avg_px", a)?;
     }
 
     builder.at(timestamp)?;
 
+    // Fills are written as signed rows so that summing `qty` per account nets out busted and
+    // corrected trades.
+    if let (Some(match_id), Some(last_qty), Some(last_px)) =
+        (report.trd_match_id, report.last_qty, report.last_px)
+    {
+        let fill_row = FillRow {
+            symbol,
+            account: &account,
+            cl_ord_id: &cl_ord_id,
+            side: side_bool,
+            match_id,
+            timestamp,
+        };
+
+        for fill in fills.apply(exec_type, (match_id, report.order_id), (last_qty, last_px)) {
+            write_fill(qdb_buffer, &fill_row, &fill)?;
+        }
+    }
+
     qdb_sender.flush(qdb_buffer)?;
     Ok(())
 }
 
+struct FillRow<'a> {
+    symbol: &'a str,
+    account: &'a str,
+    cl_ord_id: &'a str,
+    side: bool,
+    match_id: u64,
+    timestamp: TimestampNanos,
+}
+
+fn write_fill(qdb_buffer: &mut Buffer, row: &FillRow<'_>, fill: &Fill) -> questdb::Result<()> {
+    let builder = qdb_buffer
+        .table("fills")?
+        .symbol("symbol", row.symbol)?
+        .symbol("exec_type", fill.exec_type)?
+        .column_str("account", row.account)?
+        .column_str("cl_ord_id", row.cl_ord_id)?
+        .column_bool("side", row.side)?
+        .column_i64("trd_match_id", row.match_id as i64)?;
+    let builder = column_amount(builder, "qty", fill.qty)?;
+    column_amount(builder, "px", fill.px)?.at(row.timestamp)
+}
+
+/// Writes an amount twice: as a double, for reading the tables by eye, and exactly, as the
+/// decimal's mantissa in `<name>_mantissa`, which is what the gateway reads back.
+fn column_amount<'a>(
+    builder: &'a mut Buffer,
+    name: &str,
+    amount: Decimal64,
+) -> questdb::Result<&'a mut Buffer> {
+    builder
+        .column_f64(name, amount.to_f64())?
+        .column_i64(format!("{name}_mantissa").as_str(), amount.mantissa())
+}
+
 fn main() -> questdb::Result<()> {
+    let config: Config = settings::load("trade-reporter");
     env_logger::init();
 
     let start_time = Instant::now();
 
-    let aeron_dir = get_aeron_dir();
-    info!("Aeron: Using directory: {:?}", aeron_dir);
+    info!("Aeron: Using directory: {:?}", config.aeron.dir);
 
-    let context = build_context(&aeron_dir);
-    let mut aeron = match Aeron::new(context) {
-        Ok(a) => {
+    let mut transport = match AeronTransport::connect(&config.aeron) {
+        Ok(transport) => {
             info!("Aeron: Instance created");
-            a
+            transport
         }
         Err(e) => {
-            error!("Aeron: Failed to create instance: {:?}", e);
+            error!("Aeron: Failed to create instance: {}", e);
             return Ok(());
         }
     };
 
-    let subscription = create_subscription(
-        &mut aeron,
-        "aeron:udp?endpoint=224.1.1.1:40456|interface=localhost",
-        1002,
-    );
-    let sub_status = subscription.lock().unwrap().channel_status();
-    info!("Aeron: Subscription {}", channel_status_to_str(sub_status));
+    let mut subscription = match recorder::subscribe(&mut transport, &config.sub, &config.replay) {
+        Ok(subscription) => {
+            info!("Aeron: Subscription on {}", config.sub);
+            subscription
+        }
+        Err(e) => {
+            error!("Aeron: Failed to add subscription: {}", e);
+            return Ok(());
+        }
+    };
 
     let startup_duration = start_time.elapsed();
     info!(
@@ -173,25 +269,17 @@ fn main() -> questdb::Result<()> {
         startup_duration
     );
 
-    let mut qdb_sender = Sender::from_conf("http::addr=localhost:9000;")?;
+    let mut qdb_sender = Sender::from_conf(&config.questdb_conf)?;
     let mut qdb_buffer = Buffer::new();
-
-    let mut subscription = subscription.lock().unwrap();
+    let mut fills = FillLedger::new(config.max_trades as usize);
 
     let mut msg_count = 0;
-    let flush_every = 1000;
+    let flush_every = config.flush_every;
 
     loop {
         subscription.poll(
-            &mut |buffer, offset, length, header| {
-                read_message(
-                    buffer,
-                    offset,
-                    length,
-                    header,
-                    &mut qdb_buffer,
-                    &mut qdb_sender,
-                );
+            &mut |frame| {
+                read_message(frame, &mut qdb_buffer, &mut qdb_sender, &mut fills);
                 msg_count += 1;
                 if msg_count >= flush_every {
                     let _ = qdb_sender.flush(&mut qdb_buffer);
@@ -202,3 +290,141 @@ fn main() -> questdb::Result<()> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: (u64, u64) = (7, 1);
+
+    fn dec(value: i64) -> Decimal64 {
+        Decimal64::from_mantissa(value * Decimal64::SCALE)
+    }
+
+    fn fill(exec_type: &'static str, qty: i64, px: i64) -> Fill {
+        Fill {
+            exec_type,
+            qty: dec(qty),
+            px: dec(px),
+        }
+    }
+
+    #[test]
+    fn bust_reverses_the_fill_once() {
+        let mut fills = FillLedger::new(16);
+        assert_eq!(
+            fills.apply(ExecTypeEnum::Trade, KEY, (dec(4), dec(100))),
+            [fill("trade", 4, 100)]
+        );
+        // A bust report carries the busted quantity, but the reversal is of what was recorded.
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCancel, KEY, (dec(9), dec(1))),
+            [fill("trade_cancel", -4, 100)]
+        );
+        assert!(
+            fills
+                .apply(ExecTypeEnum::TradeCancel, KEY, (dec(4), dec(100)))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn correction_replaces_the_fill() {
+        let mut fills = FillLedger::new(16);
+        fills.apply(ExecTypeEnum::Trade, KEY, (dec(4), dec(100)));
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCorrect, KEY, (dec(3), dec(90))),
+            [fill("trade_correct", -4, 100), fill("trade_correct", 3, 90)]
+        );
+        // A later bust reverses the corrected fill.
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCancel, KEY, (dec(3), dec(90))),
+            [fill("trade_cancel", -3, 90)]
+        );
+    }
+
+    #[test]
+    fn keeps_fills_of_each_order_apart() {
+        let mut fills = FillLedger::new(16);
+        fills.apply(ExecTypeEnum::Trade, (7, 1), (dec(4), dec(100)));
+        fills.apply(ExecTypeEnum::Trade, (7, 2), (dec(4), dec(100)));
+        fills.apply(ExecTypeEnum::Trade, (8, 1), (dec(2), dec(101)));
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCancel, (8, 1), (dec(2), dec(101))),
+            [fill("trade_cancel", -2, 101)]
+        );
+        assert_eq!(fills.fills.len(), 2);
+    }
+
+    #[test]
+    fn writes_amounts_exactly() {
+        let mut buffer = Buffer::new();
+        let row = FillRow {
+            symbol: "JSP",
+            account: "a",
+            cl_ord_id: "c",
+            side: true,
+            match_id: 7,
+            timestamp: TimestampNanos::new(1),
+        };
+        let px = Decimal64::from_mantissa(30_000_001);
+        write_fill(&mut buffer, &row, &fill("trade_cancel", -4, 0))
+            .and_then(|()| {
+                write_fill(
+                    &mut buffer,
+                    &row,
+                    &Fill {
+                        px,
+                        ..fill("trade", 4, 0)
+                    },
+                )
+            })
+            .unwrap();
+
+        let lines = buffer.as_str();
+        assert!(lines.contains("qty_mantissa=-400000000i"), "{lines}");
+        assert!(lines.contains("px_mantissa=30000001i"), "{lines}");
+    }
+
+    #[test]
+    fn correction_of_an_unseen_fill_only_writes_the_correction() {
+        let mut fills = FillLedger::new(16);
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCorrect, KEY, (dec(3), dec(90))),
+            [fill("trade_correct", 3, 90)]
+        );
+        assert!(
+            fills
+                .apply(ExecTypeEnum::New, KEY, (dec(3), dec(90)))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn forgets_the_fills_of_the_oldest_trades_once_full() {
+        let mut fills = FillLedger::new(2);
+        for match_id in 1..=3 {
+            fills.apply(ExecTypeEnum::Trade, (match_id, 1), (dec(4), dec(100)));
+            fills.apply(ExecTypeEnum::Trade, (match_id, 2), (dec(4), dec(100)));
+        }
+        assert_eq!(fills.fills.len(), 4);
+
+        // Trade 1 is forgotten: its bust reverses nothing and its correction only writes itself.
+        assert!(
+            fills
+                .apply(ExecTypeEnum::TradeCancel, (1, 1), (dec(4), dec(100)))
+                .is_empty()
+        );
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCorrect, (1, 2), (dec(3), dec(100))),
+            [fill("trade_correct", 3, 100)]
+        );
+        // The corrected fill is older than any kept, so it is forgotten straight away.
+        assert_eq!(fills.fills.len(), 4);
+        assert!(!fills.fills.contains_key(&(1, 2)));
+        assert_eq!(
+            fills.apply(ExecTypeEnum::TradeCancel, (3, 2), (dec(4), dec(100))),
+            [fill("trade_cancel", -4, 100)]
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Aeron backend. Publications and subscriptions are owned outright rather than shared through
+//! the client's `Arc<Mutex<_>>`, and fragmented messages are reassembled per session before
+//! they reach a `MessageSource` handler.
+
+use crate::config::{ChannelConfig, TransportConfig};
+use crate::{MessageSink, MessageSource, SendError};
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fmt;
+use std::process;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use aeron_rs::aeron::Aeron;
+use aeron_rs::concurrent::atomic_buffer::AtomicBuffer;
+use aeron_rs::concurrent::logbuffer::buffer_claim::BufferClaim;
+use aeron_rs::concurrent::logbuffer::frame_descriptor::{BEGIN_FRAG, END_FRAG, UNFRAGMENTED};
+use aeron_rs::concurrent::logbuffer::header::Header;
+use aeron_rs::concurrent::status::status_indicator_reader::channel_status_to_str;
+use aeron_rs::context::Context;
+use aeron_rs::exclusive_publication::ExclusivePublication;
+use aeron_rs::publication::Publication;
+use aeron_rs::subscription::Subscription;
+use aeron_rs::utils::errors::AeronError;
+use aeron_rs::utils::types::Index;
+
+use tracing::{error, info, warn};
+
+#[derive(Debug)]
+pub enum TransportError {
+    /// The client could not be created or the driver rejected a registration.
+    Aeron(AeronError),
+    /// The driver did not make a publication or subscription available in time.
+    Timeout { channel: ChannelConfig },
+    /// The channel URI contains a NUL byte.
+    InvalidChannel { channel: ChannelConfig },
+    /// The client still shares the publication or subscription with another owner.
+    Shared { channel: ChannelConfig },
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Aeron(err) => write!(f, "{err}"),
+            Self::Timeout { channel } => write!(f, "timed out waiting for {channel}"),
+            Self::InvalidChannel { channel } => write!(f, "invalid channel {channel}"),
+            Self::Shared { channel } => write!(f, "{channel} is shared with another owner"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<AeronError> for TransportError {
+    fn from(err: AeronError) -> Self {
+        Self::Aeron(err)
+    }
+}
+
+/// A connection to the media driver. It must outlive the sinks and sources created from it.
+pub struct AeronTransport {
+    aeron: Aeron,
+    resource_timeout: Duration,
+}
+
+impl AeronTransport {
+    pub fn connect(config: &TransportConfig) -> Result<Self, TransportError> {
+        info!(target: "aeron_setup", aeron_dir = %config.dir, "Connecting to the Aeron media driver.");
+
+        let mut context = Context::new();
+        context.set_aeron_dir(config.dir.clone());
+        context.set_new_publication_handler(Box::new(on_new_publication));
+        context.set_new_exclusive_publication_handler(Box::new(on_new_publication));
+        context.set_new_subscription_handler(Box::new(on_new_subscription));
+        context.set_error_handler(Box::new(error_handler));
+        context.set_pre_touch_mapped_memory(true);
+
+        Ok(Self {
+            aeron: Aeron::new(context)?,
+            resource_timeout: config.resource_timeout(),
+        })
+    }
+
+    /// Adds a publication that may share its stream with other publishers.
+    pub fn add_publication(
+        &mut self,
+        channel: &ChannelConfig,
+    ) -> Result<AeronSink, TransportError> {
+        let id = self
+            .aeron
+            .add_publication(c_channel(channel)?, channel.stream_id)?;
+        let publication = self.wait_for(channel, |aeron| aeron.find_publication(id).ok())?;
+        let publication = unwrap_shared(publication, channel)?;
+
+        info!(target: "aeron_status", %channel, status = %channel_status_to_str(publication.channel_status()), "Publication ready.");
+        Ok(AeronSink::new(publication))
+    }
+
+    /// Adds a publication that is the only publisher on its session, which makes claims cheaper.
+    pub fn add_exclusive_publication(
+        &mut self,
+        channel: &ChannelConfig,
+    ) -> Result<ExclusiveAeronSink, TransportError> {
+        let id = self
+            .aeron
+            .add_exclusive_publication(c_channel(channel)?, channel.stream_id)?;
+        let publication =
+            self.wait_for(channel, |aeron| aeron.find_exclusive_publication(id).ok())?;
+        let publication = unwrap_shared(publication, channel)?;
+
+        info!(target: "aeron_status", %channel, status = %channel_status_to_str(publication.channel_status()), "Exclusive publication ready.");
+        Ok(AeronSink::new(publication))
+    }
+
+    pub fn add_subscription(
+        &mut self,
+        channel: &ChannelConfig,
+    ) -> Result<AeronSource, TransportError> {
+        let id = self
+            .aeron
+            .add_subscription(c_channel(channel)?, channel.stream_id)?;
+        let subscription = self.wait_for(channel, |aeron| aeron.find_subscription(id).ok())?;
+        let subscription = unwrap_shared(subscription, channel)?;
+
+        info!(target: "aeron_status", %channel, status = %channel_status_to_str(subscription.channel_status()), "Subscription ready.");
+        Ok(AeronSource {
+            subscription,
+            assemblies: HashMap::new(),
+        })
+    }
+
+    fn wait_for<T>(
+        &mut self,
+        channel: &ChannelConfig,
+        mut find: impl FnMut(&mut Aeron) -> Option<T>,
+    ) -> Result<T, TransportError> {
+        let start = Instant::now();
+        loop {
+            if let Some(found) = find(&mut self.aeron) {
+                return Ok(found);
+            }
+            if start.elapsed() > self.resource_timeout {
+                return Err(TransportError::Timeout {
+                    channel: channel.clone(),
+                });
+            }
+            sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// The publication types an `AeronSink` can publish on.
+pub trait AeronPublication {
+    fn offer_part(&mut self, buffer: AtomicBuffer, length: Index) -> Result<(), AeronError>;
+    fn try_claim(
+        &mut self,
+        length: Index,
+        buffer_claim: &mut BufferClaim,
+    ) -> Result<(), AeronError>;
+    fn is_connected(&self) -> bool;
+}
+
+impl AeronPublication for Publication {
+    fn offer_part(&mut self, buffer: AtomicBuffer, length: Index) -> Result<(), AeronError> {
+        Self::offer_part(self, buffer, 0, length).map(drop)
+    }
+
+    fn try_claim(
+        &mut self,
+        length: Index,
+        buffer_claim: &mut BufferClaim,
+    ) -> Result<(), AeronError> {
+        Self::try_claim(self, length, buffer_claim).map(drop)
+    }
+
+    fn is_connected(&self) -> bool {
+        Self::is_connected(self)
+    }
+}
+
+impl AeronPublication for ExclusivePublication {
+    fn offer_part(&mut self, buffer: AtomicBuffer, length: Index) -> Result<(), AeronError> {
+        Self::offer_part(self, buffer, 0, length).map(drop)
+    }
+
+    fn try_claim(
+        &mut self,
+        length: Index,
+        buffer_claim: &mut BufferClaim,
+    ) -> Result<(), AeronError> {
+        Self::try_claim(self, length, buffer_claim).map(drop)
+    }
+
+    fn is_connected(&self) -> bool {
+        Self::is_connected(self)
+    }
+}
+
+/// Publishes on a publication shared with other publishers. It can be sent between threads.
+pub struct AeronSink<P: AeronPublication = Publication> {
+    publication: P,
+    buffer_claim: BufferClaim,
+}
+
+/// Publishes on an exclusive publication, which makes claims cheaper but must stay on the
+/// thread that created it.
+pub type ExclusiveAeronSink = AeronSink<ExclusivePublication>;
+
+impl<P: AeronPublication> AeronSink<P> {
+    fn new(publication: P) -> Self {
+        Self {
+            publication,
+            buffer_claim: BufferClaim::default(),
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.publication.is_connected()
+    }
+}
+
+impl<P: AeronPublication> MessageSink for AeronSink<P> {
+    fn offer(&mut self, frame: &[u8]) -> Result<(), SendError> {
+        // Offers only read from the buffer, so wrapping the shared slice is sound.
+        let buffer = AtomicBuffer::wrap_raw_slice(ptr::from_ref(frame).cast_mut());
+        let length = frame_length(frame.len())?;
+        self.publication
+            .offer_part(buffer, length)
+            .map_err(send_error)
+    }
+
+    fn try_publish(
+        &mut self,
+        length: usize,
+        write: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<(), SendError> {
+        self.publication
+            .try_claim(frame_length(length)?, &mut self.buffer_claim)
+            .map_err(send_error)?;
+
+        let offset =
+            usize::try_from(self.buffer_claim.offset()).expect("claimed offsets are non-negative");
+        let mut buffer = self.buffer_claim.buffer();
+        write(&mut buffer.as_mutable_slice()[offset..offset + length]);
+        self.buffer_claim.commit();
+        Ok(())
+    }
+}
+
+pub struct AeronSource {
+    subscription: Subscription,
+    /// Fragments of messages in progress, by session.
+    assemblies: HashMap<i32, Vec<u8>>,
+}
+
+impl AeronSource {
+    pub fn is_connected(&self) -> bool {
+        self.subscription.is_connected()
+    }
+}
+
+impl MessageSource for AeronSource {
+    /// `limit` and the result count fragments, so a reassembled message counts once per fragment.
+    fn poll(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        let assemblies = &mut self.assemblies;
+        let mut on_fragment =
+            |buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header| {
+                let (Ok(offset), Ok(length)) = (usize::try_from(offset), usize::try_from(length))
+                else {
+                    return;
+                };
+                let fragment = &buffer.as_slice()[offset..offset + length];
+
+                let flags = header.flags();
+                if flags & UNFRAGMENTED == UNFRAGMENTED {
+                    handler(fragment);
+                } else if flags & BEGIN_FRAG == BEGIN_FRAG {
+                    let assembly = assemblies.entry(header.session_id()).or_default();
+                    assembly.clear();
+                    assembly.extend_from_slice(fragment);
+                } else if let Some(assembly) = assemblies.get_mut(&header.session_id()) {
+                    // Fragments of a message whose start was missed are dropped.
+                    if !assembly.is_empty() {
+                        assembly.extend_from_slice(fragment);
+                        if flags & END_FRAG == END_FRAG {
+                            handler(assembly);
+                            assembly.clear();
+                        }
+                    }
+                }
+            };
+
+        let limit = i32::try_from(limit).unwrap_or(i32::MAX);
+        let read = self.subscription.poll(&mut on_fragment, limit);
+        usize::try_from(read).unwrap_or(0)
+    }
+}
+
+fn frame_length(length: usize) -> Result<Index, SendError> {
+    Index::try_from(length)
+        .map_err(|_| SendError::Failed(format!("frame of {length} bytes is too long")))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn send_error(err: AeronError) -> SendError {
+    match err {
+        AeronError::BackPressured => SendError::BackPressured,
+        AeronError::NotConnected => SendError::NotConnected,
+        AeronError::AdminAction => SendError::AdminAction,
+        AeronError::PublicationClosed => SendError::Closed,
+        err => SendError::Failed(err.to_string()),
+    }
+}
+
+fn c_channel(channel: &ChannelConfig) -> Result<CString, TransportError> {
+    CString::new(channel.channel.as_str()).map_err(|_| TransportError::InvalidChannel {
+        channel: channel.clone(),
+    })
+}
+
+/// The client keeps only weak references to what it hands out, so the caller is normally the
+/// sole owner.
+fn unwrap_shared<T>(shared: Arc<Mutex<T>>, channel: &ChannelConfig) -> Result<T, TransportError> {
+    let mutex = Arc::try_unwrap(shared).map_err(|_| TransportError::Shared {
+        channel: channel.clone(),
+    })?;
+    Ok(mutex
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()))
+}
+
+/// Errors raised on the client's conductor thread. Losing the driver cannot be recovered from
+/// by the services, so those errors end the process.
+#[allow(clippy::needless_pass_by_value)]
+fn error_handler(error: AeronError) {
+    match error {
+        AeronError::SubscriptionNotReady(id) => {
+            warn!(target: "aeron", subscription_id = id, "Subscription not ready");
+        }
+        AeronError::PublicationNotReady(id) => {
+            warn!(target: "aeron", publication_id = id, "Publication not ready");
+        }
+        AeronError::DriverTimeout(_)
+        | AeronError::ClientTimeoutException
+        | AeronError::RegistrationException(_, _)
+        | AeronError::ChannelEndpointException(_, _) => {
+            error!(target: "aeron", ?error, "Critical Aeron communication failure. Exiting.");
+            process::exit(1);
+        }
+        _ => {
+            error!(target: "aeron", ?error, "Aeron error");
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn on_new_publication(channel: CString, stream_id: i32, session_id: i32, correlation_id: i64) {
+    info!(
+        target: "aeron_callbacks",
+        correlation_id,
+        channel = %channel.to_string_lossy(),
+        stream_id,
+        session_id,
+        "New publication successfully established."
+    );
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn on_new_subscription(channel: CString, stream_id: i32, correlation_id: i64) {
+    info!(
+        target: "aeron_callbacks",
+        correlation_id,
+        channel = %channel.to_string_lossy(),
+        stream_id,
+        "New subscription successfully established."
+    );
+}
@@ -0,0 +1,98 @@
+//! Where each stream lives. These are sections of a service's configuration, so they are read
+//! from its config file, environment and flags like the rest of its settings:
+//!
+//! ```toml
+//! [aeron]
+//! dir = "/dev/shm/aeron"
+//! resource_timeout_ms = 15000
+//!
+//! [sub]
+//! channel = "aeron:udp?endpoint=localhost:40123"
+//! stream_id = 1001
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// A channel URI and stream id, e.g. `aeron:ipc` stream 1002. An empty channel is not
+/// configured.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChannelConfig {
+    pub channel: String,
+    pub stream_id: i32,
+}
+
+impl ChannelConfig {
+    pub fn new(channel: impl Into<String>, stream_id: i32) -> Self {
+        Self {
+            channel: channel.into(),
+            stream_id,
+        }
+    }
+
+    /// Fails unless the stream is configured; `name` is the section, e.g. `"sub"`.
+    pub fn validate(&self, name: &str) -> Result<(), String> {
+        if self.channel.trim().is_empty() {
+            return Err(format!("{name}.channel is required"));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ChannelConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} stream {}", self.channel, self.stream_id)
+    }
+}
+
+/// How to reach the media driver; the `[aeron]` section.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportConfig {
+    /// Directory shared with the media driver.
+    pub dir: String,
+    /// How long to wait for the media driver to set up a publication or subscription.
+    pub resource_timeout_ms: u64,
+}
+
+impl TransportConfig {
+    pub const DEFAULT_RESOURCE_TIMEOUT: Duration = Duration::from_secs(15);
+
+    pub const fn resource_timeout(&self) -> Duration {
+        Duration::from_millis(self.resource_timeout_ms)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.dir.trim().is_empty() {
+            return Err("aeron.dir is required".to_owned());
+        }
+        if self.resource_timeout_ms == 0 {
+            return Err("aeron.resource_timeout_ms must be greater than zero".to_owned());
+        }
+        Ok(())
+    }
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        #[allow(clippy::cast_possible_truncation)]
+        Self {
+            dir: default_aeron_dir().to_owned(),
+            resource_timeout_ms: Self::DEFAULT_RESOURCE_TIMEOUT.as_millis() as u64,
+        }
+    }
+}
+
+/// Where the media driver runs by default; elsewhere the directory must be configured.
+const fn default_aeron_dir() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "/Volumes/DevShm/aeron"
+    } else if cfg!(target_os = "linux") {
+        "/dev/shm/aeron"
+    } else {
+        ""
+    }
+}
@@ -0,0 +1,99 @@
+//! Message transport shared by the services: a `MessageSink` publishes whole SBE frames and a
+//! `MessageSource` delivers them, whatever carries them in between. `aeron` talks to a media
+//! driver; `loopback` connects sinks and sources within one process so services can be tested
+//! without one. Channels and stream ids are described by `config`.
+
+pub mod aeron;
+pub mod config;
+pub mod loopback;
+
+pub use aeron::{AeronSink, AeronSource, AeronTransport, ExclusiveAeronSink, TransportError};
+pub use config::{ChannelConfig, TransportConfig};
+pub use loopback::{Loopback, LoopbackSink, LoopbackSource};
+
+use std::fmt;
+
+/// Why a frame was not published. Transient errors may succeed when retried; the others will
+/// not.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SendError {
+    /// Subscribers have not kept up and the stream is full.
+    BackPressured,
+    /// No subscriber is connected.
+    NotConnected,
+    /// The transport was busy with housekeeping.
+    AdminAction,
+    /// The publication was closed.
+    Closed,
+    /// The frame cannot be published on this stream, e.g. it is too long.
+    Failed(String),
+}
+
+impl SendError {
+    pub const fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::BackPressured | Self::NotConnected | Self::AdminAction
+        )
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BackPressured => f.write_str("back pressured"),
+            Self::NotConnected => f.write_str("not connected"),
+            Self::AdminAction => f.write_str("admin action"),
+            Self::Closed => f.write_str("publication closed"),
+            Self::Failed(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+/// Publishes frames on one stream.
+pub trait MessageSink {
+    /// Publishes a copy of `frame`.
+    fn offer(&mut self, frame: &[u8]) -> Result<(), SendError>;
+
+    /// Reserves `length` bytes on the stream, lets `write` encode the frame in place and
+    /// publishes it. `write` is only called once space has been reserved, so a failed attempt
+    /// can be retried with the same closure.
+    fn try_publish(
+        &mut self,
+        length: usize,
+        write: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<(), SendError> {
+        let mut frame = vec![0; length];
+        write(&mut frame);
+        self.offer(&frame)
+    }
+}
+
+/// Delivers frames from one stream, each whole and in the order published.
+pub trait MessageSource {
+    /// Hands up to `limit` waiting frames to `handler` and returns how many were read, without
+    /// blocking.
+    fn poll(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize;
+}
+
+impl<S: MessageSink + ?Sized> MessageSink for Box<S> {
+    fn offer(&mut self, frame: &[u8]) -> Result<(), SendError> {
+        (**self).offer(frame)
+    }
+
+    fn try_publish(
+        &mut self,
+        length: usize,
+        write: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<(), SendError> {
+        (**self).try_publish(length, write)
+    }
+}
+
+impl<S: MessageSource + ?Sized> MessageSource for Box<S> {
+    fn poll(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        (**self).poll(handler, limit)
+    }
+}
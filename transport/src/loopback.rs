@@ -0,0 +1,124 @@
+//! In-process transport for tests. Every frame offered to a sink is delivered to each source of
+//! the same `Loopback` that exists at the time, like subscribers joining a live stream. Each
+//! source buffers up to `capacity` frames; a sink is back pressured while any source is full,
+//! and not connected while there are no sources.
+
+use crate::{MessageSink, MessageSource, SendError};
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+type Queue = Mutex<VecDeque<Vec<u8>>>;
+
+/// A stream connecting the sinks and sources created from it. Clones share the stream.
+#[derive(Clone)]
+pub struct Loopback {
+    sources: Arc<Mutex<Vec<Weak<Queue>>>>,
+    capacity: usize,
+}
+
+impl Loopback {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            sources: Arc::default(),
+            capacity,
+        }
+    }
+
+    pub fn sink(&self) -> LoopbackSink {
+        LoopbackSink {
+            loopback: self.clone(),
+        }
+    }
+
+    pub fn source(&self) -> LoopbackSource {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        lock(&self.sources).push(Arc::downgrade(&queue));
+        LoopbackSource { queue }
+    }
+}
+
+impl Default for Loopback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone)]
+pub struct LoopbackSink {
+    loopback: Loopback,
+}
+
+impl MessageSink for LoopbackSink {
+    fn offer(&mut self, frame: &[u8]) -> Result<(), SendError> {
+        self.try_publish(frame.len(), &mut |buffer| buffer.copy_from_slice(frame))
+    }
+
+    fn try_publish(
+        &mut self,
+        length: usize,
+        write: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<(), SendError> {
+        let mut sources = lock(&self.loopback.sources);
+        sources.retain(|source| source.strong_count() > 0);
+
+        let queues: Vec<Arc<Queue>> = sources.iter().filter_map(Weak::upgrade).collect();
+        if queues.is_empty() {
+            return Err(SendError::NotConnected);
+        }
+        let mut queues: Vec<_> = queues.iter().map(|queue| lock(queue)).collect();
+        if queues
+            .iter()
+            .any(|queue| queue.len() >= self.loopback.capacity)
+        {
+            return Err(SendError::BackPressured);
+        }
+
+        let mut frame = vec![0; length];
+        write(&mut frame);
+        for queue in &mut queues {
+            queue.push_back(frame.clone());
+        }
+        Ok(())
+    }
+}
+
+pub struct LoopbackSource {
+    queue: Arc<Queue>,
+}
+
+impl LoopbackSource {
+    /// Number of frames waiting to be polled.
+    pub fn pending(&self) -> usize {
+        lock(&self.queue).len()
+    }
+}
+
+impl MessageSource for LoopbackSource {
+    fn poll(&mut self, handler: &mut dyn FnMut(&[u8]), limit: usize) -> usize {
+        let mut read = 0;
+        while read < limit {
+            // Released before calling the handler, which may publish back onto this stream.
+            let Some(frame) = lock(&self.queue).pop_front() else {
+                break;
+            };
+            handler(&frame);
+            read += 1;
+        }
+        read
+    }
+}
+
+/// A panic while holding one of these locks leaves the queues consistent, so poisoning is
+/// ignored.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use transport::{ChannelConfig, TransportConfig};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    aeron: TransportConfig,
+    sub: ChannelConfig,
+}
+
+const FILE: &str = r#"
+[aeron]
+dir = "/tmp/aeron-test"
+resource_timeout_ms = 500
+
+[sub]
+channel = "aeron:udp?endpoint=localhost:40123"
+stream_id = 1001
+"#;
+
+#[test]
+fn reads_sections_from_the_file() {
+    let config: Config = toml::from_str(FILE).unwrap();
+    assert_eq!(config.aeron.dir, "/tmp/aeron-test");
+    assert_eq!(config.aeron.resource_timeout(), Duration::from_millis(500));
+    assert_eq!(
+        config.sub,
+        ChannelConfig::new("aeron:udp?endpoint=localhost:40123", 1001)
+    );
+}
+
+#[test]
+fn fills_unset_settings_from_the_default() {
+    let config: Config = toml::from_str("[sub]\nstream_id = 7\n").unwrap();
+    assert_eq!(config.aeron, TransportConfig::default());
+    assert_eq!(
+        config.aeron.resource_timeout(),
+        TransportConfig::DEFAULT_RESOURCE_TIMEOUT
+    );
+    assert_eq!(config.sub, ChannelConfig::new("", 7));
+}
+
+#[test]
+fn requires_a_channel() {
+    let config: Config = toml::from_str("[sub]\nstream_id = 7\n").unwrap();
+    assert_eq!(
+        config.sub.validate("sub").unwrap_err(),
+        "sub.channel is required"
+    );
+    assert!(ChannelConfig::new("aeron:ipc", 1).validate("sub").is_ok());
+}
+
+#[test]
+fn rejects_a_zero_resource_timeout() {
+    let config: Config = toml::from_str("[aeron]\nresource_timeout_ms = 0\n").unwrap();
+    assert!(config.aeron.validate().is_err());
+}
+
+#[test]
+fn rejects_a_stream_id_that_is_not_a_number() {
+    assert!(toml::from_str::<Config>("[sub]\nstream_id = \"ten\"\n").is_err());
+}
+
+#[test]
+fn rejects_unknown_settings() {
+    assert!(toml::from_str::<Config>("[sub]\nstream = 1\n").is_err());
+    assert!(toml::from_str::<Config>("[aeron]\naeron_dir = \"/tmp\"\n").is_err());
+}
@@ -0,0 +1,100 @@
+use transport::{Loopback, MessageSink, MessageSource, SendError};
+
+fn drain(source: &mut impl MessageSource) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    source.poll(&mut |frame| frames.push(frame.to_vec()), usize::MAX);
+    frames
+}
+
+#[test]
+fn delivers_frames_in_order_to_every_source() {
+    let loopback = Loopback::new();
+    let mut first = loopback.source();
+    let mut second = loopback.source();
+    let mut sink = loopback.sink();
+
+    sink.offer(b"one").unwrap();
+    sink.try_publish(3, &mut |frame| frame.copy_from_slice(b"two"))
+        .unwrap();
+
+    let expected = vec![b"one".to_vec(), b"two".to_vec()];
+    assert_eq!(drain(&mut first), expected);
+    assert_eq!(drain(&mut second), expected);
+    assert_eq!(drain(&mut first), Vec::<Vec<u8>>::new());
+}
+
+#[test]
+fn poll_reads_at_most_limit_frames() {
+    let loopback = Loopback::new();
+    let mut source = loopback.source();
+    let mut sink = loopback.sink();
+    for frame in [b"a", b"b", b"c"] {
+        sink.offer(frame).unwrap();
+    }
+
+    let mut frames = Vec::new();
+    assert_eq!(source.poll(&mut |frame| frames.push(frame.to_vec()), 2), 2);
+    assert_eq!(frames, vec![b"a".to_vec(), b"b".to_vec()]);
+    assert_eq!(source.pending(), 1);
+}
+
+#[test]
+fn sink_is_not_connected_without_sources() {
+    let loopback = Loopback::new();
+    let mut sink = loopback.sink();
+    assert_eq!(sink.offer(b"lost"), Err(SendError::NotConnected));
+
+    let source = loopback.source();
+    assert_eq!(sink.offer(b"kept"), Ok(()));
+    drop(source);
+    assert_eq!(sink.offer(b"lost"), Err(SendError::NotConnected));
+}
+
+#[test]
+fn full_source_back_pressures_the_sink() {
+    let loopback = Loopback::with_capacity(1);
+    let mut source = loopback.source();
+    let mut sink = loopback.sink();
+
+    sink.offer(b"first").unwrap();
+    let mut written = false;
+    let result = sink.try_publish(6, &mut |_| written = true);
+    assert_eq!(result, Err(SendError::BackPressured));
+    assert!(result.unwrap_err().is_transient());
+    assert!(!written, "nothing is encoded until space is available");
+
+    assert_eq!(drain(&mut source), vec![b"first".to_vec()]);
+    sink.offer(b"second").unwrap();
+    assert_eq!(drain(&mut source), vec![b"second".to_vec()]);
+}
+
+#[test]
+fn sources_only_see_frames_published_after_they_join() {
+    let loopback = Loopback::new();
+    let _early = loopback.source();
+    let mut sink = loopback.sink();
+    sink.offer(b"before").unwrap();
+
+    let mut late = loopback.source();
+    sink.offer(b"after").unwrap();
+    assert_eq!(drain(&mut late), vec![b"after".to_vec()]);
+}
+
+#[test]
+fn handler_may_publish_back_onto_the_stream() {
+    let requests = Loopback::new();
+    let responses = Loopback::new();
+    let mut request_source = requests.source();
+    let mut response_source = responses.source();
+    let mut response_sink = responses.sink();
+
+    requests.sink().offer(b"ping").unwrap();
+    request_source.poll(
+        &mut |frame| {
+            assert_eq!(frame, b"ping");
+            response_sink.offer(b"pong").unwrap();
+        },
+        10,
+    );
+    assert_eq!(drain(&mut response_source), vec![b"pong".to_vec()]);
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use settings::Settings;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Address the WebSocket server listens on.
+    pub bind_addr: String,
+    pub nats_url: String,
+    /// Subject the level 2 updates are read from.
+    pub nats_subject: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bind_addr: "127.0.0.1:8080".to_owned(),
+            nats_url: "localhost".to_owned(),
+            nats_subject: "foo".to_owned(),
+        }
+    }
+}
+
+impl Settings for Config {
+    fn validate(&self) -> Result<(), String> {
+        settings::socket_addr("bind_addr", &self.bind_addr)?;
+        settings::required("nats_url", &self.nats_url)?;
+        settings::required("nats_subject", &self.nats_subject)
+    }
+}
@@ -1,7 +1,10 @@
+mod config;
+
 use futures_util::{SinkExt, StreamExt};
 
 use std::net::SocketAddr;
 use std::process;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 use tokio::net::{TcpListener, TcpStream};
@@ -21,8 +24,10 @@ use tracing_subscriber::FmtSubscriber;
 
 use tracing::{error, info};
 
-async fn accept_connection(peer: SocketAddr, stream: TcpStream) {
-    if let Err(e) = handle_connection(peer, stream).await {
+use config::Config;
+
+async fn accept_connection(peer: SocketAddr, stream: TcpStream, config: Arc<Config>) {
+    if let Err(e) = handle_connection(peer, stream, &config).await {
         match e {
             Error::ConnectionClosed | Error::Protocol(_) | Error::Utf8(_) => (),
             err => error!("Error processing connection: {}", err),
@@ -41,21 +46,19 @@ pub fn format_timestamp_ns(timestamp_ns: u64) -> String {
     datetime.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
 }
 
-async fn handle_connection(peer: SocketAddr, stream: TcpStream) -> Result<()> {
+async fn handle_connection(peer: SocketAddr, stream: TcpStream, config: &Config) -> Result<()> {
     let mut ws_stream = accept_async(stream).await.expect("Failed to accept");
 
     info!("New WebSocket connection: {}", peer);
 
     // Connect to the NATS server
-    // TODO: GET NATS_SERVER FROM ENV VARIABLE
-    info!("Connecting to NATS server at localhost");
-    let client = async_nats::connect("localhost")
+    info!("Connecting to NATS server at {}", config.nats_url);
+    let client = async_nats::connect(config.nats_url.as_str())
         .await
         .expect("nats connection failed"); // TODO: Replace expect with explicit handle and trace
 
-    // TODO: GET NATS_CHANNEL FROM ENV VARIABLE
     let mut subscriber = client
-        .subscribe("foo")
+        .subscribe(config.nats_subject.clone())
         .await
         .expect("Failed to connect to NATS");
 
@@ -99,6 +102,8 @@ async fn handle_connection(peer: SocketAddr, stream: TcpStream) -> Result<()> {
 
 #[tokio::main]
 async fn main() {
+    let config = Arc::new(settings::load::<Config>("websocket"));
+
     let subscriber = FmtSubscriber::builder()
         .with_max_level(Level::INFO)
         .finish();
@@ -108,9 +113,10 @@ async fn main() {
         process::exit(1);
     });
 
-    let addr = "127.0.0.1:8080";
-    let listener = TcpListener::bind(&addr).await.expect("Can't listen");
-    info!("Listening on: {}", addr);
+    let listener = TcpListener::bind(&config.bind_addr)
+        .await
+        .expect("Can't listen");
+    info!("Listening on: {}", config.bind_addr);
 
     while let Ok((stream, _)) = listener.accept().await {
         let peer = stream
@@ -118,6 +124,6 @@ async fn main() {
             .expect("connected streams should have a peer address");
         info!("Peer address: {}", peer);
 
-        tokio::spawn(accept_connection(peer, stream));
+        tokio::spawn(accept_connection(peer, stream, Arc::clone(&config)));
     }
 }